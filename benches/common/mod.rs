@@ -0,0 +1,34 @@
+//! Fixtures shared by the benchmarks.
+
+use voxel_forge::world::{Block, Chunk, ChunkPos, SECTION_SIZE};
+
+/// Fill a chunk with terrain for benchmarking.
+pub fn create_terrain_chunk(cx: i32, cz: i32) -> Chunk {
+    let mut chunk = Chunk::new(ChunkPos::new(cx, cz));
+
+    for x in 0..SECTION_SIZE {
+        for z in 0..SECTION_SIZE {
+            let wx = cx * SECTION_SIZE as i32 + x as i32;
+            let wz = cz * SECTION_SIZE as i32 + z as i32;
+
+            // Simple height variation
+            let height = 64
+                + ((wx as f32 * 0.1).sin() * 3.0) as usize
+                + ((wz as f32 * 0.15).cos() * 2.0) as usize;
+
+            chunk.set_block(x, 0, z, Block::Bedrock);
+
+            for y in 1..height.saturating_sub(4) {
+                chunk.set_block(x, y, z, Block::Stone);
+            }
+
+            for y in height.saturating_sub(4)..height {
+                chunk.set_block(x, y, z, Block::Dirt);
+            }
+
+            chunk.set_block(x, height, z, Block::Grass);
+        }
+    }
+
+    chunk
+}
@@ -1,40 +1,11 @@
 //! Benchmarks for mesh generation performance.
 
 use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
-use voxel_forge::world::{Block, Chunk, ChunkPos, MeshGenerator};
+use voxel_forge::world::{Block, Chunk, ChunkPos, ChunkSection, MeshGenerator};
 
-/// Fill a chunk with terrain for benchmarking.
-fn create_terrain_chunk(cx: i32, cz: i32) -> Chunk {
-    use voxel_forge::world::SECTION_SIZE;
+mod common;
 
-    let mut chunk = Chunk::new(ChunkPos::new(cx, cz));
-
-    for x in 0..SECTION_SIZE {
-        for z in 0..SECTION_SIZE {
-            let wx = cx * SECTION_SIZE as i32 + x as i32;
-            let wz = cz * SECTION_SIZE as i32 + z as i32;
-
-            // Simple height variation
-            let height = 64
-                + ((wx as f32 * 0.1).sin() * 3.0) as usize
-                + ((wz as f32 * 0.15).cos() * 2.0) as usize;
-
-            chunk.set_block(x, 0, z, Block::Bedrock);
-
-            for y in 1..height.saturating_sub(4) {
-                chunk.set_block(x, y, z, Block::Stone);
-            }
-
-            for y in height.saturating_sub(4)..height {
-                chunk.set_block(x, y, z, Block::Dirt);
-            }
-
-            chunk.set_block(x, height, z, Block::Grass);
-        }
-    }
-
-    chunk
-}
+use common::create_terrain_chunk;
 
 fn mesh_generation_benchmark(c: &mut Criterion) {
     let mut group = c.benchmark_group("mesh_generation");
@@ -84,6 +55,29 @@ fn mesh_generation_benchmark(c: &mut Criterion) {
         });
     });
 
+    // Only the bottom section holds blocks; the rest are skipped, whether
+    // missing or allocated but emptied
+    for allocated in [false, true] {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.set_section(0, Some(ChunkSection::filled(Block::Stone)));
+        if allocated {
+            for y in 1..chunk.section_count() {
+                chunk.set_section(y, Some(ChunkSection::new()));
+            }
+        }
+        let name = if allocated {
+            "bottom_section_allocated_above"
+        } else {
+            "bottom_section_only"
+        };
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let generator = MeshGenerator::new(black_box(chunk.clone()));
+                black_box(generator.generate())
+            });
+        });
+    }
+
     group.finish();
 }
 
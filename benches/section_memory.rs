@@ -0,0 +1,51 @@
+//! Benchmarks for palette-compressed section storage.
+
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use voxel_forge::world::{Block, ChunkSection, SECTION_SIZE};
+
+mod common;
+
+use common::create_terrain_chunk;
+
+/// Bytes a section took with a flat block array and nibble metadata.
+const FLAT_SECTION_BYTES: usize =
+    SECTION_SIZE.pow(3) * size_of::<Block>() + SECTION_SIZE.pow(3) / 2;
+
+fn section_memory_benchmark(c: &mut Criterion) {
+    let chunk = create_terrain_chunk(0, 0);
+    // Section 4 holds the surface: stone, dirt, grass and air
+    let section = chunk.get_section(4).expect("terrain section");
+    let sections = (0..chunk.section_count())
+        .filter(|&y| chunk.get_section(y).is_some())
+        .count();
+    // Paletted storage must stay smaller than the flat layout it replaced
+    assert!(section.heap_size() < FLAT_SECTION_BYTES);
+    assert!(chunk.heap_size() < FLAT_SECTION_BYTES * sections);
+
+    let mut group = c.benchmark_group("section_memory");
+
+    group.bench_function("get_all_blocks", |b| {
+        b.iter(|| {
+            black_box(section)
+                .blocks()
+                .filter(|block| !block.is_air())
+                .count()
+        });
+    });
+
+    group.bench_function("set_widening_past_16_types", |b| {
+        let types: Vec<Block> = Block::all().iter().copied().skip(1).take(20).collect();
+        b.iter(|| {
+            let mut section = ChunkSection::new();
+            for (i, &block) in types.iter().enumerate() {
+                section.set(i % SECTION_SIZE, 0, i / SECTION_SIZE, block);
+            }
+            black_box(section)
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, section_memory_benchmark);
+criterion_main!(benches);
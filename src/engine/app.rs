@@ -5,29 +5,92 @@
 use std::time::Instant;
 
 use anyhow::Result;
-use glam::Vec3;
-use tracing::{error, info};
+use glam::{Mat4, Vec2, Vec3};
+use tracing::{error, info, warn};
 use winit::{
     application::ApplicationHandler,
+    dpi::PhysicalSize,
     event::{DeviceEvent, ElementState, MouseScrollDelta, WindowEvent},
     event_loop::ActiveEventLoop,
     keyboard::{KeyCode, PhysicalKey},
     window::{CursorGrabMode, WindowId},
 };
 
-use crate::world::{Block, ChunkManager, ChunkManagerConfig, ChunkPos, RaycastHit, raycast};
+use crate::math::{Aabb, Frustum};
+use crate::world::{
+    ANIMATION_LOOP_SECONDS, Block, ChunkManager, ChunkManagerConfig, ChunkMesh, ChunkPos,
+    ColorOverrides, DEFAULT_ATLAS_SEED, FluidConfig, GeneratedChunk, MeshLayer, Orientation,
+    RaycastHit, WorldIssue, update_water,
+};
 
 use super::camera::{Camera, CameraConfig};
-use super::chunk_renderer::{CameraUniform, ChunkBuffers, ChunkRenderer};
+use super::chunk_renderer::{CameraUniform, ChunkBuffers, ChunkRenderer, CullStats};
+use super::console::{Console, ConsoleCommand};
+use super::dynamic_resolution::{DynamicResolution, scaled_size};
+use super::error::EngineError;
 use super::fps_counter::FpsCounter;
-use super::input::{InputState, MouseButton};
-use super::overlay::OverlayRenderer;
-use super::renderer::{Renderer, RendererConfig};
+use super::hotbar::{HOTBAR, HOTBAR_SLOTS, hotbar_slot, scroll_hotbar};
+use super::input::{GameAction, InputAction, InputState, MouseButton};
+use super::mining::Mining;
+use super::overlay::{OverlayRenderer, Viewport, crosshair_scale};
+use super::player::Player;
+use super::renderer::{Renderer, RendererConfig, vsync_toggled};
+use super::scaled_target::ScaledTarget;
+use super::settings::Settings;
+use super::sky::{Sky, SkyConfig, SkyRenderer};
+use super::timestep::FixedTimestep;
 use super::window::{GameWindow, WindowConfig};
-use super::wireframe::WireframeRenderer;
+use super::wireframe::{HIGHLIGHT_COLOR, WireframeRenderer, crack_color};
 
 use std::collections::HashMap;
 
+/// Maximum distance (in blocks) the player can reach to target blocks.
+const REACH_DISTANCE: f32 = 6.0;
+
+/// Length of one game tick in seconds (20 ticks per second).
+const TICK_DURATION: f32 = 1.0 / 20.0;
+
+/// Maximum ticks run in a single frame, so a long stall doesn't snowball.
+const MAX_TICKS_PER_FRAME: u32 = 4;
+
+/// Length of one player physics step in seconds (60 steps per second).
+const PHYSICS_STEP: f32 = 1.0 / 60.0;
+
+/// Maximum physics steps run in a single frame.
+const MAX_PHYSICS_STEPS_PER_FRAME: u32 = 5;
+
+/// Where the camera starts unless configured otherwise.
+pub const DEFAULT_SPAWN: Vec3 = Vec3::new(8.0, 80.0, 24.0);
+
+/// Key held to zoom in.
+const ZOOM_KEY: KeyCode = KeyCode::KeyC;
+
+/// Zoom factor applied when the zoom key is first pressed.
+const ZOOM_START: f32 = 2.0;
+
+/// Zoom change per scroll wheel step while zoomed.
+const ZOOM_STEP: f32 = 1.25;
+
+/// Keys selecting each hotbar slot.
+const HOTBAR_KEYS: [KeyCode; HOTBAR_SLOTS] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+/// Distance of the debug HUD text from the window's top-left, in logical
+/// pixels.
+const DEBUG_HUD_MARGIN: f32 = 8.0;
+
+/// Logical pixels per font pixel in the debug HUD.
+const DEBUG_HUD_SCALE: f32 = 2.0;
+
 /// The main application state.
 pub struct App {
     /// Window configuration.
@@ -44,41 +107,112 @@ pub struct App {
     chunk_manager: ChunkManager,
     /// Loaded chunk GPU buffers, keyed by position.
     chunk_buffers: HashMap<ChunkPos, ChunkBuffers>,
+    /// Translucent chunk GPU buffers (glass, water), keyed by position.
+    transparent_buffers: HashMap<ChunkPos, ChunkBuffers>,
     /// The camera for viewing the world.
     camera: Camera,
+    /// Player movement state (mode, fly, noclip).
+    player: Player,
     /// Input state tracker.
     input: InputState,
     /// FPS counter.
     fps_counter: FpsCounter,
     /// Last frame time for delta calculation.
     last_frame: Instant,
+    /// Seconds animating textures, wrapped at [`ANIMATION_LOOP_SECONDS`].
+    animation_time: f32,
     /// Whether the app should close.
     should_close: bool,
     /// Frame counter for periodic logging.
     frame_count: u64,
     /// Currently targeted block (if any).
     targeted_block: Option<RaycastHit>,
-    /// Block type to place (simple hotbar simulation).
+    /// Progress on breaking the targeted block in survival.
+    mining: Mining,
+    /// Block type to place, usually one from the hotbar.
     selected_block: Block,
     /// Overlay renderer for HUD elements.
     overlay_renderer: Option<OverlayRenderer>,
+    /// Whether the debug HUD (F3) is shown.
+    show_debug_hud: bool,
     /// Wireframe renderer for block selection.
     wireframe_renderer: Option<WireframeRenderer>,
+    /// Sky gradient and fog settings.
+    sky: Sky,
+    /// Sky background renderer.
+    sky_renderer: Option<SkyRenderer>,
+    /// Liquid flow settings.
+    fluid_config: FluidConfig,
+    /// Game tick timing.
+    ticks: FixedTimestep,
+    /// Player physics timing.
+    physics: FixedTimestep,
+    /// Eye position before the latest physics step, for interpolation.
+    previous_eye: Vec3,
+    /// Chunks drawn and culled in the last frame.
+    cull_stats: CullStats,
+    /// Render scale controller.
+    dynamic_resolution: DynamicResolution,
+    /// Reduced-resolution scene target, while the render scale is below 1.
+    scaled_target: Option<ScaledTarget>,
+    /// Command console.
+    console: Console,
+    /// Player settings.
+    settings: Settings,
+}
+
+/// The chunk manager settings the app uses unless configured otherwise.
+#[must_use]
+pub fn default_chunk_manager_config() -> ChunkManagerConfig {
+    ChunkManagerConfig {
+        render_distance: 6, // 6 chunk radius = 113 chunks
+        lod_distance: Some(4),
+        max_chunks_per_frame: 8,
+        max_unloads_per_frame: 16,
+        max_mesh_uploads_per_frame: 16,
+        ..ChunkManagerConfig::default()
+    }
 }
 
 impl App {
-    /// Creates a new application instance.
+    /// Creates a new application instance with the default camera, chunk
+    /// manager and spawn point.
     #[must_use]
     pub fn new(window_config: WindowConfig, renderer_config: RendererConfig) -> Self {
-        // Start camera at a good viewing position
-        let camera = Camera::new(CameraConfig::default()).at_position(Vec3::new(8.0, 80.0, 24.0));
-
-        // Create chunk manager with default config
-        let chunk_manager = ChunkManager::new(ChunkManagerConfig {
-            render_distance: 6, // 6 chunk radius = 113 chunks
-            max_chunks_per_frame: 8,
-            max_unloads_per_frame: 16,
+        Self::with_world(
+            window_config,
+            renderer_config,
+            CameraConfig::default(),
+            default_chunk_manager_config(),
+            DEFAULT_SPAWN,
+        )
+    }
+
+    /// Creates a new application instance with every sub-config given.
+    #[must_use]
+    pub fn with_world(
+        window_config: WindowConfig,
+        renderer_config: RendererConfig,
+        camera_config: CameraConfig,
+        chunk_manager_config: ChunkManagerConfig,
+        spawn: Vec3,
+    ) -> Self {
+        let camera = Camera::new(camera_config).at_position(spawn);
+        let chunk_manager = ChunkManager::new(chunk_manager_config);
+
+        let mut sky = Sky::new(SkyConfig {
+            fog_start: renderer_config.fog_start,
+            fog_end: renderer_config.fog_end,
+            fog_color: renderer_config.fog_color,
+            day_length: renderer_config.day_length,
+            ..SkyConfig::default()
         });
+        sky.set_time_of_day(renderer_config.time_of_day);
+
+        let dynamic_resolution = DynamicResolution::new(
+            renderer_config.dynamic_resolution.clone(),
+            renderer_config.render_scale,
+        );
 
         Self {
             window_config,
@@ -88,19 +222,78 @@ impl App {
             chunk_renderer: None,
             chunk_manager,
             chunk_buffers: HashMap::new(),
+            transparent_buffers: HashMap::new(),
             camera,
+            player: Player::default(),
             input: InputState::new(),
             fps_counter: FpsCounter::new(),
             last_frame: Instant::now(),
+            animation_time: 0.0,
             should_close: false,
             frame_count: 0,
             targeted_block: None,
+            mining: Mining::new(),
             selected_block: Block::Stone,
             overlay_renderer: None,
+            show_debug_hud: false,
             wireframe_renderer: None,
+            sky,
+            sky_renderer: None,
+            fluid_config: FluidConfig::default(),
+            ticks: FixedTimestep::new(TICK_DURATION, MAX_TICKS_PER_FRAME),
+            physics: FixedTimestep::new(PHYSICS_STEP, MAX_PHYSICS_STEPS_PER_FRAME),
+            previous_eye: spawn,
+            cull_stats: CullStats::default(),
+            dynamic_resolution,
+            scaled_target: None,
+            console: Console::new(),
+            settings: Settings::default(),
         }
     }
 
+    /// Applies player settings, e.g. block color overrides.
+    #[must_use]
+    pub fn with_settings(mut self, settings: Settings) -> Self {
+        // The atlas is tinted once the renderer is created
+        self.chunk_manager
+            .set_color_overrides(settings.color_overrides.clone());
+        self.input.set_bindings(settings.key_bindings.clone());
+        self.settings = settings;
+        self
+    }
+
+    /// Returns the time of day in `[0, 1)`: 0 is midnight, 0.5 is noon.
+    #[must_use]
+    pub const fn time_of_day(&self) -> f32 {
+        self.sky.time_of_day()
+    }
+
+    /// Sets the time of day, wrapping into `[0, 1)`.
+    pub fn set_time_of_day(&mut self, time: f32) {
+        self.sky.set_time_of_day(time);
+    }
+
+    /// Stops or restarts the time of day advancing each frame.
+    pub const fn set_time_paused(&mut self, paused: bool) {
+        self.sky.set_paused(paused);
+    }
+
+    /// Replaces the block color overrides, regenerating the texture atlas and
+    /// re-meshing every loaded chunk with the new colors.
+    pub fn set_color_overrides(&mut self, overrides: ColorOverrides) {
+        if let (Some(renderer), Some(chunk_renderer)) = (&self.renderer, &self.chunk_renderer) {
+            chunk_renderer.set_atlas(
+                renderer.queue(),
+                self.renderer_config.atlas_seed,
+                &overrides,
+            );
+        }
+        let count = self.chunk_manager.set_color_overrides(overrides.clone());
+        self.settings.color_overrides = overrides;
+        self.rebuild_dirty_chunks();
+        info!("Applied color overrides to {count} chunks");
+    }
+
     /// Creates the renderer and chunk renderer.
     fn create_renderer(&mut self) -> Result<()> {
         if let Some(ref window) = self.window {
@@ -115,17 +308,36 @@ impl App {
                 .set_aspect_ratio(size.width as f32, size.height as f32);
 
             // Create chunk renderer
-            let chunk_renderer = ChunkRenderer::new(
+            let mut chunk_renderer = ChunkRenderer::new(
                 renderer.device(),
                 renderer.queue(),
                 renderer.surface_format(),
                 size.width,
                 size.height,
             )?;
+            chunk_renderer.set_shadows(
+                renderer.device(),
+                self.renderer_config.shadows,
+                self.renderer_config.shadow_resolution,
+            )?;
+            if self.renderer_config.atlas_seed != DEFAULT_ATLAS_SEED
+                || !self.settings.color_overrides.is_empty()
+            {
+                chunk_renderer.set_atlas(
+                    renderer.queue(),
+                    self.renderer_config.atlas_seed,
+                    &self.settings.color_overrides,
+                );
+            }
 
             // Create overlay renderer for HUD
-            let overlay_renderer =
-                OverlayRenderer::new(renderer.device(), renderer.surface_format());
+            let overlay_renderer = OverlayRenderer::new(
+                renderer.device(),
+                renderer.queue(),
+                renderer.surface_format(),
+                &self.viewport(),
+                &chunk_renderer.atlas_view(),
+            );
 
             // Create wireframe renderer for block selection
             let wireframe_renderer = WireframeRenderer::new(
@@ -134,6 +346,13 @@ impl App {
                 wgpu::TextureFormat::Depth32Float,
             );
 
+            // Create sky renderer for the background gradient
+            let sky_renderer = SkyRenderer::new(
+                renderer.device(),
+                renderer.surface_format(),
+                wgpu::TextureFormat::Depth32Float,
+            )?;
+
             info!(
                 "Chunk manager started with render distance {}",
                 self.chunk_manager.render_distance()
@@ -142,11 +361,80 @@ impl App {
             self.chunk_renderer = Some(chunk_renderer);
             self.overlay_renderer = Some(overlay_renderer);
             self.wireframe_renderer = Some(wireframe_renderer);
+            self.sky_renderer = Some(sky_renderer);
             self.renderer = Some(renderer);
+            self.apply_render_scale();
         }
         Ok(())
     }
 
+    /// Resizes the surface and everything laid out for the window.
+    #[allow(clippy::cast_precision_loss)]
+    fn resize(&mut self, new_size: PhysicalSize<u32>) {
+        if let Some(ref mut renderer) = self.renderer {
+            renderer.resize(new_size);
+            self.camera
+                .set_aspect_ratio(new_size.width as f32, new_size.height as f32);
+        }
+        self.apply_render_scale();
+        let viewport = self.viewport();
+        if let (Some(overlay), Some(renderer)) = (&mut self.overlay_renderer, &self.renderer) {
+            overlay.resize(renderer.queue(), &viewport);
+        }
+    }
+
+    /// Sizes the scene target and depth buffer for the current render scale.
+    ///
+    /// At full scale the world draws straight to the window and the scaled
+    /// target is dropped.
+    fn apply_render_scale(&mut self) {
+        let (Some(renderer), Some(chunk_renderer)) = (&self.renderer, &mut self.chunk_renderer)
+        else {
+            return;
+        };
+
+        let size = renderer.size();
+        let scale = self.dynamic_resolution.scale();
+        let (width, height) = scaled_size(size.width, size.height, scale);
+
+        if (width, height) == (size.width, size.height) {
+            self.scaled_target = None;
+        } else if let Some(target) = &mut self.scaled_target {
+            target.resize(renderer.device(), width, height);
+        } else {
+            info!("Rendering at {width}x{height} (scale {scale:.2})");
+            self.scaled_target = Some(ScaledTarget::new(
+                renderer.device(),
+                renderer.surface_format(),
+                width,
+                height,
+            ));
+        }
+        chunk_renderer.resize(renderer.device(), width, height);
+    }
+
+    /// Returns the window's current size and DPI scale for overlay layout.
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    fn viewport(&self) -> Viewport {
+        self.window.as_ref().map_or_else(
+            || {
+                Viewport::new(
+                    self.window_config.width as f32,
+                    self.window_config.height as f32,
+                    1.0,
+                )
+            },
+            |window| {
+                let size = window.inner_size();
+                Viewport::new(
+                    size.width as f32,
+                    size.height as f32,
+                    window.window().scale_factor() as f32,
+                )
+            },
+        )
+    }
+
     /// Captures or releases the mouse cursor.
     fn set_cursor_captured(&mut self, captured: bool) {
         if let Some(ref window) = self.window {
@@ -182,6 +470,94 @@ impl App {
         }
     }
 
+    /// Opens or closes the console. While it's open, typed text goes to the
+    /// console instead of the game.
+    fn set_console_open(&mut self, open: bool) {
+        self.console.set_open(open);
+        self.input.set_text_capture(open);
+        if open {
+            info!("Console open (Enter to run, Escape to close)");
+        }
+    }
+
+    /// Moves text typed since the last call into the console line.
+    fn sync_console_text(&mut self) {
+        let text = self.input.take_text();
+        self.console.push_str(&text);
+    }
+
+    /// Handles a key press while the console is open.
+    fn handle_console_key(&mut self, key_code: KeyCode) {
+        match key_code {
+            KeyCode::Escape => self.set_console_open(false),
+            KeyCode::Backspace => {
+                self.sync_console_text();
+                self.console.backspace();
+            }
+            KeyCode::Enter | KeyCode::NumpadEnter => {
+                self.sync_console_text();
+                match self.console.submit() {
+                    Ok(command) => self.run_command(command),
+                    Err(e) => {
+                        warn!("Console: {e}");
+                        self.console.log(e.to_string());
+                    }
+                }
+                self.set_console_open(false);
+            }
+            _ => {}
+        }
+    }
+
+    /// Runs a console command and logs the outcome.
+    fn run_command(&mut self, command: ConsoleCommand) {
+        let message = match command {
+            ConsoleCommand::Teleport { x, y, z } => {
+                self.camera.position = Vec3::new(x, y, z);
+                self.previous_eye = self.camera.position;
+                format!("Teleported to ({x:.1}, {y:.1}, {z:.1})")
+            }
+            ConsoleCommand::Give { block } => {
+                self.selected_block = block;
+                format!("Selected {}", block.name())
+            }
+            ConsoleCommand::SetTime { time } => {
+                self.set_time_of_day(time);
+                format!("Time set to {:.2}", self.time_of_day())
+            }
+            ConsoleCommand::PauseTime { paused } => {
+                self.set_time_paused(paused);
+                if paused {
+                    "Time paused"
+                } else {
+                    "Time resumed"
+                }
+                .to_string()
+            }
+            ConsoleCommand::SetBlock { x, y, z, block } => {
+                if self.chunk_manager.set_block(x, y, z, block) {
+                    format!("Placed {} at ({x}, {y}, {z})", block.name())
+                } else if self.chunk_manager.get_block(x, y, z) == Some(block) {
+                    format!("{} is already at ({x}, {y}, {z})", block.name())
+                } else {
+                    format!("Can't place a block at ({x}, {y}, {z}): chunk not loaded")
+                }
+            }
+        };
+        info!("Console: {message}");
+        self.console.log(message);
+    }
+
+    /// Records a mouse press. A click on the unlocked window only captures
+    /// the cursor; it's consumed so it doesn't also break or place a block.
+    fn handle_mouse_pressed(&mut self, button: MouseButton) {
+        self.input.mouse_button_pressed(button);
+        if !self.input.is_cursor_locked() {
+            self.set_cursor_captured(true);
+            self.input.consume_mouse_button(button);
+        }
+    }
+
     /// Re-centers the cursor to allow infinite mouselook.
     fn recenter_cursor(&mut self) {
         if let Some(ref window) = self.window {
@@ -208,6 +584,13 @@ impl App {
 
     /// Updates game logic each frame.
     fn update(&mut self, delta_time: f32) {
+        self.animation_time = (self.animation_time + delta_time) % ANIMATION_LOOP_SECONDS;
+
+        // Move typed text into the console line
+        if self.console.is_open() {
+            self.sync_console_text();
+        }
+
         // Handle camera rotation from mouse
         if self.input.is_cursor_locked() {
             let (dx, dy) = self.input.take_mouse_delta();
@@ -218,22 +601,30 @@ impl App {
             self.recenter_cursor();
         }
 
-        // Handle camera movement from keyboard
-        let movement = self.input.movement_direction();
-        if movement.length_squared() > 0.0 {
-            self.camera.move_by(
-                movement,
-                delta_time,
-                self.input.is_sprinting(),
-                self.input.is_crouching(),
-            );
+        // Double-tapping jump switches between flying and walking
+        if !self.console.is_open()
+            && self.input.is_action_just_pressed(GameAction::Jump)
+            && let Some(flying) = self.player.tap_jump(Instant::now())
+        {
+            info!("{}", if flying { "Flying" } else { "Walking" });
         }
 
+        // Move the player in fixed physics steps
+        self.run_physics(delta_time);
+
+        self.update_zoom();
+
         // Raycast to find targeted block
         self.update_targeted_block();
 
         // Handle block interactions
-        self.handle_block_interactions();
+        self.handle_block_interactions(delta_time);
+
+        // Run scheduled block updates at a fixed tick rate
+        self.run_ticks(delta_time);
+
+        // Move the sun and sky on
+        self.sky.advance(delta_time);
 
         // Update chunk manager - load/unload chunks based on player position
         self.update_chunks();
@@ -242,98 +633,373 @@ impl App {
         self.rebuild_dirty_chunks();
     }
 
+    /// Zooms while the zoom key is held, with the scroll wheel adjusting the
+    /// zoom level, and rescales the crosshair to match.
+    fn update_zoom(&mut self) {
+        let zoom = if self.console.is_open() || !self.input.is_key_held(ZOOM_KEY) {
+            1.0
+        } else if self.input.is_key_just_pressed(ZOOM_KEY) {
+            ZOOM_START
+        } else {
+            self.camera.zoom() * ZOOM_STEP.powf(self.input.get_scroll_delta().1)
+        };
+        self.camera.set_zoom(zoom);
+
+        let viewport = self.viewport();
+        if let (Some(overlay), Some(renderer)) = (&mut self.overlay_renderer, &self.renderer) {
+            let scale = crosshair_scale(
+                self.camera.config().projection.size(),
+                self.camera.effective_projection().size(),
+                self.renderer_config.crosshair_scale_with_zoom,
+            );
+            overlay.set_crosshair_scale(renderer.queue(), &viewport, scale);
+        }
+    }
+
+    /// Runs as many fixed-rate game ticks as `delta_time` covers.
+    fn run_ticks(&mut self, delta_time: f32) {
+        for _ in 0..self.ticks.advance(delta_time) {
+            let fluid_config = &self.fluid_config;
+            self.chunk_manager
+                .tick(|world, pos| update_water(world, pos, fluid_config));
+        }
+    }
+
+    /// Moves the player from keyboard input in as many fixed-length physics
+    /// steps as `delta_time` covers, so movement and collision don't depend
+    /// on the frame rate. Walking players also fall without input.
+    fn run_physics(&mut self, delta_time: f32) {
+        let movement = self.input.movement_direction();
+        let sprinting = self.input.is_sprinting();
+        let crouching = self.input.is_crouching();
+
+        for _ in 0..self.physics.advance(delta_time) {
+            self.previous_eye = self.camera.position;
+            if movement.length_squared() > 0.0 || !self.player.flying {
+                let chunk_manager = &self.chunk_manager;
+                self.player.move_camera(
+                    &mut self.camera,
+                    movement,
+                    self.physics.step(),
+                    sprinting,
+                    crouching,
+                    |x, y, z| chunk_manager.collision_shape(x, y, z),
+                );
+            }
+        }
+    }
+
+    /// Returns the view frustum of `camera`, testing chunks at the world's
+    /// height.
+    fn frustum(&self, camera: &Camera) -> Frustum {
+        camera
+            .frustum()
+            .with_world_height(self.chunk_manager.world_height())
+    }
+
+    /// Returns the camera to render from: the player camera focused on its
+    /// eye interpolated between the last two physics steps.
+    fn render_camera(&self) -> Camera {
+        let mut camera = self.camera.clone();
+        let eye = self
+            .previous_eye
+            .lerp(self.camera.position, self.physics.alpha());
+        camera.set_focus(eye, |x, y, z| self.chunk_manager.is_block_solid(x, y, z));
+        camera
+    }
+
     /// Updates the currently targeted block using raycasting.
     fn update_targeted_block(&mut self) {
+        // Spectators can't interact, so nothing is highlighted
+        if !self.player.mode().can_interact() {
+            self.targeted_block = None;
+            return;
+        }
+
         let origin = self.camera.position;
         let direction = self.camera.forward();
-        let max_distance = 6.0; // Reach distance
 
-        self.targeted_block = raycast(origin, direction, max_distance, |x, y, z| {
-            self.chunk_manager.is_block_solid(x, y, z)
-        });
+        self.targeted_block =
+            self.chunk_manager
+                .raycast_where(origin, direction, REACH_DISTANCE, Block::is_solid);
+    }
+
+    /// Picks the block at the exact screen center by unprojecting a ray.
+    ///
+    /// Unlike the per-frame targeting, this doesn't rely on the camera
+    /// forward vector, so it's suitable for tools and tests.
+    #[must_use]
+    pub fn pick_center(&self) -> Option<RaycastHit> {
+        let (origin, direction) = self.camera.screen_to_world_ray(Vec2::ZERO);
+
+        self.chunk_manager
+            .raycast_where(origin, direction, REACH_DISTANCE, Block::is_solid)
     }
 
     /// Handles block breaking and placing based on mouse input.
-    fn handle_block_interactions(&mut self) {
-        // Only handle if cursor is locked (in game mode)
-        if !self.input.is_cursor_locked() {
+    fn handle_block_interactions(&mut self, delta_time: f32) {
+        // Only handle if cursor is locked (in game mode), the console isn't
+        // taking input and the mode allows it
+        if !self.input.is_cursor_locked()
+            || self.console.is_open()
+            || !self.player.mode().can_interact()
+        {
+            self.mining.reset();
             return;
         }
 
         // Left click - break block
-        if self.input.mouse_just_pressed(MouseButton::Left) {
-            if let Some(hit) = &self.targeted_block {
-                let pos = hit.block_pos;
-                self.chunk_manager
-                    .set_block(pos.x, pos.y, pos.z, Block::Air);
-            }
-        }
+        self.break_targeted_block(delta_time);
 
         // Right click - place block
-        if self.input.mouse_just_pressed(MouseButton::Right) {
-            if let Some(hit) = &self.targeted_block {
-                // Place on the face we hit (adjacent to the hit block)
-                let place_pos = hit.block_pos.offset(hit.face);
-
-                // Don't place if it would intersect the player (simple check)
-                let player_block_x = self.camera.position.x.floor() as i32;
-                let player_block_y = self.camera.position.y.floor() as i32;
-                let player_block_z = self.camera.position.z.floor() as i32;
-
-                // Player occupies 2 blocks vertically
-                let would_intersect = place_pos.x == player_block_x
-                    && place_pos.z == player_block_z
-                    && (place_pos.y == player_block_y || place_pos.y == player_block_y - 1);
-
-                if !would_intersect {
-                    self.chunk_manager.set_block(
-                        place_pos.x,
-                        place_pos.y,
-                        place_pos.z,
-                        self.selected_block,
+        if self
+            .input
+            .take_buffered(InputAction::Mouse(MouseButton::Right))
+            && let Some(hit) = self.targeted_block
+        {
+            self.place_selected_block(&hit);
+        }
+
+        // Number keys select a hotbar slot, or water on 0
+        if let Some(slot) = HOTBAR_KEYS
+            .iter()
+            .position(|&key| self.input.is_key_just_pressed(key))
+        {
+            self.selected_block = HOTBAR[slot];
+        } else if self.input.is_key_just_pressed(KeyCode::Digit0) {
+            self.selected_block = Block::Water;
+        }
+
+        // The scroll wheel steps through the hotbar, unless it's zooming;
+        // scrolling down moves right
+        let scroll = self.input.get_scroll_delta().1;
+        if scroll != 0.0 && !self.input.is_key_held(ZOOM_KEY) {
+            let steps = if scroll < 0.0 { 1 } else { -1 };
+            self.selected_block = scroll_hotbar(self.selected_block, steps);
+        }
+    }
+
+    /// Breaks the targeted block: on a click in creative, or once the left
+    /// button has been held on it for its hardness in survival.
+    fn break_targeted_block(&mut self, delta_time: f32) {
+        let clicked = self
+            .input
+            .take_buffered(InputAction::Mouse(MouseButton::Left));
+        let held = clicked || self.input.is_mouse_held(MouseButton::Left);
+        let target = self.targeted_block.as_ref().and_then(|hit| {
+            let pos = hit.block_pos;
+            self.chunk_manager
+                .get_block(pos.x, pos.y, pos.z)
+                .map(|block| (pos, block))
+        });
+        let Some((pos, block)) = target.filter(|_| held) else {
+            self.mining.reset();
+            return;
+        };
+
+        let breaks = if self.player.mode().breaks_instantly() {
+            clicked && block.properties().is_breakable
+        } else {
+            self.mining.mine(pos, block, delta_time)
+        };
+        if breaks {
+            self.chunk_manager
+                .set_block(pos.x, pos.y, pos.z, Block::Air);
+        }
+    }
+
+    /// Places the selected block against the hit face, oriented by the
+    /// face and camera direction.
+    fn place_selected_block(&mut self, hit: &RaycastHit) {
+        // Place on the face we hit (adjacent to the hit block)
+        let place_pos = hit.block_pos.offset(hit.face);
+
+        // Only fill empty space or liquid, never overwrite another block
+        if !self
+            .chunk_manager
+            .get_block(place_pos.x, place_pos.y, place_pos.z)
+            .is_some_and(Block::is_replaceable)
+        {
+            return;
+        }
+
+        // Never place a block inside the player
+        if self
+            .player
+            .blocks_placement(self.camera.position, self.selected_block, place_pos)
+        {
+            return;
+        }
+
+        let orientation =
+            Orientation::for_placement(self.selected_block, hit.face, self.camera.yaw);
+        self.chunk_manager.place_block(
+            place_pos.x,
+            place_pos.y,
+            place_pos.z,
+            self.selected_block,
+            orientation.to_metadata(),
+        );
+    }
+
+    /// Handles one-shot key commands (menus, toggles, debug output).
+    fn handle_key_pressed(&mut self, event_loop: &ActiveEventLoop, key_code: KeyCode) {
+        match key_code {
+            KeyCode::Escape => {
+                if self.input.is_cursor_locked() {
+                    self.set_cursor_captured(false);
+                } else {
+                    info!("Escape pressed, closing...");
+                    self.should_close = true;
+                    event_loop.exit();
+                }
+            }
+            KeyCode::KeyN => {
+                let noclip = self.player.toggle_noclip();
+                info!("Noclip {}", if noclip { "on" } else { "off" });
+            }
+            KeyCode::KeyF => {
+                let flying = self.player.toggle_flying();
+                info!("{}", if flying { "Flying" } else { "Walking" });
+            }
+            KeyCode::F4 => {
+                let mode = self.player.mode().next();
+                self.player.set_mode(mode);
+                info!("Player mode: {:?}", mode);
+            }
+            KeyCode::F5 => {
+                let mode = self.camera.config().mode.toggled();
+                self.camera.config_mut().mode = mode;
+                info!("Camera mode: {:?}", mode);
+            }
+            KeyCode::F6 => {
+                let issues = self.validate_world();
+                if issues.is_empty() {
+                    info!(
+                        "World valid: {} chunks checked",
+                        self.chunk_manager.loaded_count()
                     );
                 }
+                for issue in &issues {
+                    warn!("World invariant broken: {issue}");
+                }
+            }
+            KeyCode::F7 => self.refresh_meshes(),
+            KeyCode::F8 => {
+                if let Some(renderer) = &mut self.renderer {
+                    renderer.set_present_mode(vsync_toggled(renderer.present_mode()));
+                }
+            }
+            KeyCode::F9 => {
+                if let Some(chunk_renderer) = &mut self.chunk_renderer {
+                    let wanted = !chunk_renderer.is_wireframe();
+                    if chunk_renderer.set_wireframe(wanted) == wanted {
+                        info!("Wireframe {}", if wanted { "on" } else { "off" });
+                    } else {
+                        warn!("Wireframe unavailable: the GPU can't draw lines");
+                    }
+                }
+            }
+            KeyCode::Slash => self.set_console_open(true),
+            KeyCode::F3 => {
+                self.show_debug_hud = !self.show_debug_hud;
+                let pos = self.camera.position;
+                info!(
+                    "Pos: ({:.1}, {:.1}, {:.1}) | Yaw: {:.1}° Pitch: {:.1}° | FPS: {:.1}",
+                    pos.x,
+                    pos.y,
+                    pos.z,
+                    self.camera.yaw,
+                    self.camera.pitch,
+                    self.fps_counter.fps()
+                );
             }
+            _ => {}
         }
+    }
 
-        // Number keys to select block type
-        if self.input.is_key_just_pressed(KeyCode::Digit1) {
-            self.selected_block = Block::Stone;
-        } else if self.input.is_key_just_pressed(KeyCode::Digit2) {
-            self.selected_block = Block::Dirt;
-        } else if self.input.is_key_just_pressed(KeyCode::Digit3) {
-            self.selected_block = Block::Grass;
-        } else if self.input.is_key_just_pressed(KeyCode::Digit4) {
-            self.selected_block = Block::Log;
-        } else if self.input.is_key_just_pressed(KeyCode::Digit5) {
-            self.selected_block = Block::Planks;
-        } else if self.input.is_key_just_pressed(KeyCode::Digit6) {
-            self.selected_block = Block::Bricks;
-        } else if self.input.is_key_just_pressed(KeyCode::Digit7) {
-            self.selected_block = Block::Glass;
-        } else if self.input.is_key_just_pressed(KeyCode::Digit8) {
-            self.selected_block = Block::Sand;
-        } else if self.input.is_key_just_pressed(KeyCode::Digit9) {
-            self.selected_block = Block::Cobblestone;
+    /// Checks loaded chunks, their meshes and GPU buffers for corruption.
+    ///
+    /// Debug command (F6); rebuilds every loaded chunk's mesh, so it is too
+    /// slow to run every frame.
+    pub fn validate_world(&self) -> Vec<WorldIssue> {
+        let mut issues = self.chunk_manager.validate(|pos, layer| {
+            let buffers = match layer {
+                MeshLayer::Opaque => &self.chunk_buffers,
+                MeshLayer::Transparent => &self.transparent_buffers,
+            };
+            buffers.contains_key(&pos)
+        });
+
+        let mut stale: Vec<ChunkPos> = self
+            .chunk_buffers
+            .keys()
+            .chain(self.transparent_buffers.keys())
+            .filter(|&&pos| !self.chunk_manager.is_loaded(pos))
+            .copied()
+            .collect();
+        stale.sort_unstable_by_key(|pos| (pos.x, pos.z));
+        stale.dedup();
+        issues.extend(
+            stale
+                .into_iter()
+                .map(|chunk| WorldIssue::StaleBuffers { chunk }),
+        );
+        issues
+    }
+
+    /// Regenerates the block texture atlas and every loaded chunk's mesh,
+    /// re-uploading both. Debug command (F7) for tweaking the generators.
+    fn refresh_meshes(&mut self) {
+        if let (Some(renderer), Some(chunk_renderer)) = (&self.renderer, &self.chunk_renderer) {
+            chunk_renderer.set_atlas(
+                renderer.queue(),
+                self.renderer_config.atlas_seed,
+                &self.settings.color_overrides,
+            );
         }
+        let count = self.chunk_manager.mark_all_dirty();
+        self.rebuild_dirty_chunks();
+        info!("Regenerated atlas, re-meshing {count} chunks");
     }
 
-    /// Rebuilds chunk meshes that were modified.
+    /// Sends modified chunks off to be re-meshed on the thread pool, and
+    /// uploads the meshes that have finished since the last frame.
     fn rebuild_dirty_chunks(&mut self) {
         let Some(renderer) = self.renderer.as_ref() else {
             return;
         };
 
-        let dirty = self.chunk_manager.take_dirty_chunks();
-        for pos in dirty {
-            if let Some(generated) = self.chunk_manager.rebuild_chunk_mesh(pos) {
-                if !generated.mesh.is_empty() {
-                    let buffers = ChunkBuffers::from_mesh(renderer.device(), &generated.mesh);
-                    self.chunk_buffers.insert(pos, buffers);
-                } else {
-                    self.chunk_buffers.remove(&pos);
-                }
-            }
+        self.chunk_manager.dispatch_dirty_meshes();
+        for generated in self.chunk_manager.take_rebuilt_meshes() {
+            Self::upload_mesh(
+                renderer.device(),
+                &mut self.chunk_buffers,
+                &generated,
+                &generated.mesh,
+            );
+            Self::upload_mesh(
+                renderer.device(),
+                &mut self.transparent_buffers,
+                &generated,
+                &generated.transparent_mesh,
+            );
+        }
+    }
+
+    /// Uploads one of a generated chunk's meshes into a buffer map, tagged
+    /// with its level of detail, removing the entry if the mesh is empty.
+    fn upload_mesh(
+        device: &wgpu::Device,
+        buffers: &mut HashMap<ChunkPos, ChunkBuffers>,
+        generated: &GeneratedChunk,
+        mesh: &ChunkMesh,
+    ) {
+        if mesh.is_empty() {
+            buffers.remove(&generated.pos);
+        } else {
+            let chunk_buffers = ChunkBuffers::from_mesh(device, mesh).with_lod(generated.lod);
+            buffers.insert(generated.pos, chunk_buffers);
         }
     }
 
@@ -344,19 +1010,37 @@ impl App {
         };
 
         // Get new and unloaded chunks from manager
-        let (ready_chunks, unload_chunks) = self.chunk_manager.update(self.camera.position);
+        let (ready_chunks, unload_chunks) = self
+            .chunk_manager
+            .update(self.camera.position, &self.frustum(&self.camera));
 
         // Create GPU buffers for new chunks
         for generated in ready_chunks {
-            if !generated.mesh.is_empty() {
-                let buffers = ChunkBuffers::from_mesh(renderer.device(), &generated.mesh);
-                self.chunk_buffers.insert(generated.pos, buffers);
-            }
+            Self::upload_mesh(
+                renderer.device(),
+                &mut self.chunk_buffers,
+                &generated,
+                &generated.mesh,
+            );
+            Self::upload_mesh(
+                renderer.device(),
+                &mut self.transparent_buffers,
+                &generated,
+                &generated.transparent_mesh,
+            );
         }
 
         // Remove GPU buffers for unloaded chunks
         for pos in unload_chunks {
             self.chunk_buffers.remove(&pos);
+            self.transparent_buffers.remove(&pos);
+        }
+
+        // Fill the loading bar until the first region has loaded
+        let viewport = self.viewport();
+        if let Some(overlay) = &mut self.overlay_renderer {
+            let progress = self.chunk_manager.initial_load_progress();
+            overlay.set_loading_progress(renderer.queue(), &viewport, progress);
         }
 
         // Periodic logging
@@ -371,24 +1055,162 @@ impl App {
         }
     }
 
+    /// Counts the frame, periodically logging the frame rate and 1%-low
+    /// frame time along with how many chunks the last frame drew and culled.
+    fn log_frame_rate(&mut self) {
+        if let Some(fps) = self.fps_counter.should_log() {
+            let CullStats { drawn, culled } = self.cull_stats;
+            let low = self.fps_counter.percentile_ms(99.0);
+            info!("FPS: {fps:.1}, 1% low {low:.1} ms ({drawn} chunks drawn, {culled} culled)");
+        }
+        self.fps_counter.tick();
+    }
+
+    /// Returns the chunk buffers inside `frustum`, sorted from farthest to
+    /// nearest the camera.
+    fn back_to_front<'a>(
+        buffers: &'a HashMap<ChunkPos, ChunkBuffers>,
+        frustum: &Frustum,
+        camera_pos: Vec3,
+    ) -> Vec<&'a ChunkBuffers> {
+        let mut sorted: Vec<_> = buffers
+            .iter()
+            .filter(|(pos, _)| frustum.is_chunk_visible(pos.x, pos.z))
+            .map(|(pos, buffers)| {
+                let center = Aabb::from_chunk(pos.x, pos.z, frustum.world_height()).center();
+                (center.distance_squared(camera_pos), buffers)
+            })
+            .collect();
+        sorted.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        sorted.into_iter().map(|(_, buffers)| buffers).collect()
+    }
+
+    /// Returns the bounds of the meshed chunks inside the view frustum.
+    fn visible_bounds(
+        frustum: &Frustum,
+        chunk_buffers: &HashMap<ChunkPos, ChunkBuffers>,
+    ) -> Option<Aabb> {
+        chunk_buffers
+            .keys()
+            .filter(|pos| frustum.is_chunk_visible(pos.x, pos.z))
+            .map(|pos| Aabb::from_chunk(pos.x, pos.z, frustum.world_height()))
+            .reduce(|bounds, chunk| bounds.union(&chunk))
+    }
+
+    /// Draws HUD elements over the finished frame at full resolution
+    /// (no depth testing for 2D elements). The crosshair and hotbar are
+    /// only drawn with `show_hud`.
+    fn render_overlay(
+        overlay_renderer: &OverlayRenderer,
+        show_hud: bool,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+    ) {
+        let mut overlay_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Overlay Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load, // Don't clear, draw on top
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None, // No depth for 2D
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+
+        if show_hud {
+            overlay_renderer.render_crosshair(&mut overlay_pass);
+            overlay_renderer.render_hotbar(&mut overlay_pass);
+        }
+        overlay_renderer.render_loading_bar(&mut overlay_pass);
+        overlay_renderer.render_text(&mut overlay_pass);
+    }
+
+    /// Highlights the selected block's hotbar slot, and lays out the debug
+    /// HUD text when it is shown (F3) and uploads it for this frame.
+    fn update_hud(&mut self) {
+        let (Some(overlay), Some(renderer)) = (&mut self.overlay_renderer, &self.renderer) else {
+            return;
+        };
+        overlay.set_hotbar_selection(renderer.queue(), hotbar_slot(self.selected_block));
+        if self.show_debug_hud {
+            let pos = self.camera.position;
+            let text = format!(
+                "FPS: {:.1}\nXYZ: {:.1} / {:.1} / {:.1}\nBlock: {}",
+                self.fps_counter.fps(),
+                pos.x,
+                pos.y,
+                pos.z,
+                self.selected_block.name()
+            );
+            overlay.draw_text(&text, DEBUG_HUD_MARGIN, DEBUG_HUD_MARGIN, DEBUG_HUD_SCALE);
+        }
+        overlay.prepare_text(renderer.device(), renderer.queue());
+    }
+
+    /// Moves the selection highlight to the targeted block.
+    #[allow(clippy::cast_precision_loss)]
+    fn update_highlight(&mut self, view_proj: Mat4) {
+        let (Some(renderer), Some(wireframe_renderer), Some(hit)) = (
+            &self.renderer,
+            &mut self.wireframe_renderer,
+            &self.targeted_block,
+        ) else {
+            return;
+        };
+        wireframe_renderer.update_camera(renderer.queue(), view_proj);
+        let pos = Vec3::new(
+            hit.block_pos.x as f32,
+            hit.block_pos.y as f32,
+            hit.block_pos.z as f32,
+        );
+        // The outline shows the crack stage of a block being mined
+        let color = match self.mining.crack_stage() {
+            Some(stage) if self.mining.target() == Some(hit.block_pos) => crack_color(stage),
+            _ => HIGHLIGHT_COLOR,
+        };
+        wireframe_renderer.set_boxes(renderer.device(), renderer.queue(), &[(pos, color)]);
+    }
+
     /// Renders the frame.
     fn render_frame(&mut self) -> Result<()> {
+        let camera = self.render_camera();
+        let frustum = self.frustum(&camera);
+        let view_proj = camera.view_projection_matrix();
+        self.update_highlight(view_proj);
+        self.update_hud();
+
         let renderer = self
             .renderer
             .as_mut()
             .ok_or_else(|| anyhow::anyhow!("No renderer"))?;
         let chunk_renderer = self
             .chunk_renderer
-            .as_ref()
+            .as_mut()
             .ok_or_else(|| anyhow::anyhow!("No chunk renderer"))?;
 
-        // Update camera uniform
-        let camera_uniform =
-            CameraUniform::new(self.camera.view_projection_matrix(), self.camera.position);
+        // Light the world from the sun or moon for the time of day, with
+        // shadows covering the chunks in view
+        chunk_renderer.set_sun_from_time(self.sky.time_of_day(), self.sky.config().sun_tilt);
+        if let Some(visible) = Self::visible_bounds(&frustum, &self.chunk_buffers) {
+            chunk_renderer.fit_shadows(&visible);
+        }
+
+        // Update camera uniform (with sky gradient for fog and the time for
+        // animated textures)
+        let camera_uniform = CameraUniform::new(view_proj, camera.eye_position())
+            .with_sky(self.sky.uniform())
+            .with_time(self.animation_time);
         chunk_renderer.update_camera(renderer.queue(), &camera_uniform);
 
-        // Get surface texture
-        let output = renderer.surface().get_current_texture()?;
+        // Get surface texture, skipping the frame if the surface needs
+        // reconfiguring
+        let Some(output) = renderer.acquire_frame()? else {
+            return Ok(());
+        };
         let view = output
             .texture
             .create_view(&wgpu::TextureViewDescriptor::default());
@@ -401,20 +1223,30 @@ impl App {
                     label: Some("Render Encoder"),
                 });
 
-        // Render pass
+        // Shadow pass (sun depth), read by the main pass
+        chunk_renderer.render_shadows(&mut encoder, self.chunk_buffers.values());
+
+        // Render pass (into the scaled target when below full resolution),
+        // cleared to the horizon color for the time of day
+        let horizon = self.sky.gradient().horizon;
+        let clear_color = wgpu::Color {
+            r: f64::from(horizon.x),
+            g: f64::from(horizon.y),
+            b: f64::from(horizon.z),
+            a: 1.0,
+        };
+        let scene_view = self
+            .scaled_target
+            .as_ref()
+            .map_or(&view, ScaledTarget::view);
         {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Main Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
+                    view: scene_view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.5,
-                            g: 0.7,
-                            b: 1.0,
-                            a: 1.0,
-                        }),
+                        load: wgpu::LoadOp::Clear(clear_color),
                         store: wgpu::StoreOp::Store,
                     },
                 })],
@@ -430,45 +1262,38 @@ impl App {
                 timestamp_writes: None,
             });
 
-            // Render chunks
-            chunk_renderer.render(&mut render_pass, self.chunk_buffers.values());
+            // Sky background first; terrain draws over it
+            if let Some(sky_renderer) = &self.sky_renderer {
+                sky_renderer.update(renderer.queue(), view_proj, &self.sky);
+                sky_renderer.render(&mut render_pass);
+            }
+
+            // Render opaque chunks
+            self.cull_stats =
+                chunk_renderer.render(&mut render_pass, &frustum, &self.chunk_buffers);
+
+            // Render translucent chunks back-to-front for correct blending
+            let transparent =
+                Self::back_to_front(&self.transparent_buffers, &frustum, camera.eye_position());
+            chunk_renderer.render_transparent(&mut render_pass, transparent.into_iter());
 
             // Render block selection wireframe if we have a target
-            if let (Some(wireframe_renderer), Some(hit)) =
+            if let (Some(wireframe_renderer), Some(_)) =
                 (&self.wireframe_renderer, &self.targeted_block)
             {
-                wireframe_renderer
-                    .update_camera(renderer.queue(), self.camera.view_projection_matrix());
-                wireframe_renderer.update_highlight(
-                    renderer.queue(),
-                    Vec3::new(
-                        hit.block_pos.x as f32,
-                        hit.block_pos.y as f32,
-                        hit.block_pos.z as f32,
-                    ),
-                );
                 wireframe_renderer.render(&mut render_pass);
             }
         }
 
-        // Overlay render pass (no depth testing for 2D elements)
-        if let Some(overlay_renderer) = &self.overlay_renderer {
-            let mut overlay_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Overlay Render Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Load, // Don't clear, draw on top
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: None, // No depth for 2D
-                occlusion_query_set: None,
-                timestamp_writes: None,
-            });
+        // Upscale the scene to the window
+        if let Some(scaled_target) = &self.scaled_target {
+            scaled_target.blit(&mut encoder, &view);
+        }
 
-            overlay_renderer.render_crosshair(&mut overlay_pass);
+        // Overlay render pass
+        if let Some(overlay_renderer) = &self.overlay_renderer {
+            let show_hud = self.player.mode().shows_crosshair();
+            Self::render_overlay(overlay_renderer, show_hud, &mut encoder, &view);
         }
 
         // Submit and present
@@ -496,7 +1321,7 @@ impl ApplicationHandler for App {
                         info!("Renderer created successfully");
                         info!("Click in window to capture mouse. ESC to release.");
                         info!(
-                            "Controls: WASD move, Mouse look, Space/Shift fly up/down, Ctrl sprint"
+                            "Controls: WASD move, Mouse look, Space/Shift fly up/down (jump/crouch when walking), double-tap Space or F toggle flying, Ctrl sprint"
                         );
                         info!("Blocks: Left-click break, Right-click place, 1-9 select block type");
                     }
@@ -518,6 +1343,9 @@ impl ApplicationHandler for App {
         match event {
             WindowEvent::CloseRequested => {
                 info!("Close requested");
+                if let Err(err) = self.chunk_manager.save_edited() {
+                    warn!("Failed to save edited chunks: {err}");
+                }
                 self.should_close = true;
                 event_loop.exit();
             }
@@ -531,49 +1359,25 @@ impl ApplicationHandler for App {
             }
 
             WindowEvent::Resized(new_size) => {
-                if let Some(ref mut renderer) = self.renderer {
-                    renderer.resize(new_size);
-                    self.camera
-                        .set_aspect_ratio(new_size.width as f32, new_size.height as f32);
-                }
-                if let Some(ref mut chunk_renderer) = self.chunk_renderer {
-                    if let Some(ref renderer) = self.renderer {
-                        chunk_renderer.resize(renderer.device(), new_size.width, new_size.height);
-                    }
-                }
+                self.resize(new_size);
                 // Don't auto-capture - let user click to re-capture
             }
 
             WindowEvent::KeyboardInput { event, .. } => {
+                // Text first, so the key that opens the console isn't typed into it
+                if let (ElementState::Pressed, Some(text)) = (event.state, &event.text) {
+                    self.input.text_entered(text);
+                }
                 if let PhysicalKey::Code(key_code) = event.physical_key {
                     match event.state {
+                        // The open console takes keys away from the game
+                        ElementState::Pressed if self.console.is_open() => {
+                            self.handle_console_key(key_code);
+                        }
                         ElementState::Pressed => {
                             self.input.key_pressed(key_code);
 
-                            match key_code {
-                                KeyCode::Escape => {
-                                    if self.input.is_cursor_locked() {
-                                        self.set_cursor_captured(false);
-                                    } else {
-                                        info!("Escape pressed, closing...");
-                                        self.should_close = true;
-                                        event_loop.exit();
-                                    }
-                                }
-                                KeyCode::F3 => {
-                                    let pos = self.camera.position;
-                                    info!(
-                                        "Pos: ({:.1}, {:.1}, {:.1}) | Yaw: {:.1}° Pitch: {:.1}° | FPS: {:.1}",
-                                        pos.x,
-                                        pos.y,
-                                        pos.z,
-                                        self.camera.yaw,
-                                        self.camera.pitch,
-                                        self.fps_counter.fps()
-                                    );
-                                }
-                                _ => {}
-                            }
+                            self.handle_key_pressed(event_loop, key_code);
                         }
                         ElementState::Released => {
                             self.input.key_released(key_code);
@@ -585,12 +1389,7 @@ impl ApplicationHandler for App {
             WindowEvent::MouseInput { state, button, .. } => {
                 let btn = MouseButton::from(button);
                 match state {
-                    ElementState::Pressed => {
-                        self.input.mouse_button_pressed(btn);
-                        if !self.input.is_cursor_locked() {
-                            self.set_cursor_captured(true);
-                        }
-                    }
+                    ElementState::Pressed => self.handle_mouse_pressed(btn),
                     ElementState::Released => {
                         self.input.mouse_button_released(btn);
                     }
@@ -634,16 +1433,21 @@ impl ApplicationHandler for App {
                 // Update BEFORE clearing frame state
                 self.update(delta_time);
 
+                // Trade resolution for frame rate if needed
+                self.dynamic_resolution.update(delta_time);
+                self.apply_render_scale();
+
                 // Clear per-frame input state AFTER processing
-                self.input.begin_frame();
+                self.input.begin_frame(now);
 
-                if let Some(fps) = self.fps_counter.should_log() {
-                    info!("FPS: {fps:.1}");
-                }
-                self.fps_counter.tick();
+                self.log_frame_rate();
 
                 if let Err(e) = self.render_frame() {
                     error!("Render error: {e}");
+                    // Surface errors that reach here can't be recovered from
+                    if let Some(EngineError::SurfaceTexture(_)) = e.downcast_ref() {
+                        event_loop.exit();
+                    }
                 }
 
                 if let Some(ref window) = self.window {
@@ -671,3 +1475,236 @@ impl ApplicationHandler for App {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::player::PlayerMode;
+    use crate::world::{Axis, BlockPos, Chunk, HitFace};
+
+    /// An app with one loaded chunk, a stone block targeted, and the cursor captured.
+    fn app_targeting_stone() -> (App, BlockPos) {
+        let mut app = App::new(WindowConfig::default(), RendererConfig::default());
+        let pos = BlockPos::new(4, 64, 4);
+
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.set_block(4, 64, 4, Block::Stone);
+        app.chunk_manager.insert_chunk(chunk);
+        app.targeted_block = Some(RaycastHit {
+            block_pos: pos,
+            face: HitFace::Top,
+            distance: 2.0,
+            hit_point: Vec3::new(4.5, 65.0, 4.5),
+            face_uv: Vec2::new(0.5, 0.5),
+        });
+        app.input.set_cursor_locked(true);
+
+        (app, pos)
+    }
+
+    #[test]
+    fn center_pick_matches_the_forward_target() {
+        let (mut app, pos) = app_targeting_stone();
+        app.camera.position = Vec3::new(2.3, 66.7, 1.9);
+        app.camera.look_at(Vec3::new(4.6, 64.8, 4.4));
+        app.update_targeted_block();
+
+        let center = app.pick_center();
+        assert_eq!(center.map(|hit| hit.block_pos), Some(pos));
+        assert_eq!(
+            center.map(|hit| (hit.block_pos, hit.face)),
+            app.targeted_block.map(|hit| (hit.block_pos, hit.face))
+        );
+    }
+
+    #[test]
+    fn cursor_capturing_click_does_not_break_block() {
+        let (mut app, pos) = app_targeting_stone();
+        app.input.set_cursor_locked(false);
+
+        app.handle_mouse_pressed(MouseButton::Left);
+        // No window in tests, so lock the cursor the way capturing would
+        app.input.set_cursor_locked(true);
+        app.handle_block_interactions(0.0);
+
+        assert_eq!(
+            app.chunk_manager.get_block(pos.x, pos.y, pos.z),
+            Some(Block::Stone)
+        );
+    }
+
+    #[test]
+    fn console_setblock_edits_world() {
+        let (mut app, pos) = app_targeting_stone();
+
+        app.set_console_open(true);
+        app.input.text_entered("setblock 4 64 4 glass");
+        app.handle_console_key(KeyCode::Enter);
+
+        assert_eq!(
+            app.chunk_manager.get_block(pos.x, pos.y, pos.z),
+            Some(Block::Glass)
+        );
+        assert!(!app.console.is_open());
+        assert!(!app.input.is_capturing_text());
+    }
+
+    #[test]
+    fn spectator_clicks_do_not_modify_world() {
+        let (mut app, pos) = app_targeting_stone();
+        app.player.set_mode(PlayerMode::Spectator);
+
+        app.input.mouse_button_pressed(MouseButton::Left);
+        app.input.mouse_button_pressed(MouseButton::Right);
+        app.handle_block_interactions(0.0);
+
+        let above = pos.offset(HitFace::Top);
+        assert_eq!(
+            app.chunk_manager.get_block(pos.x, pos.y, pos.z),
+            Some(Block::Stone)
+        );
+        assert_eq!(
+            app.chunk_manager.get_block(above.x, above.y, above.z),
+            Some(Block::Air)
+        );
+        assert!(!app.player.mode().shows_crosshair());
+    }
+
+    #[test]
+    fn creative_clicks_modify_world() {
+        let (mut app, pos) = app_targeting_stone();
+        assert!(app.player.mode().shows_crosshair());
+
+        app.input.mouse_button_pressed(MouseButton::Left);
+        app.handle_block_interactions(0.0);
+
+        assert_eq!(
+            app.chunk_manager.get_block(pos.x, pos.y, pos.z),
+            Some(Block::Air)
+        );
+    }
+
+    #[test]
+    fn survival_breaks_after_holding_for_hardness() {
+        let (mut app, pos) = app_targeting_stone();
+        app.player.set_mode(PlayerMode::Survival);
+        let hardness = Block::Stone.properties().hardness;
+
+        app.input.mouse_button_pressed(MouseButton::Left);
+        app.handle_block_interactions(hardness * 0.5);
+        assert_eq!(
+            app.chunk_manager.get_block(pos.x, pos.y, pos.z),
+            Some(Block::Stone)
+        );
+        assert_eq!(app.mining.target(), Some(pos));
+
+        app.handle_block_interactions(hardness * 0.5);
+        assert_eq!(
+            app.chunk_manager.get_block(pos.x, pos.y, pos.z),
+            Some(Block::Air)
+        );
+    }
+
+    #[test]
+    fn survival_mining_restarts_on_a_new_target() {
+        let (mut app, pos) = app_targeting_stone();
+        app.player.set_mode(PlayerMode::Survival);
+        let other = BlockPos::new(5, 64, 4);
+        app.chunk_manager
+            .set_block(other.x, other.y, other.z, Block::Stone);
+        let hardness = Block::Stone.properties().hardness;
+
+        app.input.mouse_button_pressed(MouseButton::Left);
+        app.handle_block_interactions(hardness * 0.8);
+
+        // Looking at another block starts over, so 0.8 + 0.8 isn't enough
+        app.targeted_block.as_mut().unwrap().block_pos = other;
+        app.handle_block_interactions(hardness * 0.8);
+        assert_eq!(app.mining.target(), Some(other));
+        assert_eq!(
+            app.chunk_manager.get_block(other.x, other.y, other.z),
+            Some(Block::Stone)
+        );
+
+        // Letting go drops the progress too
+        app.input.mouse_button_released(MouseButton::Left);
+        app.handle_block_interactions(hardness);
+        assert_eq!(app.mining.target(), None);
+        assert_eq!(
+            app.chunk_manager.get_block(pos.x, pos.y, pos.z),
+            Some(Block::Stone)
+        );
+    }
+
+    #[test]
+    fn bedrock_never_breaks() {
+        let (mut app, pos) = app_targeting_stone();
+        app.chunk_manager
+            .set_block(pos.x, pos.y, pos.z, Block::Bedrock);
+
+        app.input.mouse_button_pressed(MouseButton::Left);
+        app.handle_block_interactions(0.0);
+        app.player.set_mode(PlayerMode::Survival);
+        app.handle_block_interactions(1000.0);
+
+        assert_eq!(
+            app.chunk_manager.get_block(pos.x, pos.y, pos.z),
+            Some(Block::Bedrock)
+        );
+    }
+
+    /// Places a log against `face` of the targeted stone, returning its orientation.
+    fn place_log_against(face: HitFace) -> Orientation {
+        let (mut app, pos) = app_targeting_stone();
+        let mut hit = app.targeted_block.unwrap();
+        hit.face = face;
+        app.selected_block = Block::Log;
+        app.place_selected_block(&hit);
+
+        let placed = pos.offset(face);
+        assert_eq!(
+            app.chunk_manager.get_block(placed.x, placed.y, placed.z),
+            Some(Block::Log)
+        );
+        let metadata = app
+            .chunk_manager
+            .get_metadata(placed.x, placed.y, placed.z)
+            .unwrap();
+        Orientation::from_metadata(Block::Log, metadata)
+    }
+
+    #[test]
+    fn placing_replaces_only_air_and_liquids() {
+        let (mut app, pos) = app_targeting_stone();
+        let hit = app.targeted_block.unwrap();
+        let above = pos.offset(HitFace::Top);
+        app.selected_block = Block::Planks;
+
+        app.chunk_manager
+            .set_block(above.x, above.y, above.z, Block::Water);
+        app.place_selected_block(&hit);
+        assert_eq!(
+            app.chunk_manager.get_block(above.x, above.y, above.z),
+            Some(Block::Planks)
+        );
+
+        // A block already in the way stays put
+        app.chunk_manager
+            .set_block(above.x, above.y, above.z, Block::Glass);
+        app.place_selected_block(&hit);
+        assert_eq!(
+            app.chunk_manager.get_block(above.x, above.y, above.z),
+            Some(Block::Glass)
+        );
+    }
+
+    #[test]
+    fn placed_logs_align_with_clicked_face() {
+        assert_eq!(place_log_against(HitFace::Top), Orientation::Axis(Axis::Y));
+        assert_eq!(place_log_against(HitFace::East), Orientation::Axis(Axis::X));
+        assert_eq!(
+            place_log_against(HitFace::North),
+            Orientation::Axis(Axis::Z)
+        );
+    }
+}
@@ -1,181 +1,105 @@
 //! Camera system module.
 //!
 //! Provides first-person camera with mouse look and movement,
-//! plus frustum culling for efficient rendering.
-
-use glam::{Mat4, Vec3, Vec4};
-
-/// Axis-aligned bounding box for frustum testing.
-#[derive(Debug, Clone, Copy)]
-pub struct Aabb {
-    /// Minimum corner (lowest x, y, z).
-    pub min: Vec3,
-    /// Maximum corner (highest x, y, z).
-    pub max: Vec3,
+//! an optional third-person view orbiting the player,
+//! plus its view frustum for efficient rendering.
+
+use glam::{Mat4, Vec2, Vec3};
+
+use crate::math::Frustum;
+use crate::world::raycast;
+
+/// Maximum pitch in degrees, just short of straight up/down to prevent flipping.
+pub const MAX_PITCH: f32 = 89.0;
+
+/// Maximum zoom factor (the field of view divided by eight).
+pub const MAX_ZOOM: f32 = 8.0;
+
+/// Distance in blocks a third-person camera orbits behind the player.
+pub const DEFAULT_ORBIT_DISTANCE: f32 = 4.0;
+
+/// How far in front of a wall a third-person camera stops, so the near
+/// plane doesn't clip into it.
+const ORBIT_WALL_MARGIN: f32 = 0.2;
+
+/// Where the camera views the world from.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum CameraMode {
+    /// From the player's eyes.
+    #[default]
+    FirstPerson,
+    /// From behind the player, orbiting them with the look direction.
+    ThirdPerson {
+        /// Orbit distance in blocks, shortened when a wall is in the way.
+        distance: f32,
+    },
 }
 
-impl Aabb {
-    /// Creates a new AABB from min and max corners.
-    #[must_use]
-    pub const fn new(min: Vec3, max: Vec3) -> Self {
-        Self { min, max }
-    }
-
-    /// Creates an AABB for a chunk at the given position.
-    /// Chunks are 16x256x16 blocks.
+impl CameraMode {
+    /// Switches between first person and third person at
+    /// [`DEFAULT_ORBIT_DISTANCE`].
     #[must_use]
-    pub fn from_chunk(chunk_x: i32, chunk_z: i32) -> Self {
-        let min = Vec3::new((chunk_x * 16) as f32, 0.0, (chunk_z * 16) as f32);
-        let max = Vec3::new(
-            (chunk_x * 16 + 16) as f32,
-            256.0,
-            (chunk_z * 16 + 16) as f32,
-        );
-        Self { min, max }
-    }
-
-    /// Returns the center point of the AABB.
-    #[must_use]
-    pub fn center(&self) -> Vec3 {
-        (self.min + self.max) * 0.5
-    }
-
-    /// Returns the positive vertex relative to a plane normal.
-    /// (The corner furthest in the direction of the normal.)
-    #[must_use]
-    fn positive_vertex(&self, normal: Vec3) -> Vec3 {
-        Vec3::new(
-            if normal.x >= 0.0 {
-                self.max.x
-            } else {
-                self.min.x
-            },
-            if normal.y >= 0.0 {
-                self.max.y
-            } else {
-                self.min.y
-            },
-            if normal.z >= 0.0 {
-                self.max.z
-            } else {
-                self.min.z
-            },
-        )
-    }
-
-    /// Returns the negative vertex relative to a plane normal.
-    /// (The corner closest in the direction of the normal.)
-    #[must_use]
-    #[allow(dead_code)] // Part of complete AABB algorithm, may be used for containment tests
-    fn negative_vertex(&self, normal: Vec3) -> Vec3 {
-        Vec3::new(
-            if normal.x >= 0.0 {
-                self.min.x
-            } else {
-                self.max.x
-            },
-            if normal.y >= 0.0 {
-                self.min.y
-            } else {
-                self.max.y
+    pub const fn toggled(self) -> Self {
+        match self {
+            Self::FirstPerson => Self::ThirdPerson {
+                distance: DEFAULT_ORBIT_DISTANCE,
             },
-            if normal.z >= 0.0 {
-                self.min.z
-            } else {
-                self.max.z
-            },
-        )
+            Self::ThirdPerson { .. } => Self::FirstPerson,
+        }
     }
 }
 
-/// A plane in 3D space, represented as ax + by + cz + d = 0.
-#[derive(Debug, Clone, Copy)]
-pub struct Plane {
-    /// Normal vector (a, b, c).
-    pub normal: Vec3,
-    /// Distance from origin (d).
-    pub distance: f32,
+/// How the camera projects the world onto the screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProjectionMode {
+    /// Distant things look smaller, as the eye sees them.
+    Perspective {
+        /// Vertical field of view in degrees.
+        fov: f32,
+    },
+    /// Things keep their size at any distance, for map and isometric views.
+    Orthographic {
+        /// Height of the view in blocks; the width follows the aspect ratio.
+        height: f32,
+    },
 }
 
-impl Plane {
-    /// Creates a plane from a Vec4 (normal.xyz, distance.w).
+impl ProjectionMode {
+    /// Returns the field of view in degrees, or the view height in blocks.
     #[must_use]
-    pub fn from_vec4(v: Vec4) -> Self {
-        let normal = Vec3::new(v.x, v.y, v.z);
-        let length = normal.length();
-        Self {
-            normal: normal / length,
-            distance: v.w / length,
+    pub const fn size(self) -> f32 {
+        match self {
+            Self::Perspective { fov } => fov,
+            Self::Orthographic { height } => height,
         }
     }
 
-    /// Returns the signed distance from a point to the plane.
-    /// Positive = in front, Negative = behind.
-    #[must_use]
-    pub fn distance_to_point(&self, point: Vec3) -> f32 {
-        self.normal.dot(point) + self.distance
-    }
-}
-
-/// View frustum for culling - 6 planes extracted from view-projection matrix.
-#[derive(Debug, Clone)]
-pub struct Frustum {
-    /// The 6 frustum planes: left, right, bottom, top, near, far.
-    planes: [Plane; 6],
-}
-
-impl Frustum {
-    /// Extracts frustum planes from a view-projection matrix.
+    /// Returns this projection magnified by `zoom`, narrowing the field of
+    /// view or shrinking the view height.
     #[must_use]
-    pub fn from_view_projection(vp: Mat4) -> Self {
-        // Extract planes using Gribb/Hartmann method
-        // Each row of the matrix gives us plane coefficients
-        let row0 = vp.row(0);
-        let row1 = vp.row(1);
-        let row2 = vp.row(2);
-        let row3 = vp.row(3);
-
-        let planes = [
-            Plane::from_vec4(row3 + row0), // Left
-            Plane::from_vec4(row3 - row0), // Right
-            Plane::from_vec4(row3 + row1), // Bottom
-            Plane::from_vec4(row3 - row1), // Top
-            Plane::from_vec4(row3 + row2), // Near
-            Plane::from_vec4(row3 - row2), // Far
-        ];
-
-        Self { planes }
-    }
-
-    /// Tests if an AABB intersects or is inside the frustum.
-    /// Returns true if visible, false if completely outside.
-    #[must_use]
-    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
-        for plane in &self.planes {
-            // Get the positive vertex (furthest in normal direction)
-            let p_vertex = aabb.positive_vertex(plane.normal);
-
-            // If positive vertex is behind the plane, AABB is completely outside
-            if plane.distance_to_point(p_vertex) < 0.0 {
-                return false;
-            }
+    pub fn zoomed(self, zoom: f32) -> Self {
+        match self {
+            Self::Perspective { fov } => Self::Perspective { fov: fov / zoom },
+            Self::Orthographic { height } => Self::Orthographic {
+                height: height / zoom,
+            },
         }
-        true
     }
+}
 
-    /// Tests if a chunk at the given position is visible.
-    #[must_use]
-    pub fn is_chunk_visible(&self, chunk_x: i32, chunk_z: i32) -> bool {
-        let aabb = Aabb::from_chunk(chunk_x, chunk_z);
-        self.intersects_aabb(&aabb)
+impl Default for ProjectionMode {
+    fn default() -> Self {
+        Self::Perspective { fov: 70.0 }
     }
 }
 
 /// Camera configuration options.
 #[derive(Debug, Clone)]
 pub struct CameraConfig {
-    /// Field of view in degrees.
-    pub fov_degrees: f32,
+    /// Projection onto the screen.
+    pub projection: ProjectionMode,
+    /// First or third person view.
+    pub mode: CameraMode,
     /// Near clipping plane distance.
     pub near: f32,
     /// Far clipping plane distance.
@@ -193,7 +117,8 @@ pub struct CameraConfig {
 impl Default for CameraConfig {
     fn default() -> Self {
         Self {
-            fov_degrees: 70.0,
+            projection: ProjectionMode::default(),
+            mode: CameraMode::default(),
             near: 0.1,
             far: 1000.0,
             sensitivity: 0.1,
@@ -209,14 +134,22 @@ impl Default for CameraConfig {
 pub struct Camera {
     /// Camera position in world space.
     pub position: Vec3,
-    /// Yaw rotation in degrees (horizontal look).
+    /// Yaw rotation in degrees (horizontal look), in `[0, 360)`.
+    ///
+    /// Prefer [`set_orientation`](Self::set_orientation) over writing this
+    /// directly, so the range is kept.
     pub yaw: f32,
-    /// Pitch rotation in degrees (vertical look).
+    /// Pitch rotation in degrees (vertical look), within `±MAX_PITCH`.
     pub pitch: f32,
     /// Camera configuration.
     config: CameraConfig,
     /// Cached aspect ratio.
     aspect_ratio: f32,
+    /// Zoom factor dividing the configured field of view, 1 for none.
+    zoom: f32,
+    /// How far behind `position` the view is, after pulling in from walls;
+    /// 0 in first person.
+    orbit_distance: f32,
 }
 
 impl Default for Camera {
@@ -231,10 +164,12 @@ impl Camera {
     pub fn new(config: CameraConfig) -> Self {
         Self {
             position: Vec3::new(0.0, 0.0, 3.0),
-            yaw: -90.0, // Face negative Z by default
+            yaw: 270.0, // Face negative Z by default
             pitch: 0.0,
             config,
             aspect_ratio: 16.0 / 9.0,
+            zoom: 1.0,
+            orbit_distance: 0.0,
         }
     }
 
@@ -252,30 +187,70 @@ impl Camera {
         }
     }
 
-    /// Updates the camera rotation based on mouse delta movement.
-    pub fn rotate(&mut self, delta_x: f32, delta_y: f32) {
-        self.yaw += delta_x * self.config.sensitivity;
-        self.pitch -= delta_y * self.config.sensitivity;
+    /// Creates a camera with the specified orientation (e.g. restored from a save).
+    #[must_use]
+    pub fn with_orientation(mut self, yaw: f32, pitch: f32) -> Self {
+        self.set_orientation(yaw, pitch);
+        self
+    }
+
+    /// Sets the look direction, wrapping yaw into `[0, 360)` and clamping
+    /// pitch to `±MAX_PITCH`.
+    ///
+    /// All orientation changes go through here so yaw and pitch always stay
+    /// in range, however they were produced.
+    pub fn set_orientation(&mut self, yaw: f32, pitch: f32) {
+        // rem_euclid can round up to exactly 360 for tiny negative inputs
+        let yaw = yaw.rem_euclid(360.0);
+        self.yaw = if yaw >= 360.0 { 0.0 } else { yaw };
+        self.pitch = pitch.clamp(-MAX_PITCH, MAX_PITCH);
+    }
 
-        // Clamp pitch to prevent camera flipping
-        self.pitch = self.pitch.clamp(-89.0, 89.0);
+    /// Turns the camera to look at a point in world space.
+    ///
+    /// Does nothing if the target is the camera position.
+    pub fn look_at(&mut self, target: Vec3) {
+        let direction = (target - self.position).normalize_or_zero();
+        if direction == Vec3::ZERO {
+            return;
+        }
+        let yaw = direction.z.atan2(direction.x).to_degrees();
+        let pitch = direction.y.asin().to_degrees();
+        self.set_orientation(yaw, pitch);
+    }
 
-        // Normalize yaw to 0-360 range
-        self.yaw = self.yaw.rem_euclid(360.0);
+    /// Returns the zoom factor (1 when not zoomed).
+    #[must_use]
+    pub const fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Sets the zoom factor, clamped to `1..=MAX_ZOOM`.
+    pub const fn set_zoom(&mut self, zoom: f32) {
+        self.zoom = zoom.clamp(1.0, MAX_ZOOM);
+    }
+
+    /// Returns the configured projection after zooming.
+    #[must_use]
+    pub fn effective_projection(&self) -> ProjectionMode {
+        self.config.projection.zoomed(self.zoom)
+    }
+
+    /// Updates the camera rotation based on mouse delta movement.
+    ///
+    /// Sensitivity drops while zoomed so aiming stays steady.
+    pub fn rotate(&mut self, delta_x: f32, delta_y: f32) {
+        let sensitivity = self.config.sensitivity / self.zoom;
+        self.set_orientation(
+            delta_x.mul_add(sensitivity, self.yaw),
+            delta_y.mul_add(-sensitivity, self.pitch),
+        );
     }
 
     /// Returns the forward direction vector (where the camera is looking).
     #[must_use]
     pub fn forward(&self) -> Vec3 {
-        let yaw_rad = self.yaw.to_radians();
-        let pitch_rad = self.pitch.to_radians();
-
-        Vec3::new(
-            yaw_rad.cos() * pitch_rad.cos(),
-            pitch_rad.sin(),
-            yaw_rad.sin() * pitch_rad.cos(),
-        )
-        .normalize()
+        look_direction(self.yaw, self.pitch)
     }
 
     /// Returns the right direction vector.
@@ -327,21 +302,62 @@ impl Camera {
         self.position += Vec3::Y * velocity.y; // Up/down (Space/Shift)
     }
 
+    /// Moves the camera to `focus`, the player's eye, and in third person
+    /// works out how far behind it the view can orbit. Call every frame.
+    ///
+    /// The orbit is pulled in front of any block `is_solid` reports between
+    /// the focus and the view, so walls don't hide the player.
+    pub fn set_focus<F>(&mut self, focus: Vec3, is_solid: F)
+    where
+        F: Fn(i32, i32, i32) -> bool,
+    {
+        self.position = focus;
+        self.orbit_distance = match self.config.mode {
+            CameraMode::FirstPerson => 0.0,
+            CameraMode::ThirdPerson { distance } => {
+                raycast(focus, -self.forward(), distance, is_solid)
+                    .map_or(distance, |hit| (hit.distance - ORBIT_WALL_MARGIN).max(0.0))
+            }
+        };
+    }
+
+    /// Returns where the view is rendered from: `position` in first person,
+    /// or the orbit position behind it in third person.
+    #[must_use]
+    pub fn eye_position(&self) -> Vec3 {
+        orbit_position(self.position, self.yaw, self.pitch, self.orbit_distance)
+    }
+
     /// Returns the view matrix for rendering.
+    ///
+    /// In third person this looks from the orbit position toward the focus.
     #[must_use]
     pub fn view_matrix(&self) -> Mat4 {
-        Mat4::look_at_rh(self.position, self.position + self.forward(), Vec3::Y)
+        let eye = self.eye_position();
+        Mat4::look_at_rh(eye, eye + self.forward(), Vec3::Y)
     }
 
     /// Returns the projection matrix for rendering.
     #[must_use]
     pub fn projection_matrix(&self) -> Mat4 {
-        Mat4::perspective_rh(
-            self.config.fov_degrees.to_radians(),
-            self.aspect_ratio,
-            self.config.near,
-            self.config.far,
-        )
+        let CameraConfig { near, far, .. } = self.config;
+        match self.effective_projection() {
+            ProjectionMode::Perspective { fov } => {
+                Mat4::perspective_rh(fov.to_radians(), self.aspect_ratio, near, far)
+            }
+            ProjectionMode::Orthographic { height } => {
+                let half_height = height * 0.5;
+                let half_width = half_height * self.aspect_ratio;
+                Mat4::orthographic_rh(
+                    -half_width,
+                    half_width,
+                    -half_height,
+                    half_height,
+                    near,
+                    far,
+                )
+            }
+        }
     }
 
     /// Returns the combined view-projection matrix.
@@ -350,6 +366,24 @@ impl Camera {
         self.projection_matrix() * self.view_matrix()
     }
 
+    /// Builds a world-space ray through a point on the screen.
+    ///
+    /// `ndc` is in normalized device coordinates (-1 to 1, +Y up), so
+    /// `Vec2::ZERO` is the exact screen center.
+    ///
+    /// Returns `(origin, direction)` where origin lies on the near plane and
+    /// direction is normalized.
+    #[must_use]
+    pub fn screen_to_world_ray(&self, ndc: Vec2) -> (Vec3, Vec3) {
+        let inverse = self.view_projection_matrix().inverse();
+
+        // wgpu clip space depth runs from 0 (near) to 1 (far)
+        let near = inverse.project_point3(Vec3::new(ndc.x, ndc.y, 0.0));
+        let far = inverse.project_point3(Vec3::new(ndc.x, ndc.y, 1.0));
+
+        (near, (far - near).normalize())
+    }
+
     /// Returns a reference to the camera configuration.
     #[must_use]
     pub const fn config(&self) -> &CameraConfig {
@@ -368,9 +402,32 @@ impl Camera {
     }
 }
 
+/// Returns the unit direction of a look along `yaw` and `pitch`, in degrees.
+fn look_direction(yaw: f32, pitch: f32) -> Vec3 {
+    let yaw_rad = yaw.to_radians();
+    let pitch_rad = pitch.to_radians();
+
+    Vec3::new(
+        yaw_rad.cos() * pitch_rad.cos(),
+        pitch_rad.sin(),
+        yaw_rad.sin() * pitch_rad.cos(),
+    )
+    .normalize()
+}
+
+/// Returns the point `distance` blocks behind `focus` when looking along
+/// `yaw` and `pitch` (in degrees), so the focus is centered in view.
+#[must_use]
+pub fn orbit_position(focus: Vec3, yaw: f32, pitch: f32, distance: f32) -> Vec3 {
+    focus - look_direction(yaw, pitch) * distance
+}
+
 #[cfg(test)]
 mod tests {
+    use glam::Vec4;
+
     use super::*;
+    use crate::world::CHUNK_HEIGHT;
 
     const EPSILON: f32 = 0.0001;
 
@@ -397,7 +454,7 @@ mod tests {
     #[test]
     fn camera_forward_default() {
         let camera = Camera::default();
-        // With yaw=-90 and pitch=0, should face -Z
+        // With yaw=270 and pitch=0, should face -Z
         let forward = camera.forward();
         assert!(approx_eq(forward.x, 0.0));
         assert!(approx_eq(forward.y, 0.0));
@@ -416,6 +473,42 @@ mod tests {
         assert!(camera.pitch >= -89.0 && camera.pitch <= 89.0);
     }
 
+    #[test]
+    fn set_orientation_normalizes_restored_values() {
+        let camera = Camera::default().with_orientation(450.0, 100.0);
+        assert!(approx_eq(camera.yaw, 90.0));
+        assert!(approx_eq(camera.pitch, MAX_PITCH));
+
+        let camera = Camera::default().with_orientation(-90.0, -100.0);
+        assert!(approx_eq(camera.yaw, 270.0));
+        assert!(approx_eq(camera.pitch, -MAX_PITCH));
+
+        // Tiny negative yaw must not round up to 360
+        let camera = Camera::default().with_orientation(-1e-6, 0.0);
+        assert!(camera.yaw >= 0.0 && camera.yaw < 360.0);
+    }
+
+    #[test]
+    fn rotate_keeps_orientation_in_range() {
+        let mut camera = Camera::default();
+        for _ in 0..100 {
+            camera.rotate(-500.0, 300.0);
+            assert!(camera.yaw >= 0.0 && camera.yaw < 360.0);
+            assert!(camera.pitch.abs() <= MAX_PITCH);
+        }
+    }
+
+    #[test]
+    fn look_at_faces_target() {
+        let mut camera = Camera::default().at_position(Vec3::new(1.0, 2.0, 3.0));
+        let target = Vec3::new(-4.0, 5.0, 0.0);
+        camera.look_at(target);
+
+        let expected = (target - camera.position).normalize();
+        assert!(vec3_approx_eq(camera.forward(), expected));
+        assert!(camera.yaw >= 0.0 && camera.yaw < 360.0);
+    }
+
     #[test]
     fn camera_right_perpendicular_to_forward() {
         let camera = Camera::default();
@@ -512,32 +605,170 @@ mod tests {
     }
 
     #[test]
-    fn aabb_from_chunk() {
-        let aabb = Aabb::from_chunk(1, 2);
-        assert!(vec3_approx_eq(aabb.min, Vec3::new(16.0, 0.0, 32.0)));
-        assert!(vec3_approx_eq(aabb.max, Vec3::new(32.0, 256.0, 48.0)));
+    fn frustum_tests_chunks_at_the_world_height() {
+        // High above a short world, looking straight ahead
+        let camera = Camera::default().at_position(Vec3::new(8.0, 200.0, 50.0));
+        let frustum = camera.frustum();
+        assert_eq!(frustum.world_height(), CHUNK_HEIGHT);
+        assert!(frustum.is_chunk_visible(0, 0));
+
+        let short = frustum.with_world_height(32);
+        assert!(!short.is_chunk_visible(0, 0));
+        assert!(short.with_world_height(512).is_chunk_visible(0, 0));
     }
 
     #[test]
-    fn aabb_center() {
-        let aabb = Aabb::new(Vec3::ZERO, Vec3::new(10.0, 10.0, 10.0));
-        assert!(vec3_approx_eq(aabb.center(), Vec3::new(5.0, 5.0, 5.0)));
+    fn frustum_sphere_in_front_is_visible() {
+        let camera = Camera::default().at_position(Vec3::new(0.0, 64.0, 0.0));
+        let frustum = camera.frustum();
+
+        assert!(frustum.intersects_sphere(Vec3::new(0.0, 64.0, -20.0), 1.0));
+        // Centered behind the camera, but big enough to reach in front
+        assert!(frustum.intersects_sphere(Vec3::new(0.0, 64.0, 5.0), 10.0));
     }
 
     #[test]
-    fn plane_distance_to_point() {
-        // Plane at Z=5, facing +Z
-        let plane = Plane {
-            normal: Vec3::Z,
-            distance: -5.0,
+    fn frustum_sphere_behind_is_not_visible() {
+        let camera = Camera::default().at_position(Vec3::new(0.0, 64.0, 0.0));
+        let frustum = camera.frustum();
+
+        assert!(!frustum.intersects_sphere(Vec3::new(0.0, 64.0, 20.0), 1.0));
+    }
+
+    #[test]
+    fn frustum_sphere_to_side_is_not_visible() {
+        let camera = Camera::default().at_position(Vec3::new(0.0, 64.0, 0.0));
+        let frustum = camera.frustum();
+
+        assert!(!frustum.intersects_sphere(Vec3::new(100.0, 64.0, -10.0), 5.0));
+    }
+
+    #[test]
+    fn screen_center_ray_matches_forward() {
+        let mut camera = Camera::default().at_position(Vec3::new(8.5, 70.0, 8.5));
+        camera.rotate(137.0, 212.0);
+
+        let (origin, direction) = camera.screen_to_world_ray(Vec2::ZERO);
+
+        assert!(vec3_approx_eq(direction, camera.forward()));
+        // Origin sits on the near plane, directly ahead of the camera
+        let expected_origin = camera.position + camera.forward() * camera.config.near;
+        assert!((origin - expected_origin).length() < 0.001);
+    }
+
+    #[test]
+    fn zoom_divides_fov_and_is_clamped() {
+        let mut camera = Camera::default();
+        camera.set_zoom(2.0);
+        assert!(approx_eq(camera.effective_projection().size(), 35.0));
+
+        camera.set_zoom(0.5);
+        assert!(approx_eq(camera.zoom(), 1.0));
+        camera.set_zoom(100.0);
+        assert!(approx_eq(camera.zoom(), MAX_ZOOM));
+    }
+
+    fn orthographic_camera() -> Camera {
+        let config = CameraConfig {
+            projection: ProjectionMode::Orthographic { height: 32.0 },
+            ..Default::default()
         };
+        Camera::new(config).at_position(Vec3::new(8.0, 64.0, 8.0))
+    }
 
-        // Point at Z=10 is 5 units in front
-        let dist = plane.distance_to_point(Vec3::new(0.0, 0.0, 10.0));
-        assert!(approx_eq(dist, 5.0));
+    #[test]
+    fn orthographic_projection_has_no_perspective_divide() {
+        let mut camera = orthographic_camera();
+        camera.set_aspect_ratio(2.0, 1.0);
+        let projection = camera.projection_matrix();
+
+        let inverse = projection.inverse();
+        assert!(inverse.is_finite());
+        assert!((inverse * projection).abs_diff_eq(Mat4::IDENTITY, EPSILON));
+
+        // The same offset lands at the same spot on screen at any depth
+        for depth in [1.0, 10.0, 500.0] {
+            let clip = projection * Vec4::new(16.0, 8.0, -depth, 1.0);
+            assert!(approx_eq(clip.w, 1.0));
+            assert!(approx_eq(clip.x, 0.5));
+            assert!(approx_eq(clip.y, 0.5));
+        }
+    }
+
+    #[test]
+    fn orthographic_frustum_culls_behind() {
+        let camera = orthographic_camera();
+        let frustum = camera.frustum();
 
-        // Point at Z=0 is 5 units behind
-        let dist = plane.distance_to_point(Vec3::new(0.0, 0.0, 0.0));
-        assert!(approx_eq(dist, -5.0));
+        assert!(frustum.is_chunk_visible(0, -3));
+        assert!(!frustum.is_chunk_visible(0, 3));
+        // Outside the view's width, however near
+        assert!(!frustum.is_chunk_visible(4, -1));
+    }
+
+    #[test]
+    fn orbit_position_is_behind_the_focus() {
+        let focus = Vec3::new(10.0, 64.0, -5.0);
+
+        // Looking toward -Z puts the camera on the +Z side
+        let orbit = orbit_position(focus, 270.0, 0.0, 4.0);
+        assert!(vec3_approx_eq(orbit, Vec3::new(10.0, 64.0, -1.0)));
+
+        // Looking 45° down toward +X puts it up and to the -X side
+        let orbit = orbit_position(focus, 0.0, -45.0, 2.0_f32.sqrt());
+        assert!(vec3_approx_eq(orbit, Vec3::new(9.0, 65.0, -5.0)));
+
+        assert!(vec3_approx_eq(
+            orbit_position(focus, 123.0, 45.0, 0.0),
+            focus
+        ));
+    }
+
+    #[test]
+    fn third_person_view_looks_at_the_focus() {
+        let mut camera = Camera::new(CameraConfig {
+            mode: CameraMode::ThirdPerson { distance: 4.0 },
+            ..Default::default()
+        })
+        .with_orientation(30.0, -20.0);
+        let focus = Vec3::new(8.0, 70.0, 8.0);
+        camera.set_focus(focus, |_, _, _| false);
+
+        assert!(approx_eq(camera.eye_position().distance(focus), 4.0));
+        // The focus lands in the middle of the screen
+        let center = camera.view_projection_matrix().project_point3(focus);
+        assert!(approx_eq(center.x, 0.0) && approx_eq(center.y, 0.0));
+
+        // First person views from the focus itself
+        camera.config_mut().mode = CameraMode::FirstPerson;
+        camera.set_focus(focus, |_, _, _| false);
+        assert!(vec3_approx_eq(camera.eye_position(), focus));
+    }
+
+    #[test]
+    fn third_person_orbit_is_pulled_in_by_walls() {
+        let mut camera = Camera::new(CameraConfig {
+            mode: CameraMode::ThirdPerson { distance: 4.0 },
+            ..Default::default()
+        });
+        // Looking toward -Z with a wall two blocks behind, at z = 10
+        let focus = Vec3::new(0.5, 64.5, 8.5);
+        camera.set_focus(focus, |_, _, z| z == 10);
+
+        let eye = camera.eye_position();
+        assert!(eye.z < 10.0);
+        assert!(approx_eq(eye.z, 10.0 - ORBIT_WALL_MARGIN));
+    }
+
+    #[test]
+    fn camera_mode_toggles() {
+        let third = CameraMode::FirstPerson.toggled();
+        assert_eq!(
+            third,
+            CameraMode::ThirdPerson {
+                distance: DEFAULT_ORBIT_DISTANCE
+            }
+        );
+        assert_eq!(third.toggled(), CameraMode::FirstPerson);
     }
 }
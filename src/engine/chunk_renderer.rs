@@ -2,12 +2,51 @@
 //!
 //! Handles GPU resources and rendering for voxel chunks.
 
-use anyhow::Result;
 use bytemuck::{Pod, Zeroable};
 use glam::Mat4;
 use wgpu::util::DeviceExt;
 
-use crate::world::{ChunkMesh, ChunkVertex, TextureAtlas};
+use super::error::EngineError;
+use super::renderer::create_shader;
+use super::shadow::{ShadowBindings, ShadowMap, ShadowUniform, light_view_projection};
+use super::sky::{SkyUniform, SunLight, SunUniform};
+use crate::math::{Aabb, Frustum};
+use crate::world::texture_atlas::MIP_LEVELS;
+use crate::world::{
+    ChunkMesh, ChunkPos, ChunkVertex, ColorOverrides, DEFAULT_ATLAS_SEED, TextureAtlas,
+};
+
+/// How many chunks a render call drew, and how many it skipped as outside
+/// the view frustum.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CullStats {
+    /// Chunks inside the frustum.
+    pub drawn: usize,
+    /// Chunks outside the frustum.
+    pub culled: usize,
+}
+
+/// Returns the chunks whose bounds intersect `frustum`, in the order given,
+/// along with how many were kept and skipped.
+pub fn cull_chunks<'a, T, I>(frustum: &Frustum, chunks: I) -> (Vec<&'a T>, CullStats)
+where
+    I: IntoIterator<Item = (&'a ChunkPos, &'a T)>,
+{
+    let mut stats = CullStats::default();
+    let visible = chunks
+        .into_iter()
+        .filter_map(|(pos, chunk)| {
+            if frustum.is_chunk_visible(pos.x, pos.z) {
+                stats.drawn += 1;
+                Some(chunk)
+            } else {
+                stats.culled += 1;
+                None
+            }
+        })
+        .collect();
+    (visible, stats)
+}
 
 /// Camera uniform data sent to the GPU.
 #[repr(C)]
@@ -17,8 +56,14 @@ pub struct CameraUniform {
     pub view_proj: [[f32; 4]; 4],
     /// Camera position in world space.
     pub view_pos: [f32; 3],
-    /// Padding for alignment.
-    pub _padding: f32,
+    /// Seconds of animation time, wrapped at
+    /// [`ANIMATION_LOOP_SECONDS`](crate::world::ANIMATION_LOOP_SECONDS);
+    /// also pads `view_pos` to 16 bytes.
+    pub time: f32,
+    /// Sky gradient and fog range, so fog fades into the sky behind terrain.
+    pub sky: SkyUniform,
+    /// Directional light (sun or moon).
+    pub sun: SunUniform,
 }
 
 impl CameraUniform {
@@ -28,9 +73,32 @@ impl CameraUniform {
         Self {
             view_proj: view_proj.to_cols_array_2d(),
             view_pos: view_pos.to_array(),
-            _padding: 0.0,
+            time: 0.0,
+            sky: SkyUniform::default(),
+            sun: SunLight::default().uniform(),
         }
     }
+
+    /// Sets the sky gradient and fog range.
+    #[must_use]
+    pub const fn with_sky(mut self, sky: SkyUniform) -> Self {
+        self.sky = sky;
+        self
+    }
+
+    /// Sets the animation time, stepping animated textures.
+    #[must_use]
+    pub const fn with_time(mut self, time: f32) -> Self {
+        self.time = time;
+        self
+    }
+
+    /// Sets the directional light.
+    #[must_use]
+    pub const fn with_sun(mut self, sun: SunUniform) -> Self {
+        self.sun = sun;
+        self
+    }
 }
 
 impl Default for CameraUniform {
@@ -47,10 +115,31 @@ pub struct ChunkBuffers {
     pub index_buffer: wgpu::Buffer,
     /// Number of indices.
     pub index_count: u32,
+    /// Width of the indices in `index_buffer`.
+    pub index_format: wgpu::IndexFormat,
+    /// Level of detail the mesh was built at, 1 for full detail.
+    pub lod: usize,
+}
+
+/// Returns the narrowest index format that can address `vertex_count`
+/// vertices: 16-bit for most chunks, halving their index memory, and
+/// 32-bit for the rare huge mesh.
+#[must_use]
+pub fn index_format_for(vertex_count: usize) -> wgpu::IndexFormat {
+    if u16::try_from(vertex_count).is_ok() {
+        wgpu::IndexFormat::Uint16
+    } else {
+        wgpu::IndexFormat::Uint32
+    }
 }
 
 impl ChunkBuffers {
-    /// Creates GPU buffers from a chunk mesh.
+    /// Creates GPU buffers from a chunk mesh, with 16-bit indices when the
+    /// mesh is small enough.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index points past the mesh's vertices.
     #[must_use]
     pub fn from_mesh(device: &wgpu::Device, mesh: &ChunkMesh) -> Self {
         let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -59,28 +148,84 @@ impl ChunkBuffers {
             usage: wgpu::BufferUsages::VERTEX,
         });
 
+        let index_format = index_format_for(mesh.vertices.len());
+        let narrow_indices: Vec<u16>;
+        let contents = match index_format {
+            wgpu::IndexFormat::Uint16 => {
+                // Every index is below the vertex count, so fits in 16 bits
+                narrow_indices = mesh
+                    .indices
+                    .iter()
+                    .map(|&index| u16::try_from(index).expect("index exceeds the vertex count"))
+                    .collect();
+                bytemuck::cast_slice(&narrow_indices)
+            }
+            wgpu::IndexFormat::Uint32 => bytemuck::cast_slice(&mesh.indices),
+        };
         let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Chunk Index Buffer"),
-            contents: bytemuck::cast_slice(&mesh.indices),
+            contents,
             usage: wgpu::BufferUsages::INDEX,
         });
 
+        #[allow(clippy::cast_possible_truncation)]
+        let index_count = mesh.indices.len() as u32;
+
         Self {
             vertex_buffer,
             index_buffer,
-            index_count: mesh.indices.len() as u32,
+            index_count,
+            index_format,
+            lod: 1,
+        }
+    }
+
+    /// Tags the buffers with the level of detail their mesh was built at.
+    #[must_use]
+    pub const fn with_lod(mut self, lod: usize) -> Self {
+        self.lod = lod;
+        self
+    }
+}
+
+/// Which chunk render pass a pipeline is built for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PassKind {
+    /// Opaque and cutout geometry.
+    Opaque,
+    /// Translucent geometry (glass, water).
+    Transparent,
+    /// Opaque geometry drawn as triangle outlines, for inspecting meshes.
+    Wireframe,
+}
+
+impl PassKind {
+    /// Returns how the pass rasterizes triangles.
+    const fn polygon_mode(self) -> wgpu::PolygonMode {
+        match self {
+            Self::Opaque | Self::Transparent => wgpu::PolygonMode::Fill,
+            Self::Wireframe => wgpu::PolygonMode::Line,
         }
     }
 }
 
 /// Handles chunk rendering with a dedicated pipeline.
 pub struct ChunkRenderer {
-    /// The render pipeline.
+    /// The render pipeline for opaque geometry.
     pipeline: wgpu::RenderPipeline,
+    /// The render pipeline for translucent geometry.
+    transparent_pipeline: wgpu::RenderPipeline,
+    /// The render pipeline drawing opaque geometry as lines, if the device
+    /// supports [`wgpu::Features::POLYGON_MODE_LINE`].
+    wireframe_pipeline: Option<wgpu::RenderPipeline>,
+    /// Whether opaque geometry is drawn as lines.
+    wireframe: bool,
     /// Camera uniform buffer.
     camera_buffer: wgpu::Buffer,
     /// Camera bind group.
     camera_bind_group: wgpu::BindGroup,
+    /// Block texture atlas.
+    atlas_texture: wgpu::Texture,
     /// Texture bind group.
     texture_bind_group: wgpu::BindGroup,
     /// Depth texture.
@@ -89,6 +234,14 @@ pub struct ChunkRenderer {
     depth_view: wgpu::TextureView,
     /// Current size.
     size: (u32, u32),
+    /// Directional light applied on every camera update.
+    sun: SunUniform,
+    /// Shadow map sampling resources for the block shader.
+    shadow_bindings: ShadowBindings,
+    /// Shadow map, when shadows are enabled.
+    shadow_map: Option<ShadowMap>,
+    /// Shadow projection applied on every camera update.
+    shadow_uniform: ShadowUniform,
 }
 
 impl ChunkRenderer {
@@ -96,19 +249,16 @@ impl ChunkRenderer {
     ///
     /// # Errors
     ///
-    /// Returns an error if pipeline creation fails.
+    /// Returns [`EngineError::Shader`] if the block shader is invalid.
     pub fn new(
         device: &wgpu::Device,
         queue: &wgpu::Queue,
         surface_format: wgpu::TextureFormat,
         width: u32,
         height: u32,
-    ) -> Result<Self> {
+    ) -> Result<Self, EngineError> {
         // Load shader
-        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Block Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/block.wgsl").into()),
-        });
+        let shader = create_shader(device, "Block Shader", include_str!("shaders/block.wgsl"))?;
 
         // Camera uniform buffer
         let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -143,47 +293,106 @@ impl ChunkRenderer {
             }],
         });
 
+        // Block texture atlas
+        let (atlas_texture, texture_bind_group_layout, texture_bind_group) =
+            Self::create_atlas_bind_group(device, queue);
+
+        // Pipeline layout with camera, texture and shadow bind groups
+        let shadow_bindings = ShadowBindings::new(device);
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Block Pipeline Layout"),
+            bind_group_layouts: &[
+                &camera_bind_group_layout,
+                &texture_bind_group_layout,
+                shadow_bindings.layout(),
+            ],
+            push_constant_ranges: &[],
+        });
+
+        // Depth texture
+        let (depth_texture, depth_view) = Self::create_depth_texture(device, width, height);
+
+        // Opaque pipeline (writes depth)
+        let pipeline = Self::create_pipeline(
+            device,
+            &pipeline_layout,
+            &shader,
+            surface_format,
+            PassKind::Opaque,
+        );
+
+        // Transparent pipeline (blends over opaque geometry, depth test only)
+        let transparent_pipeline = Self::create_pipeline(
+            device,
+            &pipeline_layout,
+            &shader,
+            surface_format,
+            PassKind::Transparent,
+        );
+
+        // Wireframe pipeline, for debugging meshing
+        let wireframe_pipeline = device
+            .features()
+            .contains(wgpu::Features::POLYGON_MODE_LINE)
+            .then(|| {
+                Self::create_pipeline(
+                    device,
+                    &pipeline_layout,
+                    &shader,
+                    surface_format,
+                    PassKind::Wireframe,
+                )
+            });
+
+        Ok(Self {
+            pipeline,
+            transparent_pipeline,
+            wireframe_pipeline,
+            wireframe: false,
+            camera_buffer,
+            camera_bind_group,
+            atlas_texture,
+            texture_bind_group,
+            depth_texture,
+            depth_view,
+            size: (width, height),
+            sun: SunLight::default().uniform(),
+            shadow_bindings,
+            shadow_map: None,
+            shadow_uniform: ShadowUniform::disabled(),
+        })
+    }
+
+    /// Generates the block texture atlas with the default seed, uploads it
+    /// and returns the texture, its bind group layout and bind group.
+    fn create_atlas_bind_group(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) -> (wgpu::Texture, wgpu::BindGroupLayout, wgpu::BindGroup) {
         // Generate texture atlas
-        let atlas = TextureAtlas::generate();
+        let atlas = TextureAtlas::generate(DEFAULT_ATLAS_SEED);
 
         // Create GPU texture for atlas
-        let texture_size = wgpu::Extent3d {
-            width: atlas.width,
-            height: atlas.height,
-            depth_or_array_layers: 1,
-        };
-
         let atlas_texture = device.create_texture(&wgpu::TextureDescriptor {
             label: Some("Block Atlas Texture"),
-            size: texture_size,
-            mip_level_count: 1,
+            size: wgpu::Extent3d {
+                width: atlas.width,
+                height: atlas.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: MIP_LEVELS,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
             format: wgpu::TextureFormat::Rgba8UnormSrgb,
             usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
             view_formats: &[],
         });
-
-        // Upload atlas data to GPU
-        queue.write_texture(
-            wgpu::TexelCopyTextureInfo {
-                texture: &atlas_texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
-            &atlas.data,
-            wgpu::TexelCopyBufferLayout {
-                offset: 0,
-                bytes_per_row: Some(4 * atlas.width),
-                rows_per_image: Some(atlas.height),
-            },
-            texture_size,
-        );
+        Self::upload_atlas(queue, &atlas_texture, &atlas);
 
         let atlas_texture_view = atlas_texture.create_view(&wgpu::TextureViewDescriptor::default());
 
-        // Create sampler with nearest-neighbor filtering (pixel art style)
+        // Create sampler with nearest-neighbor filtering (pixel art style),
+        // blending between mip levels so distant blocks don't shimmer
         let atlas_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
             label: Some("Block Atlas Sampler"),
             address_mode_u: wgpu::AddressMode::Repeat,
@@ -191,7 +400,7 @@ impl ChunkRenderer {
             address_mode_w: wgpu::AddressMode::Repeat,
             mag_filter: wgpu::FilterMode::Nearest,
             min_filter: wgpu::FilterMode::Nearest,
-            mipmap_filter: wgpu::FilterMode::Nearest,
+            mipmap_filter: wgpu::FilterMode::Linear,
             ..Default::default()
         });
 
@@ -235,32 +444,101 @@ impl ChunkRenderer {
             ],
         });
 
-        // Pipeline layout with both bind groups
-        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-            label: Some("Block Pipeline Layout"),
-            bind_group_layouts: &[&camera_bind_group_layout, &texture_bind_group_layout],
-            push_constant_ranges: &[],
-        });
+        (atlas_texture, texture_bind_group_layout, texture_bind_group)
+    }
 
-        // Depth texture
-        let (depth_texture, depth_view) = Self::create_depth_texture(device, width, height);
+    /// Uploads atlas pixel data and its mip levels to the atlas texture.
+    fn upload_atlas(queue: &wgpu::Queue, texture: &wgpu::Texture, atlas: &TextureAtlas) {
+        let mipmaps = atlas.mipmaps();
+        let levels = std::iter::once(atlas).chain(&mipmaps);
+        for (mip_level, level) in (0..).zip(levels) {
+            queue.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    texture,
+                    mip_level,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                &level.data,
+                wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(4 * level.width),
+                    rows_per_image: Some(level.height),
+                },
+                wgpu::Extent3d {
+                    width: level.width,
+                    height: level.height,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+    }
 
-        // Render pipeline
-        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Block Pipeline"),
-            layout: Some(&pipeline_layout),
+    /// Regenerates the block texture atlas with `seed` and re-uploads it.
+    pub fn set_atlas_seed(&self, queue: &wgpu::Queue, seed: u32) {
+        self.set_atlas(queue, seed, &ColorOverrides::new());
+    }
+
+    /// Regenerates the block texture atlas with `seed`, tinted with the
+    /// color overrides, and re-uploads it.
+    pub fn set_atlas(&self, queue: &wgpu::Queue, seed: u32, overrides: &ColorOverrides) {
+        let atlas = TextureAtlas::generate_with_colors(seed, overrides);
+        Self::upload_atlas(queue, &self.atlas_texture, &atlas);
+    }
+
+    /// Creates the block render pipeline for the given pass.
+    fn create_pipeline(
+        device: &wgpu::Device,
+        layout: &wgpu::PipelineLayout,
+        shader: &wgpu::ShaderModule,
+        surface_format: wgpu::TextureFormat,
+        kind: PassKind,
+    ) -> wgpu::RenderPipeline {
+        let (label, entry_point, blend, depth_write_enabled) = match kind {
+            PassKind::Opaque => (
+                "Block Pipeline",
+                "fs_main",
+                wgpu::BlendState::ALPHA_BLENDING,
+                true,
+            ),
+            // Translucent faces multiply the framebuffer by their tint and
+            // don't write depth, so panes behind panes still show through.
+            PassKind::Transparent => (
+                "Transparent Block Pipeline",
+                "fs_transparent",
+                wgpu::BlendState {
+                    color: wgpu::BlendComponent {
+                        src_factor: wgpu::BlendFactor::Dst,
+                        dst_factor: wgpu::BlendFactor::Zero,
+                        operation: wgpu::BlendOperation::Add,
+                    },
+                    alpha: wgpu::BlendComponent::OVER,
+                },
+                false,
+            ),
+            PassKind::Wireframe => (
+                "Wireframe Block Pipeline",
+                "fs_main",
+                wgpu::BlendState::ALPHA_BLENDING,
+                true,
+            ),
+        };
+
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(layout),
             vertex: wgpu::VertexState {
-                module: &shader,
+                module: shader,
                 entry_point: Some("vs_main"),
                 buffers: &[ChunkVertex::layout()],
                 compilation_options: Default::default(),
             },
             fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: Some("fs_main"),
+                module: shader,
+                entry_point: Some(entry_point),
                 targets: &[Some(wgpu::ColorTargetState {
                     format: surface_format,
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    blend: Some(blend),
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
                 compilation_options: Default::default(),
@@ -270,13 +548,13 @@ impl ChunkRenderer {
                 strip_index_format: None,
                 front_face: wgpu::FrontFace::Ccw,
                 cull_mode: Some(wgpu::Face::Back),
-                polygon_mode: wgpu::PolygonMode::Fill,
+                polygon_mode: kind.polygon_mode(),
                 unclipped_depth: false,
                 conservative: false,
             },
             depth_stencil: Some(wgpu::DepthStencilState {
                 format: wgpu::TextureFormat::Depth32Float,
-                depth_write_enabled: true,
+                depth_write_enabled,
                 depth_compare: wgpu::CompareFunction::Less,
                 stencil: wgpu::StencilState::default(),
                 bias: wgpu::DepthBiasState::default(),
@@ -288,16 +566,6 @@ impl ChunkRenderer {
             },
             multiview: None,
             cache: None,
-        });
-
-        Ok(Self {
-            pipeline,
-            camera_buffer,
-            camera_bind_group,
-            texture_bind_group,
-            depth_texture,
-            depth_view,
-            size: (width, height),
         })
     }
 
@@ -339,9 +607,83 @@ impl ChunkRenderer {
         }
     }
 
-    /// Updates the camera uniform.
+    /// Updates the camera uniform, along with the current directional light
+    /// and shadow projection.
     pub fn update_camera(&self, queue: &wgpu::Queue, uniform: &CameraUniform) {
-        queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[*uniform]));
+        let uniform = uniform.with_sun(self.sun);
+        queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[uniform]));
+        self.shadow_bindings.update(queue, &self.shadow_uniform);
+    }
+
+    /// Enables or disables sun shadows with a `resolution` x `resolution`
+    /// shadow map. The shadow pipeline and depth target only exist while
+    /// shadows are enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EngineError::Shader`] if the shadow shader is invalid.
+    pub fn set_shadows(
+        &mut self,
+        device: &wgpu::Device,
+        enabled: bool,
+        resolution: u32,
+    ) -> Result<(), EngineError> {
+        if !enabled {
+            self.shadow_map = None;
+            self.shadow_uniform = ShadowUniform::disabled();
+        } else if self.shadow_resolution() != Some(resolution) {
+            self.shadow_map = Some(ShadowMap::new(
+                device,
+                self.shadow_bindings.buffer(),
+                resolution,
+            )?);
+        }
+        self.shadow_bindings.bind(device, self.shadow_map.as_ref());
+        Ok(())
+    }
+
+    /// Returns the shadow map resolution, or `None` when shadows are off.
+    #[must_use]
+    pub fn shadow_resolution(&self) -> Option<u32> {
+        self.shadow_map.as_ref().map(ShadowMap::resolution)
+    }
+
+    /// Fits the shadow projection around `visible`, the bounds of the
+    /// chunks in view, using the current sun direction. Takes effect on the
+    /// next [`update_camera`](Self::update_camera).
+    pub fn fit_shadows(&mut self, visible: &Aabb) {
+        self.shadow_uniform = match &self.shadow_map {
+            Some(map) => {
+                let light_dir = glam::Vec4::from_array(self.sun.direction).truncate();
+                ShadowUniform::new(light_view_projection(light_dir, visible), map.resolution())
+            }
+            None => ShadowUniform::disabled(),
+        };
+    }
+
+    /// Records the shadow pass for the given opaque chunks. Does nothing
+    /// when shadows are off. Must be submitted before the main pass.
+    pub fn render_shadows<'a, I>(&self, encoder: &mut wgpu::CommandEncoder, chunks: I)
+    where
+        I: Iterator<Item = &'a ChunkBuffers>,
+    {
+        if let Some(map) = &self.shadow_map {
+            map.render(encoder, chunks);
+        }
+    }
+
+    /// Points the directional light at the sun (or moon) for `time` of day,
+    /// with the sun's path tilted `tilt` degrees. Takes effect on the next
+    /// [`update_camera`](Self::update_camera).
+    pub fn set_sun_from_time(&mut self, time: f32, tilt: f32) {
+        self.sun = SunLight::from_time(time, tilt).uniform();
+    }
+
+    /// Returns a view of the block texture atlas.
+    #[must_use]
+    pub fn atlas_view(&self) -> wgpu::TextureView {
+        self.atlas_texture
+            .create_view(&wgpu::TextureViewDescriptor::default())
     }
 
     /// Returns the depth texture view.
@@ -350,19 +692,118 @@ impl ChunkRenderer {
         &self.depth_view
     }
 
-    /// Renders chunks using the given render pass.
-    pub fn render<'a, I>(&'a self, render_pass: &mut wgpu::RenderPass<'a>, chunks: I)
+    /// Draws opaque geometry as triangle outlines, so the quads the mesher
+    /// produced can be inspected, or as filled triangles again. Returns
+    /// whether wireframe is now on; it stays off if the device doesn't
+    /// support line rasterization.
+    pub const fn set_wireframe(&mut self, enabled: bool) -> bool {
+        self.wireframe = enabled && self.wireframe_pipeline.is_some();
+        self.wireframe
+    }
+
+    /// Returns true if opaque geometry is drawn as triangle outlines.
+    #[must_use]
+    pub const fn is_wireframe(&self) -> bool {
+        self.wireframe
+    }
+
+    /// Renders the chunks inside `frustum` using the given render pass, and
+    /// returns how many were drawn and culled. Uses the wireframe pipeline
+    /// while [wireframe](Self::set_wireframe) is on.
+    pub fn render<'a, I>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        frustum: &Frustum,
+        chunks: I,
+    ) -> CullStats
+    where
+        I: IntoIterator<Item = (&'a ChunkPos, &'a ChunkBuffers)>,
+    {
+        let (visible, stats) = cull_chunks(frustum, chunks);
+
+        let pipeline = self
+            .wireframe_pipeline
+            .as_ref()
+            .filter(|_| self.wireframe)
+            .unwrap_or(&self.pipeline);
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+        render_pass.set_bind_group(1, &self.texture_bind_group, &[]);
+        render_pass.set_bind_group(2, self.shadow_bindings.bind_group(), &[]);
+
+        for chunk in visible {
+            render_pass.set_vertex_buffer(0, chunk.vertex_buffer.slice(..));
+            render_pass.set_index_buffer(chunk.index_buffer.slice(..), chunk.index_format);
+            render_pass.draw_indexed(0..chunk.index_count, 0, 0..1);
+        }
+        stats
+    }
+
+    /// Renders translucent chunk geometry.
+    ///
+    /// Must be called after [`render`](Self::render) in the same pass. Chunks
+    /// should be supplied back-to-front for correct blending.
+    pub fn render_transparent<'a, I>(&'a self, render_pass: &mut wgpu::RenderPass<'a>, chunks: I)
     where
         I: Iterator<Item = &'a ChunkBuffers>,
     {
-        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_pipeline(&self.transparent_pipeline);
         render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
         render_pass.set_bind_group(1, &self.texture_bind_group, &[]);
+        render_pass.set_bind_group(2, self.shadow_bindings.bind_group(), &[]);
 
         for chunk in chunks {
             render_pass.set_vertex_buffer(0, chunk.vertex_buffer.slice(..));
-            render_pass.set_index_buffer(chunk.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+            render_pass.set_index_buffer(chunk.index_buffer.slice(..), chunk.index_format);
             render_pass.draw_indexed(0..chunk.index_count, 0, 0..1);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::camera::Camera;
+
+    #[test]
+    fn only_the_wireframe_pass_draws_lines() {
+        assert_eq!(PassKind::Opaque.polygon_mode(), wgpu::PolygonMode::Fill);
+        assert_eq!(
+            PassKind::Transparent.polygon_mode(),
+            wgpu::PolygonMode::Fill
+        );
+        assert_eq!(PassKind::Wireframe.polygon_mode(), wgpu::PolygonMode::Line);
+    }
+
+    #[test]
+    fn small_meshes_use_16_bit_indices() {
+        assert_eq!(index_format_for(0), wgpu::IndexFormat::Uint16);
+        assert_eq!(index_format_for(65_535), wgpu::IndexFormat::Uint16);
+        assert_eq!(index_format_for(65_536), wgpu::IndexFormat::Uint32);
+        assert_eq!(index_format_for(1 << 20), wgpu::IndexFormat::Uint32);
+    }
+
+    #[test]
+    fn culling_keeps_chunks_in_front_and_counts_the_rest() {
+        // Default camera looks at -Z
+        let frustum = Camera::default()
+            .at_position(glam::Vec3::new(8.0, 64.0, 8.0))
+            .frustum();
+        let chunks = [
+            (ChunkPos::new(0, -2), "ahead"),
+            (ChunkPos::new(0, 100), "behind"),
+            (ChunkPos::new(0, -4), "far ahead"),
+            (ChunkPos::new(100, -10), "aside"),
+        ];
+
+        let (visible, stats) = cull_chunks(&frustum, chunks.iter().map(|(pos, name)| (pos, name)));
+        assert_eq!(visible, [&"ahead", &"far ahead"]);
+        assert_eq!(
+            stats,
+            CullStats {
+                drawn: 2,
+                culled: 2
+            }
+        );
+    }
+}
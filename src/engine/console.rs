@@ -0,0 +1,309 @@
+//! In-game command console.
+//!
+//! Opened with `/`, the console captures typed text until Enter submits the
+//! line or Escape closes it. Supported commands:
+//!
+//! - `tp <x> <y> <z>`: teleport the camera
+//! - `give <block>`: select a block for placing
+//! - `time set <0..1 | dawn | noon | dusk | midnight>`: set the time of day
+//! - `time <pause | resume>`: stop or restart the day/night cycle
+//! - `setblock <x> <y> <z> <block>`: place a block in the world
+//!
+//! Blocks are named as in [`Block::name`], e.g. `stone` or `coal_ore`.
+
+use std::collections::VecDeque;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::world::Block;
+
+/// Number of output lines the console keeps.
+const MAX_LOG_LINES: usize = 50;
+
+/// A parsed console command.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConsoleCommand {
+    /// Moves the camera to a position.
+    Teleport {
+        /// World X.
+        x: f32,
+        /// World Y.
+        y: f32,
+        /// World Z.
+        z: f32,
+    },
+    /// Selects a block for placing.
+    Give {
+        /// The block to select.
+        block: Block,
+    },
+    /// Sets the time of day.
+    SetTime {
+        /// Time in `[0, 1)`: 0 is midnight, 0.5 is noon.
+        time: f32,
+    },
+    /// Stops or restarts the time of day advancing.
+    PauseTime {
+        /// Whether time stands still.
+        paused: bool,
+    },
+    /// Places a block in the world.
+    SetBlock {
+        /// World X.
+        x: i32,
+        /// World Y.
+        y: i32,
+        /// World Z.
+        z: i32,
+        /// The block to place.
+        block: Block,
+    },
+}
+
+/// Reasons a console line couldn't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ConsoleError {
+    /// The line was blank.
+    #[error("no command entered")]
+    Empty,
+
+    /// The first word isn't a known command.
+    #[error("unknown command `{0}`")]
+    UnknownCommand(String),
+
+    /// The command was given the wrong arguments.
+    #[error("usage: {0}")]
+    Usage(&'static str),
+
+    /// An argument wasn't a valid number.
+    #[error("`{0}` is not a valid number")]
+    InvalidNumber(String),
+
+    /// An argument wasn't a known block name.
+    #[error("unknown block `{0}`")]
+    UnknownBlock(String),
+}
+
+impl ConsoleCommand {
+    /// Parses a command line.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ConsoleError`] describing the first problem found.
+    pub fn parse(line: &str) -> Result<Self, ConsoleError> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (&name, args) = words.split_first().ok_or(ConsoleError::Empty)?;
+
+        match (name.to_ascii_lowercase().as_str(), args) {
+            ("tp", [x, y, z]) => Ok(Self::Teleport {
+                x: parse_number(x)?,
+                y: parse_number(y)?,
+                z: parse_number(z)?,
+            }),
+            ("tp", _) => Err(ConsoleError::Usage("tp <x> <y> <z>")),
+            ("give", [block]) => Ok(Self::Give {
+                block: parse_block(block)?,
+            }),
+            ("give", _) => Err(ConsoleError::Usage("give <block>")),
+            ("time", ["set", time]) => Ok(Self::SetTime {
+                time: parse_time(time)?,
+            }),
+            ("time", ["pause"]) => Ok(Self::PauseTime { paused: true }),
+            ("time", ["resume"]) => Ok(Self::PauseTime { paused: false }),
+            ("time", _) => Err(ConsoleError::Usage(
+                "time <set <0..1 | dawn | noon | dusk | midnight> | pause | resume>",
+            )),
+            ("setblock", [x, y, z, block]) => Ok(Self::SetBlock {
+                x: parse_number(x)?,
+                y: parse_number(y)?,
+                z: parse_number(z)?,
+                block: parse_block(block)?,
+            }),
+            ("setblock", _) => Err(ConsoleError::Usage("setblock <x> <y> <z> <block>")),
+            _ => Err(ConsoleError::UnknownCommand(name.to_string())),
+        }
+    }
+}
+
+/// Parses a numeric argument.
+fn parse_number<T: FromStr>(word: &str) -> Result<T, ConsoleError> {
+    word.parse()
+        .map_err(|_| ConsoleError::InvalidNumber(word.to_string()))
+}
+
+/// Parses a block name argument.
+fn parse_block(word: &str) -> Result<Block, ConsoleError> {
+    Block::from_name(word).ok_or_else(|| ConsoleError::UnknownBlock(word.to_string()))
+}
+
+/// Parses a time of day, either a fraction of the day or a named time.
+fn parse_time(word: &str) -> Result<f32, ConsoleError> {
+    match word.to_ascii_lowercase().as_str() {
+        "midnight" => Ok(0.0),
+        "dawn" => Ok(0.25),
+        "noon" => Ok(0.5),
+        "dusk" => Ok(0.75),
+        _ => parse_number(word),
+    }
+}
+
+/// Console state: whether it's open, the line being typed and recent output.
+#[derive(Debug, Default)]
+pub struct Console {
+    /// Whether the console is capturing input.
+    open: bool,
+    /// The line being typed.
+    line: String,
+    /// Recent output, oldest first.
+    log: VecDeque<String>,
+}
+
+impl Console {
+    /// Creates a closed, empty console.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether the console is open.
+    #[must_use]
+    pub const fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Opens or closes the console, clearing the typed line.
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+        self.line.clear();
+    }
+
+    /// Returns the line being typed.
+    #[must_use]
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+
+    /// Appends typed text to the line.
+    pub fn push_str(&mut self, text: &str) {
+        self.line.push_str(text);
+    }
+
+    /// Deletes the last typed character.
+    pub fn backspace(&mut self) {
+        self.line.pop();
+    }
+
+    /// Takes the typed line and parses it, echoing it to the log.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ConsoleError`] if the line isn't a valid command.
+    pub fn submit(&mut self) -> Result<ConsoleCommand, ConsoleError> {
+        let line = std::mem::take(&mut self.line);
+        self.log(format!("> {line}"));
+        ConsoleCommand::parse(&line)
+    }
+
+    /// Adds a line of output, dropping the oldest beyond the limit.
+    pub fn log(&mut self, message: impl Into<String>) {
+        if self.log.len() == MAX_LOG_LINES {
+            self.log.pop_front();
+        }
+        self.log.push_back(message.into());
+    }
+
+    /// Returns recent output, oldest first.
+    pub fn log_lines(&self) -> impl Iterator<Item = &str> {
+        self.log.iter().map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_setblock() {
+        assert_eq!(
+            ConsoleCommand::parse("setblock 1 64 1 stone"),
+            Ok(ConsoleCommand::SetBlock {
+                x: 1,
+                y: 64,
+                z: 1,
+                block: Block::Stone,
+            })
+        );
+        assert_eq!(
+            ConsoleCommand::parse("  setblock -3 10 7 coal_ore "),
+            Ok(ConsoleCommand::SetBlock {
+                x: -3,
+                y: 10,
+                z: 7,
+                block: Block::CoalOre,
+            })
+        );
+    }
+
+    #[test]
+    fn parses_other_commands() {
+        assert_eq!(
+            ConsoleCommand::parse("tp 0.5 80 -12"),
+            Ok(ConsoleCommand::Teleport {
+                x: 0.5,
+                y: 80.0,
+                z: -12.0,
+            })
+        );
+        assert_eq!(
+            ConsoleCommand::parse("give glass"),
+            Ok(ConsoleCommand::Give {
+                block: Block::Glass
+            })
+        );
+        assert_eq!(
+            ConsoleCommand::parse("time set dusk"),
+            Ok(ConsoleCommand::SetTime { time: 0.75 })
+        );
+        assert_eq!(
+            ConsoleCommand::parse("time pause"),
+            Ok(ConsoleCommand::PauseTime { paused: true })
+        );
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        assert_eq!(
+            ConsoleCommand::parse("fly 1 2 3"),
+            Err(ConsoleError::UnknownCommand("fly".to_string()))
+        );
+        assert_eq!(ConsoleCommand::parse("   "), Err(ConsoleError::Empty));
+        assert!(matches!(
+            ConsoleCommand::parse("setblock 1 64 stone"),
+            Err(ConsoleError::Usage(_))
+        ));
+        assert_eq!(
+            ConsoleCommand::parse("setblock 1 high 1 stone"),
+            Err(ConsoleError::InvalidNumber("high".to_string()))
+        );
+        assert_eq!(
+            ConsoleCommand::parse("give cheese"),
+            Err(ConsoleError::UnknownBlock("cheese".to_string()))
+        );
+    }
+
+    #[test]
+    fn submit_takes_line_and_logs_it() {
+        let mut console = Console::new();
+        console.set_open(true);
+        console.push_str("give sandd");
+        console.backspace();
+
+        assert_eq!(
+            console.submit(),
+            Ok(ConsoleCommand::Give { block: Block::Sand })
+        );
+        assert_eq!(console.line(), "");
+        assert_eq!(console.log_lines().collect::<Vec<_>>(), ["> give sand"]);
+    }
+}
@@ -0,0 +1,208 @@
+//! Dynamic resolution.
+//!
+//! Adjusts the render scale from frame times to hold a target frame rate:
+//! the world is drawn at a fraction of the window size when frames run long
+//! and scaled back up to full size when there's headroom.
+//!
+//! Scale changes use hysteresis. Lowering needs several consecutive slow
+//! frames, and raising needs many consecutive frames comfortably under
+//! budget, so a frame time hovering near the target doesn't flip the scale
+//! back and forth.
+
+/// Dynamic resolution settings.
+#[derive(Debug, Clone)]
+pub struct DynamicResolutionConfig {
+    /// Whether the scale adapts to frame times.
+    pub enabled: bool,
+    /// Frame rate to hold.
+    pub target_fps: f32,
+    /// Lowest render scale.
+    pub min_scale: f32,
+    /// Highest render scale.
+    pub max_scale: f32,
+    /// Amount the scale changes per adjustment.
+    pub step: f32,
+    /// Consecutive over-budget frames before lowering the scale.
+    pub lower_after_frames: u32,
+    /// Consecutive under-budget frames before raising the scale.
+    pub raise_after_frames: u32,
+    /// Fraction of the frame budget a frame must stay under to count
+    /// towards raising the scale.
+    pub headroom: f32,
+}
+
+impl Default for DynamicResolutionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_fps: 60.0,
+            min_scale: 0.5,
+            max_scale: 1.0,
+            step: 0.1,
+            lower_after_frames: 5,
+            raise_after_frames: 60,
+            headroom: 0.8,
+        }
+    }
+}
+
+/// Frame-time driven render scale controller.
+#[derive(Debug, Clone)]
+pub struct DynamicResolution {
+    /// Settings.
+    config: DynamicResolutionConfig,
+    /// Current render scale.
+    scale: f32,
+    /// Consecutive frames over the budget.
+    over_budget_frames: u32,
+    /// Consecutive frames under the headroom threshold.
+    under_budget_frames: u32,
+}
+
+impl DynamicResolution {
+    /// Creates a controller starting at `scale`, clamped to the configured bounds.
+    #[must_use]
+    pub const fn new(config: DynamicResolutionConfig, scale: f32) -> Self {
+        let scale = scale.clamp(config.min_scale, config.max_scale);
+        Self {
+            config,
+            scale,
+            over_budget_frames: 0,
+            under_budget_frames: 0,
+        }
+    }
+
+    /// Returns the current render scale.
+    #[must_use]
+    pub const fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Returns the settings.
+    #[must_use]
+    pub const fn config(&self) -> &DynamicResolutionConfig {
+        &self.config
+    }
+
+    /// Records a frame that took `frame_time` seconds and returns the
+    /// (possibly adjusted) render scale. Does nothing when disabled.
+    pub fn update(&mut self, frame_time: f32) -> f32 {
+        if !self.config.enabled {
+            return self.scale;
+        }
+
+        let budget = self.config.target_fps.recip();
+        if frame_time > budget {
+            self.over_budget_frames += 1;
+            self.under_budget_frames = 0;
+        } else if frame_time < budget * self.config.headroom {
+            self.under_budget_frames += 1;
+            self.over_budget_frames = 0;
+        } else {
+            // Close to budget: hold the current scale
+            self.over_budget_frames = 0;
+            self.under_budget_frames = 0;
+        }
+
+        if self.over_budget_frames >= self.config.lower_after_frames {
+            self.scale = (self.scale - self.config.step).max(self.config.min_scale);
+            self.over_budget_frames = 0;
+        } else if self.under_budget_frames >= self.config.raise_after_frames {
+            self.scale = (self.scale + self.config.step).min(self.config.max_scale);
+            self.under_budget_frames = 0;
+        }
+
+        self.scale
+    }
+}
+
+/// Returns the size to render at for a window of `width` x `height` at `scale`.
+#[must_use]
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+pub fn scaled_size(width: u32, height: u32, scale: f32) -> (u32, u32) {
+    let scale_dimension = |size: u32| ((size as f32 * scale).round() as u32).clamp(1, size.max(1));
+    (scale_dimension(width), scale_dimension(height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SLOW_FRAME: f32 = 1.0 / 30.0;
+    const FAST_FRAME: f32 = 1.0 / 120.0;
+
+    fn controller() -> DynamicResolution {
+        let config = DynamicResolutionConfig {
+            enabled: true,
+            min_scale: 0.6,
+            ..DynamicResolutionConfig::default()
+        };
+        DynamicResolution::new(config, 1.0)
+    }
+
+    fn run(controller: &mut DynamicResolution, frame_time: f32, frames: u32) -> f32 {
+        for _ in 0..frames {
+            controller.update(frame_time);
+        }
+        controller.scale()
+    }
+
+    #[test]
+    fn over_budget_frames_lower_scale_stepwise() {
+        let mut resolution = controller();
+        let lower_after = resolution.config().lower_after_frames;
+
+        // One frame short of the threshold changes nothing
+        assert!((run(&mut resolution, SLOW_FRAME, lower_after - 1) - 1.0).abs() < 1e-6);
+        assert!((run(&mut resolution, SLOW_FRAME, 1) - 0.9).abs() < 1e-6);
+        assert!((run(&mut resolution, SLOW_FRAME, lower_after) - 0.8).abs() < 1e-6);
+
+        // Clamped to the minimum
+        assert!((run(&mut resolution, SLOW_FRAME, 100) - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn under_budget_frames_recover_scale() {
+        let mut resolution = controller();
+        run(&mut resolution, SLOW_FRAME, 100);
+        let raise_after = resolution.config().raise_after_frames;
+
+        assert!((run(&mut resolution, FAST_FRAME, raise_after) - 0.7).abs() < 1e-6);
+
+        // Clamped to the maximum
+        assert!((run(&mut resolution, FAST_FRAME, raise_after * 10) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn frames_near_budget_hold_scale() {
+        let mut resolution = controller();
+        run(&mut resolution, SLOW_FRAME, 100);
+        let scale = resolution.scale();
+
+        // Alternating slow and fast frames never build a streak
+        for _ in 0..200 {
+            resolution.update(SLOW_FRAME);
+            resolution.update(FAST_FRAME);
+        }
+        // Frames just under budget (but above headroom) don't raise it
+        run(&mut resolution, 0.9 / 60.0, 500);
+        assert!((resolution.scale() - scale).abs() < 1e-6);
+    }
+
+    #[test]
+    fn disabled_controller_keeps_scale() {
+        let mut resolution = DynamicResolution::new(DynamicResolutionConfig::default(), 0.8);
+        assert!((run(&mut resolution, SLOW_FRAME, 100) - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn scaled_size_rounds_and_stays_positive() {
+        assert_eq!(scaled_size(1280, 720, 1.0), (1280, 720));
+        assert_eq!(scaled_size(1280, 720, 0.5), (640, 360));
+        assert_eq!(scaled_size(1, 1, 0.1), (1, 1));
+    }
+}
@@ -0,0 +1,52 @@
+//! Engine error types.
+//!
+//! Public API functions return [`EngineError`] so callers can match on the
+//! kind of failure. Internally the engine still uses `anyhow`, which any
+//! `EngineError` converts into.
+
+use thiserror::Error;
+
+/// Errors returned by the engine's public API.
+#[derive(Debug, Error)]
+pub enum EngineError {
+    /// The event loop could not be created or exited with an error.
+    #[error("event loop error: {0}")]
+    EventLoop(#[from] winit::error::EventLoopError),
+
+    /// The OS refused to create the window.
+    #[error("failed to create window: {0}")]
+    Window(#[from] winit::error::OsError),
+
+    /// The window surface could not be created.
+    #[error("failed to create surface: {0}")]
+    Surface(#[from] wgpu::CreateSurfaceError),
+
+    /// No GPU adapter supports the window surface.
+    #[error("failed to find a suitable GPU adapter")]
+    NoAdapter,
+
+    /// The GPU device could not be created.
+    #[error("failed to create device: {0}")]
+    Device(#[from] wgpu::RequestDeviceError),
+
+    /// The next frame's surface texture could not be acquired.
+    #[error("failed to get surface texture: {0}")]
+    SurfaceTexture(#[from] wgpu::SurfaceError),
+
+    /// An offscreen render couldn't be read back to the CPU.
+    #[error("failed to read back rendered image: {0}")]
+    Readback(String),
+
+    /// An engine setting is out of range.
+    #[error("invalid engine config: {0}")]
+    InvalidConfig(String),
+
+    /// A WGSL shader failed to parse or validate.
+    #[error("shader `{label}` is invalid: {message}")]
+    Shader {
+        /// The shader's label.
+        label: String,
+        /// The parser or validator message.
+        message: String,
+    },
+}
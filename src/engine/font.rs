@@ -0,0 +1,232 @@
+//! Built-in bitmap font for overlay text.
+//!
+//! Printable ASCII glyphs are 5x7 pixels, packed into a small single-channel
+//! atlas with a pixel of padding around each so neighbors never bleed in
+//! when sampled. Other characters are drawn as `?`.
+
+use glam::Vec2;
+
+/// Glyph width in font pixels.
+pub const GLYPH_WIDTH: u32 = 5;
+
+/// Glyph height in font pixels.
+pub const GLYPH_HEIGHT: u32 = 7;
+
+/// Horizontal distance from one glyph to the next, in font pixels.
+pub const GLYPH_ADVANCE: u32 = GLYPH_WIDTH + 1;
+
+/// Vertical distance from one line to the next, in font pixels.
+pub const LINE_HEIGHT: u32 = GLYPH_HEIGHT + 2;
+
+/// Size of each glyph's cell in the atlas, including padding.
+const CELL_SIZE: u32 = 8;
+
+/// Glyph cells per atlas row.
+const ATLAS_COLUMNS: u32 = 16;
+
+/// Atlas width in pixels.
+pub const ATLAS_WIDTH: u32 = ATLAS_COLUMNS * CELL_SIZE;
+
+/// Atlas height in pixels.
+#[allow(clippy::cast_possible_truncation)]
+pub const ATLAS_HEIGHT: u32 = GLYPHS.len().div_ceil(ATLAS_COLUMNS as usize) as u32 * CELL_SIZE;
+
+/// First character in [`GLYPHS`].
+const FIRST_CHAR: char = ' ';
+
+/// Glyph drawn for characters the font doesn't have.
+const FALLBACK_CHAR: char = '?';
+
+/// Glyphs for `' '..='~'`, one byte per column from left to right; bit 0 is
+/// the top row.
+const GLYPHS: [[u8; 5]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x00, 0x00, 0x5F, 0x00, 0x00], // !
+    [0x00, 0x07, 0x00, 0x07, 0x00], // "
+    [0x14, 0x7F, 0x14, 0x7F, 0x14], // #
+    [0x24, 0x2A, 0x7F, 0x2A, 0x12], // $
+    [0x23, 0x13, 0x08, 0x64, 0x62], // %
+    [0x36, 0x49, 0x55, 0x22, 0x50], // &
+    [0x00, 0x05, 0x03, 0x00, 0x00], // '
+    [0x00, 0x1C, 0x22, 0x41, 0x00], // (
+    [0x00, 0x41, 0x22, 0x1C, 0x00], // )
+    [0x08, 0x2A, 0x1C, 0x2A, 0x08], // *
+    [0x08, 0x08, 0x3E, 0x08, 0x08], // +
+    [0x00, 0x50, 0x30, 0x00, 0x00], // ,
+    [0x08, 0x08, 0x08, 0x08, 0x08], // -
+    [0x00, 0x60, 0x60, 0x00, 0x00], // .
+    [0x20, 0x10, 0x08, 0x04, 0x02], // /
+    [0x3E, 0x51, 0x49, 0x45, 0x3E], // 0
+    [0x00, 0x42, 0x7F, 0x40, 0x00], // 1
+    [0x42, 0x61, 0x51, 0x49, 0x46], // 2
+    [0x21, 0x41, 0x45, 0x4B, 0x31], // 3
+    [0x18, 0x14, 0x12, 0x7F, 0x10], // 4
+    [0x27, 0x45, 0x45, 0x45, 0x39], // 5
+    [0x3C, 0x4A, 0x49, 0x49, 0x30], // 6
+    [0x01, 0x71, 0x09, 0x05, 0x03], // 7
+    [0x36, 0x49, 0x49, 0x49, 0x36], // 8
+    [0x06, 0x49, 0x49, 0x29, 0x1E], // 9
+    [0x00, 0x36, 0x36, 0x00, 0x00], // :
+    [0x00, 0x56, 0x36, 0x00, 0x00], // ;
+    [0x08, 0x14, 0x22, 0x41, 0x00], // <
+    [0x14, 0x14, 0x14, 0x14, 0x14], // =
+    [0x00, 0x41, 0x22, 0x14, 0x08], // >
+    [0x02, 0x01, 0x51, 0x09, 0x06], // ?
+    [0x32, 0x49, 0x79, 0x41, 0x3E], // @
+    [0x7E, 0x11, 0x11, 0x11, 0x7E], // A
+    [0x7F, 0x49, 0x49, 0x49, 0x36], // B
+    [0x3E, 0x41, 0x41, 0x41, 0x22], // C
+    [0x7F, 0x41, 0x41, 0x22, 0x1C], // D
+    [0x7F, 0x49, 0x49, 0x49, 0x41], // E
+    [0x7F, 0x09, 0x09, 0x09, 0x01], // F
+    [0x3E, 0x41, 0x49, 0x49, 0x7A], // G
+    [0x7F, 0x08, 0x08, 0x08, 0x7F], // H
+    [0x00, 0x41, 0x7F, 0x41, 0x00], // I
+    [0x20, 0x40, 0x41, 0x3F, 0x01], // J
+    [0x7F, 0x08, 0x14, 0x22, 0x41], // K
+    [0x7F, 0x40, 0x40, 0x40, 0x40], // L
+    [0x7F, 0x02, 0x0C, 0x02, 0x7F], // M
+    [0x7F, 0x04, 0x08, 0x10, 0x7F], // N
+    [0x3E, 0x41, 0x41, 0x41, 0x3E], // O
+    [0x7F, 0x09, 0x09, 0x09, 0x06], // P
+    [0x3E, 0x41, 0x51, 0x21, 0x5E], // Q
+    [0x7F, 0x09, 0x19, 0x29, 0x46], // R
+    [0x46, 0x49, 0x49, 0x49, 0x31], // S
+    [0x01, 0x01, 0x7F, 0x01, 0x01], // T
+    [0x3F, 0x40, 0x40, 0x40, 0x3F], // U
+    [0x1F, 0x20, 0x40, 0x20, 0x1F], // V
+    [0x3F, 0x40, 0x38, 0x40, 0x3F], // W
+    [0x63, 0x14, 0x08, 0x14, 0x63], // X
+    [0x07, 0x08, 0x70, 0x08, 0x07], // Y
+    [0x61, 0x51, 0x49, 0x45, 0x43], // Z
+    [0x00, 0x7F, 0x41, 0x41, 0x00], // [
+    [0x02, 0x04, 0x08, 0x10, 0x20], // \
+    [0x00, 0x41, 0x41, 0x7F, 0x00], // ]
+    [0x04, 0x02, 0x01, 0x02, 0x04], // ^
+    [0x40, 0x40, 0x40, 0x40, 0x40], // _
+    [0x00, 0x01, 0x02, 0x04, 0x00], // `
+    [0x20, 0x54, 0x54, 0x54, 0x78], // a
+    [0x7F, 0x48, 0x44, 0x44, 0x38], // b
+    [0x38, 0x44, 0x44, 0x44, 0x20], // c
+    [0x38, 0x44, 0x44, 0x48, 0x7F], // d
+    [0x38, 0x54, 0x54, 0x54, 0x18], // e
+    [0x08, 0x7E, 0x09, 0x01, 0x02], // f
+    [0x0C, 0x52, 0x52, 0x52, 0x3E], // g
+    [0x7F, 0x08, 0x04, 0x04, 0x78], // h
+    [0x00, 0x44, 0x7D, 0x40, 0x00], // i
+    [0x20, 0x40, 0x44, 0x3D, 0x00], // j
+    [0x7F, 0x10, 0x28, 0x44, 0x00], // k
+    [0x00, 0x41, 0x7F, 0x40, 0x00], // l
+    [0x7C, 0x04, 0x18, 0x04, 0x78], // m
+    [0x7C, 0x08, 0x04, 0x04, 0x78], // n
+    [0x38, 0x44, 0x44, 0x44, 0x38], // o
+    [0x7C, 0x14, 0x14, 0x14, 0x08], // p
+    [0x08, 0x14, 0x14, 0x18, 0x7C], // q
+    [0x7C, 0x08, 0x04, 0x04, 0x08], // r
+    [0x48, 0x54, 0x54, 0x54, 0x20], // s
+    [0x04, 0x3F, 0x44, 0x40, 0x20], // t
+    [0x3C, 0x40, 0x40, 0x20, 0x7C], // u
+    [0x1C, 0x20, 0x40, 0x20, 0x1C], // v
+    [0x3C, 0x40, 0x30, 0x40, 0x3C], // w
+    [0x44, 0x28, 0x10, 0x28, 0x44], // x
+    [0x0C, 0x50, 0x50, 0x50, 0x3C], // y
+    [0x44, 0x64, 0x54, 0x4C, 0x44], // z
+    [0x00, 0x08, 0x36, 0x41, 0x00], // {
+    [0x00, 0x00, 0x7F, 0x00, 0x00], // |
+    [0x00, 0x41, 0x36, 0x08, 0x00], // }
+    [0x08, 0x04, 0x08, 0x10, 0x08], // ~
+];
+
+/// Returns the index of `c` in [`GLYPHS`], or of the fallback glyph.
+const fn glyph_index(c: char) -> usize {
+    let index = (c as usize).wrapping_sub(FIRST_CHAR as usize);
+    if index < GLYPHS.len() {
+        index
+    } else {
+        FALLBACK_CHAR as usize - FIRST_CHAR as usize
+    }
+}
+
+/// Returns the top-left pixel of glyph `index` in the atlas.
+#[allow(clippy::cast_possible_truncation)]
+const fn glyph_origin(index: usize) -> (u32, u32) {
+    let index = index as u32;
+    (
+        (index % ATLAS_COLUMNS) * CELL_SIZE + 1,
+        (index / ATLAS_COLUMNS) * CELL_SIZE + 1,
+    )
+}
+
+/// Returns the atlas texels, one coverage byte (0 or 255) per pixel, rows
+/// from the top.
+#[must_use]
+pub fn atlas_pixels() -> Vec<u8> {
+    let mut pixels = vec![0; (ATLAS_WIDTH * ATLAS_HEIGHT) as usize];
+    for (index, columns) in GLYPHS.iter().enumerate() {
+        let (left, top) = glyph_origin(index);
+        for (dx, column) in (0..).zip(columns) {
+            for dy in 0..GLYPH_HEIGHT {
+                if column & (1 << dy) != 0 {
+                    pixels[((top + dy) * ATLAS_WIDTH + left + dx) as usize] = u8::MAX;
+                }
+            }
+        }
+    }
+    pixels
+}
+
+/// Returns the texture coordinates of `c`'s glyph as (top-left,
+/// bottom-right).
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn glyph_uv(c: char) -> (Vec2, Vec2) {
+    let (left, top) = glyph_origin(glyph_index(c));
+    let atlas = Vec2::new(ATLAS_WIDTH as f32, ATLAS_HEIGHT as f32);
+    let min = Vec2::new(left as f32, top as f32) / atlas;
+    let max = Vec2::new((left + GLYPH_WIDTH) as f32, (top + GLYPH_HEIGHT) as f32) / atlas;
+    (min, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns whether the atlas pixel at `x`, `y` of `c`'s glyph is set.
+    fn is_set(pixels: &[u8], c: char, x: u32, y: u32) -> bool {
+        let (left, top) = glyph_origin(glyph_index(c));
+        pixels[((top + y) * ATLAS_WIDTH + left + x) as usize] == u8::MAX
+    }
+
+    #[test]
+    fn atlas_holds_every_glyph_with_padding() {
+        let pixels = atlas_pixels();
+        assert_eq!(pixels.len(), (ATLAS_WIDTH * ATLAS_HEIGHT) as usize);
+
+        // '!' is a bar down the middle with a gap above the dot
+        assert!(is_set(&pixels, '!', 2, 0));
+        assert!(!is_set(&pixels, '!', 2, 5));
+        assert!(is_set(&pixels, '!', 2, 6));
+        assert!(!is_set(&pixels, '!', 0, 0));
+
+        // Nothing is drawn in the padding between cells
+        for y in 0..ATLAS_HEIGHT {
+            for x in 0..ATLAS_WIDTH {
+                let (cx, cy) = (x % CELL_SIZE, y % CELL_SIZE);
+                if cx == 0 || cx > GLYPH_WIDTH || cy == 0 || cy > GLYPH_HEIGHT {
+                    assert_eq!(pixels[(y * ATLAS_WIDTH + x) as usize], 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_characters_use_the_fallback_glyph() {
+        assert_eq!(glyph_uv('é'), glyph_uv('?'));
+        assert_eq!(glyph_uv('\u{7f}'), glyph_uv('?'));
+        assert_ne!(glyph_uv('A'), glyph_uv('?'));
+
+        let (min, max) = glyph_uv('~');
+        assert!(min.cmplt(max).all());
+        assert!(max.cmple(Vec2::ONE).all());
+    }
+}
@@ -1,6 +1,8 @@
 //! FPS counter and timing utilities.
 //!
-//! Tracks frame timing for performance monitoring.
+//! Tracks frame timing for performance monitoring. Besides the average frame
+//! rate it reports frame-time percentiles, which show stutter that an
+//! average hides.
 
 use std::collections::VecDeque;
 use std::time::{Duration, Instant};
@@ -38,7 +40,11 @@ impl FpsCounter {
 
     /// Records a new frame and returns the current FPS.
     pub fn tick(&mut self) -> f64 {
-        let now = Instant::now();
+        self.tick_at(Instant::now())
+    }
+
+    /// Records a frame shown at `now` and returns the current FPS.
+    fn tick_at(&mut self, now: Instant) -> f64 {
         self.frame_times.push_back(now);
 
         // Remove old samples
@@ -74,6 +80,58 @@ impl FpsCounter {
         if fps > 0.0 { 1000.0 / fps } else { 0.0 }
     }
 
+    /// Returns the `p`-th percentile (0 to 100) of recent frame times in
+    /// milliseconds, e.g. 99 for the "1% low" frame time. Uses the nearest
+    /// rank, so the result is always one of the recorded frame times.
+    ///
+    /// Returns 0 until two frames have been recorded.
+    #[must_use]
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub fn percentile_ms(&self, p: f64) -> f64 {
+        let mut intervals: Vec<f64> = self.frame_intervals_ms().collect();
+        if intervals.is_empty() {
+            return 0.0;
+        }
+        intervals.sort_by(f64::total_cmp);
+        let rank = (p.clamp(0.0, 100.0) / 100.0 * intervals.len() as f64).ceil() as usize;
+        intervals[rank.clamp(1, intervals.len()) - 1]
+    }
+
+    /// Returns the shortest recent frame time in milliseconds, or 0 until
+    /// two frames have been recorded.
+    #[must_use]
+    pub fn min_frame_time_ms(&self) -> f64 {
+        self.frame_intervals_ms().reduce(f64::min).unwrap_or(0.0)
+    }
+
+    /// Returns the longest recent frame time in milliseconds, or 0 until two
+    /// frames have been recorded.
+    #[must_use]
+    pub fn max_frame_time_ms(&self) -> f64 {
+        self.frame_intervals_ms().reduce(f64::max).unwrap_or(0.0)
+    }
+
+    /// Returns the duration of each recent frame, oldest first: the time
+    /// between each pair of consecutive ticks, one fewer than the frames
+    /// tracked. For drawing frame-time graphs.
+    #[must_use]
+    pub fn frame_durations(&self) -> impl ExactSizeIterator<Item = Duration> + '_ {
+        self.frame_times
+            .iter()
+            .zip(self.frame_times.iter().skip(1))
+            .map(|(earlier, later)| later.duration_since(*earlier))
+    }
+
+    /// Returns [`frame_durations`](Self::frame_durations) in milliseconds.
+    fn frame_intervals_ms(&self) -> impl Iterator<Item = f64> + '_ {
+        self.frame_durations()
+            .map(|duration| duration.as_secs_f64() * 1000.0)
+    }
+
     /// Checks if it's time to log FPS and returns the value if so.
     ///
     /// Returns `Some(fps)` if the log interval has elapsed, `None` otherwise.
@@ -182,6 +240,94 @@ mod tests {
         }
     }
 
+    /// Returns a counter fed frames `intervals_ms` milliseconds apart.
+    fn counter_with_intervals(intervals_ms: &[u64]) -> FpsCounter {
+        let mut counter = FpsCounter::new();
+        let mut now = Instant::now();
+        counter.tick_at(now);
+        for &interval in intervals_ms {
+            now += Duration::from_millis(interval);
+            counter.tick_at(now);
+        }
+        counter
+    }
+
+    #[test]
+    fn percentiles_of_known_intervals() {
+        // A full window of 99 intervals: 97 smooth frames and two hitches
+        let mut intervals = vec![10; 97];
+        intervals.extend([50, 40]);
+        let counter = counter_with_intervals(&intervals);
+
+        assert!((counter.percentile_ms(50.0) - 10.0).abs() < 1e-6);
+        assert!((counter.percentile_ms(97.0) - 10.0).abs() < 1e-6);
+        assert!((counter.percentile_ms(98.0) - 40.0).abs() < 1e-6);
+        assert!((counter.percentile_ms(99.0) - 50.0).abs() < 1e-6);
+        assert!((counter.percentile_ms(100.0) - 50.0).abs() < 1e-6);
+        // Out-of-range percentiles are clamped
+        assert!((counter.percentile_ms(150.0) - 50.0).abs() < 1e-6);
+        assert!((counter.percentile_ms(-5.0) - 10.0).abs() < 1e-6);
+
+        assert!((counter.min_frame_time_ms() - 10.0).abs() < 1e-6);
+        assert!((counter.max_frame_time_ms() - 50.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn percentiles_use_only_recent_frames() {
+        // The early hitch falls out of the 100-frame window
+        let mut intervals = vec![80];
+        intervals.extend([20; 99]);
+        let counter = counter_with_intervals(&intervals);
+
+        assert!((counter.max_frame_time_ms() - 20.0).abs() < 1e-6);
+        assert!((counter.percentile_ms(99.0) - 20.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn frame_durations_follow_the_ticks() {
+        let counter = counter_with_intervals(&[16, 17, 33, 16]);
+        let durations: Vec<_> = counter.frame_durations().collect();
+        assert_eq!(
+            durations,
+            [16, 17, 33, 16].map(Duration::from_millis).to_vec()
+        );
+
+        // The history is bounded by the tracked frames
+        let counter = counter_with_intervals(&[5; 150]);
+        assert_eq!(counter.frame_durations().len(), 99);
+        assert!(
+            counter
+                .frame_durations()
+                .all(|duration| duration == Duration::from_millis(5))
+        );
+    }
+
+    #[test]
+    fn frame_durations_match_real_sleeps() {
+        let mut counter = FpsCounter::new();
+        for _ in 0..5 {
+            counter.tick();
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let durations: Vec<_> = counter.frame_durations().collect();
+        assert_eq!(durations.len(), 4);
+        for duration in durations {
+            assert!(
+                duration >= Duration::from_millis(10) && duration < Duration::from_millis(100),
+                "frame took {duration:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn frame_time_stats_are_zero_without_frames() {
+        let counter = counter_with_intervals(&[]);
+        assert!(counter.percentile_ms(99.0).abs() < f64::EPSILON);
+        assert!(counter.min_frame_time_ms().abs() < f64::EPSILON);
+        assert!(counter.max_frame_time_ms().abs() < f64::EPSILON);
+    }
+
     #[test]
     fn default_matches_new() {
         let counter1 = FpsCounter::new();
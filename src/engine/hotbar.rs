@@ -0,0 +1,63 @@
+//! Hotbar: the blocks on the number keys.
+//!
+//! Keys 1-9 select the blocks in [`HOTBAR`], and the scroll wheel steps
+//! through them. The selected block doesn't have to be in the hotbar (water
+//! is on 0, and `give` can pick any block); then no slot is highlighted.
+
+use crate::world::Block;
+
+/// Number of hotbar slots.
+pub const HOTBAR_SLOTS: usize = 9;
+
+/// Blocks in the hotbar, from left to right.
+pub const HOTBAR: [Block; HOTBAR_SLOTS] = [
+    Block::Stone,
+    Block::Dirt,
+    Block::Grass,
+    Block::Log,
+    Block::Planks,
+    Block::Bricks,
+    Block::Glass,
+    Block::Sand,
+    Block::Cobblestone,
+];
+
+/// Returns the slot holding `block`, if it is in the hotbar.
+#[must_use]
+pub fn hotbar_slot(block: Block) -> Option<usize> {
+    HOTBAR.iter().position(|&slot| slot == block)
+}
+
+/// Returns the block `steps` slots to the right of `block` (left when
+/// negative), wrapping around the ends. Blocks outside the hotbar start
+/// from the first slot.
+#[must_use]
+pub fn scroll_hotbar(block: Block, steps: i32) -> Block {
+    let slot = hotbar_slot(block).unwrap_or(0);
+    let slots = i64::try_from(HOTBAR_SLOTS).unwrap_or(i64::MAX);
+    let target = (i64::try_from(slot).unwrap_or(0) + i64::from(steps)).rem_euclid(slots);
+    HOTBAR[usize::try_from(target).unwrap_or(0)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slots_match_their_blocks() {
+        for (slot, &block) in HOTBAR.iter().enumerate() {
+            assert_eq!(hotbar_slot(block), Some(slot));
+        }
+        assert_eq!(hotbar_slot(Block::Water), None);
+    }
+
+    #[test]
+    fn scrolling_wraps_around_the_ends() {
+        assert_eq!(scroll_hotbar(Block::Stone, 1), Block::Dirt);
+        assert_eq!(scroll_hotbar(Block::Stone, -1), Block::Cobblestone);
+        assert_eq!(scroll_hotbar(Block::Cobblestone, 1), Block::Stone);
+        assert_eq!(scroll_hotbar(Block::Dirt, 19), Block::Grass);
+        // Blocks outside the hotbar scroll from the first slot
+        assert_eq!(scroll_hotbar(Block::Water, 2), Block::Grass);
+    }
+}
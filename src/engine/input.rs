@@ -1,11 +1,22 @@
 //! Input handling module.
 //!
 //! Tracks keyboard and mouse state with support for querying
-//! pressed, just_pressed, and just_released states.
+//! pressed, `just_pressed`, and `just_released` states, along with mouse
+//! double-clicks and how long buttons have been held.
+//! Movement and other game controls are looked up through [`KeyBindings`],
+//! so they can be remapped.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 use winit::keyboard::KeyCode;
 
+/// Frames a buffered press stays available after the frame it happened in.
+pub const DEFAULT_BUFFER_FRAMES: u32 = 3;
+
+/// Longest gap between two presses of a mouse button that makes a
+/// double-click.
+pub const DEFAULT_DOUBLE_CLICK_WINDOW: Duration = Duration::from_millis(300);
+
 /// Mouse button identifiers.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MouseButton {
@@ -28,6 +39,100 @@ impl From<winit::event::MouseButton> for MouseButton {
     }
 }
 
+/// A key or mouse press that can be buffered until it's handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputAction {
+    /// A key press.
+    Key(KeyCode),
+    /// A mouse button press.
+    Mouse(MouseButton),
+}
+
+/// A game control that keys can be bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameAction {
+    /// Move forward.
+    Forward,
+    /// Move backward.
+    Back,
+    /// Strafe left.
+    Left,
+    /// Strafe right.
+    Right,
+    /// Fly up.
+    Up,
+    /// Fly down.
+    Down,
+    /// Move faster.
+    Sprint,
+    /// Crouch while walking.
+    Crouch,
+    /// Jump while walking; a double tap toggles flying.
+    Jump,
+}
+
+/// Maps game actions to the keys that trigger them.
+///
+/// An action can have several keys (e.g. both Shift keys), and a key can
+/// trigger several actions. The default is WASD to move, Space/Shift to
+/// fly up/down and jump, and Ctrl to sprint and crouch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBindings {
+    /// Keys bound to each action.
+    keys: HashMap<GameAction, Vec<KeyCode>>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        use GameAction as A;
+        use KeyCode as K;
+
+        Self::unbound()
+            .with(A::Forward, &[K::KeyW])
+            .with(A::Back, &[K::KeyS])
+            .with(A::Left, &[K::KeyA])
+            .with(A::Right, &[K::KeyD])
+            .with(A::Up, &[K::Space])
+            .with(A::Down, &[K::ShiftLeft, K::ShiftRight])
+            .with(A::Sprint, &[K::ControlLeft, K::ControlRight])
+            .with(A::Crouch, &[K::ControlLeft, K::ControlRight])
+            .with(A::Jump, &[K::Space])
+    }
+}
+
+impl KeyBindings {
+    /// Creates bindings with no keys bound.
+    #[must_use]
+    pub fn unbound() -> Self {
+        Self {
+            keys: HashMap::new(),
+        }
+    }
+
+    /// Binds `action` to `keys`, replacing its previous keys.
+    #[must_use]
+    pub fn with(mut self, action: GameAction, keys: &[KeyCode]) -> Self {
+        self.bind(action, keys);
+        self
+    }
+
+    /// Binds `action` to `keys`, replacing its previous keys. An empty
+    /// slice unbinds it.
+    pub fn bind(&mut self, action: GameAction, keys: &[KeyCode]) {
+        if keys.is_empty() {
+            self.keys.remove(&action);
+        } else {
+            self.keys.insert(action, keys.to_vec());
+        }
+    }
+
+    /// Returns the keys bound to `action`.
+    #[must_use]
+    pub fn keys(&self, action: GameAction) -> &[KeyCode] {
+        self.keys.get(&action).map_or(&[], Vec::as_slice)
+    }
+}
+
 /// Tracks all input state for keyboard and mouse.
 #[derive(Debug, Default)]
 pub struct InputState {
@@ -44,6 +149,18 @@ pub struct InputState {
     mouse_pressed: HashSet<MouseButton>,
     /// Mouse buttons released this frame.
     mouse_released: HashSet<MouseButton>,
+    /// Mouse buttons double-clicked this frame.
+    mouse_double_clicked: HashSet<MouseButton>,
+    /// When each held mouse button went down.
+    mouse_pressed_at: HashMap<MouseButton, Instant>,
+    /// When each mouse button was last pressed, if that press could start
+    /// a double-click.
+    last_clicks: HashMap<MouseButton, Instant>,
+    /// Longest gap between the presses of a double-click.
+    double_click_window: Duration,
+    /// Start of the current frame, from [`begin_frame`](Self::begin_frame);
+    /// presses are timed by the frame they arrive in.
+    now: Option<Instant>,
 
     /// Mouse position in screen coordinates.
     mouse_position: (f64, f64),
@@ -56,21 +173,69 @@ pub struct InputState {
 
     /// Whether the cursor is locked (for FPS controls).
     cursor_locked: bool,
+
+    /// Whether typed text is being captured (e.g. by the console).
+    text_capture: bool,
+    /// Text typed since it was last taken.
+    text: String,
+
+    /// Buffered presses and the frames each has left before expiring.
+    buffered: HashMap<InputAction, u32>,
+    /// Frames a buffered press outlives the frame it happened in.
+    buffer_frames: u32,
+
+    /// Keys bound to game actions.
+    bindings: KeyBindings,
 }
 
 impl InputState {
-    /// Creates a new input state tracker.
+    /// Creates a new input state tracker buffering presses for
+    /// [`DEFAULT_BUFFER_FRAMES`] and detecting double-clicks within
+    /// [`DEFAULT_DOUBLE_CLICK_WINDOW`].
     #[must_use]
     pub fn new() -> Self {
-        Self::default()
+        Self {
+            buffer_frames: DEFAULT_BUFFER_FRAMES,
+            double_click_window: DEFAULT_DOUBLE_CLICK_WINDOW,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the key bindings.
+    #[must_use]
+    pub fn with_bindings(mut self, bindings: KeyBindings) -> Self {
+        self.bindings = bindings;
+        self
     }
 
-    /// Called at the start of each frame to reset per-frame state.
-    pub fn begin_frame(&mut self) {
+    /// Replaces the key bindings.
+    pub fn set_bindings(&mut self, bindings: KeyBindings) {
+        self.bindings = bindings;
+    }
+
+    /// Returns the key bindings.
+    #[must_use]
+    pub const fn bindings(&self) -> &KeyBindings {
+        &self.bindings
+    }
+
+    /// Called at the start of each frame, at `now`, to reset per-frame
+    /// state.
+    ///
+    /// Buffered presses age by one frame; those past the window expire.
+    /// Presses recorded until the next call are timed at `now`.
+    pub fn begin_frame(&mut self, now: Instant) {
+        self.now = Some(now);
+        self.buffered.retain(|_, frames_left| {
+            let alive = *frames_left > 0;
+            *frames_left = frames_left.saturating_sub(1);
+            alive
+        });
         self.keys_pressed.clear();
         self.keys_released.clear();
         self.mouse_pressed.clear();
         self.mouse_released.clear();
+        self.mouse_double_clicked.clear();
         self.mouse_delta = (0.0, 0.0);
         self.scroll_delta = (0.0, 0.0);
     }
@@ -89,10 +254,28 @@ impl InputState {
         self.keys_released.insert(key);
     }
 
-    /// Records a mouse button press event.
+    /// Records a mouse button press event. New presses are buffered (see
+    /// [`take_buffered`](Self::take_buffered)).
+    ///
+    /// A press within the double-click window of the last one is a
+    /// double-click; a third press starts a new one.
     pub fn mouse_button_pressed(&mut self, button: MouseButton) {
         if !self.mouse_held.contains(&button) {
             self.mouse_pressed.insert(button);
+            self.buffer_action(InputAction::Mouse(button));
+            if let Some(now) = self.now {
+                self.mouse_pressed_at.insert(button, now);
+                match self.last_clicks.remove(&button) {
+                    Some(last)
+                        if now.saturating_duration_since(last) <= self.double_click_window =>
+                    {
+                        self.mouse_double_clicked.insert(button);
+                    }
+                    _ => {
+                        self.last_clicks.insert(button, now);
+                    }
+                }
+            }
         }
         self.mouse_held.insert(button);
     }
@@ -100,9 +283,64 @@ impl InputState {
     /// Records a mouse button release event.
     pub fn mouse_button_released(&mut self, button: MouseButton) {
         self.mouse_held.remove(&button);
+        self.mouse_pressed_at.remove(&button);
         self.mouse_released.insert(button);
     }
 
+    /// Sets the longest gap between two presses of a mouse button that
+    /// makes a double-click.
+    pub const fn set_double_click_window(&mut self, window: Duration) {
+        self.double_click_window = window;
+    }
+
+    /// Marks this frame's press of `key` as handled, so later
+    /// [`is_key_just_pressed`](Self::is_key_just_pressed) queries return
+    /// false. The held state is unaffected.
+    ///
+    /// Returns true if the key was just pressed.
+    pub fn consume_key(&mut self, key: KeyCode) -> bool {
+        self.keys_pressed.remove(&key)
+    }
+
+    /// Marks this frame's press of `button` as handled, so later
+    /// [`is_mouse_just_pressed`](Self::is_mouse_just_pressed) queries return
+    /// false. The held state is unaffected.
+    ///
+    /// UI layers consume the clicks they handle before world interaction runs.
+    /// The buffered press is dropped too.
+    /// Returns true if the button was just pressed.
+    pub fn consume_mouse_button(&mut self, button: MouseButton) -> bool {
+        self.buffered.remove(&InputAction::Mouse(button));
+        self.mouse_pressed.remove(&button)
+    }
+
+    /// Sets how many frames a buffered press outlives the frame it happened
+    /// in. 0 keeps it for its own frame only.
+    pub const fn set_buffer_frames(&mut self, frames: u32) {
+        self.buffer_frames = frames;
+    }
+
+    /// Returns how many frames a buffered press outlives its own frame.
+    #[must_use]
+    pub const fn buffer_frames(&self) -> u32 {
+        self.buffer_frames
+    }
+
+    /// Buffers a press so it stays available to
+    /// [`take_buffered`](Self::take_buffered) for the buffer window, even if
+    /// nothing handles it this frame. Re-buffering restarts the window.
+    ///
+    /// Mouse presses are buffered automatically.
+    pub fn buffer_action(&mut self, action: InputAction) {
+        self.buffered.insert(action, self.buffer_frames);
+    }
+
+    /// Takes a buffered press, returning true if one was pending. Each press
+    /// is only taken once.
+    pub fn take_buffered(&mut self, action: InputAction) -> bool {
+        self.buffered.remove(&action).is_some()
+    }
+
     /// Records mouse movement.
     pub fn mouse_moved(&mut self, position: (f64, f64)) {
         self.mouse_position = position;
@@ -130,6 +368,25 @@ impl InputState {
         }
     }
 
+    /// Starts or stops capturing typed text. Any pending text is dropped.
+    pub fn set_text_capture(&mut self, capture: bool) {
+        self.text_capture = capture;
+        self.text.clear();
+    }
+
+    /// Records typed text. Ignored unless text is being captured; control
+    /// characters (Enter, Backspace, ...) are dropped.
+    pub fn text_entered(&mut self, text: &str) {
+        if self.text_capture {
+            self.text.extend(text.chars().filter(|c| !c.is_control()));
+        }
+    }
+
+    /// Takes the text typed since the last call.
+    pub fn take_text(&mut self) -> String {
+        std::mem::take(&mut self.text)
+    }
+
     // --- Query methods ---
 
     /// Returns true if the key is currently held down.
@@ -168,6 +425,23 @@ impl InputState {
         self.is_mouse_just_pressed(button)
     }
 
+    /// Returns true if the mouse button was double-clicked this frame: its
+    /// second press came within the double-click window of the first.
+    #[must_use]
+    pub fn is_mouse_double_click(&self, button: MouseButton) -> bool {
+        self.mouse_double_clicked.contains(&button)
+    }
+
+    /// Returns how long the mouse button has been held, as of the start of
+    /// this frame, or zero if it isn't held.
+    #[must_use]
+    pub fn mouse_hold_duration(&self, button: MouseButton) -> Duration {
+        match (self.mouse_pressed_at.get(&button), self.now) {
+            (Some(&pressed), Some(now)) => now.saturating_duration_since(pressed),
+            _ => Duration::ZERO,
+        }
+    }
+
     /// Returns true if the mouse button was just released this frame.
     #[must_use]
     pub fn is_mouse_just_released(&self, button: MouseButton) -> bool {
@@ -205,47 +479,58 @@ impl InputState {
         self.cursor_locked
     }
 
+    /// Returns whether typed text is being captured.
+    #[must_use]
+    pub const fn is_capturing_text(&self) -> bool {
+        self.text_capture
+    }
+
+    /// Returns true if any key bound to `action` is held.
+    #[must_use]
+    pub fn is_action_held(&self, action: GameAction) -> bool {
+        self.bindings
+            .keys(action)
+            .iter()
+            .any(|&key| self.is_key_held(key))
+    }
+
+    /// Returns true if any key bound to `action` was just pressed this frame.
+    #[must_use]
+    pub fn is_action_just_pressed(&self, action: GameAction) -> bool {
+        self.bindings
+            .keys(action)
+            .iter()
+            .any(|&key| self.is_key_just_pressed(key))
+    }
+
     // --- Convenience methods for common game inputs ---
 
-    /// Returns the movement direction based on WASD keys.
+    /// Returns the movement direction from the bound movement keys.
     /// Returns (right, up, forward) where each component is -1, 0, or 1.
     #[must_use]
     pub fn movement_direction(&self) -> glam::Vec3 {
-        let mut direction = glam::Vec3::ZERO;
+        let axis = |positive, negative| {
+            f32::from(u8::from(self.is_action_held(positive)))
+                - f32::from(u8::from(self.is_action_held(negative)))
+        };
 
-        if self.is_key_held(KeyCode::KeyW) {
-            direction.z += 1.0;
-        }
-        if self.is_key_held(KeyCode::KeyS) {
-            direction.z -= 1.0;
-        }
-        if self.is_key_held(KeyCode::KeyA) {
-            direction.x -= 1.0;
-        }
-        if self.is_key_held(KeyCode::KeyD) {
-            direction.x += 1.0;
-        }
-        if self.is_key_held(KeyCode::Space) {
-            direction.y += 1.0; // Fly up
-        }
-        if self.is_key_held(KeyCode::ShiftLeft) || self.is_key_held(KeyCode::ShiftRight) {
-            direction.y -= 1.0; // Fly down (Shift - like Minecraft creative)
-        }
-        // TODO: Add is_flying flag - when walking, Shift = crouch instead
-
-        direction
+        glam::Vec3::new(
+            axis(GameAction::Right, GameAction::Left),
+            axis(GameAction::Up, GameAction::Down),
+            axis(GameAction::Forward, GameAction::Back),
+        )
     }
 
-    /// Returns true if the sprint key is held (Ctrl in fly mode).
+    /// Returns true if the sprint key is held.
     #[must_use]
     pub fn is_sprinting(&self) -> bool {
-        self.is_key_held(KeyCode::ControlLeft) || self.is_key_held(KeyCode::ControlRight)
+        self.is_action_held(GameAction::Sprint)
     }
 
     /// Returns true if the crouch key is held.
     #[must_use]
     pub fn is_crouching(&self) -> bool {
-        self.is_key_held(KeyCode::ControlLeft) || self.is_key_held(KeyCode::ControlRight)
+        self.is_action_held(GameAction::Crouch)
     }
 }
 
@@ -276,7 +561,7 @@ mod tests {
         let mut input = InputState::new();
 
         input.key_pressed(KeyCode::KeyW);
-        input.begin_frame();
+        input.begin_frame(Instant::now());
         input.key_released(KeyCode::KeyW);
 
         assert!(!input.is_key_held(KeyCode::KeyW));
@@ -291,7 +576,7 @@ mod tests {
         input.key_pressed(KeyCode::KeyW);
         assert!(input.is_key_just_pressed(KeyCode::KeyW));
 
-        input.begin_frame();
+        input.begin_frame(Instant::now());
         // Key still held but not "just pressed"
         assert!(input.is_key_held(KeyCode::KeyW));
         assert!(!input.is_key_just_pressed(KeyCode::KeyW));
@@ -305,12 +590,45 @@ mod tests {
         assert!(input.is_mouse_held(MouseButton::Left));
         assert!(input.is_mouse_just_pressed(MouseButton::Left));
 
-        input.begin_frame();
+        input.begin_frame(Instant::now());
         input.mouse_button_released(MouseButton::Left);
         assert!(!input.is_mouse_held(MouseButton::Left));
         assert!(input.is_mouse_just_released(MouseButton::Left));
     }
 
+    #[test]
+    fn consumed_press_is_not_just_pressed() {
+        let mut input = InputState::new();
+
+        input.mouse_button_pressed(MouseButton::Left);
+        assert!(input.consume_mouse_button(MouseButton::Left));
+        assert!(!input.is_mouse_just_pressed(MouseButton::Left));
+        assert!(input.is_mouse_held(MouseButton::Left));
+
+        // Nothing left to consume
+        assert!(!input.consume_mouse_button(MouseButton::Left));
+
+        input.key_pressed(KeyCode::KeyE);
+        assert!(input.consume_key(KeyCode::KeyE));
+        assert!(!input.is_key_just_pressed(KeyCode::KeyE));
+        assert!(input.is_key_held(KeyCode::KeyE));
+    }
+
+    #[test]
+    fn text_is_only_captured_when_enabled() {
+        let mut input = InputState::new();
+
+        input.text_entered("w");
+        assert_eq!(input.take_text(), "");
+
+        input.set_text_capture(true);
+        input.text_entered("tp 1");
+        input.text_entered("\r");
+        input.text_entered(" 2");
+        assert_eq!(input.take_text(), "tp 1 2");
+        assert_eq!(input.take_text(), "");
+    }
+
     #[test]
     fn mouse_delta_accumulation() {
         let mut input = InputState::new();
@@ -353,6 +671,30 @@ mod tests {
         assert!((dir.z).abs() < 0.001); // Cancelled
     }
 
+    #[test]
+    fn remapped_forward_moves_with_arrow_key() {
+        let bindings = KeyBindings::default().with(GameAction::Forward, &[KeyCode::ArrowUp]);
+        let mut input = InputState::new().with_bindings(bindings);
+
+        input.key_pressed(KeyCode::KeyW);
+        assert_eq!(input.movement_direction(), glam::Vec3::ZERO);
+
+        input.key_pressed(KeyCode::ArrowUp);
+        assert_eq!(input.movement_direction(), glam::Vec3::Z);
+        assert!(input.is_action_just_pressed(GameAction::Forward));
+    }
+
+    #[test]
+    fn unbound_action_is_never_held() {
+        let mut bindings = KeyBindings::default();
+        bindings.bind(GameAction::Sprint, &[]);
+        let mut input = InputState::new().with_bindings(bindings);
+
+        input.key_pressed(KeyCode::ControlLeft);
+        assert!(!input.is_sprinting());
+        assert!(input.is_crouching());
+    }
+
     #[test]
     fn sprint_with_ctrl() {
         let mut input = InputState::new();
@@ -366,6 +708,33 @@ mod tests {
         assert!(!input.is_sprinting());
     }
 
+    #[test]
+    fn buffered_click_survives_until_taken_or_expired() {
+        let mut input = InputState::new();
+        input.set_buffer_frames(2);
+        let left = InputAction::Mouse(MouseButton::Left);
+
+        // Not handled on the frame it happened, still there the next frame
+        input.mouse_button_pressed(MouseButton::Left);
+        input.begin_frame(Instant::now());
+        assert!(!input.is_mouse_just_pressed(MouseButton::Left));
+        assert!(input.take_buffered(left));
+        assert!(!input.take_buffered(left));
+
+        // Never handled: gone once the window has passed
+        input.mouse_button_released(MouseButton::Left);
+        input.mouse_button_pressed(MouseButton::Left);
+        input.begin_frame(Instant::now());
+        input.begin_frame(Instant::now());
+        input.begin_frame(Instant::now());
+        assert!(!input.take_buffered(left));
+
+        // Keys are buffered on request
+        input.buffer_action(InputAction::Key(KeyCode::KeyE));
+        input.begin_frame(Instant::now());
+        assert!(input.take_buffered(InputAction::Key(KeyCode::KeyE)));
+    }
+
     #[test]
     fn scroll_delta() {
         let mut input = InputState::new();
@@ -376,8 +745,77 @@ mod tests {
         let scroll = input.get_scroll_delta();
         assert!((scroll.1 - 1.5).abs() < 0.001);
 
-        input.begin_frame();
+        input.begin_frame(Instant::now());
         let scroll2 = input.get_scroll_delta();
         assert!((scroll2.1).abs() < 0.001); // Reset
     }
+
+    #[test]
+    fn second_press_within_the_window_double_clicks() {
+        let mut input = InputState::new();
+        let start = Instant::now();
+        let left = MouseButton::Left;
+
+        input.begin_frame(start);
+        input.mouse_button_pressed(left);
+        assert!(!input.is_mouse_double_click(left));
+        input.begin_frame(start + Duration::from_millis(100));
+        input.mouse_button_released(left);
+        input.begin_frame(start + Duration::from_millis(200));
+        input.mouse_button_pressed(left);
+        assert!(input.is_mouse_double_click(left));
+        assert!(!input.is_mouse_double_click(MouseButton::Right));
+
+        // Only for the frame it happened in
+        input.begin_frame(start + Duration::from_millis(250));
+        assert!(!input.is_mouse_double_click(left));
+
+        // A third press starts a new double-click instead of making another
+        input.mouse_button_released(left);
+        input.begin_frame(start + Duration::from_millis(300));
+        input.mouse_button_pressed(left);
+        assert!(!input.is_mouse_double_click(left));
+    }
+
+    #[test]
+    fn presses_outside_the_window_are_single_clicks() {
+        let mut input = InputState::new();
+        input.set_double_click_window(Duration::from_millis(200));
+        let start = Instant::now();
+        let left = MouseButton::Left;
+
+        input.begin_frame(start);
+        input.mouse_button_pressed(left);
+        input.mouse_button_released(left);
+        input.begin_frame(start + Duration::from_millis(250));
+        input.mouse_button_pressed(left);
+        assert!(input.is_mouse_just_pressed(left));
+        assert!(!input.is_mouse_double_click(left));
+
+        // That press can still start a double-click of its own
+        input.mouse_button_released(left);
+        input.begin_frame(start + Duration::from_millis(400));
+        input.mouse_button_pressed(left);
+        assert!(input.is_mouse_double_click(left));
+    }
+
+    #[test]
+    fn hold_duration_counts_from_the_press() {
+        let mut input = InputState::new();
+        let start = Instant::now();
+        let left = MouseButton::Left;
+        assert_eq!(input.mouse_hold_duration(left), Duration::ZERO);
+
+        input.begin_frame(start);
+        input.mouse_button_pressed(left);
+        assert_eq!(input.mouse_hold_duration(left), Duration::ZERO);
+        input.begin_frame(start + Duration::from_millis(500));
+        // Repeated press events while held don't restart the hold
+        input.mouse_button_pressed(left);
+        input.begin_frame(start + Duration::from_millis(750));
+        assert_eq!(input.mouse_hold_duration(left), Duration::from_millis(750));
+
+        input.mouse_button_released(left);
+        assert_eq!(input.mouse_hold_duration(left), Duration::ZERO);
+    }
 }
@@ -0,0 +1,150 @@
+//! Mining: breaking blocks over time.
+//!
+//! Holding the break button on a block accumulates time; the block breaks
+//! once that reaches its [`hardness`](crate::world::BlockProperties::hardness).
+//! Looking at a different block starts over, and unbreakable blocks never
+//! make progress.
+
+use crate::world::{Block, BlockPos};
+
+/// Number of crack stages drawn while a block is being mined.
+pub const CRACK_STAGES: u32 = 10;
+
+/// Progress on breaking the targeted block.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Mining {
+    /// The block being mined, if any.
+    target: Option<BlockPos>,
+    /// Seconds of mining the target needs.
+    hardness: f32,
+    /// Seconds spent mining the target so far.
+    elapsed: f32,
+}
+
+impl Mining {
+    /// Creates an idle mining state.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            target: None,
+            hardness: 0.0,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Mines `block` at `pos` for another `delta_time` seconds. Returns true
+    /// once it should break, after which mining starts over.
+    pub fn mine(&mut self, pos: BlockPos, block: Block, delta_time: f32) -> bool {
+        let properties = block.properties();
+        if !properties.is_breakable {
+            self.reset();
+            return false;
+        }
+        if self.target != Some(pos) {
+            self.target = Some(pos);
+            self.hardness = properties.hardness;
+            self.elapsed = 0.0;
+        }
+
+        self.elapsed += delta_time;
+        if self.elapsed >= self.hardness {
+            self.reset();
+            return true;
+        }
+        false
+    }
+
+    /// Stops mining, dropping any progress.
+    pub const fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Returns the block being mined, if any.
+    #[must_use]
+    pub const fn target(&self) -> Option<BlockPos> {
+        self.target
+    }
+
+    /// Returns how far along the target is, from 0 to 1.
+    #[must_use]
+    pub fn progress(&self) -> f32 {
+        if self.target.is_none() || self.hardness <= 0.0 {
+            return 0.0;
+        }
+        (self.elapsed / self.hardness).clamp(0.0, 1.0)
+    }
+
+    /// Returns the crack stage to draw on the target, from 0 to
+    /// `CRACK_STAGES - 1`, or `None` when nothing is being mined.
+    #[must_use]
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub fn crack_stage(&self) -> Option<u32> {
+        self.target?;
+        let stage = (self.progress() * CRACK_STAGES as f32) as u32;
+        Some(stage.min(CRACK_STAGES - 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn breaks_once_hardness_is_reached() {
+        let pos = BlockPos::new(0, 64, 0);
+        let hardness = Block::Stone.properties().hardness;
+        let mut mining = Mining::new();
+
+        assert!(!mining.mine(pos, Block::Stone, hardness * 0.5));
+        assert!((mining.progress() - 0.5).abs() < 1e-6);
+        assert_eq!(mining.crack_stage(), Some(CRACK_STAGES / 2));
+
+        assert!(mining.mine(pos, Block::Stone, hardness * 0.5));
+        // Breaking starts over
+        assert_eq!(mining.target(), None);
+        assert_eq!(mining.crack_stage(), None);
+    }
+
+    #[test]
+    fn changing_target_resets_progress() {
+        let first = BlockPos::new(0, 64, 0);
+        let second = BlockPos::new(1, 64, 0);
+        let hardness = Block::Stone.properties().hardness;
+        let mut mining = Mining::new();
+
+        assert!(!mining.mine(first, Block::Stone, hardness * 0.9));
+        assert!(!mining.mine(second, Block::Stone, hardness * 0.2));
+        assert_eq!(mining.target(), Some(second));
+        assert!((mining.progress() - 0.2).abs() < 1e-6);
+
+        // Going back to the first block doesn't pick up where it left off
+        assert!(!mining.mine(first, Block::Stone, hardness * 0.2));
+        assert!((mining.progress() - 0.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn reset_drops_progress() {
+        let pos = BlockPos::new(0, 64, 0);
+        let mut mining = Mining::new();
+        mining.mine(pos, Block::Stone, 0.5);
+
+        mining.reset();
+        assert_eq!(mining.target(), None);
+        assert!(mining.progress().abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn unbreakable_blocks_never_break() {
+        let pos = BlockPos::new(0, 0, 0);
+        let mut mining = Mining::new();
+
+        for _ in 0..100 {
+            assert!(!mining.mine(pos, Block::Bedrock, 10.0));
+        }
+        assert_eq!(mining.target(), None);
+    }
+}
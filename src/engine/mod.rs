@@ -5,36 +5,64 @@
 pub mod app;
 pub mod camera;
 pub mod chunk_renderer;
+pub mod console;
+pub mod dynamic_resolution;
+pub mod error;
+pub mod font;
 pub mod fps_counter;
+pub mod hotbar;
 pub mod input;
+pub mod mining;
+pub mod offscreen;
 pub mod overlay;
+pub mod physics;
+pub mod player;
 pub mod renderer;
+pub mod scaled_target;
+pub mod settings;
+pub mod shadow;
+pub mod sky;
+pub mod timestep;
 pub mod window;
 pub mod wireframe;
 
-use anyhow::Result;
+use std::path::PathBuf;
+
+use glam::Vec3;
 use tracing::info;
 
-use app::App;
+use crate::world::{BlockRegistry, ChunkManagerConfig};
+use app::{App, DEFAULT_SPAWN, default_chunk_manager_config};
+use camera::CameraConfig;
+pub use error::EngineError;
 use renderer::RendererConfig;
+use settings::Settings;
 use window::{WindowConfig, create_event_loop};
 
 /// The main game engine.
 ///
 /// This struct manages the game loop and coordinates all subsystems.
+/// Use [`Engine::builder`] to configure it.
 pub struct Engine {
     /// Window configuration.
     window_config: WindowConfig,
     /// Renderer configuration.
     renderer_config: RendererConfig,
+    /// Camera configuration.
+    camera_config: CameraConfig,
+    /// Chunk loading, world seed and save directory.
+    chunk_manager_config: ChunkManagerConfig,
+    /// Where the camera starts.
+    spawn: Vec3,
+    /// Player settings.
+    settings: Settings,
+    /// Block types to install before the world loads, if not the built-ins.
+    blocks: Option<BlockRegistry>,
 }
 
 impl Default for Engine {
     fn default() -> Self {
-        Self {
-            window_config: WindowConfig::default(),
-            renderer_config: RendererConfig::default(),
-        }
+        EngineBuilder::new().finish()
     }
 }
 
@@ -44,40 +72,46 @@ impl Engine {
     /// # Errors
     ///
     /// Returns an error if engine initialization fails.
-    pub fn new() -> Result<Self> {
-        info!("Initializing Voxel Forge engine...");
-        Ok(Self::default())
+    pub fn new() -> Result<Self, EngineError> {
+        Self::builder().build()
+    }
+
+    /// Returns a builder covering every engine setting.
+    #[must_use]
+    pub fn builder() -> EngineBuilder {
+        EngineBuilder::new()
     }
 
     /// Creates a new engine instance with custom configuration.
     #[must_use]
-    pub const fn with_config(window_config: WindowConfig, renderer_config: RendererConfig) -> Self {
-        Self {
-            window_config,
-            renderer_config,
-        }
+    pub fn with_config(window_config: WindowConfig, renderer_config: RendererConfig) -> Self {
+        Self::builder()
+            .window(window_config)
+            .renderer(renderer_config)
+            .finish()
+    }
+
+    /// Returns a builder holding this engine's settings.
+    const fn into_builder(self) -> EngineBuilder {
+        EngineBuilder { engine: self }
     }
 
     /// Sets the window title.
     #[must_use]
-    pub fn with_title(mut self, title: impl Into<String>) -> Self {
-        self.window_config.title = title.into();
-        self
+    pub fn with_title(self, title: impl Into<String>) -> Self {
+        self.into_builder().title(title).finish()
     }
 
     /// Sets the initial window size.
     #[must_use]
-    pub const fn with_size(mut self, width: u32, height: u32) -> Self {
-        self.window_config.width = width;
-        self.window_config.height = height;
-        self
+    pub fn with_size(self, width: u32, height: u32) -> Self {
+        self.into_builder().size(width, height).finish()
     }
 
     /// Sets the clear color (RGBA values from 0.0 to 1.0).
     #[must_use]
-    pub fn with_clear_color(mut self, r: f64, g: f64, b: f64, a: f64) -> Self {
-        self.renderer_config.clear_color = wgpu::Color { r, g, b, a };
-        self
+    pub fn with_clear_color(self, r: f64, g: f64, b: f64, a: f64) -> Self {
+        self.into_builder().clear_color(r, g, b, a).finish()
     }
 
     /// Runs the main game loop.
@@ -86,12 +120,28 @@ impl Engine {
     ///
     /// # Errors
     ///
-    /// Returns an error if the game loop encounters a fatal error.
-    pub fn run(self) -> Result<()> {
+    /// Returns [`EngineError::EventLoop`] if the event loop can't be created
+    /// or exits with an error, and [`EngineError::InvalidConfig`] if a block
+    /// registry was set but another one is already in use.
+    pub fn run(self) -> Result<(), EngineError> {
         info!("Starting Voxel Forge...");
 
+        if let Some(blocks) = self.blocks {
+            info!("Registered {} block types", blocks.len());
+            blocks.install().map_err(|_| {
+                EngineError::InvalidConfig("a block registry is already in use".to_string())
+            })?;
+        }
+
         let event_loop = create_event_loop()?;
-        let mut app = App::new(self.window_config, self.renderer_config);
+        let mut app = App::with_world(
+            self.window_config,
+            self.renderer_config,
+            self.camera_config,
+            self.chunk_manager_config,
+            self.spawn,
+        )
+        .with_settings(self.settings);
 
         event_loop.run_app(&mut app)?;
 
@@ -100,6 +150,192 @@ impl Engine {
     }
 }
 
+/// Builds an [`Engine`], with chained setters for every sub-config.
+///
+/// ```no_run
+/// use glam::Vec3;
+/// use voxel_forge::Engine;
+///
+/// fn main() -> anyhow::Result<()> {
+///     let engine = Engine::builder()
+///         .title("My World")
+///         .seed(1234)
+///         .spawn(Vec3::new(0.0, 90.0, 0.0))
+///         .world_dir("saves/my-world")
+///         .build()?;
+///     engine.run()?;
+///     Ok(())
+/// }
+/// ```
+pub struct EngineBuilder {
+    /// The engine being configured.
+    engine: Engine,
+}
+
+impl Default for EngineBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EngineBuilder {
+    /// Creates a builder with the default settings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            engine: Engine {
+                window_config: WindowConfig::default(),
+                renderer_config: RendererConfig::default(),
+                camera_config: CameraConfig::default(),
+                chunk_manager_config: default_chunk_manager_config(),
+                spawn: DEFAULT_SPAWN,
+                settings: Settings::default(),
+                blocks: None,
+            },
+        }
+    }
+
+    /// Sets the window configuration.
+    #[must_use]
+    pub fn window(mut self, config: WindowConfig) -> Self {
+        self.engine.window_config = config;
+        self
+    }
+
+    /// Sets the renderer configuration.
+    #[must_use]
+    pub fn renderer(mut self, config: RendererConfig) -> Self {
+        self.engine.renderer_config = config;
+        self
+    }
+
+    /// Sets the camera configuration.
+    #[must_use]
+    pub const fn camera(mut self, config: CameraConfig) -> Self {
+        self.engine.camera_config = config;
+        self
+    }
+
+    /// Sets the chunk manager configuration, including the world seed and
+    /// save directory.
+    #[must_use]
+    pub fn chunk_manager(mut self, config: ChunkManagerConfig) -> Self {
+        self.engine.chunk_manager_config = config;
+        self
+    }
+
+    /// Sets the player settings.
+    #[must_use]
+    pub fn settings(mut self, settings: Settings) -> Self {
+        self.engine.settings = settings;
+        self
+    }
+
+    /// Sets the block types, e.g. the built-ins plus blocks added by mods.
+    #[must_use]
+    pub fn blocks(mut self, registry: BlockRegistry) -> Self {
+        self.engine.blocks = Some(registry);
+        self
+    }
+
+    /// Sets the window title.
+    #[must_use]
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.engine.window_config.title = title.into();
+        self
+    }
+
+    /// Sets the initial window size.
+    #[must_use]
+    pub const fn size(mut self, width: u32, height: u32) -> Self {
+        self.engine.window_config.width = width;
+        self.engine.window_config.height = height;
+        self
+    }
+
+    /// Sets the clear color (RGBA values from 0.0 to 1.0).
+    #[must_use]
+    pub const fn clear_color(mut self, r: f64, g: f64, b: f64, a: f64) -> Self {
+        self.engine.renderer_config.clear_color = wgpu::Color { r, g, b, a };
+        self
+    }
+
+    /// Sets the world seed.
+    #[must_use]
+    pub const fn seed(mut self, seed: u32) -> Self {
+        self.engine.chunk_manager_config.seed = seed;
+        self
+    }
+
+    /// Sets where the camera starts.
+    #[must_use]
+    pub const fn spawn(mut self, spawn: Vec3) -> Self {
+        self.engine.spawn = spawn;
+        self
+    }
+
+    /// Sets the starting time of day: 0 is midnight, 0.5 is noon.
+    #[must_use]
+    pub const fn time_of_day(mut self, time: f32) -> Self {
+        self.engine.renderer_config.time_of_day = time;
+        self
+    }
+
+    /// Sets how many seconds a full day takes, or 0 to keep the starting
+    /// time of day.
+    #[must_use]
+    pub const fn day_length(mut self, seconds: f32) -> Self {
+        self.engine.renderer_config.day_length = seconds;
+        self
+    }
+
+    /// Sets the directory chunks are saved to.
+    #[must_use]
+    pub fn world_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.engine.chunk_manager_config.save_dir = Some(dir.into());
+        self
+    }
+
+    /// Returns the engine without validating its settings.
+    fn finish(self) -> Engine {
+        self.engine
+    }
+
+    /// Validates the settings and creates the engine.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EngineError::InvalidConfig`] if the window size is zero,
+    /// the spawn point isn't finite or the fog range is empty or negative.
+    pub fn build(self) -> Result<Engine, EngineError> {
+        let engine = &self.engine;
+        if engine.window_config.width == 0 || engine.window_config.height == 0 {
+            return Err(EngineError::InvalidConfig(format!(
+                "window size {}x{} must be non-zero",
+                engine.window_config.width, engine.window_config.height
+            )));
+        }
+        if !engine.spawn.is_finite() {
+            return Err(EngineError::InvalidConfig(format!(
+                "spawn point {} must be finite",
+                engine.spawn
+            )));
+        }
+        let (fog_start, fog_end) = (
+            engine.renderer_config.fog_start,
+            engine.renderer_config.fog_end,
+        );
+        if !(0.0..fog_end).contains(&fog_start) {
+            return Err(EngineError::InvalidConfig(format!(
+                "fog start {fog_start} must be at least 0 and before fog end {fog_end}"
+            )));
+        }
+
+        info!("Initializing Voxel Forge engine...");
+        Ok(self.finish())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -168,4 +404,47 @@ mod tests {
         assert_eq!(engine.window_config.width, 640);
         assert!((engine.renderer_config.clear_color.r).abs() < 0.001);
     }
+
+    #[test]
+    fn builder_sets_every_sub_config() {
+        let engine = Engine::builder()
+            .size(800, 600)
+            .seed(42)
+            .spawn(Vec3::new(1.0, 100.0, -3.0))
+            .world_dir("saves/test")
+            .build()
+            .unwrap();
+
+        assert_eq!(engine.window_config.width, 800);
+        assert_eq!(engine.window_config.height, 600);
+        assert_eq!(engine.chunk_manager_config.seed, 42);
+        assert_eq!(
+            engine.chunk_manager_config.save_dir,
+            Some(PathBuf::from("saves/test"))
+        );
+        assert_eq!(engine.spawn, Vec3::new(1.0, 100.0, -3.0));
+        // Untouched settings keep the app's defaults
+        assert_eq!(engine.chunk_manager_config.render_distance, 6);
+    }
+
+    #[test]
+    fn builder_rejects_invalid_settings() {
+        assert!(matches!(
+            Engine::builder().size(0, 600).build(),
+            Err(EngineError::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            Engine::builder().spawn(Vec3::NAN).build(),
+            Err(EngineError::InvalidConfig(_))
+        ));
+        let inverted_fog = RendererConfig {
+            fog_start: 100.0,
+            fog_end: 50.0,
+            ..RendererConfig::default()
+        };
+        assert!(matches!(
+            Engine::builder().renderer(inverted_fog).build(),
+            Err(EngineError::InvalidConfig(_))
+        ));
+    }
 }
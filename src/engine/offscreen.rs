@@ -0,0 +1,282 @@
+//! Offscreen rendering and pixel readback.
+//!
+//! Renders into a texture instead of the window surface and copies the
+//! result back to the CPU. This lets rendering be tested without a display,
+//! e.g. in headless CI with a software adapter such as lavapipe.
+
+use image::RgbaImage;
+
+use super::error::EngineError;
+
+/// Depth format used for offscreen passes (matches the chunk pipelines).
+pub const OFFSCREEN_DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+/// Requests a GPU device that isn't tied to any window surface.
+///
+/// # Errors
+///
+/// Returns [`EngineError::NoAdapter`] if no adapter is available, or
+/// [`EngineError::Device`] if the device can't be created.
+pub async fn request_headless_device() -> Result<(wgpu::Device, wgpu::Queue), EngineError> {
+    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+        backends: wgpu::Backends::all(),
+        ..Default::default()
+    });
+
+    let adapter = instance
+        .request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::default(),
+            compatible_surface: None,
+            force_fallback_adapter: false,
+        })
+        .await
+        .ok_or(EngineError::NoAdapter)?;
+
+    let (device, queue) = adapter
+        .request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some("Headless Device"),
+                required_features: wgpu::Features::empty(),
+                required_limits: wgpu::Limits::default(),
+                memory_hints: wgpu::MemoryHints::default(),
+            },
+            None,
+        )
+        .await?;
+
+    Ok((device, queue))
+}
+
+/// Renders one pass into an offscreen texture and reads it back as RGBA.
+///
+/// The pass has a color target in `format`, cleared to `clear_color`, and a
+/// depth target in [`OFFSCREEN_DEPTH_FORMAT`]. `draw` records the draw calls.
+/// BGRA formats are swizzled to RGBA on readback.
+///
+/// # Errors
+///
+/// Returns [`EngineError::Readback`] if the size is zero, the format isn't an
+/// 8-bit RGBA/BGRA format, or the readback buffer can't be mapped.
+pub fn render_to_image<'a, F>(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    format: wgpu::TextureFormat,
+    width: u32,
+    height: u32,
+    clear_color: wgpu::Color,
+    draw: F,
+) -> Result<RgbaImage, EngineError>
+where
+    F: FnOnce(&mut wgpu::RenderPass<'a>),
+{
+    let swap_red_blue = needs_red_blue_swap(format)?;
+    if width == 0 || height == 0 {
+        return Err(EngineError::Readback(format!(
+            "empty size {width}x{height}"
+        )));
+    }
+
+    let size = wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+    let create_target = |label, format, usage| {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage,
+            view_formats: &[],
+        })
+    };
+    let color = create_target(
+        "Offscreen Color Texture",
+        format,
+        wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+    );
+    let depth = create_target(
+        "Offscreen Depth Texture",
+        OFFSCREEN_DEPTH_FORMAT,
+        wgpu::TextureUsages::RENDER_ATTACHMENT,
+    );
+    let color_view = color.create_view(&wgpu::TextureViewDescriptor::default());
+    let depth_view = depth.create_view(&wgpu::TextureViewDescriptor::default());
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Offscreen Encoder"),
+    });
+
+    {
+        // Detach the pass from the encoder borrow so `draw` can bind
+        // resources that live for 'a; the pass is dropped before finish()
+        let mut render_pass: wgpu::RenderPass<'a> = encoder
+            .begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Offscreen Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &color_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(clear_color),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            })
+            .forget_lifetime();
+        draw(&mut render_pass);
+    }
+
+    // Buffer rows must be padded to the copy alignment
+    let unpadded_row = 4 * width;
+    let padded_row = unpadded_row.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+        * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Offscreen Readback Buffer"),
+        size: u64::from(padded_row) * u64::from(height),
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    encoder.copy_texture_to_buffer(
+        color.as_image_copy(),
+        wgpu::TexelCopyBufferInfo {
+            buffer: &buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_row),
+                rows_per_image: Some(height),
+            },
+        },
+        size,
+    );
+    queue.submit(std::iter::once(encoder.finish()));
+
+    let mut pixels = read_rows(device, &buffer, unpadded_row, padded_row)?;
+    if swap_red_blue {
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+    }
+
+    RgbaImage::from_raw(width, height, pixels)
+        .ok_or_else(|| EngineError::Readback("pixel data doesn't match size".to_string()))
+}
+
+/// Returns whether pixels in `format` need red and blue swapped to be RGBA.
+fn needs_red_blue_swap(format: wgpu::TextureFormat) -> Result<bool, EngineError> {
+    use wgpu::TextureFormat::{Bgra8Unorm, Bgra8UnormSrgb, Rgba8Unorm, Rgba8UnormSrgb};
+
+    match format {
+        Rgba8Unorm | Rgba8UnormSrgb => Ok(false),
+        Bgra8Unorm | Bgra8UnormSrgb => Ok(true),
+        _ => Err(EngineError::Readback(format!(
+            "unsupported format {format:?}"
+        ))),
+    }
+}
+
+/// Waits for `buffer` to be mappable and copies out its rows without padding.
+fn read_rows(
+    device: &wgpu::Device,
+    buffer: &wgpu::Buffer,
+    unpadded_row: u32,
+    padded_row: u32,
+) -> Result<Vec<u8>, EngineError> {
+    let slice = buffer.slice(..);
+    let (sender, receiver) = std::sync::mpsc::channel();
+    slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+    device.poll(wgpu::Maintain::Wait);
+    receiver
+        .recv()
+        .map_err(|e| EngineError::Readback(e.to_string()))?
+        .map_err(|e| EngineError::Readback(e.to_string()))?;
+
+    let pixels = {
+        let data = slice.get_mapped_range();
+        data.chunks(padded_row as usize)
+            .flat_map(|row| &row[..unpadded_row as usize])
+            .copied()
+            .collect()
+    };
+    buffer.unmap();
+    Ok(pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+
+    use super::*;
+    use crate::engine::camera::{Camera, CameraConfig};
+    use crate::engine::chunk_renderer::{CameraUniform, ChunkBuffers, ChunkRenderer};
+    use crate::engine::sky::Sky;
+    use crate::world::{Block, Chunk, ChunkPos, MeshGenerator};
+
+    #[test]
+    fn readback_formats() {
+        assert!(!needs_red_blue_swap(wgpu::TextureFormat::Rgba8UnormSrgb).unwrap());
+        assert!(needs_red_blue_swap(wgpu::TextureFormat::Bgra8UnormSrgb).unwrap());
+        assert!(matches!(
+            needs_red_blue_swap(wgpu::TextureFormat::R8Unorm),
+            Err(EngineError::Readback(_))
+        ));
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter (e.g. lavapipe); run with --ignored"]
+    fn single_block_renders_at_screen_center() {
+        let (device, queue) =
+            pollster::block_on(request_headless_device()).expect("no GPU adapter available");
+        let format = wgpu::TextureFormat::Rgba8UnormSrgb;
+        let size = 64;
+
+        let chunk_renderer = ChunkRenderer::new(&device, &queue, format, size, size).unwrap();
+
+        let pos = ChunkPos::new(0, 0);
+        let mut chunk = Chunk::new(pos);
+        chunk.set_block(8, 64, 8, Block::Stone);
+        let buffers = ChunkBuffers::from_mesh(&device, &MeshGenerator::new(chunk).generate());
+
+        // Looking down -Z at the block from 3 blocks away
+        let mut camera =
+            Camera::new(CameraConfig::default()).at_position(Vec3::new(8.5, 64.5, 12.0));
+        camera.set_aspect_ratio(1.0, 1.0);
+        let uniform = CameraUniform::new(camera.view_projection_matrix(), camera.position)
+            .with_sky(Sky::default().uniform());
+        chunk_renderer.update_camera(&queue, &uniform);
+        let frustum = camera.frustum();
+
+        let image = render_to_image(
+            &device,
+            &queue,
+            format,
+            size,
+            size,
+            wgpu::Color::BLACK,
+            |pass| {
+                let stats = chunk_renderer.render(pass, &frustum, [(&pos, &buffers)]);
+                assert_eq!(stats.drawn, 1);
+            },
+        )
+        .unwrap();
+
+        let background = [0, 0, 0, 255];
+        assert_ne!(image.get_pixel(size / 2, size / 2).0, background);
+        assert_eq!(image.get_pixel(0, 0).0, background);
+    }
+}
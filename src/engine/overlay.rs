@@ -1,9 +1,212 @@
 //! 2D overlay rendering for HUD elements.
 //!
-//! Renders simple 2D shapes like crosshairs directly to the screen.
+//! Renders simple 2D shapes like crosshairs and the loading bar directly
+//! to the screen, plus text in the built-in bitmap [`font`](super::font)
+//! and the block icons in the hotbar.
+//! Elements are laid out in logical pixels with [`ScreenRect`] and
+//! converted to normalized device coordinates for the current window.
 
+use glam::Vec2;
 use wgpu::util::DeviceExt;
 
+use crate::world::TextureAtlas;
+
+use super::font::{
+    ATLAS_HEIGHT, ATLAS_WIDTH, GLYPH_ADVANCE, GLYPH_HEIGHT, GLYPH_WIDTH, LINE_HEIGHT, atlas_pixels,
+    glyph_uv,
+};
+use super::hotbar::{HOTBAR, HOTBAR_SLOTS};
+
+/// The window's size and DPI scale, for converting pixels to NDC.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    /// Width in physical pixels.
+    pub width: f32,
+    /// Height in physical pixels.
+    pub height: f32,
+    /// Physical pixels per logical pixel.
+    pub scale_factor: f32,
+}
+
+impl Viewport {
+    /// Creates a viewport from a physical size and scale factor.
+    #[must_use]
+    pub const fn new(width: f32, height: f32, scale_factor: f32) -> Self {
+        Self {
+            width,
+            height,
+            scale_factor,
+        }
+    }
+
+    /// Returns the size in logical pixels.
+    #[must_use]
+    pub fn logical_size(&self) -> Vec2 {
+        Vec2::new(self.width, self.height) / self.scale_factor
+    }
+}
+
+/// Reference point an overlay element is positioned against.
+///
+/// The element's own anchor point is placed on the window's anchor point,
+/// so a `BottomCenter` element sits centered on the bottom edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    /// Top-left corner.
+    TopLeft,
+    /// Middle of the top edge.
+    TopCenter,
+    /// Top-right corner.
+    TopRight,
+    /// Middle of the left edge.
+    CenterLeft,
+    /// Center of the window.
+    Center,
+    /// Middle of the right edge.
+    CenterRight,
+    /// Bottom-left corner.
+    BottomLeft,
+    /// Middle of the bottom edge.
+    BottomCenter,
+    /// Bottom-right corner.
+    BottomRight,
+}
+
+impl Anchor {
+    /// Returns the anchor point as a fraction of a size (0 = left/top, 1 = right/bottom).
+    #[must_use]
+    pub const fn factors(self) -> Vec2 {
+        match self {
+            Self::TopLeft => Vec2::new(0.0, 0.0),
+            Self::TopCenter => Vec2::new(0.5, 0.0),
+            Self::TopRight => Vec2::new(1.0, 0.0),
+            Self::CenterLeft => Vec2::new(0.0, 0.5),
+            Self::Center => Vec2::new(0.5, 0.5),
+            Self::CenterRight => Vec2::new(1.0, 0.5),
+            Self::BottomLeft => Vec2::new(0.0, 1.0),
+            Self::BottomCenter => Vec2::new(0.5, 1.0),
+            Self::BottomRight => Vec2::new(1.0, 1.0),
+        }
+    }
+}
+
+/// A rectangle in normalized device coordinates (-1 to 1, Y up).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NdcRect {
+    /// Bottom-left corner.
+    pub min: Vec2,
+    /// Top-right corner.
+    pub max: Vec2,
+}
+
+/// An overlay element's rectangle in logical pixels, relative to an anchor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScreenRect {
+    /// Window point the element is attached to.
+    pub anchor: Anchor,
+    /// Offset from the anchor in logical pixels (+X right, +Y down).
+    pub offset: Vec2,
+    /// Size in logical pixels.
+    pub size: Vec2,
+}
+
+impl ScreenRect {
+    /// Creates a rect of `width` x `height` logical pixels at `anchor`.
+    #[must_use]
+    pub const fn new(anchor: Anchor, width: f32, height: f32) -> Self {
+        Self {
+            anchor,
+            offset: Vec2::ZERO,
+            size: Vec2::new(width, height),
+        }
+    }
+
+    /// Moves the rect by `x`, `y` logical pixels (+Y down).
+    #[must_use]
+    pub const fn offset_by(mut self, x: f32, y: f32) -> Self {
+        self.offset = Vec2::new(self.offset.x + x, self.offset.y + y);
+        self
+    }
+
+    /// Returns the top-left corner in logical pixels from the window's top-left.
+    #[must_use]
+    pub fn top_left(&self, viewport: &Viewport) -> Vec2 {
+        let factors = self.anchor.factors();
+        viewport.logical_size() * factors - self.size * factors + self.offset
+    }
+
+    /// Converts to NDC for the given viewport.
+    #[must_use]
+    pub fn to_ndc(&self, viewport: &Viewport) -> NdcRect {
+        let logical = viewport.logical_size();
+        let to_ndc = |p: Vec2| {
+            Vec2::new(
+                (p.x / logical.x).mul_add(2.0, -1.0),
+                (p.y / logical.y).mul_add(-2.0, 1.0),
+            )
+        };
+
+        let top_left = to_ndc(self.top_left(viewport));
+        let bottom_right = to_ndc(self.top_left(viewport) + self.size);
+        NdcRect {
+            min: Vec2::new(top_left.x, bottom_right.y),
+            max: Vec2::new(bottom_right.x, top_left.y),
+        }
+    }
+}
+
+/// Returns how much to scale the crosshair for the camera's zoom.
+///
+/// With `scale_with_zoom` the crosshair grows as the field of view narrows,
+/// like a scope reticle (half the FOV, twice the size); otherwise it stays
+/// at its normal size.
+#[must_use]
+pub fn crosshair_scale(base_fov: f32, effective_fov: f32, scale_with_zoom: bool) -> f32 {
+    if scale_with_zoom && effective_fov > 0.0 {
+        base_fov / effective_fov
+    } else {
+        1.0
+    }
+}
+
+/// Width of the loading bar's fill in logical pixels.
+const LOADING_BAR_WIDTH: f32 = 300.0;
+
+/// Height of the loading bar's fill in logical pixels.
+const LOADING_BAR_HEIGHT: f32 = 10.0;
+
+/// Vertices in the loading bar: outline, track and fill rects.
+const LOADING_BAR_VERTEX_COUNT: u32 = 18;
+
+/// Text color (RGBA).
+const TEXT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+/// Vertices the text buffer starts with room for.
+const INITIAL_TEXT_VERTICES: usize = 1024;
+
+/// Size of each hotbar slot in logical pixels.
+const HOTBAR_SLOT_SIZE: f32 = 40.0;
+
+/// Space between hotbar slots in logical pixels.
+const HOTBAR_SLOT_GAP: f32 = 4.0;
+
+/// Distance of the hotbar from the bottom of the window in logical pixels.
+const HOTBAR_MARGIN: f32 = 8.0;
+
+/// Space between a slot's edge and its block icon in logical pixels.
+const HOTBAR_ICON_INSET: f32 = 8.0;
+
+/// Width of the selected slot's highlight border in logical pixels.
+const HOTBAR_HIGHLIGHT_BORDER: f32 = 3.0;
+
+/// Vertices in the hotbar: the highlight, then a rect per slot.
+#[allow(clippy::cast_possible_truncation)]
+const HOTBAR_VERTEX_COUNT: u32 = 6 * (HOTBAR_SLOTS as u32 + 1);
+
+/// Vertices in the hotbar's block icons.
+#[allow(clippy::cast_possible_truncation)]
+const HOTBAR_ICON_VERTEX_COUNT: u32 = 6 * HOTBAR_SLOTS as u32;
+
 /// Vertex for 2D overlay rendering.
 #[repr(C)]
 #[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
@@ -46,45 +249,238 @@ impl OverlayVertex {
     }
 }
 
-/// Renders 2D overlay elements like crosshairs.
+/// Vertex for textured overlay quads: text glyphs and hotbar icons.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct TexturedVertex {
+    /// Position in normalized device coordinates (-1 to 1).
+    pub position: [f32; 2],
+    /// Font atlas texture coordinates.
+    pub uv: [f32; 2],
+    /// Color (RGBA).
+    pub color: [f32; 4],
+}
+
+impl TexturedVertex {
+    /// Creates a new text vertex.
+    #[must_use]
+    pub const fn new(x: f32, y: f32, uv: Vec2, color: [f32; 4]) -> Self {
+        Self {
+            position: [x, y],
+            uv: [uv.x, uv.y],
+            color,
+        }
+    }
+
+    /// Returns the vertex buffer layout.
+    #[must_use]
+    pub const fn layout() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+            ],
+        }
+    }
+}
+
+/// Renders 2D overlay elements like crosshairs and text.
 pub struct OverlayRenderer {
-    /// The render pipeline.
+    /// The render pipeline for solid shapes.
     pipeline: wgpu::RenderPipeline,
+    /// The render pipeline for text.
+    text_pipeline: wgpu::RenderPipeline,
+    /// The render pipeline for block icons.
+    icon_pipeline: wgpu::RenderPipeline,
+    /// Font atlas texture and sampler.
+    font_bind_group: wgpu::BindGroup,
+    /// Block texture atlas and sampler.
+    block_atlas_bind_group: wgpu::BindGroup,
+    /// The viewport elements are laid out for.
+    viewport: Viewport,
     /// Crosshair vertex buffer.
     crosshair_buffer: wgpu::Buffer,
     /// Number of crosshair vertices.
     crosshair_vertex_count: u32,
+    /// Current crosshair scale (see [`crosshair_scale`]).
+    crosshair_scale: f32,
+    /// Loading bar vertex buffer.
+    loading_bar_buffer: wgpu::Buffer,
+    /// Fraction of the loading bar that is filled.
+    loading_progress: f32,
+    /// Text vertex buffer, grown as needed.
+    text_buffer: wgpu::Buffer,
+    /// Text queued by [`draw_text`](Self::draw_text) for the next upload.
+    text_vertices: Vec<TexturedVertex>,
+    /// Number of text vertices in the buffer.
+    text_vertex_count: u32,
+    /// Hotbar slot vertex buffer.
+    hotbar_buffer: wgpu::Buffer,
+    /// Hotbar block icon vertex buffer.
+    hotbar_icon_buffer: wgpu::Buffer,
+    /// Highlighted hotbar slot.
+    hotbar_selection: Option<usize>,
 }
 
 impl OverlayRenderer {
-    /// Creates a new overlay renderer.
-    pub fn new(device: &wgpu::Device, surface_format: wgpu::TextureFormat) -> Self {
+    /// Creates a new overlay renderer laid out for the given viewport,
+    /// drawing hotbar icons from `block_atlas`.
+    #[must_use]
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        surface_format: wgpu::TextureFormat,
+        viewport: &Viewport,
+        block_atlas: &wgpu::TextureView,
+    ) -> Self {
         // Create shader
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Overlay Shader"),
             source: wgpu::ShaderSource::Wgsl(include_str!("shaders/overlay.wgsl").into()),
         });
 
-        // Pipeline layout (no bind groups needed)
+        // Shapes need no bind groups
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Overlay Pipeline Layout"),
             bind_group_layouts: &[],
             push_constant_ranges: &[],
         });
 
-        // Render pipeline
-        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        let pipeline = Self::create_pipeline(
+            device,
+            &pipeline_layout,
+            &shader,
+            ("vs_main", "fs_main"),
+            OverlayVertex::layout(),
+            surface_format,
+        );
+
+        // Text samples the font atlas, icons the block atlas
+        let texture_layout = Self::create_texture_layout(device);
+        // Nearest filtering keeps glyph and texture pixels crisp when scaled up
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Overlay Sampler"),
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+        let font_view = Self::create_font_view(device, queue);
+        let font_bind_group =
+            Self::create_texture_bind_group(device, &texture_layout, &font_view, &sampler);
+        let block_atlas_bind_group =
+            Self::create_texture_bind_group(device, &texture_layout, block_atlas, &sampler);
+
+        let textured_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Overlay Textured Pipeline Layout"),
+                bind_group_layouts: &[&texture_layout],
+                push_constant_ranges: &[],
+            });
+        let text_pipeline = Self::create_pipeline(
+            device,
+            &textured_pipeline_layout,
+            &shader,
+            ("vs_textured", "fs_text"),
+            TexturedVertex::layout(),
+            surface_format,
+        );
+        let icon_pipeline = Self::create_pipeline(
+            device,
+            &textured_pipeline_layout,
+            &shader,
+            ("vs_textured", "fs_icon"),
+            TexturedVertex::layout(),
+            surface_format,
+        );
+
+        // Create crosshair geometry
+        let crosshair_vertices = Self::create_crosshair_vertices(viewport, 1.0);
+        #[allow(clippy::cast_possible_truncation)]
+        let crosshair_vertex_count = crosshair_vertices.len() as u32;
+        let crosshair_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Crosshair Buffer"),
+            contents: bytemuck::cast_slice(&crosshair_vertices),
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        });
+
+        // Create loading bar geometry
+        let loading_bar_vertices = Self::create_loading_bar_vertices(viewport, 0.0);
+        let loading_bar_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Loading Bar Buffer"),
+            contents: bytemuck::cast_slice(&loading_bar_vertices),
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        });
+
+        // Create hotbar geometry
+        let hotbar_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Hotbar Buffer"),
+            contents: bytemuck::cast_slice(&Self::create_hotbar_vertices(viewport, None)),
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        });
+        let hotbar_icon_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Hotbar Icon Buffer"),
+            contents: bytemuck::cast_slice(&Self::create_hotbar_icon_vertices(viewport)),
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        });
+
+        Self {
+            pipeline,
+            text_pipeline,
+            icon_pipeline,
+            font_bind_group,
+            block_atlas_bind_group,
+            viewport: *viewport,
+            crosshair_buffer,
+            crosshair_vertex_count,
+            crosshair_scale: 1.0,
+            loading_bar_buffer,
+            loading_progress: 0.0,
+            text_buffer: Self::create_text_buffer(device, INITIAL_TEXT_VERTICES),
+            text_vertices: Vec::new(),
+            text_vertex_count: 0,
+            hotbar_buffer,
+            hotbar_icon_buffer,
+            hotbar_selection: None,
+        }
+    }
+
+    /// Creates an alpha-blended overlay pipeline using the given shader
+    /// entry points (vertex, fragment).
+    fn create_pipeline(
+        device: &wgpu::Device,
+        layout: &wgpu::PipelineLayout,
+        shader: &wgpu::ShaderModule,
+        (vs_entry, fs_entry): (&str, &str),
+        vertex_layout: wgpu::VertexBufferLayout<'_>,
+        surface_format: wgpu::TextureFormat,
+    ) -> wgpu::RenderPipeline {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("Overlay Pipeline"),
-            layout: Some(&pipeline_layout),
+            layout: Some(layout),
             vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: Some("vs_main"),
-                buffers: &[OverlayVertex::layout()],
+                module: shader,
+                entry_point: Some(vs_entry),
+                buffers: &[vertex_layout],
                 compilation_options: Default::default(),
             },
             fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: Some("fs_main"),
+                module: shader,
+                entry_point: Some(fs_entry),
                 targets: &[Some(wgpu::ColorTargetState {
                     format: surface_format,
                     blend: Some(wgpu::BlendState::ALPHA_BLENDING),
@@ -105,99 +501,384 @@ impl OverlayRenderer {
             multisample: wgpu::MultisampleState::default(),
             multiview: None,
             cache: None,
-        });
+        })
+    }
 
-        // Create crosshair geometry
-        let crosshair_vertices = Self::create_crosshair_vertices();
-        let crosshair_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Crosshair Buffer"),
-            contents: bytemuck::cast_slice(&crosshair_vertices),
-            usage: wgpu::BufferUsages::VERTEX,
-        });
+    /// Uploads the font atlas, returning a view of it.
+    fn create_font_view(device: &wgpu::Device, queue: &wgpu::Queue) -> wgpu::TextureView {
+        let texture = device.create_texture_with_data(
+            queue,
+            &wgpu::TextureDescriptor {
+                label: Some("Font Atlas Texture"),
+                size: wgpu::Extent3d {
+                    width: ATLAS_WIDTH,
+                    height: ATLAS_HEIGHT,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::R8Unorm,
+                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            },
+            wgpu::util::TextureDataOrder::LayerMajor,
+            &atlas_pixels(),
+        );
+        texture.create_view(&wgpu::TextureViewDescriptor::default())
+    }
 
-        Self {
-            pipeline,
-            crosshair_buffer,
-            crosshair_vertex_count: crosshair_vertices.len() as u32,
+    /// Creates the bind group layout for a texture and its sampler.
+    fn create_texture_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Overlay Texture Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        })
+    }
+
+    /// Binds `view` and `sampler` for the textured pipelines.
+    fn create_texture_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        view: &wgpu::TextureView,
+        sampler: &wgpu::Sampler,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Overlay Texture Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+            ],
+        })
+    }
+
+    /// Creates a text vertex buffer with room for `vertices` vertices.
+    fn create_text_buffer(device: &wgpu::Device, vertices: usize) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Overlay Text Buffer"),
+            size: (vertices * std::mem::size_of::<TexturedVertex>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Re-lays out overlay elements after the window size or DPI changes.
+    pub fn resize(&mut self, queue: &wgpu::Queue, viewport: &Viewport) {
+        self.viewport = *viewport;
+        let vertices = Self::create_crosshair_vertices(viewport, self.crosshair_scale);
+        queue.write_buffer(&self.crosshair_buffer, 0, bytemuck::cast_slice(&vertices));
+        let vertices = Self::create_loading_bar_vertices(viewport, self.loading_progress);
+        queue.write_buffer(&self.loading_bar_buffer, 0, bytemuck::cast_slice(&vertices));
+        let vertices = Self::create_hotbar_vertices(viewport, self.hotbar_selection);
+        queue.write_buffer(&self.hotbar_buffer, 0, bytemuck::cast_slice(&vertices));
+        let vertices = Self::create_hotbar_icon_vertices(viewport);
+        queue.write_buffer(&self.hotbar_icon_buffer, 0, bytemuck::cast_slice(&vertices));
+    }
+
+    /// Scales the crosshair, re-laying it out if the scale changed.
+    pub fn set_crosshair_scale(&mut self, queue: &wgpu::Queue, viewport: &Viewport, scale: f32) {
+        if (scale - self.crosshair_scale).abs() > f32::EPSILON {
+            self.crosshair_scale = scale;
+            self.resize(queue, viewport);
+        }
+    }
+
+    /// Fills the loading bar to `progress` (0 to 1), re-laying it out if the
+    /// progress changed.
+    pub fn set_loading_progress(
+        &mut self,
+        queue: &wgpu::Queue,
+        viewport: &Viewport,
+        progress: f32,
+    ) {
+        let progress = progress.clamp(0.0, 1.0);
+        if (progress - self.loading_progress).abs() > f32::EPSILON {
+            self.loading_progress = progress;
+            self.resize(queue, viewport);
+        }
+    }
+
+    /// Highlights hotbar `slot` (or none), re-laying out the hotbar if the
+    /// selection changed.
+    pub fn set_hotbar_selection(&mut self, queue: &wgpu::Queue, slot: Option<usize>) {
+        if slot != self.hotbar_selection {
+            self.hotbar_selection = slot;
+            let vertices = Self::create_hotbar_vertices(&self.viewport, slot);
+            queue.write_buffer(&self.hotbar_buffer, 0, bytemuck::cast_slice(&vertices));
         }
     }
 
-    /// Creates crosshair vertices (two crossing rectangles).
-    fn create_crosshair_vertices() -> Vec<OverlayVertex> {
+    /// Queues `text` to be drawn with its top-left corner `x`, `y` logical
+    /// pixels from the window's top-left. Each font pixel covers `scale`
+    /// logical pixels, and `\n` starts a new line.
+    ///
+    /// Queued text is shown by the next [`prepare_text`](Self::prepare_text).
+    pub fn draw_text(&mut self, text: &str, x: f32, y: f32, scale: f32) {
+        Self::add_text(
+            &mut self.text_vertices,
+            &self.viewport,
+            text,
+            Vec2::new(x, y),
+            scale,
+        );
+    }
+
+    /// Uploads the text queued since the last call, replacing what was
+    /// drawn before, and clears the queue.
+    pub fn prepare_text(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        let count = self.text_vertices.len();
+        let size = (count * std::mem::size_of::<TexturedVertex>()) as wgpu::BufferAddress;
+        if size > self.text_buffer.size() {
+            self.text_buffer = Self::create_text_buffer(device, count.next_power_of_two());
+        }
+        if count > 0 {
+            queue.write_buffer(
+                &self.text_buffer,
+                0,
+                bytemuck::cast_slice(&self.text_vertices),
+            );
+        }
+        self.text_vertex_count = u32::try_from(count).unwrap_or(u32::MAX);
+        self.text_vertices.clear();
+    }
+
+    /// Appends a quad per visible character of `text`, laid out from
+    /// `origin` in logical pixels.
+    #[allow(clippy::cast_precision_loss)]
+    fn add_text(
+        vertices: &mut Vec<TexturedVertex>,
+        viewport: &Viewport,
+        text: &str,
+        origin: Vec2,
+        scale: f32,
+    ) {
+        let (width, height) = (GLYPH_WIDTH as f32 * scale, GLYPH_HEIGHT as f32 * scale);
+        let mut pen = origin;
+        for c in text.chars() {
+            if c == '\n' {
+                pen = Vec2::new(origin.x, (LINE_HEIGHT as f32).mul_add(scale, pen.y));
+                continue;
+            }
+            if !c.is_whitespace() {
+                let rect = ScreenRect::new(Anchor::TopLeft, width, height)
+                    .offset_by(pen.x, pen.y)
+                    .to_ndc(viewport);
+                Self::add_textured_rect(vertices, rect, glyph_uv(c), TEXT_COLOR);
+            }
+            pen.x = (GLYPH_ADVANCE as f32).mul_add(scale, pen.x);
+        }
+    }
+
+    /// Appends two triangles covering `rect`, textured with the atlas
+    /// region from `uv.0` (top-left) to `uv.1` (bottom-right).
+    fn add_textured_rect(
+        vertices: &mut Vec<TexturedVertex>,
+        rect: NdcRect,
+        (uv_min, uv_max): (Vec2, Vec2),
+        color: [f32; 4],
+    ) {
+        let NdcRect { min, max } = rect;
+        // Texture V runs down while NDC Y runs up
+        let bottom_left = TexturedVertex::new(min.x, min.y, Vec2::new(uv_min.x, uv_max.y), color);
+        let top_right = TexturedVertex::new(max.x, max.y, Vec2::new(uv_max.x, uv_min.y), color);
+        vertices.extend_from_slice(&[
+            bottom_left,
+            TexturedVertex::new(max.x, min.y, uv_max, color),
+            top_right,
+            bottom_left,
+            top_right,
+            TexturedVertex::new(min.x, max.y, uv_min, color),
+        ]);
+    }
+
+    /// Appends two triangles covering `rect`.
+    fn add_rect(vertices: &mut Vec<OverlayVertex>, rect: NdcRect, color: [f32; 4]) {
+        let NdcRect { min, max } = rect;
+        vertices.extend_from_slice(&[
+            OverlayVertex::new(min.x, min.y, color),
+            OverlayVertex::new(max.x, min.y, color),
+            OverlayVertex::new(max.x, max.y, color),
+            OverlayVertex::new(min.x, min.y, color),
+            OverlayVertex::new(max.x, max.y, color),
+            OverlayVertex::new(min.x, max.y, color),
+        ]);
+    }
+
+    /// Creates crosshair vertices (four arms around a center gap), with
+    /// every dimension multiplied by `scale`.
+    ///
+    /// The vertex count doesn't depend on the viewport or scale, so
+    /// [`resize`](Self::resize) can rewrite the buffer in place.
+    fn create_crosshair_vertices(viewport: &Viewport, scale: f32) -> Vec<OverlayVertex> {
         let color = [1.0, 1.0, 1.0, 0.8]; // White with slight transparency
         let outline = [0.0, 0.0, 0.0, 0.5]; // Black outline
 
-        // Crosshair dimensions in NDC (screen goes from -1 to 1)
-        let size = 0.02; // Length of each arm
-        let thickness = 0.003; // Thickness of lines
-        let gap = 0.005; // Gap in the center
-        let outline_t = 0.001; // Outline thickness
+        // Crosshair dimensions in logical pixels
+        let arm = 7.0 * scale; // Length of each arm
+        let thickness = 2.0 * scale; // Thickness of lines
+        let gap = 2.0 * scale; // Gap between the center and each arm
+        let border = 1.0 * scale; // Outline thickness
+
+        // Arms as (width, height, offset from center)
+        let reach = gap + arm / 2.0;
+        let arms = [
+            (arm, thickness, -reach, 0.0), // Left
+            (arm, thickness, reach, 0.0),  // Right
+            (thickness, arm, 0.0, -reach), // Top
+            (thickness, arm, 0.0, reach),  // Bottom
+        ];
 
         let mut vertices = Vec::new();
+        for (width, height, x, y) in arms {
+            let outer =
+                ScreenRect::new(Anchor::Center, width + border * 2.0, height + border * 2.0)
+                    .offset_by(x, y);
+            let inner = ScreenRect::new(Anchor::Center, width, height).offset_by(x, y);
+            Self::add_rect(&mut vertices, outer.to_ndc(viewport), outline);
+            Self::add_rect(&mut vertices, inner.to_ndc(viewport), color);
+        }
 
-        // Helper to add a rectangle
-        let add_rect =
-            |verts: &mut Vec<OverlayVertex>, x1: f32, y1: f32, x2: f32, y2: f32, c: [f32; 4]| {
-                // Two triangles for a rectangle
-                verts.push(OverlayVertex::new(x1, y1, c));
-                verts.push(OverlayVertex::new(x2, y1, c));
-                verts.push(OverlayVertex::new(x2, y2, c));
-                verts.push(OverlayVertex::new(x1, y1, c));
-                verts.push(OverlayVertex::new(x2, y2, c));
-                verts.push(OverlayVertex::new(x1, y2, c));
-            };
+        vertices
+    }
 
-        // Horizontal bar (left part) - outline
-        add_rect(
-            &mut vertices,
-            -size - outline_t,
-            -thickness - outline_t,
-            -gap + outline_t,
-            thickness + outline_t,
-            outline,
-        );
-        // Horizontal bar (left part) - fill
-        add_rect(&mut vertices, -size, -thickness, -gap, thickness, color);
-
-        // Horizontal bar (right part) - outline
-        add_rect(
-            &mut vertices,
-            gap - outline_t,
-            -thickness - outline_t,
-            size + outline_t,
-            thickness + outline_t,
-            outline,
-        );
-        // Horizontal bar (right part) - fill
-        add_rect(&mut vertices, gap, -thickness, size, thickness, color);
-
-        // Vertical bar (top part) - outline
-        add_rect(
-            &mut vertices,
-            -thickness - outline_t,
-            gap - outline_t,
-            thickness + outline_t,
-            size + outline_t,
-            outline,
-        );
-        // Vertical bar (top part) - fill
-        add_rect(&mut vertices, -thickness, gap, thickness, size, color);
-
-        // Vertical bar (bottom part) - outline
-        add_rect(
-            &mut vertices,
-            -thickness - outline_t,
-            -size - outline_t,
-            thickness + outline_t,
-            -gap + outline_t,
-            outline,
+    /// Creates loading bar vertices: an outlined track in the center of the
+    /// window, filled from the left to `progress`.
+    ///
+    /// Always returns [`LOADING_BAR_VERTEX_COUNT`] vertices, so the buffer
+    /// can be rewritten in place.
+    fn create_loading_bar_vertices(viewport: &Viewport, progress: f32) -> Vec<OverlayVertex> {
+        let outline = [0.0, 0.0, 0.0, 0.6];
+        let track = [0.2, 0.2, 0.2, 0.8];
+        let fill = [1.0, 1.0, 1.0, 0.9];
+        let border = 2.0;
+
+        let fill_width = LOADING_BAR_WIDTH * progress;
+        let outer = ScreenRect::new(
+            Anchor::Center,
+            LOADING_BAR_WIDTH + border * 2.0,
+            LOADING_BAR_HEIGHT + border * 2.0,
         );
-        // Vertical bar (bottom part) - fill
-        add_rect(&mut vertices, -thickness, -size, thickness, -gap, color);
+        let inner = ScreenRect::new(Anchor::Center, LOADING_BAR_WIDTH, LOADING_BAR_HEIGHT);
+        let filled = ScreenRect::new(Anchor::Center, fill_width, LOADING_BAR_HEIGHT)
+            .offset_by((fill_width - LOADING_BAR_WIDTH) / 2.0, 0.0);
 
+        let mut vertices = Vec::new();
+        Self::add_rect(&mut vertices, outer.to_ndc(viewport), outline);
+        Self::add_rect(&mut vertices, inner.to_ndc(viewport), track);
+        Self::add_rect(&mut vertices, filled.to_ndc(viewport), fill);
         vertices
     }
 
+    /// Returns hotbar `slot`'s rect, in a row centered along the bottom of
+    /// the window.
+    #[allow(clippy::cast_precision_loss)]
+    fn hotbar_slot_rect(slot: usize) -> ScreenRect {
+        let from_center = slot as f32 - (HOTBAR_SLOTS - 1) as f32 / 2.0;
+        ScreenRect::new(Anchor::BottomCenter, HOTBAR_SLOT_SIZE, HOTBAR_SLOT_SIZE).offset_by(
+            from_center * (HOTBAR_SLOT_SIZE + HOTBAR_SLOT_GAP),
+            -HOTBAR_MARGIN,
+        )
+    }
+
+    /// Creates hotbar vertices: a border behind the `selected` slot, then
+    /// a translucent square per slot.
+    ///
+    /// Always returns [`HOTBAR_VERTEX_COUNT`] vertices (the border is empty
+    /// when no slot is selected), so the buffer can be rewritten in place.
+    fn create_hotbar_vertices(viewport: &Viewport, selected: Option<usize>) -> Vec<OverlayVertex> {
+        let highlight = [1.0, 1.0, 1.0, 0.9];
+        let slot_color = [0.1, 0.1, 0.1, 0.6];
+
+        let border = selected.map_or(ScreenRect::new(Anchor::BottomCenter, 0.0, 0.0), |slot| {
+            let rect = Self::hotbar_slot_rect(slot);
+            let size = HOTBAR_HIGHLIGHT_BORDER.mul_add(2.0, HOTBAR_SLOT_SIZE);
+            ScreenRect::new(rect.anchor, size, size)
+                .offset_by(rect.offset.x, rect.offset.y + HOTBAR_HIGHLIGHT_BORDER)
+        });
+
+        let mut vertices = Vec::new();
+        Self::add_rect(&mut vertices, border.to_ndc(viewport), highlight);
+        for slot in 0..HOTBAR_SLOTS {
+            let rect = Self::hotbar_slot_rect(slot);
+            Self::add_rect(&mut vertices, rect.to_ndc(viewport), slot_color);
+        }
+        vertices
+    }
+
+    /// Creates the hotbar's block icons, each showing the block's side
+    /// texture inset in its slot.
+    fn create_hotbar_icon_vertices(viewport: &Viewport) -> Vec<TexturedVertex> {
+        let size = HOTBAR_ICON_INSET.mul_add(-2.0, HOTBAR_SLOT_SIZE);
+        let mut vertices = Vec::new();
+        for (slot, &block) in HOTBAR.iter().enumerate() {
+            let rect = Self::hotbar_slot_rect(slot);
+            let icon = ScreenRect::new(rect.anchor, size, size)
+                .offset_by(rect.offset.x, rect.offset.y - HOTBAR_ICON_INSET);
+            let (u_min, v_min, u_max, v_max) = TextureAtlas::block_uvs(block);
+            let uv = (Vec2::new(u_min, v_min), Vec2::new(u_max, v_max));
+            Self::add_textured_rect(&mut vertices, icon.to_ndc(viewport), uv, [1.0; 4]);
+        }
+        vertices
+    }
+
+    /// Renders the hotbar slots and their block icons.
+    pub fn render_hotbar<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_vertex_buffer(0, self.hotbar_buffer.slice(..));
+        render_pass.draw(0..HOTBAR_VERTEX_COUNT, 0..1);
+
+        render_pass.set_pipeline(&self.icon_pipeline);
+        render_pass.set_bind_group(0, &self.block_atlas_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.hotbar_icon_buffer.slice(..));
+        render_pass.draw(0..HOTBAR_ICON_VERTEX_COUNT, 0..1);
+    }
+
+    /// Renders the loading bar, unless it is full.
+    pub fn render_loading_bar<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if self.loading_progress >= 1.0 {
+            return;
+        }
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_vertex_buffer(0, self.loading_bar_buffer.slice(..));
+        render_pass.draw(0..LOADING_BAR_VERTEX_COUNT, 0..1);
+    }
+
+    /// Renders the text uploaded by the last
+    /// [`prepare_text`](Self::prepare_text).
+    pub fn render_text<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if self.text_vertex_count == 0 {
+            return;
+        }
+        render_pass.set_pipeline(&self.text_pipeline);
+        render_pass.set_bind_group(0, &self.font_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.text_buffer.slice(..));
+        render_pass.draw(0..self.text_vertex_count, 0..1);
+    }
+
     /// Renders the crosshair.
     pub fn render_crosshair<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
         render_pass.set_pipeline(&self.pipeline);
@@ -205,3 +886,188 @@ impl OverlayRenderer {
         render_pass.draw(0..self.crosshair_vertex_count, 0..1);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vec2_approx_eq(a: Vec2, b: Vec2) -> bool {
+        (a - b).length() < 0.0001
+    }
+
+    #[test]
+    fn bottom_center_rect_to_ndc() {
+        let viewport = Viewport::new(1280.0, 720.0, 1.0);
+        let rect = ScreenRect::new(Anchor::BottomCenter, 100.0, 20.0).to_ndc(&viewport);
+
+        // x: 590..690 px, y: 700..720 px (from the top)
+        assert!(vec2_approx_eq(rect.min, Vec2::new(-50.0 / 640.0, -1.0)));
+        assert!(vec2_approx_eq(
+            rect.max,
+            Vec2::new(50.0 / 640.0, 1.0 - 700.0 / 360.0)
+        ));
+    }
+
+    #[test]
+    fn scale_factor_keeps_logical_size() {
+        // At 2x DPI, a 100 logical pixel rect covers the same fraction of a
+        // window twice as many physical pixels wide
+        let rect = ScreenRect::new(Anchor::TopLeft, 100.0, 20.0);
+        let low_dpi = rect.to_ndc(&Viewport::new(1280.0, 720.0, 1.0));
+        let high_dpi = rect.to_ndc(&Viewport::new(2560.0, 1440.0, 2.0));
+        assert!(vec2_approx_eq(low_dpi.min, high_dpi.min));
+        assert!(vec2_approx_eq(low_dpi.max, high_dpi.max));
+    }
+
+    #[test]
+    fn offsets_move_down_and_right() {
+        let viewport = Viewport::new(800.0, 600.0, 1.0);
+        let rect = ScreenRect::new(Anchor::TopLeft, 10.0, 10.0).offset_by(400.0, 300.0);
+        let ndc = rect.to_ndc(&viewport);
+        assert!(vec2_approx_eq(ndc.min, Vec2::new(0.0, -10.0 / 300.0)));
+        assert!(vec2_approx_eq(ndc.max, Vec2::new(10.0 / 400.0, 0.0)));
+    }
+
+    #[test]
+    fn crosshair_is_centered_with_stable_vertex_count() {
+        let small =
+            OverlayRenderer::create_crosshair_vertices(&Viewport::new(640.0, 480.0, 1.0), 1.0);
+        let large =
+            OverlayRenderer::create_crosshair_vertices(&Viewport::new(2560.0, 1440.0, 2.0), 1.0);
+        assert_eq!(small.len(), large.len());
+
+        let sum: Vec2 = small.iter().map(|v| Vec2::from(v.position)).sum();
+        #[allow(clippy::cast_precision_loss)]
+        let center = sum / small.len() as f32;
+        assert!(vec2_approx_eq(center, Vec2::ZERO));
+    }
+
+    #[test]
+    fn crosshair_arms_are_square_at_16_9() {
+        let (width, height) = (1920.0, 1080.0);
+        let vertices =
+            OverlayRenderer::create_crosshair_vertices(&Viewport::new(width, height, 1.0), 1.0);
+        // Each arm is an outline quad then a fill quad; measure the fills in pixels
+        let fill_size = |arm: usize| {
+            let fill = &vertices[arm * 12 + 6..arm * 12 + 12];
+            let (min, max) = fill.iter().map(|v| Vec2::from(v.position)).fold(
+                (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+                |(min, max), p| (min.min(p), max.max(p)),
+            );
+            (max - min) * Vec2::new(width, height) / 2.0
+        };
+
+        for horizontal in [0, 1] {
+            for vertical in [2, 3] {
+                let (h, v) = (fill_size(horizontal), fill_size(vertical));
+                assert!(vec2_approx_eq(h, Vec2::new(v.y, v.x)));
+            }
+        }
+        assert!(vec2_approx_eq(fill_size(0), Vec2::new(7.0, 2.0)));
+    }
+
+    #[test]
+    fn crosshair_scales_with_zoom_when_enabled() {
+        let viewport = Viewport::new(1280.0, 720.0, 1.0);
+        let extent = |scale| {
+            OverlayRenderer::create_crosshair_vertices(&viewport, scale)
+                .iter()
+                .map(|v| Vec2::from(v.position).abs())
+                .fold(Vec2::ZERO, Vec2::max)
+        };
+        let normal = extent(1.0);
+
+        // Half the FOV: twice the size with the option, unchanged without
+        let scoped = extent(crosshair_scale(70.0, 35.0, true));
+        assert!(vec2_approx_eq(scoped, normal * 2.0));
+        let fixed = extent(crosshair_scale(70.0, 35.0, false));
+        assert!(vec2_approx_eq(fixed, normal));
+    }
+
+    #[test]
+    fn loading_bar_fills_from_the_left() {
+        let viewport = Viewport::new(1280.0, 720.0, 1.0);
+        let fill_x = |progress| {
+            let vertices = OverlayRenderer::create_loading_bar_vertices(&viewport, progress);
+            let fill = &vertices[12..];
+            let xs = fill.iter().map(|v| v.position[0]);
+            (
+                xs.clone().fold(f32::INFINITY, f32::min),
+                xs.fold(f32::NEG_INFINITY, f32::max),
+            )
+        };
+
+        let half = LOADING_BAR_WIDTH / 1280.0;
+        let vertices = OverlayRenderer::create_loading_bar_vertices(&viewport, 0.0);
+        assert_eq!(vertices.len(), LOADING_BAR_VERTEX_COUNT as usize);
+        let (empty_min, empty_max) = fill_x(0.0);
+        assert!((empty_max - empty_min).abs() < 0.0001);
+        let (min, max) = fill_x(0.5);
+        assert!((min + half).abs() < 0.0001);
+        assert!(max.abs() < 0.0001);
+        let (min, max) = fill_x(1.0);
+        assert!((min + half).abs() < 0.0001 && (max - half).abs() < 0.0001);
+    }
+
+    #[test]
+    fn text_emits_a_quad_per_visible_character() {
+        let viewport = Viewport::new(1280.0, 720.0, 1.0);
+        let text = |text: &str| {
+            let mut vertices = Vec::new();
+            OverlayRenderer::add_text(&mut vertices, &viewport, text, Vec2::ZERO, 2.0);
+            vertices
+        };
+
+        assert_eq!(text("FPS:60").len(), 6 * 6);
+        assert!(text("").is_empty());
+        // Spaces and line breaks only move the pen
+        assert_eq!(text("XYZ: 1\nBlock").len(), 6 * 10);
+
+        // The first glyph is 5x7 font pixels at the top-left corner
+        let vertices = text("A");
+        let to_ndc = |x: f32, y: f32| Vec2::new(x / 640.0 - 1.0, 1.0 - y / 360.0);
+        assert!(vec2_approx_eq(
+            Vec2::from(vertices[0].position),
+            to_ndc(0.0, 14.0)
+        ));
+        assert!(vec2_approx_eq(
+            Vec2::from(vertices[2].position),
+            to_ndc(10.0, 0.0)
+        ));
+    }
+
+    #[test]
+    fn hotbar_highlights_the_selected_slot() {
+        let viewport = Viewport::new(1280.0, 720.0, 1.0);
+        let center = |vertices: &[OverlayVertex]| {
+            let rect = NdcRect {
+                min: Vec2::from(vertices[0].position),
+                max: Vec2::from(vertices[2].position),
+            };
+            (rect.min + rect.max) / 2.0
+        };
+
+        for slot in 0..HOTBAR_SLOTS {
+            let vertices = OverlayRenderer::create_hotbar_vertices(&viewport, Some(slot));
+            assert_eq!(vertices.len(), HOTBAR_VERTEX_COUNT as usize);
+            // The border sits behind the slot it highlights
+            let slot_vertices = &vertices[6 * (slot + 1)..6 * (slot + 2)];
+            assert!(vec2_approx_eq(
+                center(&vertices[..6]),
+                center(slot_vertices)
+            ));
+        }
+
+        // With no slot selected the border is empty
+        let vertices = OverlayRenderer::create_hotbar_vertices(&viewport, None);
+        assert_eq!(vertices.len(), HOTBAR_VERTEX_COUNT as usize);
+        assert!(vec2_approx_eq(
+            center(&vertices[..6]),
+            Vec2::from(vertices[0].position)
+        ));
+        assert_eq!(
+            OverlayRenderer::create_hotbar_icon_vertices(&viewport).len(),
+            HOTBAR_ICON_VERTEX_COUNT as usize
+        );
+    }
+}
@@ -0,0 +1,90 @@
+//! Physics settings for walking players.
+//!
+//! Velocities are in blocks per second and accelerations in blocks per
+//! second squared. The defaults match Minecraft's per-tick values scaled to
+//! 20 ticks per second.
+
+/// Gravity and jump settings.
+#[derive(Debug, Clone)]
+pub struct PhysicsConfig {
+    /// Downward acceleration.
+    pub gravity: f32,
+    /// Maximum falling speed.
+    pub terminal_velocity: f32,
+    /// Upward speed at the start of a jump.
+    pub jump_velocity: f32,
+}
+
+impl Default for PhysicsConfig {
+    fn default() -> Self {
+        Self {
+            gravity: 32.0,
+            terminal_velocity: 78.4,
+            jump_velocity: 8.4,
+        }
+    }
+}
+
+impl PhysicsConfig {
+    /// Applies `delta_time` seconds of gravity to a vertical velocity
+    /// (positive is up), never falling faster than the terminal velocity.
+    #[must_use]
+    pub fn apply_gravity(&self, velocity: f32, delta_time: f32) -> f32 {
+        self.gravity
+            .mul_add(-delta_time, velocity)
+            .max(-self.terminal_velocity)
+    }
+
+    /// Returns the height of a jump from standing, ignoring collisions.
+    #[must_use]
+    pub fn jump_height(&self) -> f32 {
+        self.jump_velocity * self.jump_velocity / (2.0 * self.gravity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Falls from rest, returning the step terminal velocity was reached on.
+    fn steps_to_terminal(config: &PhysicsConfig) -> usize {
+        let mut velocity = 0.0;
+        for step in 1..=10_000 {
+            velocity = config.apply_gravity(velocity, 1.0 / 60.0);
+            assert!(velocity >= -config.terminal_velocity);
+            if velocity <= -config.terminal_velocity {
+                return step;
+            }
+        }
+        panic!("never reached terminal velocity");
+    }
+
+    #[test]
+    fn higher_gravity_reaches_terminal_velocity_faster() {
+        let normal = PhysicsConfig::default();
+        let heavy = PhysicsConfig {
+            gravity: 128.0,
+            ..PhysicsConfig::default()
+        };
+
+        assert!(steps_to_terminal(&heavy) < steps_to_terminal(&normal));
+    }
+
+    #[test]
+    fn velocity_is_clamped_to_terminal() {
+        let config = PhysicsConfig {
+            terminal_velocity: 10.0,
+            ..PhysicsConfig::default()
+        };
+
+        // One huge step would overshoot without the clamp
+        let velocity = config.apply_gravity(0.0, 5.0);
+        assert!((velocity + 10.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn default_jump_clears_one_block() {
+        let height = PhysicsConfig::default().jump_height();
+        assert!(height > 1.0 && height < 1.5);
+    }
+}
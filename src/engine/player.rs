@@ -0,0 +1,747 @@
+//! Player state and collision.
+//!
+//! The camera sits at the player's eyes. Movement goes through the player,
+//! which resolves it against the world unless noclip is enabled. Flying
+//! players move freely; walking players fall under [`PhysicsConfig`] and
+//! crouch by lowering both their eyes and the top of their collision box.
+//! Double-tapping jump switches between the two.
+
+use std::time::{Duration, Instant};
+
+use glam::Vec3;
+
+use super::camera::Camera;
+use super::physics::PhysicsConfig;
+use crate::math::Aabb;
+use crate::world::{Block, BlockPos, BlockShape};
+
+/// Gap kept between the player and blocks they collide with.
+const COLLISION_EPSILON: f32 = 0.001;
+
+/// Longest gap between two jump presses that toggles flying.
+pub const DOUBLE_TAP_WINDOW: Duration = Duration::from_millis(300);
+
+/// Player body dimensions.
+#[derive(Debug, Clone)]
+pub struct PlayerConfig {
+    /// Width of the player's collision box (X and Z).
+    pub width: f32,
+    /// Height of the player's collision box.
+    pub height: f32,
+    /// Height of the eyes (camera) above the feet.
+    pub eye_height: f32,
+    /// Height of the collision box while crouching.
+    pub crouch_height: f32,
+    /// Height of the eyes above the feet while crouching.
+    pub crouch_eye_height: f32,
+}
+
+impl Default for PlayerConfig {
+    fn default() -> Self {
+        Self {
+            width: 0.6,
+            height: 1.8,
+            eye_height: 1.62,
+            crouch_height: 1.5,
+            crouch_eye_height: 1.27,
+        }
+    }
+}
+
+/// How the player interacts with the world.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlayerMode {
+    /// Normal play.
+    Survival,
+    /// Building mode.
+    #[default]
+    Creative,
+    /// Free camera for cinematics: flies through blocks, can't interact,
+    /// and hides the crosshair.
+    Spectator,
+}
+
+impl PlayerMode {
+    /// Returns true if the player can break and place blocks.
+    #[must_use]
+    pub const fn can_interact(self) -> bool {
+        !matches!(self, Self::Spectator)
+    }
+
+    /// Returns true if a click breaks any breakable block, instead of
+    /// mining it for its hardness.
+    #[must_use]
+    pub const fn breaks_instantly(self) -> bool {
+        matches!(self, Self::Creative)
+    }
+
+    /// Returns true if the player collides with blocks.
+    #[must_use]
+    pub const fn has_collision(self) -> bool {
+        !matches!(self, Self::Spectator)
+    }
+
+    /// Returns true if the crosshair is drawn.
+    #[must_use]
+    pub const fn shows_crosshair(self) -> bool {
+        !matches!(self, Self::Spectator)
+    }
+
+    /// Returns the next mode in the cycle Survival → Creative → Spectator.
+    #[must_use]
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Survival => Self::Creative,
+            Self::Creative => Self::Spectator,
+            Self::Spectator => Self::Survival,
+        }
+    }
+}
+
+/// Player movement state.
+#[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)] // Independent movement flags
+pub struct Player {
+    /// Game mode, gating interaction, collision and HUD.
+    mode: PlayerMode,
+    /// Whether the player is flying (free vertical movement).
+    pub flying: bool,
+    /// Whether collision is disabled entirely (debugging aid).
+    pub noclip: bool,
+    /// Body dimensions.
+    config: PlayerConfig,
+    /// Gravity and jump settings for walking.
+    physics: PhysicsConfig,
+    /// Vertical velocity while walking (positive is up).
+    vertical_velocity: f32,
+    /// Whether the player is standing on a block.
+    on_ground: bool,
+    /// Whether the player is crouched (walking only).
+    crouched: bool,
+    /// When jump was last pressed, if it could start a double tap.
+    last_jump_tap: Option<Instant>,
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self::new(PlayerConfig::default())
+    }
+}
+
+impl Player {
+    /// Creates a flying player with collision enabled.
+    #[must_use]
+    pub fn new(config: PlayerConfig) -> Self {
+        Self {
+            mode: PlayerMode::Creative,
+            flying: true,
+            noclip: false,
+            config,
+            physics: PhysicsConfig::default(),
+            vertical_velocity: 0.0,
+            on_ground: false,
+            crouched: false,
+            last_jump_tap: None,
+        }
+    }
+
+    /// Sets the gravity and jump settings used while walking.
+    #[must_use]
+    pub const fn with_physics(mut self, physics: PhysicsConfig) -> Self {
+        self.physics = physics;
+        self
+    }
+
+    /// Returns the gravity and jump settings.
+    #[must_use]
+    pub const fn physics(&self) -> &PhysicsConfig {
+        &self.physics
+    }
+
+    /// Returns the vertical velocity (positive is up).
+    #[must_use]
+    pub const fn vertical_velocity(&self) -> f32 {
+        self.vertical_velocity
+    }
+
+    /// Returns true if the player is standing on a block.
+    #[must_use]
+    pub const fn on_ground(&self) -> bool {
+        self.on_ground
+    }
+
+    /// Toggles between flying and walking, returning the new flying state.
+    /// Spectators always fly.
+    pub const fn toggle_flying(&mut self) -> bool {
+        self.flying = !self.flying || matches!(self.mode, PlayerMode::Spectator);
+        self.vertical_velocity = 0.0;
+        self.flying
+    }
+
+    /// Records a press of the jump key at `now`. A second press within
+    /// [`DOUBLE_TAP_WINDOW`] toggles flying and returns the new flying state.
+    pub fn tap_jump(&mut self, now: Instant) -> Option<bool> {
+        match self.last_jump_tap.take() {
+            Some(last) if now.saturating_duration_since(last) <= DOUBLE_TAP_WINDOW => {
+                Some(self.toggle_flying())
+            }
+            _ => {
+                self.last_jump_tap = Some(now);
+                None
+            }
+        }
+    }
+
+    /// Returns the current game mode.
+    #[must_use]
+    pub const fn mode(&self) -> PlayerMode {
+        self.mode
+    }
+
+    /// Sets the game mode. Spectators always fly.
+    pub const fn set_mode(&mut self, mode: PlayerMode) {
+        self.mode = mode;
+        if matches!(mode, PlayerMode::Spectator) {
+            self.flying = true;
+        }
+    }
+
+    /// Returns true if movement is resolved against blocks.
+    #[must_use]
+    pub const fn collides(&self) -> bool {
+        !self.noclip && self.mode.has_collision()
+    }
+
+    /// Returns the player's body dimensions.
+    #[must_use]
+    pub const fn config(&self) -> &PlayerConfig {
+        &self.config
+    }
+
+    /// Toggles noclip, returning the new state.
+    pub const fn toggle_noclip(&mut self) -> bool {
+        self.noclip = !self.noclip;
+        self.noclip
+    }
+
+    /// Returns true if the player is crouched.
+    #[must_use]
+    pub const fn is_crouched(&self) -> bool {
+        self.crouched
+    }
+
+    /// Returns the current height of the collision box.
+    #[must_use]
+    pub const fn height(&self) -> f32 {
+        if self.crouched {
+            self.config.crouch_height
+        } else {
+            self.config.height
+        }
+    }
+
+    /// Returns the current height of the eyes above the feet.
+    #[must_use]
+    pub const fn eye_height(&self) -> f32 {
+        if self.crouched {
+            self.config.crouch_eye_height
+        } else {
+            self.config.eye_height
+        }
+    }
+
+    /// Returns the collision box for a player whose eyes are at `eye`.
+    #[must_use]
+    pub fn aabb(&self, eye: Vec3) -> Aabb {
+        let half_width = self.config.width / 2.0;
+        let feet = eye - Vec3::Y * self.eye_height();
+        Aabb::new(
+            feet - Vec3::new(half_width, 0.0, half_width),
+            feet + Vec3::new(half_width, self.height(), half_width),
+        )
+    }
+
+    /// Returns true if placing `block` at `pos` would put it inside the
+    /// body of a player whose eyes are at `eye`. Blocks that can be walked
+    /// through, and players that don't collide, never get in the way.
+    #[must_use]
+    pub fn blocks_placement(&self, eye: Vec3, block: Block, pos: BlockPos) -> bool {
+        if !self.collides() || !block.blocks_movement() {
+            return false;
+        }
+        let body = self.aabb(eye);
+        block
+            .shape()
+            .world_boxes(pos)
+            .any(|part| body.intersects(&part))
+    }
+
+    /// Crouches or stands up, keeping the feet in place so the camera moves
+    /// by the difference in eye height. Stays crouched while a block is in
+    /// the way of standing up. Returns whether the player is crouched.
+    ///
+    /// `shape_at` returns the shape of a block the player collides with, or
+    /// `None` for blocks they pass through.
+    pub fn set_crouched<F>(&mut self, camera: &mut Camera, crouched: bool, shape_at: F) -> bool
+    where
+        F: Fn(i32, i32, i32) -> Option<BlockShape>,
+    {
+        if crouched == self.crouched {
+            return crouched;
+        }
+
+        let feet = camera.position - Vec3::Y * self.eye_height();
+        self.crouched = crouched;
+        let eye = feet + Vec3::Y * self.eye_height();
+        if !crouched
+            && self.collides()
+            && Self::blocking_coordinate(&self.aabb(eye), 1, true, &shape_at).is_some()
+        {
+            self.crouched = true;
+            return true;
+        }
+
+        camera.position = eye;
+        crouched
+    }
+
+    /// Moves the camera for one frame, then resolves the move against the
+    /// shapes `shape_at` returns unless noclip is on or the player is a
+    /// spectator.
+    ///
+    /// Flying moves like [`Camera::move_by`]. Walking moves on the XZ plane,
+    /// jumps when `direction.y` is positive and the player is on the ground,
+    /// crouches while `crouching` is held, and falls under gravity. Call this
+    /// every frame while walking, even without input, so the player keeps
+    /// falling.
+    pub fn move_camera<F>(
+        &mut self,
+        camera: &mut Camera,
+        direction: Vec3,
+        delta_time: f32,
+        sprinting: bool,
+        crouching: bool,
+        shape_at: F,
+    ) where
+        F: Fn(i32, i32, i32) -> Option<BlockShape>,
+    {
+        // Flying players descend with the crouch key instead of crouching
+        self.set_crouched(camera, crouching && !self.flying, &shape_at);
+
+        let start = camera.position;
+        if self.flying {
+            camera.move_by(direction, delta_time, sprinting, crouching);
+        } else {
+            camera.position += self.walk_delta(camera, direction, delta_time, sprinting, crouching);
+        }
+
+        if self.collides() {
+            let intended = camera.position;
+            camera.position = self.resolve_movement(start, intended - start, shape_at);
+
+            // A blocked vertical move means we landed or hit our head
+            if !self.flying && (camera.position.y - intended.y).abs() > f32::EPSILON {
+                self.on_ground = self.vertical_velocity < 0.0;
+                self.vertical_velocity = 0.0;
+            } else {
+                self.on_ground = false;
+            }
+        }
+    }
+
+    /// Returns the walking displacement for one frame, updating the
+    /// vertical velocity for jumping and gravity.
+    fn walk_delta(
+        &mut self,
+        camera: &Camera,
+        direction: Vec3,
+        delta_time: f32,
+        sprinting: bool,
+        crouching: bool,
+    ) -> Vec3 {
+        let camera_config = camera.config();
+        let mut speed = camera_config.move_speed;
+        if sprinting {
+            speed *= camera_config.sprint_multiplier;
+        } else if crouching {
+            speed *= camera_config.crouch_multiplier;
+        }
+
+        let horizontal = (camera.forward_xz() * direction.z + camera.right_xz() * direction.x)
+            .normalize_or_zero();
+
+        if direction.y > 0.0 && self.on_ground {
+            self.vertical_velocity = self.physics.jump_velocity;
+            self.on_ground = false;
+        }
+        self.vertical_velocity = self
+            .physics
+            .apply_gravity(self.vertical_velocity, delta_time);
+
+        horizontal * speed * delta_time + Vec3::Y * self.vertical_velocity * delta_time
+    }
+
+    /// Returns where the eyes end up after moving from `eye` by `delta`,
+    /// stopping at the boxes of the block shapes `shape_at` returns.
+    ///
+    /// Each axis is resolved separately (Y, then X, then Z), so the player
+    /// slides along walls instead of sticking to them.
+    #[must_use]
+    pub fn resolve_movement<F>(&self, eye: Vec3, delta: Vec3, shape_at: F) -> Vec3
+    where
+        F: Fn(i32, i32, i32) -> Option<BlockShape>,
+    {
+        let mut position = eye;
+
+        for axis in [1, 0, 2] {
+            let step = delta[axis];
+            if step == 0.0 {
+                continue;
+            }
+
+            let mut moved = position;
+            moved[axis] += step;
+
+            // Check the whole swept volume so fast moves can't skip a wall
+            let start = self.aabb(position);
+            let end = self.aabb(moved);
+            let swept = Aabb::new(start.min.min(end.min), start.max.max(end.max));
+
+            let Some(blocking) = Self::blocking_coordinate(&swept, axis, step > 0.0, &shape_at)
+            else {
+                position = moved;
+                continue;
+            };
+
+            // Stop flush against the nearest blocking box, never moving
+            // backwards if we already started inside one
+            let offset = position[axis] - start.min[axis];
+            if step > 0.0 {
+                let extent = start.max[axis] - start.min[axis];
+                let limit = blocking - COLLISION_EPSILON - extent + offset;
+                position[axis] = limit.max(position[axis]).min(moved[axis]);
+            } else {
+                let limit = blocking + COLLISION_EPSILON + offset;
+                position[axis] = limit.min(position[axis]).max(moved[axis]);
+            }
+        }
+
+        position
+    }
+
+    /// Finds the nearest face along `axis` of the block boxes overlapping
+    /// `aabb`: the lowest near face when moving in the positive direction,
+    /// the highest far face otherwise.
+    fn blocking_coordinate<F>(aabb: &Aabb, axis: usize, positive: bool, shape_at: &F) -> Option<f32>
+    where
+        F: Fn(i32, i32, i32) -> Option<BlockShape>,
+    {
+        let (min_x, max_x) = block_range(aabb.min.x, aabb.max.x);
+        let (min_y, max_y) = block_range(aabb.min.y, aabb.max.y);
+        let (min_z, max_z) = block_range(aabb.min.z, aabb.max.z);
+
+        let mut blocking: Option<f32> = None;
+        for y in min_y..=max_y {
+            for z in min_z..=max_z {
+                for x in min_x..=max_x {
+                    let Some(shape) = shape_at(x, y, z) else {
+                        continue;
+                    };
+                    for part in shape.world_boxes(BlockPos::new(x, y, z)) {
+                        if !part.intersects(aabb) {
+                            continue;
+                        }
+                        blocking = Some(match blocking {
+                            None if positive => part.min[axis],
+                            None => part.max[axis],
+                            Some(b) if positive => b.min(part.min[axis]),
+                            Some(b) => b.max(part.max[axis]),
+                        });
+                    }
+                }
+            }
+        }
+
+        blocking
+    }
+}
+
+/// Returns the inclusive range of block coordinates overlapped by `[min, max)`.
+#[allow(clippy::cast_possible_truncation)]
+const fn block_range(min: f32, max: f32) -> (i32, i32) {
+    (min.floor() as i32, max.ceil() as i32 - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::camera::CameraConfig;
+
+    /// A wall filling the plane x = 10.
+    fn wall(x: i32, _y: i32, _z: i32) -> Option<BlockShape> {
+        (x == 10).then_some(BlockShape::FullCube)
+    }
+
+    /// A camera at x = 8.5 facing +X, towards the wall.
+    fn camera_facing_wall() -> Camera {
+        Camera::new(CameraConfig::default())
+            .at_position(Vec3::new(8.5, 65.0, 0.5))
+            .with_orientation(0.0, 0.0)
+    }
+
+    #[test]
+    fn flying_player_is_stopped_by_wall() {
+        let mut player = Player::default();
+        assert!(player.flying);
+        assert!(!player.noclip);
+
+        let mut camera = camera_facing_wall();
+        player.move_camera(&mut camera, Vec3::Z, 1.0, false, false, wall);
+
+        // Body's +X side stops flush against the wall
+        let max_x = player.aabb(camera.position).max.x;
+        assert!(max_x < 10.0);
+        assert!(max_x > 10.0 - 0.01);
+        // Other axes are untouched
+        assert!((camera.position.y - 65.0).abs() < 0.0001);
+        assert!((camera.position.z - 0.5).abs() < 0.0001);
+    }
+
+    #[test]
+    fn noclip_player_passes_through_wall() {
+        let mut player = Player::default();
+        assert!(player.toggle_noclip());
+
+        let mut camera = camera_facing_wall();
+        player.move_camera(&mut camera, Vec3::Z, 1.0, false, false, wall);
+
+        // Full move speed, straight through the wall
+        let expected = 8.5 + CameraConfig::default().move_speed;
+        assert!((camera.position.x - expected).abs() < 0.0001);
+    }
+
+    #[test]
+    fn spectator_passes_through_wall_without_noclip() {
+        let mut player = Player::default();
+        player.set_mode(PlayerMode::Spectator);
+        assert!(!player.noclip);
+        assert!(!player.collides());
+
+        let mut camera = camera_facing_wall();
+        player.move_camera(&mut camera, Vec3::Z, 1.0, false, false, wall);
+        assert!(player.aabb(camera.position).min.x > 10.0);
+    }
+
+    #[test]
+    fn double_tapping_jump_toggles_flying() {
+        let mut player = Player::default();
+        let start = Instant::now();
+        let tap = Duration::from_millis(150);
+
+        assert_eq!(player.tap_jump(start), None);
+        assert_eq!(player.tap_jump(start + tap), Some(false));
+        // A third press starts a new double tap instead of toggling back
+        assert_eq!(player.tap_jump(start + tap * 2), None);
+        assert_eq!(player.tap_jump(start + tap * 3), Some(true));
+
+        // Presses further apart than the window never toggle
+        let slow = DOUBLE_TAP_WINDOW * 2;
+        assert_eq!(player.tap_jump(start + slow), None);
+        assert_eq!(player.tap_jump(start + slow * 2), None);
+        assert!(player.flying);
+    }
+
+    #[test]
+    fn player_mode_gates() {
+        assert_eq!(Player::default().mode(), PlayerMode::Creative);
+        for mode in [PlayerMode::Survival, PlayerMode::Creative] {
+            assert!(mode.can_interact());
+            assert!(mode.has_collision());
+            assert!(mode.shows_crosshair());
+        }
+        let spectator = PlayerMode::Spectator;
+        assert!(!spectator.can_interact());
+        assert!(!spectator.has_collision());
+        assert!(!spectator.shows_crosshair());
+        assert_eq!(spectator.next().next().next(), spectator);
+    }
+
+    #[test]
+    fn collision_slides_along_walls() {
+        let player = Player::default();
+        let eye = Vec3::new(8.5, 65.0, 0.5);
+
+        let resolved = player.resolve_movement(eye, Vec3::new(3.0, 0.0, 2.0), wall);
+
+        assert!(player.aabb(resolved).max.x < 10.0);
+        assert!((resolved.z - 2.5).abs() < 0.0001);
+    }
+
+    #[test]
+    fn collision_stops_falling_onto_floor() {
+        let player = Player::default();
+        let floor = |_x: i32, y: i32, _z: i32| (y <= 63).then_some(BlockShape::FullCube);
+        let eye = Vec3::new(0.5, 64.0 + 1.62 + 0.5, 0.5);
+
+        let resolved = player.resolve_movement(eye, Vec3::new(0.0, -2.0, 0.0), floor);
+
+        let feet = player.aabb(resolved).min.y;
+        assert!(feet >= 64.0);
+        assert!(feet < 64.01);
+    }
+
+    /// Floor filling everything below y = 64.
+    fn floor(_x: i32, y: i32, _z: i32) -> Option<BlockShape> {
+        (y < 64).then_some(BlockShape::FullCube)
+    }
+
+    /// A player with flying turned off.
+    fn walking_player(physics: PhysicsConfig) -> Player {
+        let mut player = Player::default().with_physics(physics);
+        assert!(!player.toggle_flying());
+        player
+    }
+
+    /// Walks for `frames` frames at 60 FPS with the given input.
+    fn walk(player: &mut Player, camera: &mut Camera, direction: Vec3, frames: u32) {
+        for _ in 0..frames {
+            player.move_camera(camera, direction, 1.0 / 60.0, false, false, floor);
+        }
+    }
+
+    #[test]
+    fn walking_player_falls_and_lands() {
+        let mut player = walking_player(PhysicsConfig::default());
+        let mut camera = camera_facing_wall().at_position(Vec3::new(0.5, 80.0, 0.5));
+
+        walk(&mut player, &mut camera, Vec3::ZERO, 180);
+
+        assert!(player.on_ground());
+        let feet = player.aabb(camera.position).min.y;
+        assert!((64.0..64.01).contains(&feet));
+        assert!(player.vertical_velocity().abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn walking_player_jumps_by_configured_height() {
+        let physics = PhysicsConfig {
+            gravity: 8.0,
+            ..PhysicsConfig::default()
+        };
+        let mut player = walking_player(physics);
+        let mut camera = camera_facing_wall().at_position(Vec3::new(0.5, 66.0, 0.5));
+        walk(&mut player, &mut camera, Vec3::ZERO, 60);
+        let ground = camera.position.y;
+
+        // Hold jump long enough to leave the ground, then track the peak
+        let mut peak = ground;
+        for _ in 0..240 {
+            player.move_camera(&mut camera, Vec3::Y, 1.0 / 60.0, false, false, floor);
+            peak = peak.max(camera.position.y);
+        }
+        let expected = player.physics().jump_height();
+        assert!(
+            (peak - ground - expected).abs() < 0.2,
+            "jumped {}",
+            peak - ground
+        );
+    }
+
+    #[test]
+    fn walking_moves_on_the_horizontal_plane() {
+        let mut player = walking_player(PhysicsConfig::default());
+        // Looking steeply down shouldn't make walking dig into the ground
+        let mut camera = Camera::new(CameraConfig::default())
+            .at_position(Vec3::new(0.5, 65.63, 0.5))
+            .with_orientation(0.0, -80.0);
+        walk(&mut player, &mut camera, Vec3::ZERO, 30);
+        let start = camera.position;
+
+        walk(&mut player, &mut camera, Vec3::Z, 30);
+
+        assert!(camera.position.x - start.x > 1.0);
+        assert!((camera.position.y - start.y).abs() < 0.01);
+    }
+
+    #[test]
+    fn walking_player_lands_on_top_of_a_slab() {
+        let mut player = walking_player(PhysicsConfig::default());
+        // Bottom slabs on the floor from x = 2 on
+        let slabs = |x: i32, y: i32, z: i32| {
+            if x >= 2 && y == 64 {
+                Some(BlockShape::BottomSlab)
+            } else {
+                floor(x, y, z)
+            }
+        };
+        // Walk off a ledge above the floor, out over the slabs
+        let mut camera = camera_facing_wall().at_position(Vec3::new(0.5, 66.0 + 1.62, 0.5));
+        for _ in 0..30 {
+            player.move_camera(&mut camera, Vec3::Z, 1.0 / 60.0, false, false, slabs);
+        }
+
+        assert!(camera.position.x > 3.0);
+        assert!(player.on_ground());
+        let feet = player.aabb(camera.position).min.y;
+        assert!((64.5..64.51).contains(&feet), "feet at {feet}");
+    }
+
+    #[test]
+    fn crouching_lowers_eyes_and_standing_restores_them() {
+        let mut player = walking_player(PhysicsConfig::default());
+        let mut camera = camera_facing_wall().at_position(Vec3::new(0.5, 66.0, 0.5));
+        walk(&mut player, &mut camera, Vec3::ZERO, 60);
+        let standing = camera.position.y;
+        let feet = player.aabb(camera.position).min.y;
+
+        let config = player.config().clone();
+        player.move_camera(&mut camera, Vec3::ZERO, 1.0 / 60.0, false, true, floor);
+        assert!(player.is_crouched());
+        let drop = config.eye_height - config.crouch_eye_height;
+        assert!((standing - camera.position.y - drop).abs() < 0.001);
+        let aabb = player.aabb(camera.position);
+        assert!((aabb.min.y - feet).abs() < 0.001);
+        assert!((aabb.max.y - feet - config.crouch_height).abs() < 0.001);
+
+        walk(&mut player, &mut camera, Vec3::ZERO, 1);
+        assert!(!player.is_crouched());
+        assert!((camera.position.y - standing).abs() < 0.001);
+    }
+
+    #[test]
+    fn low_ceiling_keeps_player_crouched() {
+        let mut player = walking_player(PhysicsConfig::default());
+        let mut camera = camera_facing_wall().at_position(Vec3::new(0.5, 64.3 + 1.62, 0.5));
+        assert!(player.set_crouched(&mut camera, true, floor));
+
+        // A block at head height while standing, but above the crouched box
+        let ceiling =
+            |x: i32, y: i32, z: i32| floor(x, y, z).or((y == 66).then_some(BlockShape::FullCube));
+        assert!(player.set_crouched(&mut camera, false, ceiling));
+        assert!(!player.set_crouched(&mut camera, false, floor));
+    }
+
+    #[test]
+    fn placement_is_rejected_wherever_the_body_overlaps() {
+        let mut player = Player::default();
+        // Feet at x = 10.0, so the body straddles columns x = 9 and x = 10
+        let eye = Vec3::new(10.0, 64.0 + player.eye_height(), 0.5);
+
+        for x in [9, 10] {
+            for y in [64, 65] {
+                assert!(player.blocks_placement(eye, Block::Stone, BlockPos::new(x, y, 0)));
+            }
+        }
+        // Beside, below and above the body
+        assert!(!player.blocks_placement(eye, Block::Stone, BlockPos::new(11, 64, 0)));
+        assert!(!player.blocks_placement(eye, Block::Stone, BlockPos::new(9, 63, 0)));
+        assert!(!player.blocks_placement(eye, Block::Stone, BlockPos::new(10, 66, 0)));
+        // A slab in the upper cell reaches into the body too
+        assert!(player.blocks_placement(eye, Block::StoneSlab, BlockPos::new(10, 65, 0)));
+        // Blocks the player can move through are fine
+        assert!(!player.blocks_placement(eye, Block::Water, BlockPos::new(10, 64, 0)));
+
+        player.toggle_noclip();
+        assert!(!player.blocks_placement(eye, Block::Stone, BlockPos::new(10, 64, 0)));
+    }
+}
@@ -2,39 +2,213 @@
 //!
 //! Handles wgpu initialization and rendering operations.
 
-use anyhow::{Context, Result};
+use glam::Vec3;
 use std::sync::Arc;
-use tracing::info;
+use tracing::{info, warn};
 use wgpu::{
     Backends, Device, DeviceDescriptor, Features, Instance, InstanceDescriptor, Limits,
     PowerPreference, PresentMode, Queue, RequestAdapterOptions, Surface, SurfaceConfiguration,
-    TextureFormat, TextureUsages,
+    SurfaceError, SurfaceTexture, TextureFormat, TextureUsages,
 };
 use winit::{dpi::PhysicalSize, window::Window};
 
+use super::dynamic_resolution::DynamicResolutionConfig;
+use super::error::EngineError;
+use super::offscreen::render_to_image;
+use super::shadow::DEFAULT_SHADOW_RESOLUTION;
+use super::sky::{DEFAULT_DAY_LENGTH, DEFAULT_FOG_END, DEFAULT_FOG_START};
+use crate::world::DEFAULT_ATLAS_SEED;
+
 /// Renderer configuration options.
 #[derive(Debug, Clone)]
 pub struct RendererConfig {
     /// Preferred present mode (VSync, Immediate, etc.).
     pub present_mode: PresentMode,
+    /// Present modes to try, in order, if the preferred one is unsupported.
+    pub present_mode_fallbacks: Vec<PresentMode>,
     /// The clear color for the screen (RGBA).
     pub clear_color: wgpu::Color,
+    /// Fraction of the window resolution the world is rendered at.
+    /// The starting scale when dynamic resolution is enabled.
+    pub render_scale: f32,
+    /// Frame-time driven adjustment of the render scale.
+    pub dynamic_resolution: DynamicResolutionConfig,
+    /// Whether the sun casts shadows.
+    pub shadows: bool,
+    /// Shadow map resolution in texels per side.
+    pub shadow_resolution: u32,
+    /// Seed varying the procedural block textures.
+    pub atlas_seed: u32,
+    /// Whether the crosshair grows while zoomed in, like a scope reticle.
+    pub crosshair_scale_with_zoom: bool,
+    /// Distance from the camera where fog starts.
+    pub fog_start: f32,
+    /// Distance from the camera where fog fully hides terrain.
+    pub fog_end: f32,
+    /// Fixed fog color, or `None` to fade terrain into the sky behind it.
+    pub fog_color: Option<Vec3>,
+    /// Starting time of day in `[0, 1)`: 0 is midnight, 0.5 is noon.
+    pub time_of_day: f32,
+    /// Seconds for a full day to pass, or 0 to keep the starting time.
+    pub day_length: f32,
 }
 
 impl Default for RendererConfig {
     fn default() -> Self {
         Self {
             present_mode: PresentMode::AutoVsync,
+            present_mode_fallbacks: vec![PresentMode::AutoVsync, PresentMode::Fifo],
             clear_color: wgpu::Color {
                 r: 0.1,
                 g: 0.2,
                 b: 0.3,
                 a: 1.0,
             },
+            render_scale: 1.0,
+            dynamic_resolution: DynamicResolutionConfig::default(),
+            shadows: true,
+            shadow_resolution: DEFAULT_SHADOW_RESOLUTION,
+            atlas_seed: DEFAULT_ATLAS_SEED,
+            crosshair_scale_with_zoom: false,
+            fog_start: DEFAULT_FOG_START,
+            fog_end: DEFAULT_FOG_END,
+            fog_color: None,
+            time_of_day: 0.5,
+            day_length: DEFAULT_DAY_LENGTH,
+        }
+    }
+}
+
+/// How to respond when the next frame's surface texture can't be acquired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SurfaceRecovery {
+    /// The surface no longer matches the window (e.g. after a resize or a
+    /// display change); configure it again and skip the frame.
+    Reconfigure,
+    /// The failure is transient; skip the frame.
+    SkipFrame,
+    /// Rendering can't continue.
+    Fatal,
+}
+
+impl SurfaceRecovery {
+    /// Returns the response to `error`.
+    #[must_use]
+    pub const fn for_error(error: &SurfaceError) -> Self {
+        match error {
+            SurfaceError::Lost | SurfaceError::Outdated => Self::Reconfigure,
+            SurfaceError::Timeout | SurfaceError::Other => Self::SkipFrame,
+            SurfaceError::OutOfMemory => Self::Fatal,
         }
     }
 }
 
+/// Picks the first supported present mode from the preferred mode and its fallbacks.
+///
+/// The `Auto*` modes are always accepted since wgpu resolves them itself. If
+/// nothing in the list is supported, `Fifo` is used (it's guaranteed by the
+/// spec), or the first supported mode if even that is missing.
+#[must_use]
+pub fn select_present_mode(
+    preferred: PresentMode,
+    fallbacks: &[PresentMode],
+    supported: &[PresentMode],
+) -> PresentMode {
+    let is_supported = |mode: &PresentMode| {
+        matches!(mode, PresentMode::AutoVsync | PresentMode::AutoNoVsync)
+            || supported.contains(mode)
+    };
+
+    std::iter::once(preferred)
+        .chain(fallbacks.iter().copied())
+        .find(is_supported)
+        .or_else(|| {
+            supported
+                .contains(&PresentMode::Fifo)
+                .then_some(PresentMode::Fifo)
+        })
+        .or_else(|| supported.first().copied())
+        .unwrap_or(PresentMode::Fifo)
+}
+
+/// Returns the present mode that turns vsync off if `mode` has it on, or
+/// back on if it's off.
+#[must_use]
+pub const fn vsync_toggled(mode: PresentMode) -> PresentMode {
+    match mode {
+        PresentMode::Immediate | PresentMode::Mailbox | PresentMode::AutoNoVsync => {
+            PresentMode::AutoVsync
+        }
+        PresentMode::Fifo | PresentMode::FifoRelaxed | PresentMode::AutoVsync => {
+            PresentMode::AutoNoVsync
+        }
+    }
+}
+
+/// Switches `surface_config` to the first supported mode of `preferred`
+/// and its fallbacks, as [`select_present_mode`] picks it, logging when
+/// `preferred` itself is unsupported. Returns true if the mode changed, so
+/// the surface needs configuring again.
+fn apply_present_mode(
+    surface_config: &mut SurfaceConfiguration,
+    preferred: PresentMode,
+    fallbacks: &[PresentMode],
+    supported: &[PresentMode],
+) -> bool {
+    let present_mode = select_present_mode(preferred, fallbacks, supported);
+    if present_mode != preferred {
+        warn!("Present mode {preferred:?} unsupported, falling back to {present_mode:?}");
+    }
+    if present_mode == surface_config.present_mode {
+        return false;
+    }
+    surface_config.present_mode = present_mode;
+    true
+}
+
+/// Parses and validates WGSL source without touching the GPU.
+///
+/// # Errors
+///
+/// Returns [`EngineError::Shader`] with the parser or validator message.
+pub fn validate_wgsl(label: &str, source: &str) -> Result<(), EngineError> {
+    use wgpu::naga::{front::wgsl, valid};
+
+    let shader_error = |message: String| EngineError::Shader {
+        label: label.to_string(),
+        message,
+    };
+
+    let module = wgsl::parse_str(source).map_err(|e| shader_error(e.emit_to_string(source)))?;
+    valid::Validator::new(
+        valid::ValidationFlags::all(),
+        valid::Capabilities::default(),
+    )
+    .validate(&module)
+    .map_err(|e| shader_error(e.emit_to_string(source)))?;
+    Ok(())
+}
+
+/// Validates WGSL source and creates a shader module from it.
+///
+/// wgpu treats an invalid shader as a fatal device error, so validating
+/// first turns a broken shader into a recoverable error.
+///
+/// # Errors
+///
+/// Returns [`EngineError::Shader`] if the source is invalid.
+pub fn create_shader(
+    device: &Device,
+    label: &str,
+    source: &str,
+) -> Result<wgpu::ShaderModule, EngineError> {
+    validate_wgsl(label, source)?;
+    Ok(device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some(label),
+        source: wgpu::ShaderSource::Wgsl(source.into()),
+    }))
+}
+
 /// The main renderer handling all GPU operations.
 pub struct Renderer {
     /// The wgpu surface for presenting frames.
@@ -47,6 +221,8 @@ pub struct Renderer {
     surface_config: SurfaceConfiguration,
     /// The preferred texture format.
     surface_format: TextureFormat,
+    /// Present modes the surface supports.
+    supported_present_modes: Vec<PresentMode>,
     /// Current window size.
     size: PhysicalSize<u32>,
     /// Renderer configuration.
@@ -59,7 +235,7 @@ impl Renderer {
     /// # Errors
     ///
     /// Returns an error if GPU initialization fails.
-    pub async fn new(window: Arc<Window>, config: RendererConfig) -> Result<Self> {
+    pub async fn new(window: Arc<Window>, config: RendererConfig) -> Result<Self, EngineError> {
         let size = window.inner_size();
 
         // Create wgpu instance
@@ -69,9 +245,7 @@ impl Renderer {
         });
 
         // Create surface
-        let surface = instance
-            .create_surface(window)
-            .context("Failed to create surface")?;
+        let surface = instance.create_surface(window)?;
 
         // Request adapter
         let adapter = instance
@@ -81,23 +255,23 @@ impl Renderer {
                 force_fallback_adapter: false,
             })
             .await
-            .context("Failed to find a suitable GPU adapter")?;
+            .ok_or(EngineError::NoAdapter)?;
 
         info!("Using GPU: {}", adapter.get_info().name);
 
-        // Request device and queue
+        // Request device and queue, with line rasterization for the
+        // wireframe debug view where the adapter has it
         let (device, queue) = adapter
             .request_device(
                 &DeviceDescriptor {
                     label: Some("Voxel Forge Device"),
-                    required_features: Features::empty(),
+                    required_features: adapter.features() & Features::POLYGON_MODE_LINE,
                     required_limits: Limits::default(),
                     memory_hints: Default::default(),
                 },
                 None,
             )
-            .await
-            .context("Failed to create device")?;
+            .await?;
 
         // Configure surface
         let surface_caps = surface.get_capabilities(&adapter);
@@ -108,12 +282,26 @@ impl Renderer {
             .copied()
             .unwrap_or(surface_caps.formats[0]);
 
+        let present_mode = select_present_mode(
+            config.present_mode,
+            &config.present_mode_fallbacks,
+            &surface_caps.present_modes,
+        );
+        if present_mode == config.present_mode {
+            info!("Present mode: {:?}", present_mode);
+        } else {
+            warn!(
+                "Present mode {:?} unsupported, falling back to {:?}",
+                config.present_mode, present_mode
+            );
+        }
+
         let surface_config = SurfaceConfiguration {
             usage: TextureUsages::RENDER_ATTACHMENT,
             format: surface_format,
             width: size.width,
             height: size.height,
-            present_mode: config.present_mode,
+            present_mode,
             alpha_mode: surface_caps.alpha_modes[0],
             view_formats: vec![],
             desired_maximum_frame_latency: 2,
@@ -132,6 +320,7 @@ impl Renderer {
             queue,
             surface_config,
             surface_format,
+            supported_present_modes: surface_caps.present_modes,
             size,
             config,
         })
@@ -148,6 +337,63 @@ impl Renderer {
         }
     }
 
+    /// Configures the surface again with the current size and settings,
+    /// e.g. after it was lost or became outdated.
+    pub fn reconfigure(&mut self) {
+        self.surface.configure(&self.device, &self.surface_config);
+    }
+
+    /// Returns the present mode the surface is configured with.
+    #[must_use]
+    pub const fn present_mode(&self) -> PresentMode {
+        self.surface_config.present_mode
+    }
+
+    /// Switches to another present mode, e.g. to turn vsync off for
+    /// benchmarking, and configures the surface again. An unsupported mode
+    /// falls back like the one in [`RendererConfig`] does. Returns the mode
+    /// now in use.
+    pub fn set_present_mode(&mut self, mode: PresentMode) -> PresentMode {
+        self.config.present_mode = mode;
+        if apply_present_mode(
+            &mut self.surface_config,
+            mode,
+            &self.config.present_mode_fallbacks,
+            &self.supported_present_modes,
+        ) {
+            self.reconfigure();
+        }
+        info!("Present mode: {:?}", self.surface_config.present_mode);
+        self.surface_config.present_mode
+    }
+
+    /// Acquires the next frame's surface texture.
+    ///
+    /// A lost or outdated surface is reconfigured, and it and other
+    /// transient failures skip the frame by returning `None` (see
+    /// [`SurfaceRecovery`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EngineError::SurfaceTexture`] if the GPU is out of memory.
+    pub fn acquire_frame(&mut self) -> Result<Option<SurfaceTexture>, EngineError> {
+        match self.surface.get_current_texture() {
+            Ok(frame) => Ok(Some(frame)),
+            Err(error) => match SurfaceRecovery::for_error(&error) {
+                SurfaceRecovery::Reconfigure => {
+                    warn!("Surface {error}, reconfiguring");
+                    self.reconfigure();
+                    Ok(None)
+                }
+                SurfaceRecovery::SkipFrame => {
+                    warn!("Skipping frame: {error}");
+                    Ok(None)
+                }
+                SurfaceRecovery::Fatal => Err(error.into()),
+            },
+        }
+    }
+
     /// Returns the current size.
     #[must_use]
     pub const fn size(&self) -> PhysicalSize<u32> {
@@ -178,16 +424,16 @@ impl Renderer {
         &self.surface
     }
 
-    /// Renders a frame with the current clear color.
+    /// Renders a frame with the current clear color. Does nothing if the
+    /// frame is skipped (see [`acquire_frame`](Self::acquire_frame)).
     ///
     /// # Errors
     ///
-    /// Returns an error if rendering fails.
-    pub fn render(&mut self) -> Result<()> {
-        let output = self
-            .surface
-            .get_current_texture()
-            .context("Failed to get surface texture")?;
+    /// Returns [`EngineError::SurfaceTexture`] if the GPU is out of memory.
+    pub fn render(&mut self) -> Result<(), EngineError> {
+        let Some(output) = self.acquire_frame()? else {
+            return Ok(());
+        };
 
         let view = output
             .texture
@@ -222,8 +468,178 @@ impl Renderer {
         Ok(())
     }
 
+    /// Renders one pass offscreen at the given size and reads the pixels back.
+    ///
+    /// The color target uses the surface format, so pipelines built for the
+    /// window (like the chunk renderer's) can draw into it. The pass also has
+    /// a `Depth32Float` depth target and is cleared to the configured clear
+    /// color. Intended for automated rendering tests.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EngineError::Readback`] if the size is zero, the surface
+    /// format can't be read back as RGBA, or mapping the pixels fails.
+    pub fn render_to_texture<'a, F>(
+        &self,
+        width: u32,
+        height: u32,
+        draw: F,
+    ) -> Result<image::RgbaImage, EngineError>
+    where
+        F: FnOnce(&mut wgpu::RenderPass<'a>),
+    {
+        render_to_image(
+            &self.device,
+            &self.queue,
+            self.surface_format,
+            width,
+            height,
+            self.config.clear_color,
+            draw,
+        )
+    }
+
     /// Sets the clear color.
     pub fn set_clear_color(&mut self, color: wgpu::Color) {
         self.config.clear_color = color;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn present_mode_uses_preferred_when_supported() {
+        let supported = [PresentMode::Fifo, PresentMode::Immediate];
+        let mode = select_present_mode(
+            PresentMode::Immediate,
+            &[PresentMode::AutoVsync, PresentMode::Fifo],
+            &supported,
+        );
+        assert_eq!(mode, PresentMode::Immediate);
+    }
+
+    #[test]
+    fn present_mode_falls_back_in_order() {
+        let supported = [PresentMode::Fifo];
+        let mode = select_present_mode(
+            PresentMode::Mailbox,
+            &[PresentMode::Immediate, PresentMode::Fifo],
+            &supported,
+        );
+        assert_eq!(mode, PresentMode::Fifo);
+
+        // Auto modes are always accepted
+        let mode = select_present_mode(
+            PresentMode::Mailbox,
+            &[PresentMode::AutoVsync, PresentMode::Fifo],
+            &supported,
+        );
+        assert_eq!(mode, PresentMode::AutoVsync);
+    }
+
+    #[test]
+    fn present_mode_defaults_to_fifo_when_nothing_matches() {
+        let supported = [PresentMode::Immediate, PresentMode::Fifo];
+        let mode = select_present_mode(PresentMode::Mailbox, &[], &supported);
+        assert_eq!(mode, PresentMode::Fifo);
+    }
+
+    #[test]
+    fn switching_present_mode_updates_the_surface_config() {
+        let mut config = SurfaceConfiguration {
+            usage: TextureUsages::RENDER_ATTACHMENT,
+            format: TextureFormat::Bgra8UnormSrgb,
+            width: 800,
+            height: 600,
+            present_mode: PresentMode::Fifo,
+            alpha_mode: wgpu::CompositeAlphaMode::Opaque,
+            view_formats: vec![],
+            desired_maximum_frame_latency: 2,
+        };
+        let supported = [PresentMode::Fifo, PresentMode::Immediate];
+        let fallbacks = [PresentMode::Fifo];
+
+        assert!(apply_present_mode(
+            &mut config,
+            PresentMode::Immediate,
+            &fallbacks,
+            &supported
+        ));
+        assert_eq!(config.present_mode, PresentMode::Immediate);
+
+        // Unsupported, so it falls back
+        assert!(apply_present_mode(
+            &mut config,
+            PresentMode::Mailbox,
+            &fallbacks,
+            &supported
+        ));
+        assert_eq!(config.present_mode, PresentMode::Fifo);
+
+        // Nothing changed, so there's nothing to reconfigure
+        assert!(!apply_present_mode(
+            &mut config,
+            PresentMode::Fifo,
+            &fallbacks,
+            &supported
+        ));
+    }
+
+    #[test]
+    fn vsync_toggles_back_and_forth() {
+        let off = vsync_toggled(RendererConfig::default().present_mode);
+        assert_eq!(off, PresentMode::AutoNoVsync);
+        assert_eq!(vsync_toggled(off), PresentMode::AutoVsync);
+        assert_eq!(vsync_toggled(PresentMode::Fifo), PresentMode::AutoNoVsync);
+        assert_eq!(vsync_toggled(PresentMode::Mailbox), PresentMode::AutoVsync);
+    }
+
+    #[test]
+    fn only_out_of_memory_is_fatal_for_the_surface() {
+        use SurfaceRecovery as R;
+
+        assert_eq!(R::for_error(&SurfaceError::Lost), R::Reconfigure);
+        assert_eq!(R::for_error(&SurfaceError::Outdated), R::Reconfigure);
+        assert_eq!(R::for_error(&SurfaceError::Timeout), R::SkipFrame);
+        assert_eq!(R::for_error(&SurfaceError::Other), R::SkipFrame);
+        assert_eq!(R::for_error(&SurfaceError::OutOfMemory), R::Fatal);
+    }
+
+    #[test]
+    fn broken_shader_is_a_shader_error() {
+        let err = validate_wgsl("Broken Shader", "fn vs_main( -> {").unwrap_err();
+        assert!(
+            matches!(&err, EngineError::Shader { label, .. } if label == "Broken Shader"),
+            "unexpected error: {err}"
+        );
+
+        // Parses, but fails validation (undeclared identifier)
+        let err = validate_wgsl("Invalid Shader", "fn f() -> f32 { return missing; }");
+        assert!(matches!(err, Err(EngineError::Shader { .. })));
+    }
+
+    #[test]
+    fn engine_shaders_are_valid() {
+        for (label, source) in [
+            ("Blit Shader", include_str!("shaders/blit.wgsl")),
+            ("Block Shader", include_str!("shaders/block.wgsl")),
+            ("Overlay Shader", include_str!("shaders/overlay.wgsl")),
+            ("Shadow Shader", include_str!("shaders/shadow.wgsl")),
+            ("Sky Shader", include_str!("shaders/sky.wgsl")),
+            ("Wireframe Shader", include_str!("shaders/wireframe.wgsl")),
+        ] {
+            validate_wgsl(label, source).unwrap();
+        }
+    }
+
+    #[test]
+    fn default_config_falls_back_to_vsync() {
+        let config = RendererConfig::default();
+        assert_eq!(
+            config.present_mode_fallbacks,
+            vec![PresentMode::AutoVsync, PresentMode::Fifo]
+        );
+    }
+}
@@ -0,0 +1,17 @@
+//! Player settings.
+//!
+//! Preferences that can change while the game runs, as opposed to the
+//! startup configs of the window, renderer and world.
+
+use super::input::KeyBindings;
+use crate::world::ColorOverrides;
+
+/// Player settings.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Settings {
+    /// Block colors replacing the built-in ones, e.g. a color-blind-friendly
+    /// palette. Applied to chunk meshes and the block texture atlas.
+    pub color_overrides: ColorOverrides,
+    /// Keys bound to movement and other game controls.
+    pub key_bindings: KeyBindings,
+}
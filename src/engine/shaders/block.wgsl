@@ -4,7 +4,12 @@
 struct CameraUniform {
     view_proj: mat4x4<f32>,
     view_pos: vec3<f32>,
-    _padding: f32,
+    time: f32,               // seconds, for animated textures
+    sky_zenith: vec4<f32>,   // rgb = zenith color, w = fog start
+    sky_horizon: vec4<f32>,  // rgb = horizon color, w = fog end
+    sky_fog: vec4<f32>,      // rgb = fixed fog color, w = 1 if used
+    sun_direction: vec4<f32>, // xyz = unit direction towards the sun or moon
+    sun_color: vec4<f32>,     // rgb = light color scaled by intensity
 }
 
 @group(0) @binding(0)
@@ -15,11 +20,31 @@ var t_diffuse: texture_2d<f32>;
 @group(1) @binding(1)
 var s_diffuse: sampler;
 
-// Texture atlas constants
+// Must match shadow.wgsl
+struct ShadowUniform {
+    light_view_proj: mat4x4<f32>,
+    params: vec4<f32>, // x = enabled, y = texel size, z = depth bias
+}
+
+@group(2) @binding(0)
+var<uniform> shadow: ShadowUniform;
+@group(2) @binding(1)
+var shadow_map: texture_depth_2d;
+@group(2) @binding(2)
+var shadow_sampler: sampler_comparison;
+
+// Warm tint of light from lamps and other light-emitting blocks
+const BLOCK_LIGHT_COLOR: vec3<f32> = vec3<f32>(1.0, 0.85, 0.6);
+
+// Texture atlas constants. The atlas grows in rows to fit the registered
+// blocks, so the tile height comes from the texture's size
 const ATLAS_COLUMNS: f32 = 8.0;
-const ATLAS_ROWS: f32 = 4.0;
+const TEXTURE_SIZE: f32 = 16.0;
 const TILE_SIZE_U: f32 = 1.0 / ATLAS_COLUMNS;
-const TILE_SIZE_V: f32 = 1.0 / ATLAS_ROWS;
+
+// Animated textures step through a strip of frames along the atlas row,
+// one tile apart. Must match texture_atlas::ANIMATION_FPS
+const ANIMATION_FPS: f32 = 8.0;
 
 struct VertexInput {
     @location(0) position: vec3<f32>,
@@ -28,6 +53,8 @@ struct VertexInput {
     @location(3) ao: f32,
     @location(4) local_uv: vec2<f32>,   // Local UV (0 to width, 0 to height) for tiling
     @location(5) atlas_uv: vec2<f32>,   // Atlas base position (top-left of tile)
+    @location(6) light: vec2<f32>,      // x = block light, y = sky light (0 to 1)
+    @location(7) frames: f32,           // Animation frames, 1 for a still texture
 }
 
 struct VertexOutput {
@@ -38,6 +65,17 @@ struct VertexOutput {
     @location(3) ao: f32,
     @location(4) local_uv: vec2<f32>,
     @location(5) atlas_uv: vec2<f32>,
+    @location(6) light: vec2<f32>,
+}
+
+// U offset of the current animation frame. Must match
+// TextureAtlas::frame_offset
+fn frame_offset(frames: f32) -> f32 {
+    if frames <= 1.0 {
+        return 0.0;
+    }
+    let frame = floor(max(camera.time, 0.0) * ANIMATION_FPS) % frames;
+    return frame * TILE_SIZE_U;
 }
 
 @vertex
@@ -49,29 +87,42 @@ fn vs_main(in: VertexInput) -> VertexOutput {
     out.color = in.color;
     out.ao = in.ao;
     out.local_uv = in.local_uv;
-    out.atlas_uv = in.atlas_uv;
+    out.atlas_uv = in.atlas_uv + vec2<f32>(frame_offset(in.frames), 0.0);
+    out.light = in.light;
     return out;
 }
 
-@fragment
-fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    // Tile the texture within the atlas slot using fract()
-    // local_uv goes from 0 to quad_width/height, fract gives us 0-1 for each tile
-    let tiled_uv = fract(in.local_uv);
-    
-    // Map to atlas position: atlas_base + tiled_uv * tile_size
-    let final_uv = in.atlas_uv + tiled_uv * vec2<f32>(TILE_SIZE_U, TILE_SIZE_V);
-    
-    // Sample texture at the computed atlas position
-    let tex_color = textureSample(t_diffuse, s_diffuse, final_uv);
-    
-    // Alpha test - discard fully transparent pixels (cutout transparency)
-    if tex_color.a < 0.1 {
-        discard;
+// Fraction of sunlight reaching a world position (0 = fully shadowed).
+// Uses 3x3 percentage-closer filtering to soften the shadow edges.
+fn shadow_factor(world_position: vec3<f32>) -> f32 {
+    if shadow.params.x < 0.5 {
+        return 1.0;
     }
-    
-    // Sun direction (from upper-right)
-    let sun_dir = normalize(vec3<f32>(0.5, 1.0, 0.3));
+
+    let clip = shadow.light_view_proj * vec4<f32>(world_position, 1.0);
+    let ndc = clip.xyz / clip.w;
+    let uv = ndc.xy * vec2<f32>(0.5, -0.5) + vec2<f32>(0.5, 0.5);
+
+    // Outside the shadow map counts as lit
+    if any(uv < vec2<f32>(0.0)) || any(uv > vec2<f32>(1.0)) || ndc.z > 1.0 {
+        return 1.0;
+    }
+
+    let depth = ndc.z - shadow.params.z;
+    var lit = 0.0;
+    for (var y = -1; y <= 1; y++) {
+        for (var x = -1; x <= 1; x++) {
+            let offset = vec2<f32>(f32(x), f32(y)) * shadow.params.y;
+            lit += textureSampleCompareLevel(shadow_map, shadow_sampler, uv + offset, depth);
+        }
+    }
+    return lit / 9.0;
+}
+
+// Computes the lit color shared by the opaque and transparent passes
+fn shade(in: VertexOutput, tex_color: vec4<f32>) -> vec3<f32> {
+    // Sun (or moon) direction, following the time of day
+    let sun_dir = camera.sun_direction.xyz;
     
     // Basic directional lighting
     let ndotl = max(dot(in.normal, sun_dir), 0.0);
@@ -97,13 +148,81 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     }
     
     // Combine lighting
-    let light = ambient + ndotl * 0.6;
+    let sun = ndotl * 0.6 * shadow_factor(in.world_position);
+    // Sky light dims faces under cover, like cave floors and overhangs
+    let sky_light = (ambient + sun * camera.sun_color.rgb) * in.light.y;
+
+    // Block light brightens faces the sky leaves dark, without adding up
+    let light = max(sky_light, in.light.x * BLOCK_LIGHT_COLOR);
     
     // Apply AO and face shading
     let final_light = light * in.ao * face_shade;
     
     // Final color: texture * lighting (texture already has block color baked in)
-    let final_color = tex_color.rgb * final_light;
+    return tex_color.rgb * final_light;
+}
+
+// Sky color in a view direction. Must match SkyGradient::color_in_direction
+fn sky_color(direction: vec3<f32>) -> vec3<f32> {
+    let up = clamp(direction.y, 0.0, 1.0);
+    return mix(camera.sky_horizon.rgb, camera.sky_zenith.rgb, up);
+}
+
+// Blends a lit color toward the fog color (by default the sky behind it)
+// based on view distance. Must match Sky::fog_color
+fn fog_amount(distance: f32) -> f32 {
+    let fog_start = camera.sky_zenith.w;
+    let fog_end = camera.sky_horizon.w;
+    return clamp((distance - fog_start) / max(fog_end - fog_start, 0.001), 0.0, 1.0);
+}
+
+fn apply_fog(in: VertexOutput, color: vec3<f32>) -> vec3<f32> {
+    let to_fragment = in.world_position - camera.view_pos;
+    let distance = length(to_fragment);
+    let fog = fog_amount(distance);
+    let sky = sky_color(to_fragment / max(distance, 0.001));
+    let fog_color = mix(sky, camera.sky_fog.rgb, camera.sky_fog.w);
+    return mix(color, fog_color, fog);
+}
+
+// Samples the block texture for this fragment from the atlas
+fn sample_atlas(in: VertexOutput) -> vec4<f32> {
+    // Tile the texture within the atlas slot using fract()
+    // local_uv goes from 0 to quad_width/height, fract gives us 0-1 for each tile
+    let tiled_uv = fract(in.local_uv);
+    
+    // Map to atlas position: atlas_base + tiled_uv * tile_size
+    let tile_size_v = TEXTURE_SIZE / f32(textureDimensions(t_diffuse).y);
+    let tile_size = vec2<f32>(TILE_SIZE_U, tile_size_v);
+    let final_uv = in.atlas_uv + tiled_uv * tile_size;
+    
+    // Pick the mip level from the untiled UV, since fract() jumps at tile
+    // edges and would select the smallest level along every seam
+    let uv = in.local_uv * tile_size;
+    return textureSampleGrad(t_diffuse, s_diffuse, final_uv, dpdx(uv), dpdy(uv));
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let tex_color = sample_atlas(in);
+    
+    // Alpha test - discard fully transparent pixels (cutout transparency)
+    if tex_color.a < 0.1 {
+        discard;
+    }
     
-    return vec4<f32>(final_color, tex_color.a);
+    return vec4<f32>(apply_fog(in, shade(in, tex_color)), tex_color.a);
+}
+
+// Translucent pass (glass, water, stained glass).
+// Blended multiplicatively: result = src * dst, so a pane filters whatever is
+// behind it by its color. Alpha sets how strongly it tints, fading out with
+// fog since the scene behind is already fogged.
+@fragment
+fn fs_transparent(in: VertexOutput) -> @location(0) vec4<f32> {
+    let tex_color = sample_atlas(in);
+    let distance = length(in.world_position - camera.view_pos);
+    let strength = tex_color.a * (1.0 - fog_amount(distance));
+    let tint = mix(vec3<f32>(1.0), shade(in, tex_color), strength);
+    return vec4<f32>(tint, tex_color.a);
 }
@@ -23,3 +23,43 @@ fn vs_main(in: VertexInput) -> VertexOutput {
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     return in.color;
 }
+
+// Textured quads: text glyphs sample the font atlas's coverage, hotbar
+// icons the block atlas's colors
+
+@group(0) @binding(0)
+var quad_texture: texture_2d<f32>;
+@group(0) @binding(1)
+var quad_sampler: sampler;
+
+struct TexturedVertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) color: vec4<f32>,
+}
+
+struct TexturedVertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+@vertex
+fn vs_textured(in: TexturedVertexInput) -> TexturedVertexOutput {
+    var out: TexturedVertexOutput;
+    out.clip_position = vec4<f32>(in.position, 0.0, 1.0);
+    out.uv = in.uv;
+    out.color = in.color;
+    return out;
+}
+
+@fragment
+fn fs_text(in: TexturedVertexOutput) -> @location(0) vec4<f32> {
+    let coverage = textureSample(quad_texture, quad_sampler, in.uv).r;
+    return vec4<f32>(in.color.rgb, in.color.a * coverage);
+}
+
+@fragment
+fn fs_icon(in: TexturedVertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(quad_texture, quad_sampler, in.uv) * in.color;
+}
@@ -0,0 +1,19 @@
+// Shadow map shader
+// Renders chunk depth from the sun's point of view (depth only, no color)
+
+struct ShadowUniform {
+    light_view_proj: mat4x4<f32>,
+    params: vec4<f32>, // x = enabled, y = texel size, z = depth bias
+}
+
+@group(0) @binding(0)
+var<uniform> shadow: ShadowUniform;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+}
+
+@vertex
+fn vs_main(in: VertexInput) -> @builtin(position) vec4<f32> {
+    return shadow.light_view_proj * vec4<f32>(in.position, 1.0);
+}
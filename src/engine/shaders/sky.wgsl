@@ -0,0 +1,45 @@
+// Sky gradient shader
+// Draws a fullscreen triangle colored by the view direction of each pixel
+
+struct SkyUniform {
+    inv_view_proj: mat4x4<f32>,
+    zenith: vec4<f32>,   // rgb = zenith color, w = fog start
+    horizon: vec4<f32>,  // rgb = horizon color, w = fog end
+    fog: vec4<f32>,      // rgb = fixed fog color, w = 1 if used
+}
+
+@group(0) @binding(0)
+var<uniform> sky: SkyUniform;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) ndc: vec2<f32>,
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    // One triangle covering the whole screen
+    let x = f32(i32(index & 1u) * 4 - 1);
+    let y = f32(i32(index >> 1u) * 4 - 1);
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(x, y, 1.0, 1.0);
+    out.ndc = vec2<f32>(x, y);
+    return out;
+}
+
+// Must match SkyGradient::color_in_direction
+fn sky_color(direction: vec3<f32>) -> vec3<f32> {
+    let up = clamp(direction.y, 0.0, 1.0);
+    return mix(sky.horizon.rgb, sky.zenith.rgb, up);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Unproject near and far points to get the world-space view ray
+    let near = sky.inv_view_proj * vec4<f32>(in.ndc, 0.0, 1.0);
+    let far = sky.inv_view_proj * vec4<f32>(in.ndc, 1.0, 1.0);
+    let direction = normalize(far.xyz / far.w - near.xyz / near.w);
+
+    return vec4<f32>(sky_color(direction), 1.0);
+}
@@ -1,4 +1,4 @@
-// Wireframe shader for block selection highlight
+// Wireframe shader for block selection boxes
 
 struct CameraUniform {
     view_proj: mat4x4<f32>,
@@ -9,21 +9,23 @@ var<uniform> camera: CameraUniform;
 
 struct VertexInput {
     @location(0) position: vec3<f32>,
+    @location(1) color: vec4<f32>,
 }
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
 }
 
 @vertex
 fn vs_main(in: VertexInput) -> VertexOutput {
     var out: VertexOutput;
     out.clip_position = camera.view_proj * vec4<f32>(in.position, 1.0);
+    out.color = in.color;
     return out;
 }
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    // Black outline color with slight transparency
-    return vec4<f32>(0.0, 0.0, 0.0, 0.8);
+    return in.color;
 }
@@ -0,0 +1,445 @@
+//! Sun shadow mapping.
+//!
+//! Opaque chunk geometry is rendered from the sun's point of view with an
+//! orthographic projection fitted around the visible chunks, storing only
+//! depth. The block shader then compares each fragment's depth from the sun
+//! against the map to find out whether it's in shadow.
+//!
+//! This is a single cascade: one shadow map covers everything visible, so
+//! shadow detail drops as the view distance grows.
+
+use bytemuck::{Pod, Zeroable};
+use glam::{Mat4, Vec3};
+use wgpu::util::DeviceExt;
+
+use super::chunk_renderer::ChunkBuffers;
+use super::error::EngineError;
+use super::renderer::create_shader;
+use crate::math::Aabb;
+use crate::world::ChunkVertex;
+
+/// Shadow map depth format.
+pub const SHADOW_DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+/// Default shadow map resolution in texels per side.
+pub const DEFAULT_SHADOW_RESOLUTION: u32 = 2048;
+
+/// Depth offset applied when comparing against the map, to avoid shadow acne.
+const DEPTH_BIAS: f32 = 0.0015;
+
+/// Shadow projection and sampling parameters as sent to the GPU.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct ShadowUniform {
+    /// World to light clip space.
+    pub light_view_proj: [[f32; 4]; 4],
+    /// Enabled flag (x), texel size in UV units (y) and depth bias (z).
+    pub params: [f32; 4],
+}
+
+impl ShadowUniform {
+    /// Returns a uniform that turns shadow lookups off.
+    #[must_use]
+    pub const fn disabled() -> Self {
+        Self {
+            light_view_proj: Mat4::IDENTITY.to_cols_array_2d(),
+            params: [0.0; 4],
+        }
+    }
+
+    /// Returns a uniform for a `resolution`-sized map rendered with `light_view_proj`.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub const fn new(light_view_proj: Mat4, resolution: u32) -> Self {
+        Self {
+            light_view_proj: light_view_proj.to_cols_array_2d(),
+            params: [1.0, (resolution as f32).recip(), DEPTH_BIAS, 0.0],
+        }
+    }
+}
+
+/// Returns the world-to-light-clip matrix for light shining along
+/// `-light_dir` (pointing from the ground towards the sun), with an
+/// orthographic box just enclosing `bounds`.
+#[must_use]
+pub fn light_view_projection(light_dir: Vec3, bounds: &Aabb) -> Mat4 {
+    let light_dir = light_dir.normalize_or(Vec3::Y);
+    let center = bounds.center();
+    let radius = (bounds.max - bounds.min).length() * 0.5;
+
+    // Any up vector works as long as it isn't parallel to the light
+    let up = if light_dir.y.abs() > 0.99 {
+        Vec3::Z
+    } else {
+        Vec3::Y
+    };
+    let view = Mat4::look_at_rh(center + light_dir * radius, center, up);
+
+    // Fit the projection to the box corners in light space
+    let mut min = Vec3::splat(f32::MAX);
+    let mut max = Vec3::splat(f32::MIN);
+    for corner in bounds.corners() {
+        let light_space = view.transform_point3(corner);
+        min = min.min(light_space);
+        max = max.max(light_space);
+    }
+
+    // Right-handed view space looks down -Z
+    let projection = Mat4::orthographic_rh(min.x, max.x, min.y, max.y, -max.z, -min.z);
+    projection * view
+}
+
+/// The shadow depth target and the pipeline that renders into it.
+pub struct ShadowMap {
+    /// Depth-only pipeline.
+    pipeline: wgpu::RenderPipeline,
+    /// Bind group with the shadow uniform.
+    bind_group: wgpu::BindGroup,
+    /// Depth texture.
+    depth_texture: wgpu::Texture,
+    /// Depth texture view.
+    depth_view: wgpu::TextureView,
+}
+
+impl ShadowMap {
+    /// Creates a `resolution` x `resolution` shadow map whose pass reads the
+    /// light projection from `uniform_buffer`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EngineError::Shader`] if the shadow shader is invalid.
+    pub fn new(
+        device: &wgpu::Device,
+        uniform_buffer: &wgpu::Buffer,
+        resolution: u32,
+    ) -> Result<Self, EngineError> {
+        let shader = create_shader(device, "Shadow Shader", include_str!("shaders/shadow.wgsl"))?;
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Shadow Pass Bind Group Layout"),
+            entries: &[uniform_layout_entry(0, wgpu::ShaderStages::VERTEX)],
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Shadow Pass Bind Group"),
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Shadow Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Shadow Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[ChunkVertex::layout()],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: None, // Depth only
+            primitive: wgpu::PrimitiveState {
+                // Both sides cast shadows, so thin geometry doesn't leak light
+                cull_mode: None,
+                ..wgpu::PrimitiveState::default()
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: SHADOW_DEPTH_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState {
+                    constant: 2,
+                    slope_scale: 2.0,
+                    clamp: 0.0,
+                },
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        let (depth_texture, depth_view) = create_depth_target(device, "Shadow Map", resolution);
+
+        Ok(Self {
+            pipeline,
+            bind_group,
+            depth_texture,
+            depth_view,
+        })
+    }
+
+    /// Returns the map resolution in texels per side.
+    #[must_use]
+    pub fn resolution(&self) -> u32 {
+        self.depth_texture.width()
+    }
+
+    /// Returns the depth texture view.
+    #[must_use]
+    pub const fn depth_view(&self) -> &wgpu::TextureView {
+        &self.depth_view
+    }
+
+    /// Records the shadow pass for the given opaque chunks.
+    pub fn render<'a, I>(&self, encoder: &mut wgpu::CommandEncoder, chunks: I)
+    where
+        I: Iterator<Item = &'a ChunkBuffers>,
+    {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Shadow Render Pass"),
+            color_attachments: &[],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &self.depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        for chunk in chunks {
+            pass.set_vertex_buffer(0, chunk.vertex_buffer.slice(..));
+            pass.set_index_buffer(chunk.index_buffer.slice(..), chunk.index_format);
+            pass.draw_indexed(0..chunk.index_count, 0, 0..1);
+        }
+    }
+}
+
+/// The block shader's view of the shadow map: uniform, depth texture and
+/// comparison sampler.
+///
+/// While shadows are off, a 1x1 placeholder texture is bound so the block
+/// pipelines don't need a separate variant.
+pub struct ShadowBindings {
+    /// Shadow uniform buffer, shared with the shadow pass.
+    buffer: wgpu::Buffer,
+    /// Bind group layout used by the block pipelines.
+    layout: wgpu::BindGroupLayout,
+    /// Comparison sampler for PCF lookups.
+    sampler: wgpu::Sampler,
+    /// Depth texture bound while shadows are off.
+    placeholder_view: wgpu::TextureView,
+    /// Current bind group.
+    bind_group: wgpu::BindGroup,
+}
+
+impl ShadowBindings {
+    /// Creates the bindings with shadows off.
+    #[must_use]
+    pub fn new(device: &wgpu::Device) -> Self {
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Shadow Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[ShadowUniform::disabled()]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Shadow Bind Group Layout"),
+            entries: &[
+                uniform_layout_entry(0, wgpu::ShaderStages::FRAGMENT),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Depth,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison),
+                    count: None,
+                },
+            ],
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Shadow Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            compare: Some(wgpu::CompareFunction::LessEqual),
+            ..Default::default()
+        });
+
+        let (_, placeholder_view) = create_depth_target(device, "Shadow Map Placeholder", 1);
+        let bind_group =
+            Self::create_bind_group(device, &layout, &buffer, &placeholder_view, &sampler);
+
+        Self {
+            buffer,
+            layout,
+            sampler,
+            placeholder_view,
+            bind_group,
+        }
+    }
+
+    /// Creates a bind group sampling `depth_view`.
+    fn create_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        buffer: &wgpu::Buffer,
+        depth_view: &wgpu::TextureView,
+        sampler: &wgpu::Sampler,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Shadow Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(depth_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+            ],
+        })
+    }
+
+    /// Binds `map`'s depth texture, or the placeholder when `None`.
+    pub fn bind(&mut self, device: &wgpu::Device, map: Option<&ShadowMap>) {
+        let view = map.map_or(&self.placeholder_view, ShadowMap::depth_view);
+        self.bind_group =
+            Self::create_bind_group(device, &self.layout, &self.buffer, view, &self.sampler);
+    }
+
+    /// Writes the shadow uniform.
+    pub fn update(&self, queue: &wgpu::Queue, uniform: &ShadowUniform) {
+        queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&[*uniform]));
+    }
+
+    /// Returns the uniform buffer.
+    #[must_use]
+    pub const fn buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
+    }
+
+    /// Returns the bind group layout.
+    #[must_use]
+    pub const fn layout(&self) -> &wgpu::BindGroupLayout {
+        &self.layout
+    }
+
+    /// Returns the current bind group.
+    #[must_use]
+    pub const fn bind_group(&self) -> &wgpu::BindGroup {
+        &self.bind_group
+    }
+}
+
+/// Layout entry for a uniform buffer.
+const fn uniform_layout_entry(
+    binding: u32,
+    visibility: wgpu::ShaderStages,
+) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility,
+        ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    }
+}
+
+/// Creates a square depth texture that can be rendered to and sampled.
+fn create_depth_target(
+    device: &wgpu::Device,
+    label: &str,
+    resolution: u32,
+) -> (wgpu::Texture, wgpu::TextureView) {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(label),
+        size: wgpu::Extent3d {
+            width: resolution.max(1),
+            height: resolution.max(1),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: SHADOW_DEPTH_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    (texture, view)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::chunk_renderer::ChunkRenderer;
+    use crate::engine::offscreen::request_headless_device;
+
+    #[test]
+    fn light_projection_encloses_bounds() {
+        let bounds = Aabb::new(Vec3::new(-32.0, 0.0, 16.0), Vec3::new(48.0, 128.0, 80.0));
+
+        for light_dir in [Vec3::Y, Vec3::new(0.5, 1.0, 0.3), Vec3::new(1.0, 0.05, 0.0)] {
+            let light_view_proj = light_view_projection(light_dir, &bounds);
+            for corner in bounds.corners() {
+                let ndc = light_view_proj.project_point3(corner);
+                assert!(ndc.x.abs() <= 1.0001 && ndc.y.abs() <= 1.0001, "{ndc}");
+                assert!((-0.0001..=1.0001).contains(&ndc.z), "{ndc}");
+            }
+        }
+    }
+
+    #[test]
+    fn nearer_to_sun_means_smaller_depth() {
+        let bounds = Aabb::new(Vec3::ZERO, Vec3::splat(16.0));
+        let light_view_proj = light_view_projection(Vec3::Y, &bounds);
+
+        let top = light_view_proj.project_point3(Vec3::new(8.0, 15.0, 8.0));
+        let bottom = light_view_proj.project_point3(Vec3::new(8.0, 1.0, 8.0));
+        assert!(top.z < bottom.z);
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter (e.g. lavapipe); run with --ignored"]
+    fn shadow_map_follows_settings() {
+        let (device, queue) =
+            pollster::block_on(request_headless_device()).expect("no GPU adapter available");
+        let format = wgpu::TextureFormat::Rgba8UnormSrgb;
+        let mut chunk_renderer = ChunkRenderer::new(&device, &queue, format, 64, 64).unwrap();
+
+        // Off by default: no pipeline or depth target
+        assert_eq!(chunk_renderer.shadow_resolution(), None);
+
+        chunk_renderer.set_shadows(&device, true, 1024).unwrap();
+        assert_eq!(chunk_renderer.shadow_resolution(), Some(1024));
+
+        chunk_renderer.set_shadows(&device, true, 512).unwrap();
+        assert_eq!(chunk_renderer.shadow_resolution(), Some(512));
+
+        chunk_renderer.set_shadows(&device, false, 512).unwrap();
+        assert_eq!(chunk_renderer.shadow_resolution(), None);
+    }
+}
@@ -0,0 +1,582 @@
+//! Sky gradient and distance fog.
+//!
+//! The sky is a vertical gradient from the horizon to the zenith. Distance
+//! fog samples the same gradient in the viewing direction, so far terrain
+//! fades into exactly the sky color behind it.
+//!
+//! The directional light also follows the time of day: the sun rises in the
+//! east, passes overhead (tilted towards the south by the configured tilt)
+//! and sets in the west, warming in color near the horizon. At night the
+//! moon takes over from the opposite side of the sky with dim, cool light.
+
+use bytemuck::{Pod, Zeroable};
+use glam::{Mat4, Vec3};
+use wgpu::util::DeviceExt;
+
+use super::error::EngineError;
+use super::renderer::create_shader;
+
+/// Default distance where fog starts.
+pub const DEFAULT_FOG_START: f32 = 60.0;
+
+/// Default distance where fog fully hides terrain.
+pub const DEFAULT_FOG_END: f32 = 92.0;
+
+/// Default length of a full day, in seconds.
+pub const DEFAULT_DAY_LENGTH: f32 = 1200.0;
+
+/// Sky and fog configuration.
+#[derive(Debug, Clone)]
+pub struct SkyConfig {
+    /// Zenith (straight up) color at noon.
+    pub day_zenith: Vec3,
+    /// Horizon color at noon.
+    pub day_horizon: Vec3,
+    /// Zenith color at midnight.
+    pub night_zenith: Vec3,
+    /// Horizon color at midnight.
+    pub night_horizon: Vec3,
+    /// Distance where fog starts.
+    pub fog_start: f32,
+    /// Distance where fog fully hides terrain.
+    pub fog_end: f32,
+    /// Fixed fog color, or `None` to fade terrain into the sky behind it.
+    pub fog_color: Option<Vec3>,
+    /// Tilt of the sun's path away from straight overhead, in degrees.
+    pub sun_tilt: f32,
+    /// Seconds for the time of day to go round once.
+    pub day_length: f32,
+}
+
+impl Default for SkyConfig {
+    fn default() -> Self {
+        Self {
+            day_zenith: Vec3::new(0.3, 0.5, 0.9),
+            day_horizon: Vec3::new(0.7, 0.82, 1.0),
+            night_zenith: Vec3::new(0.01, 0.01, 0.04),
+            night_horizon: Vec3::new(0.05, 0.06, 0.12),
+            fog_start: DEFAULT_FOG_START,
+            fog_end: DEFAULT_FOG_END,
+            fog_color: None,
+            sun_tilt: 25.0,
+            day_length: DEFAULT_DAY_LENGTH,
+        }
+    }
+}
+
+/// Sky colors for a single moment in time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SkyGradient {
+    /// Color straight up.
+    pub zenith: Vec3,
+    /// Color at (and below) the horizon.
+    pub horizon: Vec3,
+}
+
+impl SkyGradient {
+    /// Returns the sky color seen when looking in the given direction.
+    ///
+    /// Must match `sky_color` in `block.wgsl` and `sky.wgsl`.
+    #[must_use]
+    pub fn color_in_direction(&self, direction: Vec3) -> Vec3 {
+        let up = direction.normalize_or_zero().y.clamp(0.0, 1.0);
+        self.horizon.lerp(self.zenith, up)
+    }
+}
+
+/// The sky state: configuration plus the current time of day.
+#[derive(Debug, Clone)]
+pub struct Sky {
+    /// Sky configuration.
+    config: SkyConfig,
+    /// Time of day in `[0, 1)`: 0 is midnight, 0.5 is noon.
+    time_of_day: f32,
+    /// Whether the time of day is held still.
+    paused: bool,
+}
+
+impl Default for Sky {
+    fn default() -> Self {
+        Self::new(SkyConfig::default())
+    }
+}
+
+impl Sky {
+    /// Creates a new sky at noon.
+    #[must_use]
+    pub const fn new(config: SkyConfig) -> Self {
+        Self {
+            config,
+            time_of_day: 0.5,
+            paused: false,
+        }
+    }
+
+    /// Returns the time of day in `[0, 1)`.
+    #[must_use]
+    pub const fn time_of_day(&self) -> f32 {
+        self.time_of_day
+    }
+
+    /// Sets the time of day, wrapping into `[0, 1)`.
+    pub fn set_time_of_day(&mut self, time: f32) {
+        self.time_of_day = time.rem_euclid(1.0);
+    }
+
+    /// Moves the time of day on by `elapsed` seconds, unless paused.
+    pub fn advance(&mut self, elapsed: f32) {
+        if !self.paused && self.config.day_length > 0.0 {
+            self.set_time_of_day(self.time_of_day + elapsed / self.config.day_length);
+        }
+    }
+
+    /// Returns whether the time of day is held still.
+    #[must_use]
+    pub const fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Stops or restarts the time of day advancing.
+    pub const fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// Returns a reference to the sky configuration.
+    #[must_use]
+    pub const fn config(&self) -> &SkyConfig {
+        &self.config
+    }
+
+    /// Returns a mutable reference to the sky configuration.
+    pub const fn config_mut(&mut self) -> &mut SkyConfig {
+        &mut self.config
+    }
+
+    /// Returns how bright the sky is (0 at midnight, 1 at noon).
+    #[must_use]
+    pub fn daylight(&self) -> f32 {
+        0.5f32.mul_add(-(self.time_of_day * std::f32::consts::TAU).cos(), 0.5)
+    }
+
+    /// Returns the sky gradient at the current time of day.
+    #[must_use]
+    pub fn gradient(&self) -> SkyGradient {
+        let t = self.daylight();
+        SkyGradient {
+            zenith: self.config.night_zenith.lerp(self.config.day_zenith, t),
+            horizon: self.config.night_horizon.lerp(self.config.day_horizon, t),
+        }
+    }
+
+    /// Returns the fog color for terrain seen in the given view direction.
+    ///
+    /// Unless a fixed fog color is configured, this is the sky color behind
+    /// that terrain, so fogged chunks blend seamlessly into the sky.
+    #[must_use]
+    pub fn fog_color(&self, view_direction: Vec3) -> Vec3 {
+        self.config
+            .fog_color
+            .unwrap_or_else(|| self.gradient().color_in_direction(view_direction))
+    }
+
+    /// Returns the uniform data describing the gradient and fog range.
+    #[must_use]
+    pub fn uniform(&self) -> SkyUniform {
+        let gradient = self.gradient();
+        SkyUniform {
+            zenith: gradient.zenith.extend(self.config.fog_start).to_array(),
+            horizon: gradient.horizon.extend(self.config.fog_end).to_array(),
+            fog: self
+                .config
+                .fog_color
+                .map_or([0.0; 4], |color| color.extend(1.0).to_array()),
+        }
+    }
+}
+
+/// Noon sunlight color.
+const SUN_DAY_COLOR: Vec3 = Vec3::new(1.0, 0.97, 0.9);
+
+/// Sunlight color at sunrise and sunset.
+const SUN_DUSK_COLOR: Vec3 = Vec3::new(1.0, 0.55, 0.3);
+
+/// Moonlight color at full strength.
+const MOON_COLOR: Vec3 = Vec3::new(0.25, 0.3, 0.45);
+
+/// Sun or moon elevation (sine of the angle above the horizon) at which the
+/// light reaches full strength.
+const FULL_LIGHT_ELEVATION: f32 = 0.25;
+
+/// Sun elevation above which sunlight loses its dawn/dusk tint.
+const DUSK_ELEVATION: f32 = 0.4;
+
+/// Hermite interpolation from 0 at `edge0` to 1 at `edge1`.
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * 2.0f32.mul_add(-t, 3.0)
+}
+
+/// The directional light (sun or moon) at a moment in time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SunLight {
+    /// Unit vector pointing from the ground towards the light.
+    pub direction: Vec3,
+    /// Light color, scaled by intensity (black when below the horizon).
+    pub color: Vec3,
+}
+
+impl Default for SunLight {
+    fn default() -> Self {
+        Self::from_time(0.5, SkyConfig::default().sun_tilt)
+    }
+}
+
+impl SunLight {
+    /// Returns the light at `time` of day (0 midnight, 0.25 dawn, 0.5 noon)
+    /// with the sun's path tilted `tilt` degrees towards the south.
+    #[must_use]
+    pub fn from_time(time: f32, tilt: f32) -> Self {
+        let direction = Self::sun_direction(time, tilt);
+        let elevation = direction.y;
+
+        if elevation >= 0.0 {
+            let strength = smoothstep(0.0, FULL_LIGHT_ELEVATION, elevation);
+            let day_mix = smoothstep(0.0, DUSK_ELEVATION, elevation);
+            Self {
+                direction,
+                color: SUN_DUSK_COLOR.lerp(SUN_DAY_COLOR, day_mix) * strength,
+            }
+        } else {
+            // The moon sits opposite the sun
+            let strength = smoothstep(0.0, FULL_LIGHT_ELEVATION, -elevation);
+            Self {
+                direction: -direction,
+                color: MOON_COLOR * strength,
+            }
+        }
+    }
+
+    /// Returns the direction towards the sun, which rises in the east (+X)
+    /// at dawn and is highest at noon.
+    #[must_use]
+    pub fn sun_direction(time: f32, tilt: f32) -> Vec3 {
+        let angle = (time - 0.25) * std::f32::consts::TAU;
+        let tilt = tilt.to_radians();
+        let (height, east) = angle.sin_cos();
+        Vec3::new(east, height * tilt.cos(), height * tilt.sin())
+    }
+
+    /// Returns the uniform data for the block shader.
+    #[must_use]
+    pub fn uniform(&self) -> SunUniform {
+        SunUniform {
+            direction: self.direction.extend(0.0).to_array(),
+            color: self.color.extend(1.0).to_array(),
+        }
+    }
+}
+
+/// Directional light as sent to the GPU.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct SunUniform {
+    /// Unit direction towards the light (xyz).
+    pub direction: [f32; 4],
+    /// Light color scaled by intensity (rgb).
+    pub color: [f32; 4],
+}
+
+/// Sky gradient and fog range as sent to the GPU.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct SkyUniform {
+    /// Zenith color (rgb) and fog start distance (w).
+    pub zenith: [f32; 4],
+    /// Horizon color (rgb) and fog end distance (w).
+    pub horizon: [f32; 4],
+    /// Fixed fog color (rgb), used instead of the sky color when w is 1.
+    pub fog: [f32; 4],
+}
+
+/// Uniform for the sky pass.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+struct SkyPassUniform {
+    /// Inverse view-projection matrix, to recover view rays per pixel.
+    inv_view_proj: [[f32; 4]; 4],
+    /// Sky gradient.
+    sky: SkyUniform,
+}
+
+/// Draws the sky gradient as a fullscreen background.
+pub struct SkyRenderer {
+    /// The render pipeline.
+    pipeline: wgpu::RenderPipeline,
+    /// Uniform buffer.
+    uniform_buffer: wgpu::Buffer,
+    /// Uniform bind group.
+    bind_group: wgpu::BindGroup,
+}
+
+impl SkyRenderer {
+    /// Creates a new sky renderer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EngineError::Shader`] if the sky shader is invalid.
+    pub fn new(
+        device: &wgpu::Device,
+        surface_format: wgpu::TextureFormat,
+        depth_format: wgpu::TextureFormat,
+    ) -> Result<Self, EngineError> {
+        let shader = create_shader(device, "Sky Shader", include_str!("shaders/sky.wgsl"))?;
+
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Sky Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[SkyPassUniform {
+                inv_view_proj: Mat4::IDENTITY.to_cols_array_2d(),
+                sky: SkyUniform::default(),
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Sky Bind Group Layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Sky Bind Group"),
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Sky Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Sky Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[], // Fullscreen triangle generated from vertex index
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: surface_format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: depth_format,
+                depth_write_enabled: false, // Background only
+                depth_compare: wgpu::CompareFunction::Always,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Ok(Self {
+            pipeline,
+            uniform_buffer,
+            bind_group,
+        })
+    }
+
+    /// Updates the camera and gradient used to draw the sky.
+    pub fn update(&self, queue: &wgpu::Queue, view_proj: Mat4, sky: &Sky) {
+        let uniform = SkyPassUniform {
+            inv_view_proj: view_proj.inverse().to_cols_array_2d(),
+            sky: sky.uniform(),
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniform]));
+    }
+
+    /// Renders the sky. Call before any world geometry.
+    pub fn render<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vec3_approx_eq(a: Vec3, b: Vec3) -> bool {
+        (a - b).length() < 0.0001
+    }
+
+    #[test]
+    fn fog_color_matches_sky_in_view_direction() {
+        // Noon: the default day colors at the horizon and zenith
+        let mut sky = Sky::default();
+        assert!(vec3_approx_eq(
+            sky.fog_color(Vec3::X),
+            Vec3::new(0.7, 0.82, 1.0)
+        ));
+        assert!(vec3_approx_eq(
+            sky.fog_color(Vec3::Y),
+            Vec3::new(0.3, 0.5, 0.9)
+        ));
+        // 45 degrees up is sin(45) = 0.7071 of the way to the zenith
+        assert!(vec3_approx_eq(
+            sky.fog_color(Vec3::new(0.0, 1.0, 1.0)),
+            Vec3::new(0.41716, 0.59373, 0.92929)
+        ));
+
+        // Midnight: the night horizon color
+        sky.set_time_of_day(0.0);
+        assert!(vec3_approx_eq(
+            sky.fog_color(Vec3::NEG_Z),
+            Vec3::new(0.05, 0.06, 0.12)
+        ));
+    }
+
+    #[test]
+    fn gradient_spans_horizon_to_zenith() {
+        let sky = Sky::default();
+        let gradient = sky.gradient();
+
+        assert!(vec3_approx_eq(
+            gradient.color_in_direction(Vec3::Y),
+            gradient.zenith
+        ));
+        assert!(vec3_approx_eq(
+            gradient.color_in_direction(Vec3::X),
+            gradient.horizon
+        ));
+        // Below the horizon stays at the horizon color
+        assert!(vec3_approx_eq(
+            gradient.color_in_direction(-Vec3::Y),
+            gradient.horizon
+        ));
+    }
+
+    #[test]
+    fn daylight_follows_time_of_day() {
+        let mut sky = Sky::default();
+        assert!((sky.daylight() - 1.0).abs() < 0.0001); // Noon
+
+        sky.set_time_of_day(0.0);
+        assert!(sky.daylight().abs() < 0.0001); // Midnight
+        assert!(vec3_approx_eq(
+            sky.gradient().zenith,
+            sky.config().night_zenith
+        ));
+
+        sky.set_time_of_day(1.25);
+        assert!((sky.time_of_day() - 0.25).abs() < 0.0001);
+    }
+
+    #[test]
+    fn time_advances_by_day_length_unless_paused() {
+        let mut sky = Sky::new(SkyConfig {
+            day_length: 100.0,
+            ..SkyConfig::default()
+        });
+        sky.advance(25.0);
+        assert!((sky.time_of_day() - 0.75).abs() < 0.0001);
+
+        // Wraps past midnight
+        sky.advance(50.0);
+        assert!((sky.time_of_day() - 0.25).abs() < 0.0001);
+
+        sky.set_paused(true);
+        sky.advance(50.0);
+        assert!((sky.time_of_day() - 0.25).abs() < 0.0001);
+    }
+
+    #[test]
+    fn sun_rises_at_dawn_and_is_overhead_at_noon() {
+        let dawn = SunLight::sun_direction(0.25, 0.0);
+        assert!(dawn.y.abs() < 0.0001);
+        assert!(vec3_approx_eq(dawn, Vec3::X));
+
+        let noon = SunLight::sun_direction(0.5, 0.0);
+        assert!(vec3_approx_eq(noon, Vec3::Y));
+
+        // Sets in the west and is straight below at midnight
+        assert!(vec3_approx_eq(
+            SunLight::sun_direction(0.75, 0.0),
+            Vec3::NEG_X
+        ));
+        assert!(vec3_approx_eq(
+            SunLight::sun_direction(0.0, 0.0),
+            Vec3::NEG_Y
+        ));
+
+        // Tilt leans the noon sun towards the south
+        let tilted = SunLight::sun_direction(0.5, 30.0);
+        assert!(tilted.z > 0.0 && tilted.y < 1.0);
+        assert!((tilted.length() - 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn light_warms_near_horizon_and_turns_to_moonlight() {
+        let noon = SunLight::from_time(0.5, 0.0);
+        let morning = SunLight::from_time(0.27, 0.0);
+        let midnight = SunLight::from_time(0.0, 0.0);
+
+        // Dimmer and redder shortly after sunrise
+        assert!(morning.color.length() < noon.color.length());
+        assert!(morning.color.x / morning.color.z > noon.color.x / noon.color.z);
+
+        // The moon shines from above at midnight, dim and cool
+        assert!(vec3_approx_eq(midnight.direction, Vec3::Y));
+        assert!(midnight.color.z > midnight.color.x);
+        assert!(midnight.color.length() < morning.color.length());
+
+        // No light right at the horizon
+        assert!(SunLight::from_time(0.25, 0.0).color.length() < 0.0001);
+    }
+
+    #[test]
+    fn uniform_packs_fog_range() {
+        let sky = Sky::default();
+        let uniform = sky.uniform();
+        assert!((uniform.zenith[3] - sky.config().fog_start).abs() < 0.0001);
+        assert!((uniform.horizon[3] - sky.config().fog_end).abs() < 0.0001);
+        assert!(uniform.fog[3].abs() < 0.0001);
+    }
+
+    #[test]
+    fn fixed_fog_color_replaces_sky_color() {
+        let color = Vec3::new(0.5, 0.7, 1.0);
+        let sky = Sky::new(SkyConfig {
+            fog_color: Some(color),
+            ..SkyConfig::default()
+        });
+        assert!(vec3_approx_eq(sky.fog_color(Vec3::Y), color));
+        assert!(vec3_approx_eq(sky.fog_color(Vec3::X), color));
+        let fog = sky.uniform().fog;
+        assert!(vec3_approx_eq(Vec3::new(fog[0], fog[1], fog[2]), color));
+        assert!((fog[3] - 1.0).abs() < 0.0001);
+    }
+}
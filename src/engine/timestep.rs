@@ -0,0 +1,98 @@
+//! Fixed-timestep accumulator.
+//!
+//! Turns variable frame times into a whole number of constant-length steps,
+//! so simulation results don't depend on the frame rate. The leftover time
+//! gives the fraction to interpolate rendering by.
+
+/// Accumulates frame time and hands it out in fixed steps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedTimestep {
+    /// Length of one step in seconds.
+    step: f32,
+    /// Most steps run for a single frame.
+    max_steps: u32,
+    /// Time not yet consumed by steps.
+    accumulator: f32,
+}
+
+impl FixedTimestep {
+    /// Creates an accumulator running steps of `step` seconds, at most
+    /// `max_steps` per frame.
+    #[must_use]
+    pub const fn new(step: f32, max_steps: u32) -> Self {
+        Self {
+            step,
+            max_steps,
+            accumulator: 0.0,
+        }
+    }
+
+    /// Returns the length of one step in seconds.
+    #[must_use]
+    pub const fn step(&self) -> f32 {
+        self.step
+    }
+
+    /// Adds `elapsed` seconds and returns how many steps to run.
+    ///
+    /// When a slow frame needs more than the maximum, the time that couldn't
+    /// be caught up on is dropped, so one stall doesn't make every following
+    /// frame slower too.
+    pub fn advance(&mut self, elapsed: f32) -> u32 {
+        self.accumulator += elapsed.max(0.0);
+
+        let mut steps = 0;
+        while self.accumulator >= self.step && steps < self.max_steps {
+            self.accumulator -= self.step;
+            steps += 1;
+        }
+
+        if steps == self.max_steps {
+            self.accumulator = self.accumulator.min(self.step);
+        }
+        steps
+    }
+
+    /// Returns how far between the last two steps the current time is, from
+    /// 0.0 to 1.0, for interpolating between their states.
+    #[must_use]
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / self.step).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elapsed_time_produces_whole_steps() {
+        let mut timestep = FixedTimestep::new(1.0 / 60.0, 5);
+
+        // Less than a step runs nothing
+        assert_eq!(timestep.advance(0.01), 0);
+        assert!((timestep.alpha() - 0.6).abs() < 0.001);
+
+        // The leftover carries into the next frame
+        assert_eq!(timestep.advance(0.01), 1);
+        assert_eq!(timestep.advance(0.04), 2);
+        assert!((timestep.alpha() - 0.6).abs() < 0.001);
+    }
+
+    #[test]
+    fn slow_frame_is_clamped_to_max_steps() {
+        let mut timestep = FixedTimestep::new(0.1, 4);
+
+        assert_eq!(timestep.advance(2.0), 4);
+        // The backlog is dropped, leaving at most one step queued
+        assert_eq!(timestep.advance(0.0), 1);
+        assert_eq!(timestep.advance(0.0), 0);
+    }
+
+    #[test]
+    fn negative_elapsed_time_is_ignored() {
+        let mut timestep = FixedTimestep::new(0.1, 4);
+        assert_eq!(timestep.advance(-1.0), 0);
+        assert_eq!(timestep.advance(0.15), 1);
+    }
+}
@@ -2,7 +2,6 @@
 //!
 //! Handles window creation and event processing using winit.
 
-use anyhow::Result;
 use std::sync::Arc;
 use winit::{
     dpi::PhysicalSize,
@@ -10,6 +9,8 @@ use winit::{
     window::Window,
 };
 
+use super::error::EngineError;
+
 /// Window configuration options.
 #[derive(Debug, Clone)]
 pub struct WindowConfig {
@@ -42,7 +43,11 @@ pub struct GameWindow {
 
 impl GameWindow {
     /// Creates a new game window with the given event loop and configuration.
-    pub fn new(event_loop: &ActiveEventLoop, config: &WindowConfig) -> Result<Self> {
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EngineError::Window`] if the OS refuses to create the window.
+    pub fn new(event_loop: &ActiveEventLoop, config: &WindowConfig) -> Result<Self, EngineError> {
         let window_attributes = Window::default_attributes()
             .with_title(&config.title)
             .with_inner_size(PhysicalSize::new(config.width, config.height))
@@ -78,7 +83,7 @@ impl GameWindow {
 /// # Errors
 ///
 /// Returns an error if the event loop cannot be created.
-pub fn create_event_loop() -> Result<EventLoop<()>> {
+pub fn create_event_loop() -> Result<EventLoop<()>, EngineError> {
     let event_loop = EventLoop::new()?;
     Ok(event_loop)
 }
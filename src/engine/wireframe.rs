@@ -1,24 +1,53 @@
 //! Wireframe rendering for block selection highlights.
 //!
-//! Renders a wireframe box around the currently targeted block.
+//! Renders wireframe boxes around the currently targeted block, or around
+//! several blocks at once for selection tools. Each box has its own color.
 
 use glam::Vec3;
 use wgpu::util::DeviceExt;
 
+use super::mining::CRACK_STAGES;
+
+/// Outline color of the targeted-block highlight (black, slightly transparent).
+pub const HIGHLIGHT_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.8];
+
+/// Outline color of a block on its last crack stage (white, opaque).
+pub const CRACKED_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+/// Number of vertices per box (its 8 corners).
+const VERTICES_PER_BOX: usize = 8;
+
+/// Number of indices per box (12 edges as line pairs).
+const INDICES_PER_BOX: usize = 24;
+
+/// Returns the highlight color for a block being mined, fading from
+/// [`HIGHLIGHT_COLOR`] on the first crack stage to [`CRACKED_COLOR`] on the
+/// last.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn crack_color(stage: u32) -> [f32; 4] {
+    let last = CRACK_STAGES - 1;
+    let t = stage.min(last) as f32 / last as f32;
+    std::array::from_fn(|i| (CRACKED_COLOR[i] - HIGHLIGHT_COLOR[i]).mul_add(t, HIGHLIGHT_COLOR[i]))
+}
+
 /// Vertex for wireframe rendering.
 #[repr(C)]
 #[derive(Clone, Copy, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct WireframeVertex {
     /// Position in world space.
     pub position: [f32; 3],
+    /// RGBA line color.
+    pub color: [f32; 4],
 }
 
 impl WireframeVertex {
     /// Creates a new wireframe vertex.
     #[must_use]
-    pub const fn new(x: f32, y: f32, z: f32) -> Self {
+    pub const fn new(x: f32, y: f32, z: f32, color: [f32; 4]) -> Self {
         Self {
             position: [x, y, z],
+            color,
         }
     }
 
@@ -28,11 +57,18 @@ impl WireframeVertex {
         wgpu::VertexBufferLayout {
             array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
             step_mode: wgpu::VertexStepMode::Vertex,
-            attributes: &[wgpu::VertexAttribute {
-                offset: 0,
-                shader_location: 0,
-                format: wgpu::VertexFormat::Float32x3,
-            }],
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+            ],
         }
     }
 }
@@ -53,11 +89,13 @@ pub struct WireframeRenderer {
     camera_bind_group: wgpu::BindGroup,
     /// Camera uniform buffer.
     camera_buffer: wgpu::Buffer,
-    /// Vertex buffer for the current highlight box.
+    /// Vertex buffer for the current boxes.
     vertex_buffer: wgpu::Buffer,
-    /// Index buffer for line strips.
+    /// Index buffer for box edges (line pairs).
     index_buffer: wgpu::Buffer,
-    /// Number of indices.
+    /// Number of boxes the buffers can hold.
+    capacity: usize,
+    /// Number of indices to draw.
     index_count: u32,
 }
 
@@ -157,55 +195,65 @@ impl WireframeRenderer {
             cache: None,
         });
 
-        // Create unit cube vertices (will be scaled/translated per block)
-        let (vertices, indices) = Self::create_box_geometry(Vec3::ZERO, 1.0);
+        let (vertex_buffer, index_buffer) = Self::create_buffers(device, 1);
+
+        Self {
+            pipeline,
+            camera_bind_group,
+            camera_buffer,
+            vertex_buffer,
+            index_buffer,
+            capacity: 1,
+            index_count: 0,
+        }
+    }
 
-        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+    /// Creates vertex and index buffers sized for `capacity` boxes.
+    fn create_buffers(device: &wgpu::Device, capacity: usize) -> (wgpu::Buffer, wgpu::Buffer) {
+        let vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Wireframe Vertex Buffer"),
-            contents: bytemuck::cast_slice(&vertices),
+            size: (capacity * VERTICES_PER_BOX * std::mem::size_of::<WireframeVertex>())
+                as wgpu::BufferAddress,
             usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
         });
 
+        // Edges are the same for every box, so indices only change on growth
+        let indices = Self::box_indices(capacity);
         let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Wireframe Index Buffer"),
             contents: bytemuck::cast_slice(&indices),
             usage: wgpu::BufferUsages::INDEX,
         });
 
-        Self {
-            pipeline,
-            camera_bind_group,
-            camera_buffer,
-            vertex_buffer,
-            index_buffer,
-            index_count: indices.len() as u32,
-        }
+        (vertex_buffer, index_buffer)
     }
 
-    /// Creates box geometry for wireframe rendering.
-    /// Returns vertices and line indices.
-    fn create_box_geometry(pos: Vec3, size: f32) -> (Vec<WireframeVertex>, Vec<u16>) {
+    /// Creates the 8 corner vertices of a block-sized box at `pos`.
+    fn box_vertices(pos: Vec3, color: [f32; 4]) -> [WireframeVertex; VERTICES_PER_BOX] {
         // Slight offset to prevent z-fighting (box is slightly larger than block)
         let offset = 0.002;
         let min = pos - Vec3::splat(offset);
-        let max = pos + Vec3::splat(size + offset);
+        let max = pos + Vec3::splat(1.0 + offset);
 
-        // 8 corners of the box
         #[rustfmt::skip]
-        let vertices = vec![
-            WireframeVertex::new(min.x, min.y, min.z), // 0: front-bottom-left
-            WireframeVertex::new(max.x, min.y, min.z), // 1: front-bottom-right
-            WireframeVertex::new(max.x, max.y, min.z), // 2: front-top-right
-            WireframeVertex::new(min.x, max.y, min.z), // 3: front-top-left
-            WireframeVertex::new(min.x, min.y, max.z), // 4: back-bottom-left
-            WireframeVertex::new(max.x, min.y, max.z), // 5: back-bottom-right
-            WireframeVertex::new(max.x, max.y, max.z), // 6: back-top-right
-            WireframeVertex::new(min.x, max.y, max.z), // 7: back-top-left
+        let vertices = [
+            WireframeVertex::new(min.x, min.y, min.z, color), // 0: front-bottom-left
+            WireframeVertex::new(max.x, min.y, min.z, color), // 1: front-bottom-right
+            WireframeVertex::new(max.x, max.y, min.z, color), // 2: front-top-right
+            WireframeVertex::new(min.x, max.y, min.z, color), // 3: front-top-left
+            WireframeVertex::new(min.x, min.y, max.z, color), // 4: back-bottom-left
+            WireframeVertex::new(max.x, min.y, max.z, color), // 5: back-bottom-right
+            WireframeVertex::new(max.x, max.y, max.z, color), // 6: back-top-right
+            WireframeVertex::new(min.x, max.y, max.z, color), // 7: back-top-left
         ];
+        vertices
+    }
 
-        // 12 edges of the cube (as line pairs)
+    /// Creates line indices for the 12 edges of `count` consecutive boxes.
+    fn box_indices(count: usize) -> Vec<u32> {
         #[rustfmt::skip]
-        let indices = vec![
+        const EDGES: [u32; INDICES_PER_BOX] = [
             // Front face
             0, 1,  1, 2,  2, 3,  3, 0,
             // Back face
@@ -214,7 +262,20 @@ impl WireframeRenderer {
             0, 4,  1, 5,  2, 6,  3, 7,
         ];
 
-        (vertices, indices)
+        (0..count)
+            .flat_map(|i| {
+                let base = u32::try_from(i * VERTICES_PER_BOX).expect("too many wireframe boxes");
+                EDGES.iter().map(move |&index| base + index)
+            })
+            .collect()
+    }
+
+    /// Creates vertices for every box, each carrying its box's color.
+    fn create_box_geometry(boxes: &[(Vec3, [f32; 4])]) -> Vec<WireframeVertex> {
+        boxes
+            .iter()
+            .flat_map(|&(pos, color)| Self::box_vertices(pos, color))
+            .collect()
     }
 
     /// Updates the camera uniform.
@@ -225,18 +286,105 @@ impl WireframeRenderer {
         queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[uniform]));
     }
 
-    /// Updates the highlight box position.
-    pub fn update_highlight(&self, queue: &wgpu::Queue, block_pos: Vec3) {
-        let (vertices, _) = Self::create_box_geometry(block_pos, 1.0);
+    /// Replaces the drawn boxes with one box per `(block position, color)`.
+    ///
+    /// Buffers grow as needed; an empty slice draws nothing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more boxes than 32-bit indices can address.
+    pub fn set_boxes(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        boxes: &[(Vec3, [f32; 4])],
+    ) {
+        if boxes.len() > self.capacity {
+            let capacity = boxes.len().next_power_of_two();
+            (self.vertex_buffer, self.index_buffer) = Self::create_buffers(device, capacity);
+            self.capacity = capacity;
+        }
+
+        let vertices = Self::create_box_geometry(boxes);
         queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&vertices));
+        self.index_count =
+            u32::try_from(boxes.len() * INDICES_PER_BOX).expect("too many wireframe boxes");
     }
 
-    /// Renders the wireframe highlight.
+    /// Highlights a single block in [`HIGHLIGHT_COLOR`].
+    pub fn update_highlight(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        block_pos: Vec3,
+    ) {
+        self.set_boxes(device, queue, &[(block_pos, HIGHLIGHT_COLOR)]);
+    }
+
+    /// Renders the wireframe boxes.
     pub fn render<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if self.index_count == 0 {
+            return;
+        }
         render_pass.set_pipeline(&self.pipeline);
         render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
         render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-        render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+        render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
         render_pass.draw_indexed(0..self.index_count, 0, 0..1);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boxes_carry_their_own_color() {
+        let start = [0.0, 1.0, 0.0, 1.0];
+        let end = [1.0, 0.0, 0.0, 1.0];
+        let fill = [0.2, 0.4, 1.0, 0.5];
+        let boxes = [
+            (Vec3::new(0.0, 64.0, 0.0), start),
+            (Vec3::new(4.0, 64.0, 4.0), end),
+            (Vec3::new(2.0, 64.0, 2.0), fill),
+        ];
+
+        let vertices = WireframeRenderer::create_box_geometry(&boxes);
+        assert_eq!(vertices.len(), boxes.len() * VERTICES_PER_BOX);
+
+        for (corners, (pos, color)) in vertices.chunks(VERTICES_PER_BOX).zip(boxes) {
+            for vertex in corners {
+                let same_color = vertex
+                    .color
+                    .iter()
+                    .zip(color)
+                    .all(|(a, b)| (a - b).abs() < 1e-6);
+                assert!(same_color, "expected {color:?}, got {:?}", vertex.color);
+                // Every corner lies within the box's block (plus the z-fight offset)
+                let offset = Vec3::from(vertex.position) - pos;
+                assert!(offset.min_element() > -0.01 && offset.max_element() < 1.01);
+            }
+        }
+    }
+
+    #[test]
+    fn crack_color_fades_to_white() {
+        let same = |a: [f32; 4], b: [f32; 4]| a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-6);
+        assert!(same(crack_color(0), HIGHLIGHT_COLOR));
+        assert!(same(crack_color(CRACK_STAGES - 1), CRACKED_COLOR));
+        assert!(same(crack_color(CRACK_STAGES + 5), CRACKED_COLOR));
+        let middle = crack_color(CRACK_STAGES / 2);
+        assert!(middle[0] > HIGHLIGHT_COLOR[0] && middle[0] < CRACKED_COLOR[0]);
+    }
+
+    #[test]
+    fn indices_address_each_box() {
+        let indices = WireframeRenderer::box_indices(3);
+        assert_eq!(indices.len(), 3 * INDICES_PER_BOX);
+
+        for (i, edges) in indices.chunks(INDICES_PER_BOX).enumerate() {
+            let base = u32::try_from(i * VERTICES_PER_BOX).unwrap();
+            assert!(edges.iter().all(|&index| (base..base + 8).contains(&index)));
+        }
+    }
+}
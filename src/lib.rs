@@ -13,7 +13,7 @@
 //! ## Quick Start
 //!
 //! ```no_run
-//! use voxel_forge::Engine;
+//! use voxel_forge::prelude::*;
 //!
 //! fn main() -> anyhow::Result<()> {
 //!     let engine = Engine::new()?;
@@ -26,10 +26,12 @@
 #![allow(clippy::module_name_repetitions)]
 
 pub mod engine;
+pub mod math;
+pub mod prelude;
 pub mod world;
 
 // Re-export commonly used types
-pub use engine::Engine;
+pub use engine::{Engine, EngineBuilder, EngineError};
 pub use world::{Block, Chunk, ChunkPos};
 
 /// The current version of the engine.
@@ -0,0 +1,309 @@
+//! Geometry shared by the world and the renderer.
+//!
+//! Axis-aligned boxes describe block shapes, collision and chunk bounds;
+//! frustums cull chunks against the camera's view.
+
+use glam::{Mat4, Vec3, Vec4};
+
+use crate::world::{CHUNK_HEIGHT, SECTION_SIZE};
+
+/// Axis-aligned bounding box, for block shapes, collision and culling.
+#[derive(Debug, Clone, Copy)]
+pub struct Aabb {
+    /// Minimum corner (lowest x, y, z).
+    pub min: Vec3,
+    /// Maximum corner (highest x, y, z).
+    pub max: Vec3,
+}
+
+impl Aabb {
+    /// Creates a new AABB from min and max corners.
+    #[must_use]
+    pub const fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    /// Creates an AABB for a chunk at the given position.
+    /// Chunks are 16 blocks wide and `height` blocks tall.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn from_chunk(chunk_x: i32, chunk_z: i32, height: usize) -> Self {
+        let size = SECTION_SIZE as f32;
+        let min = Vec3::new(chunk_x as f32 * size, 0.0, chunk_z as f32 * size);
+        let max = Vec3::new(min.x + size, height as f32, min.z + size);
+        Self { min, max }
+    }
+
+    /// Returns the center point of the AABB.
+    #[must_use]
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Returns the smallest sphere containing the box, as
+    /// `(center, radius)`.
+    #[must_use]
+    pub fn bounding_sphere(&self) -> (Vec3, f32) {
+        (self.center(), (self.max - self.min).length() * 0.5)
+    }
+
+    /// Returns the eight corners of the box.
+    #[must_use]
+    pub const fn corners(&self) -> [Vec3; 8] {
+        let (min, max) = (self.min, self.max);
+        [
+            Vec3::new(min.x, min.y, min.z),
+            Vec3::new(max.x, min.y, min.z),
+            Vec3::new(min.x, max.y, min.z),
+            Vec3::new(max.x, max.y, min.z),
+            Vec3::new(min.x, min.y, max.z),
+            Vec3::new(max.x, min.y, max.z),
+            Vec3::new(min.x, max.y, max.z),
+            Vec3::new(max.x, max.y, max.z),
+        ]
+    }
+
+    /// Returns the smallest AABB containing both boxes.
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    /// Returns true if the boxes overlap. Boxes that only touch don't.
+    #[must_use]
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min.cmplt(other.max).all() && other.min.cmplt(self.max).all()
+    }
+
+    /// Returns the positive vertex relative to a plane normal.
+    /// (The corner furthest in the direction of the normal.)
+    #[must_use]
+    fn positive_vertex(&self, normal: Vec3) -> Vec3 {
+        Vec3::new(
+            if normal.x >= 0.0 {
+                self.max.x
+            } else {
+                self.min.x
+            },
+            if normal.y >= 0.0 {
+                self.max.y
+            } else {
+                self.min.y
+            },
+            if normal.z >= 0.0 {
+                self.max.z
+            } else {
+                self.min.z
+            },
+        )
+    }
+
+    /// Returns the negative vertex relative to a plane normal.
+    /// (The corner closest in the direction of the normal.)
+    #[must_use]
+    #[allow(dead_code)] // Part of complete AABB algorithm, may be used for containment tests
+    fn negative_vertex(&self, normal: Vec3) -> Vec3 {
+        Vec3::new(
+            if normal.x >= 0.0 {
+                self.min.x
+            } else {
+                self.max.x
+            },
+            if normal.y >= 0.0 {
+                self.min.y
+            } else {
+                self.max.y
+            },
+            if normal.z >= 0.0 {
+                self.min.z
+            } else {
+                self.max.z
+            },
+        )
+    }
+}
+
+/// A plane in 3D space, represented as ax + by + cz + d = 0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane {
+    /// Normal vector (a, b, c).
+    pub normal: Vec3,
+    /// Distance from origin (d).
+    pub distance: f32,
+}
+
+impl Plane {
+    /// Creates a plane from a Vec4 (normal.xyz, distance.w).
+    #[must_use]
+    pub fn from_vec4(v: Vec4) -> Self {
+        let normal = Vec3::new(v.x, v.y, v.z);
+        let length = normal.length();
+        Self {
+            normal: normal / length,
+            distance: v.w / length,
+        }
+    }
+
+    /// Returns the signed distance from a point to the plane.
+    /// Positive = in front, Negative = behind.
+    #[must_use]
+    pub fn distance_to_point(&self, point: Vec3) -> f32 {
+        self.normal.dot(point) + self.distance
+    }
+}
+
+/// View frustum for culling - 6 planes extracted from view-projection matrix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frustum {
+    /// The 6 frustum planes: left, right, bottom, top, near, far.
+    planes: [Plane; 6],
+    /// Height of the chunk columns tested by
+    /// [`is_chunk_visible`](Self::is_chunk_visible), in blocks.
+    world_height: usize,
+}
+
+impl Frustum {
+    /// Extracts frustum planes from a view-projection matrix.
+    #[must_use]
+    pub fn from_view_projection(vp: Mat4) -> Self {
+        // Extract planes using Gribb/Hartmann method, which works for both
+        // perspective and orthographic projections
+        // Each row of the matrix gives us plane coefficients
+        let row0 = vp.row(0);
+        let row1 = vp.row(1);
+        let row2 = vp.row(2);
+        let row3 = vp.row(3);
+
+        let planes = [
+            Plane::from_vec4(row3 + row0), // Left
+            Plane::from_vec4(row3 - row0), // Right
+            Plane::from_vec4(row3 + row1), // Bottom
+            Plane::from_vec4(row3 - row1), // Top
+            // wgpu clip space depth runs from 0, not -1, so the near plane
+            // is row 2 alone; `row3 + row2` would sit far behind the camera
+            // once there's no perspective divide
+            Plane::from_vec4(row2),        // Near
+            Plane::from_vec4(row3 - row2), // Far
+        ];
+
+        Self {
+            planes,
+            world_height: CHUNK_HEIGHT,
+        }
+    }
+
+    /// Tests chunks as columns `height` blocks tall instead of
+    /// [`CHUNK_HEIGHT`].
+    #[must_use]
+    pub const fn with_world_height(mut self, height: usize) -> Self {
+        self.world_height = height;
+        self
+    }
+
+    /// Returns the height of the chunk columns this tests, in blocks.
+    #[must_use]
+    pub const fn world_height(&self) -> usize {
+        self.world_height
+    }
+
+    /// Tests if an AABB intersects or is inside the frustum.
+    /// Returns true if visible, false if completely outside.
+    #[must_use]
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        for plane in &self.planes {
+            // Get the positive vertex (furthest in normal direction)
+            let p_vertex = aabb.positive_vertex(plane.normal);
+
+            // If positive vertex is behind the plane, AABB is completely outside
+            if plane.distance_to_point(p_vertex) < 0.0 {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Tests if a sphere intersects or is inside the frustum.
+    ///
+    /// Cheaper than [`intersects_aabb`](Self::intersects_aabb) but looser:
+    /// a box's bounding sphere can poke into the frustum when the box
+    /// doesn't.
+    #[must_use]
+    pub fn intersects_sphere(&self, center: Vec3, radius: f32) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.distance_to_point(center) >= -radius)
+    }
+
+    /// Tests if a chunk at the given position is visible.
+    ///
+    /// Chunks whose bounding sphere is outside are rejected before the
+    /// precise box test.
+    #[must_use]
+    pub fn is_chunk_visible(&self, chunk_x: i32, chunk_z: i32) -> bool {
+        let aabb = Aabb::from_chunk(chunk_x, chunk_z, self.world_height);
+        let (center, radius) = aabb.bounding_sphere();
+        self.intersects_sphere(center, radius) && self.intersects_aabb(&aabb)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 0.0001;
+
+    fn approx_eq(a: f32, b: f32) -> bool {
+        (a - b).abs() < EPSILON
+    }
+
+    fn vec3_approx_eq(a: Vec3, b: Vec3) -> bool {
+        approx_eq(a.x, b.x) && approx_eq(a.y, b.y) && approx_eq(a.z, b.z)
+    }
+
+    #[test]
+    fn aabb_bounding_sphere_touches_corners() {
+        let aabb = Aabb::new(Vec3::ZERO, Vec3::new(2.0, 4.0, 4.0));
+        let (center, radius) = aabb.bounding_sphere();
+
+        assert!(vec3_approx_eq(center, Vec3::new(1.0, 2.0, 2.0)));
+        assert!(approx_eq(radius, 3.0));
+        for corner in aabb.corners() {
+            assert!(approx_eq(corner.distance(center), radius));
+        }
+    }
+
+    #[test]
+    fn aabb_from_chunk() {
+        let aabb = Aabb::from_chunk(1, 2, CHUNK_HEIGHT);
+        assert!(vec3_approx_eq(aabb.min, Vec3::new(16.0, 0.0, 32.0)));
+        #[allow(clippy::cast_precision_loss)]
+        let height = CHUNK_HEIGHT as f32;
+        assert!(vec3_approx_eq(aabb.max, Vec3::new(32.0, height, 48.0)));
+    }
+
+    #[test]
+    fn aabb_center() {
+        let aabb = Aabb::new(Vec3::ZERO, Vec3::new(10.0, 10.0, 10.0));
+        assert!(vec3_approx_eq(aabb.center(), Vec3::new(5.0, 5.0, 5.0)));
+    }
+
+    #[test]
+    fn plane_distance_to_point() {
+        // Plane at Z=5, facing +Z
+        let plane = Plane {
+            normal: Vec3::Z,
+            distance: -5.0,
+        };
+
+        // Point at Z=10 is 5 units in front
+        let dist = plane.distance_to_point(Vec3::new(0.0, 0.0, 10.0));
+        assert!(approx_eq(dist, 5.0));
+
+        // Point at Z=0 is 5 units behind
+        let dist = plane.distance_to_point(Vec3::new(0.0, 0.0, 0.0));
+        assert!(approx_eq(dist, -5.0));
+    }
+}
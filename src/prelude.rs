@@ -0,0 +1,36 @@
+//! Commonly used types, for glob importing.
+//!
+//! The prelude is the stable, everyday surface of the engine: starting it,
+//! placing the camera, editing chunks, picking blocks and meshing. Less
+//! common types stay behind their module paths.
+//!
+//! ```
+//! use voxel_forge::prelude::*;
+//!
+//! let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+//! chunk.set_block(2, 64, 2, Block::Stone);
+//!
+//! let mut camera = Camera::default();
+//! camera.position = Vec3::new(2.5, 66.0, 2.5);
+//! camera.look_at(Vec3::new(2.5, 64.5, 2.5));
+//!
+//! let hit: Option<RaycastHit> = raycast(camera.position, camera.forward(), 8.0, |x, y, z| {
+//!     x == 2 && y == 64 && z == 2
+//! });
+//! let hit = hit.expect("the block is in reach");
+//! assert_eq!(hit.block_pos, BlockPos::new(2, 64, 2));
+//! assert_eq!(hit.face, HitFace::Top);
+//!
+//! let mesh: ChunkMesh = MeshGenerator::new(chunk).generate();
+//! assert!(!mesh.is_empty());
+//!
+//! let _engine: Result<Engine, EngineError> = Engine::builder().title("Prelude").build();
+//! ```
+
+pub use glam::Vec3;
+
+pub use crate::engine::camera::Camera;
+pub use crate::engine::{Engine, EngineBuilder, EngineError};
+pub use crate::world::{
+    Block, BlockPos, Chunk, ChunkMesh, ChunkPos, HitFace, MeshGenerator, RaycastHit, raycast,
+};
@@ -0,0 +1,90 @@
+//! Biomes.
+//!
+//! Every column of the world belongs to a [`Biome`], picked by
+//! [`TerrainGenerator::biome_at`](super::TerrainGenerator::biome_at) from
+//! low-frequency temperature and humidity noise. The biome chooses the
+//! surface block and how often trees grow, and tints grass and leaves.
+
+use super::block::Block;
+
+/// Climate of a region of the world.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Biome {
+    /// Grassland with scattered trees.
+    Plains,
+    /// Sand, without trees.
+    Desert,
+    /// Grassland thick with trees.
+    Forest,
+    /// Snow-covered ground with the odd tree.
+    Snowy,
+}
+
+impl Biome {
+    /// Every biome.
+    pub const ALL: [Self; 4] = [Self::Plains, Self::Desert, Self::Forest, Self::Snowy];
+
+    /// Picks the biome for a column's `temperature` and `humidity`, each in
+    /// `0.0..1.0`.
+    #[must_use]
+    pub fn from_climate(temperature: f32, humidity: f32) -> Self {
+        if temperature < 0.35 {
+            Self::Snowy
+        } else if temperature > 0.62 {
+            Self::Desert
+        } else if humidity > 0.52 {
+            Self::Forest
+        } else {
+            Self::Plains
+        }
+    }
+
+    /// Returns the block on top of each column.
+    #[must_use]
+    pub const fn surface_block(self) -> Block {
+        match self {
+            Self::Plains | Self::Forest => Block::Grass,
+            Self::Desert => Block::Sand,
+            Self::Snowy => Block::Snow,
+        }
+    }
+
+    /// Returns the block between the surface and the stone.
+    #[must_use]
+    pub const fn filler_block(self) -> Block {
+        match self {
+            Self::Desert => Block::Sand,
+            Self::Plains | Self::Forest | Self::Snowy => Block::Dirt,
+        }
+    }
+
+    /// Returns how rarely trees grow: one in this many columns, or `None`
+    /// if they don't grow here.
+    #[must_use]
+    pub const fn tree_rarity(self) -> Option<u32> {
+        match self {
+            Self::Plains => Some(90),
+            Self::Desert => None,
+            Self::Forest => Some(14),
+            Self::Snowy => Some(160),
+        }
+    }
+
+    /// Returns the color grass and leaves are multiplied by.
+    #[must_use]
+    pub const fn tint(self) -> [f32; 3] {
+        match self {
+            Self::Plains => [1.0, 1.0, 1.0],
+            Self::Desert => [1.0, 0.9, 0.55],
+            Self::Forest => [0.7, 0.9, 0.65],
+            Self::Snowy => [0.8, 0.9, 0.95],
+        }
+    }
+
+    /// Returns true if `block` takes the [`tint`](Self::tint) of the biome
+    /// it's in.
+    #[must_use]
+    pub const fn is_tinted(block: Block) -> bool {
+        matches!(block, Block::Grass | Block::Leaves)
+    }
+}
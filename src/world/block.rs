@@ -1,154 +1,254 @@
-//! Block definitions and registry.
+//! Block types and properties.
 //!
-//! Defines all block types and their properties.
+//! A [`Block`] is an ID; its definition lives in the [`BlockRegistry`].
+
+use std::collections::HashMap;
+
+use super::block_registry::{BlockDef, BlockRegistry};
+use super::orientation::Placement;
+use super::shape::BlockShape;
 
 /// Unique identifier for a block type.
 pub type BlockId = u16;
 
-/// Block type enumeration.
+/// A block type: an ID into the global [`BlockRegistry`].
 ///
-/// Each variant represents a different block type in the game.
+/// Built-in blocks are available as associated constants (`Block::Stone`),
+/// which work in patterns like enum variants did. Blocks added by mods are
+/// returned by [`BlockRegistry::register`]. Everything else about a block
+/// (name, properties, color, atlas slot) lives in its [`BlockDef`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
-#[repr(u16)]
-pub enum Block {
+pub struct Block(BlockId);
+
+/// Built-in blocks, registered in this order by [`BlockRegistry::new`].
+#[allow(non_upper_case_globals)]
+impl Block {
     /// Empty space (air).
-    #[default]
-    Air = 0,
+    pub const Air: Self = Self(0);
     /// Basic stone block.
-    Stone = 1,
+    pub const Stone: Self = Self(1);
     /// Dirt block.
-    Dirt = 2,
+    pub const Dirt: Self = Self(2);
     /// Grass block (dirt with grass on top).
-    Grass = 3,
+    pub const Grass: Self = Self(3);
     /// Sand block.
-    Sand = 4,
+    pub const Sand: Self = Self(4);
     /// Gravel block.
-    Gravel = 5,
+    pub const Gravel: Self = Self(5);
     /// Wood log block.
-    Log = 6,
+    pub const Log: Self = Self(6);
     /// Leaves block (transparent).
-    Leaves = 7,
+    pub const Leaves: Self = Self(7);
     /// Glass block (transparent).
-    Glass = 8,
+    pub const Glass: Self = Self(8);
     /// Water block (transparent, liquid).
-    Water = 9,
+    pub const Water: Self = Self(9);
     /// Cobblestone block.
-    Cobblestone = 10,
+    pub const Cobblestone: Self = Self(10);
     /// Wooden planks.
-    Planks = 11,
+    pub const Planks: Self = Self(11);
     /// Brick block.
-    Bricks = 12,
+    pub const Bricks: Self = Self(12);
     /// Coal ore.
-    CoalOre = 13,
+    pub const CoalOre: Self = Self(13);
     /// Iron ore.
-    IronOre = 14,
+    pub const IronOre: Self = Self(14);
     /// Gold ore.
-    GoldOre = 15,
+    pub const GoldOre: Self = Self(15);
     /// Diamond ore.
-    DiamondOre = 16,
+    pub const DiamondOre: Self = Self(16);
     /// Bedrock (unbreakable).
-    Bedrock = 17,
+    pub const Bedrock: Self = Self(17);
+    /// Red stained glass (translucent, tints what's behind it).
+    pub const RedStainedGlass: Self = Self(18);
+    /// Green stained glass (translucent, tints what's behind it).
+    pub const GreenStainedGlass: Self = Self(19);
+    /// Blue stained glass (translucent, tints what's behind it).
+    pub const BlueStainedGlass: Self = Self(20);
+    /// Yellow stained glass (translucent, tints what's behind it).
+    pub const YellowStainedGlass: Self = Self(21);
+    /// Lamp (emits full-brightness block light).
+    pub const Lamp: Self = Self(22);
+    /// Snow block.
+    pub const Snow: Self = Self(23);
+    /// Stone slab (the lower half of a block).
+    pub const StoneSlab: Self = Self(24);
+    /// Wooden plank slab (the lower half of a block).
+    pub const PlanksSlab: Self = Self(25);
 }
 
 impl Block {
+    /// Wraps an ID without checking it is registered.
+    pub(crate) const fn from_raw(id: BlockId) -> Self {
+        Self(id)
+    }
+
+    /// Returns the block's definition from the global registry.
+    #[must_use]
+    pub fn def(self) -> &'static BlockDef {
+        BlockRegistry::global().def(self)
+    }
+
+    /// Returns every registered block type, in ID order.
+    ///
+    /// Iterate this instead of looping over IDs, so new blocks are picked up
+    /// without bumping a loop bound.
+    #[must_use]
+    pub fn all() -> &'static [Self] {
+        BlockRegistry::global().blocks()
+    }
+
+    /// Returns the block's lowercase name, as typed in commands.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        &self.def().name
+    }
+
+    /// Looks up a block by [`name`](Self::name), ignoring case.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        BlockRegistry::global().by_name(name)
+    }
+
     /// Returns the block ID as a u16.
     #[must_use]
     pub const fn id(self) -> BlockId {
-        self as BlockId
+        self.0
     }
 
-    /// Creates a block from its ID.
+    /// Creates a block from its ID, or returns None if no block has it.
     #[must_use]
-    pub const fn from_id(id: BlockId) -> Option<Self> {
-        match id {
-            0 => Some(Self::Air),
-            1 => Some(Self::Stone),
-            2 => Some(Self::Dirt),
-            3 => Some(Self::Grass),
-            4 => Some(Self::Sand),
-            5 => Some(Self::Gravel),
-            6 => Some(Self::Log),
-            7 => Some(Self::Leaves),
-            8 => Some(Self::Glass),
-            9 => Some(Self::Water),
-            10 => Some(Self::Cobblestone),
-            11 => Some(Self::Planks),
-            12 => Some(Self::Bricks),
-            13 => Some(Self::CoalOre),
-            14 => Some(Self::IronOre),
-            15 => Some(Self::GoldOre),
-            16 => Some(Self::DiamondOre),
-            17 => Some(Self::Bedrock),
-            _ => None,
-        }
+    pub fn from_id(id: BlockId) -> Option<Self> {
+        Self::all().get(usize::from(id)).copied()
     }
 
     /// Returns the properties for this block type.
     #[must_use]
-    pub const fn properties(self) -> BlockProperties {
-        match self {
-            Self::Air => BlockProperties::AIR,
-            Self::Stone => BlockProperties::SOLID,
-            Self::Dirt => BlockProperties::SOLID,
-            Self::Grass => BlockProperties::SOLID,
-            Self::Sand => BlockProperties::SOLID,
-            Self::Gravel => BlockProperties::SOLID,
-            Self::Log => BlockProperties::SOLID,
-            Self::Leaves => BlockProperties::TRANSPARENT,
-            Self::Glass => BlockProperties::TRANSPARENT,
-            Self::Water => BlockProperties::LIQUID,
-            Self::Cobblestone => BlockProperties::SOLID,
-            Self::Planks => BlockProperties::SOLID,
-            Self::Bricks => BlockProperties::SOLID,
-            Self::CoalOre => BlockProperties::SOLID,
-            Self::IronOre => BlockProperties::SOLID,
-            Self::GoldOre => BlockProperties::SOLID,
-            Self::DiamondOre => BlockProperties::SOLID,
-            Self::Bedrock => BlockProperties::UNBREAKABLE,
-        }
+    pub fn properties(self) -> BlockProperties {
+        self.def().properties
+    }
+
+    /// Returns how this block is oriented when placed.
+    #[must_use]
+    pub fn placement(self) -> Placement {
+        self.def().placement
     }
 
     /// Returns true if this block is solid (blocks movement and light).
     #[must_use]
-    pub const fn is_solid(self) -> bool {
+    pub fn is_solid(self) -> bool {
         self.properties().is_solid
     }
 
+    /// Returns the shape of the block within its cell.
+    #[must_use]
+    pub fn shape(self) -> BlockShape {
+        self.properties().shape
+    }
+
+    /// Returns true if entities collide with this block, against the boxes
+    /// of its [`shape`](Self::shape).
+    #[must_use]
+    pub fn blocks_movement(self) -> bool {
+        let properties = self.properties();
+        properties.is_solid && !properties.is_liquid
+    }
+
     /// Returns true if this block is transparent (light passes through).
     #[must_use]
-    pub const fn is_transparent(self) -> bool {
+    pub fn is_transparent(self) -> bool {
         self.properties().is_transparent
     }
 
+    /// Returns true if this block fills its cell with opaque material, so it
+    /// stops light and darkens the corners beside it. Partial shapes like
+    /// slabs never do.
+    #[must_use]
+    pub fn occludes(self) -> bool {
+        !self.is_transparent() && self.shape().is_full_cube()
+    }
+
+    /// Returns true if this block is translucent (needs alpha blending).
+    ///
+    /// Translucent blocks are meshed separately and drawn in the transparent
+    /// pass. Cutout blocks like leaves are transparent but not translucent.
+    #[must_use]
+    pub fn is_translucent(self) -> bool {
+        self.def().translucent
+    }
+
+    /// Returns true if this block is stained glass.
+    #[must_use]
+    pub const fn is_stained_glass(self) -> bool {
+        matches!(
+            self,
+            Self::RedStainedGlass
+                | Self::GreenStainedGlass
+                | Self::BlueStainedGlass
+                | Self::YellowStainedGlass
+        )
+    }
+
     /// Returns true if this block is air (empty space).
     #[must_use]
     pub const fn is_air(self) -> bool {
-        matches!(self, Self::Air)
+        self.0 == Self::Air.0
     }
 
-    /// Returns the color for this block (temporary until textures).
+    /// Returns true if placing a block may overwrite this one: air and
+    /// liquids.
     #[must_use]
-    pub const fn color(self) -> [f32; 3] {
-        match self {
-            Self::Air => [0.0, 0.0, 0.0],
-            Self::Stone => [0.5, 0.5, 0.5],
-            Self::Dirt => [0.55, 0.35, 0.2],
-            Self::Grass => [0.3, 0.6, 0.2],
-            Self::Sand => [0.9, 0.85, 0.6],
-            Self::Gravel => [0.55, 0.55, 0.55],
-            Self::Log => [0.4, 0.25, 0.1],
-            Self::Leaves => [0.2, 0.5, 0.15],
-            Self::Glass => [0.8, 0.9, 1.0],
-            Self::Water => [0.2, 0.4, 0.8],
-            Self::Cobblestone => [0.45, 0.45, 0.45],
-            Self::Planks => [0.7, 0.5, 0.3],
-            Self::Bricks => [0.6, 0.3, 0.25],
-            Self::CoalOre => [0.3, 0.3, 0.3],
-            Self::IronOre => [0.6, 0.55, 0.5],
-            Self::GoldOre => [0.9, 0.8, 0.3],
-            Self::DiamondOre => [0.4, 0.8, 0.9],
-            Self::Bedrock => [0.2, 0.2, 0.2],
-        }
+    pub fn is_replaceable(self) -> bool {
+        self.is_air() || self.properties().is_liquid
+    }
+
+    /// Returns the base color of this block, tinting its texture.
+    #[must_use]
+    pub fn color(self) -> [f32; 3] {
+        self.def().color
+    }
+}
+
+/// Replacement colors for specific blocks, e.g. a color-blind-friendly
+/// palette.
+///
+/// Blocks without an override keep their built-in [`Block::color`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ColorOverrides {
+    /// Override color per block.
+    colors: HashMap<Block, [f32; 3]>,
+}
+
+impl ColorOverrides {
+    /// Creates an empty set of overrides.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Overrides a block's color, returning the previous override.
+    pub fn set(&mut self, block: Block, color: [f32; 3]) -> Option<[f32; 3]> {
+        self.colors.insert(block, color)
+    }
+
+    /// Removes a block's override, returning it.
+    pub fn remove(&mut self, block: Block) -> Option<[f32; 3]> {
+        self.colors.remove(&block)
+    }
+
+    /// Returns true if no block is overridden.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    /// Returns a block's color: its override, or the built-in color.
+    #[must_use]
+    pub fn color(&self, block: Block) -> [f32; 3] {
+        self.colors
+            .get(&block)
+            .copied()
+            .unwrap_or_else(|| block.color())
     }
 }
 
@@ -165,6 +265,10 @@ pub struct BlockProperties {
     pub is_breakable: bool,
     /// Whether the block is a liquid.
     pub is_liquid: bool,
+    /// Seconds of mining it takes to break the block.
+    pub hardness: f32,
+    /// Shape of the block within its cell.
+    pub shape: BlockShape,
 }
 
 impl BlockProperties {
@@ -175,6 +279,8 @@ impl BlockProperties {
         light_emission: 0,
         is_breakable: false,
         is_liquid: false,
+        hardness: 0.0,
+        shape: BlockShape::FullCube,
     };
 
     /// Solid opaque block properties.
@@ -184,6 +290,8 @@ impl BlockProperties {
         light_emission: 0,
         is_breakable: true,
         is_liquid: false,
+        hardness: 1.0,
+        shape: BlockShape::FullCube,
     };
 
     /// Transparent solid block properties (glass, leaves).
@@ -193,6 +301,8 @@ impl BlockProperties {
         light_emission: 0,
         is_breakable: true,
         is_liquid: false,
+        hardness: 0.3,
+        shape: BlockShape::FullCube,
     };
 
     /// Liquid block properties.
@@ -202,6 +312,8 @@ impl BlockProperties {
         light_emission: 0,
         is_breakable: false,
         is_liquid: true,
+        hardness: 0.0,
+        shape: BlockShape::FullCube,
     };
 
     /// Unbreakable block properties (bedrock).
@@ -211,6 +323,8 @@ impl BlockProperties {
         light_emission: 0,
         is_breakable: false,
         is_liquid: false,
+        hardness: f32::INFINITY,
+        shape: BlockShape::FullCube,
     };
 
     /// Light-emitting block properties.
@@ -219,6 +333,20 @@ impl BlockProperties {
         self.light_emission = level;
         self
     }
+
+    /// Sets the shape of the block within its cell.
+    #[must_use]
+    pub const fn with_shape(mut self, shape: BlockShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Sets how many seconds of mining break the block.
+    #[must_use]
+    pub const fn with_hardness(mut self, hardness: f32) -> Self {
+        self.hardness = hardness;
+        self
+    }
 }
 
 impl Default for BlockProperties {
@@ -233,10 +361,29 @@ mod tests {
 
     #[test]
     fn block_id_roundtrip() {
-        for id in 0..=17 {
-            let block = Block::from_id(id).unwrap();
-            assert_eq!(block.id(), id);
+        for &block in Block::all() {
+            assert_eq!(Block::from_id(block.id()), Some(block));
+        }
+    }
+
+    #[test]
+    fn all_lists_every_variant_in_id_order() {
+        // IDs are dense, so every variant is listed exactly when the IDs
+        // run from 0 and the next ID is unused
+        for (index, &block) in Block::all().iter().enumerate() {
+            assert_eq!(usize::from(block.id()), index);
+        }
+        let next_id = BlockId::try_from(Block::all().len()).unwrap();
+        assert!(Block::from_id(next_id).is_none());
+    }
+
+    #[test]
+    fn names_roundtrip() {
+        for &block in Block::all() {
+            assert_eq!(Block::from_name(block.name()), Some(block));
         }
+        assert_eq!(Block::from_name("Coal_Ore"), Some(Block::CoalOre));
+        assert_eq!(Block::from_name("unobtainium"), None);
     }
 
     #[test]
@@ -265,6 +412,16 @@ mod tests {
         assert!(Block::Glass.is_transparent());
     }
 
+    #[test]
+    fn slabs_are_solid_but_do_not_occlude() {
+        assert!(Block::Stone.occludes());
+        assert!(!Block::Glass.occludes());
+        for slab in [Block::StoneSlab, Block::PlanksSlab] {
+            assert!(slab.blocks_movement());
+            assert!(!slab.occludes());
+        }
+    }
+
     #[test]
     fn water_is_liquid() {
         assert!(Block::Water.properties().is_liquid);
@@ -276,16 +433,62 @@ mod tests {
     fn bedrock_is_unbreakable() {
         assert!(!Block::Bedrock.properties().is_breakable);
         assert!(Block::Bedrock.is_solid());
+        assert!(Block::Bedrock.properties().hardness.is_infinite());
+    }
+
+    #[test]
+    fn only_air_and_liquids_are_replaceable() {
+        assert!(Block::Air.is_replaceable());
+        assert!(Block::Water.is_replaceable());
+        assert!(!Block::Stone.is_replaceable());
+        assert!(!Block::Glass.is_replaceable());
+        assert!(!Block::Leaves.is_replaceable());
+    }
+
+    #[test]
+    fn stone_takes_longer_to_mine_than_dirt() {
+        let hardness = |block: Block| block.properties().hardness;
+        assert!(hardness(Block::Stone) > hardness(Block::Dirt));
+        assert!(hardness(Block::Dirt) > hardness(Block::Leaves));
+        assert!(hardness(Block::DiamondOre) > hardness(Block::Stone));
+    }
+
+    #[test]
+    fn stained_glass_is_translucent() {
+        for block in [
+            Block::RedStainedGlass,
+            Block::GreenStainedGlass,
+            Block::BlueStainedGlass,
+            Block::YellowStainedGlass,
+        ] {
+            assert!(block.is_translucent());
+            assert!(block.is_stained_glass());
+            assert!(block.is_solid());
+        }
+        assert!(Block::Glass.is_translucent());
+        assert!(!Block::Leaves.is_translucent());
+        assert!(!Block::Stone.is_translucent());
     }
 
     #[test]
     fn block_colors_are_valid() {
-        for id in 0..=17 {
-            let block = Block::from_id(id).unwrap();
+        for block in Block::all() {
             let color = block.color();
             for component in color {
                 assert!((0.0..=1.0).contains(&component));
             }
         }
     }
+
+    #[test]
+    fn color_overrides_fall_back_to_built_in_colors() {
+        let mut overrides = ColorOverrides::new();
+        assert!(overrides.is_empty());
+        overrides.set(Block::Grass, [0.1, 0.2, 0.9]);
+
+        assert_eq!(overrides.color(Block::Grass), [0.1, 0.2, 0.9]);
+        assert_eq!(overrides.color(Block::Dirt), Block::Dirt.color());
+        assert_eq!(overrides.remove(Block::Grass), Some([0.1, 0.2, 0.9]));
+        assert_eq!(overrides.color(Block::Grass), Block::Grass.color());
+    }
 }
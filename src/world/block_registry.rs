@@ -0,0 +1,510 @@
+//! Block registry.
+//!
+//! Block types are registered at startup instead of being hardcoded, so mods
+//! can add blocks without touching the engine. The built-in blocks are always
+//! registered first, keeping their IDs (and saved chunks) stable.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use thiserror::Error;
+
+use super::block::{Block, BlockId, BlockProperties};
+use super::orientation::Placement;
+use super::shape::BlockShape;
+use super::texture_atlas::{ATLAS_COLUMNS, ATLAS_SLOTS, MAX_ANIMATION_FRAMES};
+
+/// Most blocks the registry holds; each block takes at least one atlas slot.
+pub const MAX_BLOCKS: usize = ATLAS_SLOTS as usize;
+
+/// The registry used by [`Block`]'s methods.
+static GLOBAL: OnceLock<BlockRegistry> = OnceLock::new();
+
+/// Errors from registering a block.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RegistryError {
+    /// A block with this name is already registered.
+    #[error("block '{0}' is already registered")]
+    DuplicateName(String),
+    /// Not enough atlas slots are left for the block's textures.
+    #[error("texture atlas is full ({ATLAS_SLOTS} slots)")]
+    Full,
+}
+
+/// Which faces of a block share a texture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextureLayout {
+    /// One texture on every face.
+    #[default]
+    Uniform,
+    /// One texture on the top and bottom, another on the sides (logs).
+    Column,
+    /// Separate top, side and bottom textures (grass).
+    TopSideBottom,
+}
+
+impl TextureLayout {
+    /// Returns the number of atlas slots the layout takes.
+    #[must_use]
+    pub const fn slot_count(self) -> u32 {
+        match self {
+            Self::Uniform => 1,
+            Self::Column => 2,
+            Self::TopSideBottom => 3,
+        }
+    }
+}
+
+/// Part of a block a texture is drawn on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextureSide {
+    /// The upward-facing face.
+    Top,
+    /// The four horizontal-facing faces.
+    Side,
+    /// The downward-facing face.
+    Bottom,
+}
+
+/// Atlas slots of a block's textures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FaceSlots {
+    /// Slot drawn on the top face.
+    pub top: u32,
+    /// Slot drawn on the side faces.
+    pub side: u32,
+    /// Slot drawn on the bottom face.
+    pub bottom: u32,
+}
+
+impl FaceSlots {
+    /// Returns the slot drawn on `side`.
+    #[must_use]
+    pub const fn slot(self, side: TextureSide) -> u32 {
+        match side {
+            TextureSide::Top => self.top,
+            TextureSide::Side => self.side,
+            TextureSide::Bottom => self.bottom,
+        }
+    }
+
+    /// Returns each distinct slot with the side it was allocated for.
+    pub fn distinct(self) -> impl Iterator<Item = (TextureSide, u32)> {
+        let top = (self.top != self.side).then_some((TextureSide::Top, self.top));
+        let bottom = (self.bottom != self.side && self.bottom != self.top)
+            .then_some((TextureSide::Bottom, self.bottom));
+        top.into_iter()
+            .chain(std::iter::once((TextureSide::Side, self.side)))
+            .chain(bottom)
+    }
+}
+
+/// Everything the engine knows about a block type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockDef {
+    /// Lowercase name, as typed in commands.
+    pub name: String,
+    /// Collision, transparency and lighting behavior.
+    pub properties: BlockProperties,
+    /// Base color, tinting the block's texture.
+    pub color: [f32; 3],
+    /// Whether the block needs alpha blending (drawn in the transparent pass).
+    pub translucent: bool,
+    /// How the block is oriented when placed.
+    pub placement: Placement,
+    /// Which faces share a texture.
+    pub layout: TextureLayout,
+    /// Texture atlas slots, assigned on registration. Animated textures
+    /// start at their slot, with the later frames in the slots after it.
+    pub slots: FaceSlots,
+    /// Animation frames of each texture, side by side in one atlas row; 1
+    /// for a still texture.
+    pub frames: u32,
+}
+
+impl BlockDef {
+    /// Creates a definition with fixed placement, no translucency and one
+    /// texture on every face.
+    #[must_use]
+    pub fn new(name: impl Into<String>, properties: BlockProperties, color: [f32; 3]) -> Self {
+        Self {
+            name: name.into(),
+            properties,
+            color,
+            translucent: false,
+            placement: Placement::Fixed,
+            layout: TextureLayout::Uniform,
+            slots: FaceSlots::default(),
+            frames: 1,
+        }
+    }
+
+    /// Marks the block as translucent.
+    #[must_use]
+    pub const fn translucent(mut self) -> Self {
+        self.translucent = true;
+        self
+    }
+
+    /// Sets how the block is oriented when placed.
+    #[must_use]
+    pub const fn with_placement(mut self, placement: Placement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    /// Sets which faces share a texture.
+    #[must_use]
+    pub const fn with_layout(mut self, layout: TextureLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Animates the block's textures through `frames` frames, clamped to
+    /// `1..=MAX_ANIMATION_FRAMES`.
+    #[must_use]
+    pub const fn with_frames(mut self, frames: u32) -> Self {
+        self.frames = if frames == 0 {
+            1
+        } else if frames > MAX_ANIMATION_FRAMES {
+            MAX_ANIMATION_FRAMES
+        } else {
+            frames
+        };
+        self
+    }
+}
+
+/// All registered block types, indexed by [`BlockId`].
+#[derive(Debug, Clone)]
+pub struct BlockRegistry {
+    /// Definitions in ID order.
+    defs: Vec<BlockDef>,
+    /// Registered blocks in ID order, for [`Block::all`].
+    blocks: Vec<Block>,
+    /// Block per lowercase name.
+    names: HashMap<String, Block>,
+    /// First atlas slot not yet assigned.
+    next_slot: u32,
+}
+
+impl BlockRegistry {
+    /// Creates a registry holding the built-in blocks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the built-in blocks don't fit in the atlas.
+    #[must_use]
+    pub fn new() -> Self {
+        let mut registry = Self {
+            defs: Vec::new(),
+            blocks: Vec::new(),
+            names: HashMap::new(),
+            next_slot: 0,
+        };
+        for def in builtin_defs() {
+            registry
+                .register(def)
+                .expect("built-in blocks fit in the registry");
+        }
+        registry
+    }
+
+    /// Returns the installed registry, or the built-ins if none was installed.
+    pub fn global() -> &'static Self {
+        GLOBAL.get_or_init(Self::new)
+    }
+
+    /// Makes this the registry used by [`Block`]'s methods.
+    ///
+    /// Must be called before any block is looked up; returns the registry
+    /// back if one is already in use.
+    ///
+    /// # Errors
+    ///
+    /// Returns `self` if a registry is already installed.
+    pub fn install(self) -> Result<(), Self> {
+        GLOBAL.set(self)
+    }
+
+    /// Registers a block, assigning it the next ID and atlas slots.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is taken or the atlas is full.
+    pub fn register(&mut self, mut def: BlockDef) -> Result<Block, RegistryError> {
+        def.name.make_ascii_lowercase();
+        if self.names.contains_key(&def.name) {
+            return Err(RegistryError::DuplicateName(def.name));
+        }
+        let mut next_slot = self.next_slot;
+        let mut strip = || {
+            // Animation frames must not wrap onto the next atlas row
+            if next_slot % ATLAS_COLUMNS + def.frames > ATLAS_COLUMNS {
+                next_slot = next_slot.next_multiple_of(ATLAS_COLUMNS);
+            }
+            let first = next_slot;
+            next_slot += def.frames;
+            first
+        };
+        let slots = match def.layout {
+            TextureLayout::Uniform => {
+                let first = strip();
+                FaceSlots {
+                    top: first,
+                    side: first,
+                    bottom: first,
+                }
+            }
+            TextureLayout::Column => {
+                let top = strip();
+                FaceSlots {
+                    top,
+                    side: strip(),
+                    bottom: top,
+                }
+            }
+            TextureLayout::TopSideBottom => FaceSlots {
+                top: strip(),
+                side: strip(),
+                bottom: strip(),
+            },
+        };
+        if next_slot > ATLAS_SLOTS {
+            return Err(RegistryError::Full);
+        }
+        let id = BlockId::try_from(self.defs.len()).map_err(|_| RegistryError::Full)?;
+        let block = Block::from_raw(id);
+        def.slots = slots;
+        self.next_slot = next_slot;
+        self.names.insert(def.name.clone(), block);
+        self.defs.push(def);
+        self.blocks.push(block);
+        Ok(block)
+    }
+
+    /// Returns a block's definition, or air's if it isn't registered.
+    #[must_use]
+    pub fn def(&self, block: Block) -> &BlockDef {
+        self.defs
+            .get(usize::from(block.id()))
+            .unwrap_or(&self.defs[0])
+    }
+
+    /// Looks up a block by name, ignoring case.
+    #[must_use]
+    pub fn by_name(&self, name: &str) -> Option<Block> {
+        self.names.get(&name.to_ascii_lowercase()).copied()
+    }
+
+    /// Returns every registered block, in ID order.
+    #[must_use]
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    /// Returns the number of atlas slots assigned to the registered blocks,
+    /// including any left unused to keep animation strips in one row.
+    #[must_use]
+    pub const fn slot_count(&self) -> u32 {
+        self.next_slot
+    }
+
+    /// Returns the number of registered blocks.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.defs.len()
+    }
+
+    /// Returns true if no blocks are registered.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.defs.is_empty()
+    }
+}
+
+impl Default for BlockRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Definitions of the built-in blocks, in the order of [`Block`]'s constants.
+fn builtin_defs() -> Vec<BlockDef> {
+    use BlockProperties as P;
+    vec![
+        BlockDef::new("air", P::AIR, [0.0, 0.0, 0.0]),
+        BlockDef::new("stone", P::SOLID.with_hardness(1.5), [0.5, 0.5, 0.5]),
+        BlockDef::new("dirt", P::SOLID.with_hardness(0.5), [0.55, 0.35, 0.2]),
+        BlockDef::new("grass", P::SOLID.with_hardness(0.6), [0.3, 0.6, 0.2])
+            .with_layout(TextureLayout::TopSideBottom),
+        BlockDef::new("sand", P::SOLID.with_hardness(0.5), [0.9, 0.85, 0.6]),
+        BlockDef::new("gravel", P::SOLID.with_hardness(0.6), [0.55, 0.55, 0.55]),
+        BlockDef::new("log", P::SOLID.with_hardness(2.0), [0.4, 0.25, 0.1])
+            .with_placement(Placement::Axis)
+            .with_layout(TextureLayout::Column),
+        BlockDef::new(
+            "leaves",
+            P::TRANSPARENT.with_hardness(0.2),
+            [0.2, 0.5, 0.15],
+        ),
+        BlockDef::new("glass", P::TRANSPARENT, [0.8, 0.9, 1.0]).translucent(),
+        BlockDef::new("water", P::LIQUID, [0.2, 0.4, 0.8])
+            .translucent()
+            .with_frames(4),
+        BlockDef::new(
+            "cobblestone",
+            P::SOLID.with_hardness(2.0),
+            [0.45, 0.45, 0.45],
+        ),
+        BlockDef::new("planks", P::SOLID.with_hardness(2.0), [0.7, 0.5, 0.3]),
+        BlockDef::new("bricks", P::SOLID.with_hardness(2.0), [0.6, 0.3, 0.25]),
+        BlockDef::new("coal_ore", P::SOLID.with_hardness(3.0), [0.3, 0.3, 0.3]),
+        BlockDef::new("iron_ore", P::SOLID.with_hardness(3.0), [0.6, 0.55, 0.5]),
+        BlockDef::new("gold_ore", P::SOLID.with_hardness(3.0), [0.9, 0.8, 0.3]),
+        BlockDef::new("diamond_ore", P::SOLID.with_hardness(3.0), [0.4, 0.8, 0.9]),
+        BlockDef::new("bedrock", P::UNBREAKABLE, [0.2, 0.2, 0.2]),
+        BlockDef::new("red_stained_glass", P::TRANSPARENT, [0.9, 0.2, 0.2]).translucent(),
+        BlockDef::new("green_stained_glass", P::TRANSPARENT, [0.3, 0.8, 0.3]).translucent(),
+        BlockDef::new("blue_stained_glass", P::TRANSPARENT, [0.2, 0.35, 0.9]).translucent(),
+        BlockDef::new("yellow_stained_glass", P::TRANSPARENT, [0.95, 0.85, 0.25]).translucent(),
+        BlockDef::new(
+            "lamp",
+            P::SOLID.with_light(15).with_hardness(0.3),
+            [1.0, 0.85, 0.55],
+        ),
+        BlockDef::new("snow", P::SOLID.with_hardness(0.2), [0.95, 0.97, 1.0]),
+        BlockDef::new(
+            "stone_slab",
+            P::SOLID
+                .with_shape(BlockShape::BottomSlab)
+                .with_hardness(1.5),
+            [0.5, 0.5, 0.5],
+        ),
+        BlockDef::new(
+            "planks_slab",
+            P::SOLID
+                .with_shape(BlockShape::BottomSlab)
+                .with_hardness(2.0),
+            [0.7, 0.5, 0.3],
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtins_keep_their_ids() {
+        let registry = BlockRegistry::new();
+        assert_eq!(registry.by_name("stone"), Some(Block::Stone));
+        assert_eq!(
+            registry.by_name("Yellow_Stained_Glass"),
+            Some(Block::YellowStainedGlass)
+        );
+        assert_eq!(registry.def(Block::Log).placement, Placement::Axis);
+        assert!(registry.def(Block::Water).translucent);
+
+        // Grass and logs take extra slots for their top and bottom textures
+        let grass = registry.def(Block::Grass).slots;
+        assert_eq!((grass.top, grass.side, grass.bottom), (3, 4, 5));
+        let log = registry.def(Block::Log).slots;
+        assert_eq!((log.top, log.side, log.bottom), (8, 9, 8));
+        assert_eq!(registry.def(Block::Leaves).slots.side, 10);
+    }
+
+    #[test]
+    fn distinct_slots_cover_each_texture_once() {
+        let registry = BlockRegistry::new();
+        let slots = |block| registry.def(block).slots.distinct().collect::<Vec<_>>();
+        assert_eq!(slots(Block::Stone), [(TextureSide::Side, 1)]);
+        assert_eq!(
+            slots(Block::Log),
+            [(TextureSide::Top, 8), (TextureSide::Side, 9)]
+        );
+        assert_eq!(
+            slots(Block::Grass),
+            [
+                (TextureSide::Top, 3),
+                (TextureSide::Side, 4),
+                (TextureSide::Bottom, 5)
+            ]
+        );
+    }
+
+    #[test]
+    fn register_custom_block() {
+        let mut registry = BlockRegistry::new();
+        let builtins = registry.len();
+        let marble = registry
+            .register(BlockDef::new(
+                "Marble",
+                BlockProperties::SOLID,
+                [0.95, 0.95, 0.9],
+            ))
+            .unwrap();
+
+        assert_eq!(usize::from(marble.id()), builtins);
+        assert_eq!(registry.by_name("marble"), Some(marble));
+        assert_eq!(registry.def(marble).name, "marble");
+        assert_eq!(registry.def(marble).slots.side, registry.next_slot - 1);
+        assert_eq!(registry.blocks().last(), Some(&marble));
+    }
+
+    #[test]
+    fn register_rejects_duplicates_and_overflow() {
+        let mut registry = BlockRegistry::new();
+        let stone = BlockDef::new("STONE", BlockProperties::SOLID, [0.0; 3]);
+        assert_eq!(
+            registry.register(stone),
+            Err(RegistryError::DuplicateName("stone".to_string()))
+        );
+
+        while registry.next_slot < ATLAS_SLOTS - 1 {
+            let name = format!("block_{}", registry.len());
+            let def = BlockDef::new(name, BlockProperties::SOLID, [0.0; 3]);
+            registry.register(def).unwrap();
+        }
+        // One slot left: too few for a column block, enough for a uniform one
+        let column = BlockDef::new("pillar", BlockProperties::SOLID, [0.0; 3])
+            .with_layout(TextureLayout::Column);
+        assert_eq!(registry.register(column), Err(RegistryError::Full));
+        let last = BlockDef::new("last", BlockProperties::SOLID, [0.0; 3]);
+        assert!(registry.register(last).is_ok());
+        let extra = BlockDef::new("one_too_many", BlockProperties::SOLID, [0.0; 3]);
+        assert_eq!(registry.register(extra), Err(RegistryError::Full));
+    }
+
+    #[test]
+    fn animation_frames_stay_in_one_atlas_row() {
+        let mut registry = BlockRegistry::new();
+        let water = registry.def(Block::Water);
+        assert_eq!(water.frames, 4);
+        // The frames take the slots after the first
+        assert_eq!(
+            registry.def(Block::Cobblestone).slots.side,
+            water.slots.side + 4
+        );
+
+        while registry.next_slot % ATLAS_COLUMNS != ATLAS_COLUMNS - 2 {
+            let name = format!("block_{}", registry.len());
+            let def = BlockDef::new(name, BlockProperties::SOLID, [0.0; 3]);
+            registry.register(def).unwrap();
+        }
+        let lava = BlockDef::new("lava", BlockProperties::LIQUID, [1.0, 0.4, 0.1]).with_frames(3);
+        let lava = registry.register(lava).unwrap();
+        let first = registry.def(lava).slots.side;
+        assert_eq!(first % ATLAS_COLUMNS, 0);
+        assert_eq!(registry.next_slot, first + 3);
+
+        let still = BlockDef::new("still", BlockProperties::SOLID, [0.0; 3]).with_frames(0);
+        assert_eq!(still.frames, 1);
+    }
+
+    #[test]
+    fn unknown_block_falls_back_to_air() {
+        let registry = BlockRegistry::new();
+        assert_eq!(registry.def(Block::from_raw(1000)).name, "air");
+    }
+}
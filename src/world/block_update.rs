@@ -0,0 +1,139 @@
+//! Scheduled block updates.
+//!
+//! Blocks that change over time (flowing liquids, and later redstone) schedule
+//! an update some number of ticks in the future instead of being scanned
+//! every frame.
+
+use std::collections::BTreeMap;
+
+use super::raycast::BlockPos;
+
+/// A queue of block updates keyed by the tick they should fire on.
+#[derive(Debug, Default)]
+pub struct BlockUpdateQueue {
+    /// The current tick (number of ticks advanced so far).
+    current_tick: u64,
+    /// Pending updates, keyed by target tick.
+    scheduled: BTreeMap<u64, Vec<BlockPos>>,
+}
+
+impl BlockUpdateQueue {
+    /// Creates an empty queue at tick 0.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the current tick.
+    #[must_use]
+    pub const fn current_tick(&self) -> u64 {
+        self.current_tick
+    }
+
+    /// Schedules an update for `pos` after `delay_ticks` ticks.
+    ///
+    /// A delay of 0 is treated as 1, so an update never fires during the tick
+    /// that scheduled it. Scheduling the same position for the same tick twice
+    /// only fires once.
+    pub fn schedule(&mut self, pos: BlockPos, delay_ticks: u32) {
+        let target = self.current_tick + u64::from(delay_ticks.max(1));
+        let updates = self.scheduled.entry(target).or_default();
+        if !updates.contains(&pos) {
+            updates.push(pos);
+        }
+    }
+
+    /// Advances one tick and returns the updates due on it, in scheduling order.
+    pub fn advance(&mut self) -> Vec<BlockPos> {
+        self.current_tick += 1;
+        self.scheduled
+            .remove(&self.current_tick)
+            .unwrap_or_default()
+    }
+
+    /// Advances one tick and returns at most `limit` of the updates due on
+    /// it, in scheduling order. The rest are deferred to the next tick,
+    /// ahead of the updates already scheduled for it.
+    pub fn advance_at_most(&mut self, limit: usize) -> Vec<BlockPos> {
+        let mut due = self.advance();
+        if due.len() > limit {
+            let mut deferred = due.split_off(limit);
+            let next = self.scheduled.entry(self.current_tick + 1).or_default();
+            for pos in next.drain(..) {
+                if !deferred.contains(&pos) {
+                    deferred.push(pos);
+                }
+            }
+            *next = deferred;
+        }
+        due
+    }
+
+    /// Returns the number of pending updates.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.scheduled.values().map(Vec::len).sum()
+    }
+
+    /// Returns true if no updates are pending.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.scheduled.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_fires_on_exact_tick() {
+        let mut queue = BlockUpdateQueue::new();
+        let pos = BlockPos::new(1, 2, 3);
+        queue.schedule(pos, 3);
+
+        assert!(queue.advance().is_empty());
+        assert!(queue.advance().is_empty());
+        assert_eq!(queue.advance(), vec![pos]);
+        assert!(queue.is_empty());
+        assert!(queue.advance().is_empty());
+    }
+
+    #[test]
+    fn duplicate_updates_are_merged() {
+        let mut queue = BlockUpdateQueue::new();
+        let pos = BlockPos::new(0, 64, 0);
+        queue.schedule(pos, 1);
+        queue.schedule(pos, 1);
+        queue.schedule(pos, 2);
+
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.advance(), vec![pos]);
+        assert_eq!(queue.advance(), vec![pos]);
+    }
+
+    #[test]
+    fn updates_over_the_limit_wait_a_tick() {
+        let mut queue = BlockUpdateQueue::new();
+        let [a, b, c, d] = [0, 1, 2, 3].map(|x| BlockPos::new(x, 0, 0));
+        for pos in [a, b, c] {
+            queue.schedule(pos, 1);
+        }
+        queue.schedule(c, 2);
+        queue.schedule(d, 2);
+
+        assert_eq!(queue.advance_at_most(1), vec![a]);
+        // Deferred updates go first, without firing twice
+        assert_eq!(queue.advance_at_most(2), vec![b, c]);
+        assert_eq!(queue.advance_at_most(2), vec![d]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn zero_delay_fires_next_tick() {
+        let mut queue = BlockUpdateQueue::new();
+        let pos = BlockPos::new(5, 5, 5);
+        queue.schedule(pos, 0);
+        assert_eq!(queue.advance(), vec![pos]);
+    }
+}
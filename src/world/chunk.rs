@@ -2,7 +2,14 @@
 //!
 //! A chunk is a 16x16 column of the world, divided into 16x16x16 sections.
 
+use std::sync::OnceLock;
+
+use glam::Vec3;
+
 use super::block::Block;
+use super::block_registry::MAX_BLOCKS;
+use super::raycast::BlockPos;
+use crate::math::Aabb;
 
 /// Size of a chunk section in each dimension.
 pub const SECTION_SIZE: usize = 16;
@@ -10,18 +17,142 @@ pub const SECTION_SIZE: usize = 16;
 /// Number of blocks in a section.
 pub const SECTION_VOLUME: usize = SECTION_SIZE * SECTION_SIZE * SECTION_SIZE;
 
-/// Number of sections in a chunk (height = 256 blocks = 16 sections).
+/// Number of sections in a chunk by default (height = 256 blocks = 16
+/// sections).
 pub const SECTIONS_PER_CHUNK: usize = 16;
 
-/// Total height of a chunk in blocks.
+/// Default height of a chunk in blocks.
+///
+/// Worlds can be taller or shorter: chunks made with
+/// [`Chunk::with_height`] carry their own height, which meshing, lighting
+/// and storage follow, and the [`ChunkManager`](super::ChunkManager) takes
+/// its world height from its config.
 pub const CHUNK_HEIGHT: usize = SECTION_SIZE * SECTIONS_PER_CHUNK;
 
+/// Tallest a chunk can be, so column heights fit in a [`Heightmap`].
+pub const MAX_CHUNK_HEIGHT: usize = 1 << 15;
+
+/// Returns the number of sections a chunk `height` blocks tall holds:
+/// `height` rounded up to whole sections, between one section and
+/// [`MAX_CHUNK_HEIGHT`].
+#[must_use]
+pub const fn sections_for_height(height: usize) -> usize {
+    let sections = height.div_ceil(SECTION_SIZE);
+    if sections == 0 {
+        1
+    } else if sections > MAX_CHUNK_HEIGHT / SECTION_SIZE {
+        MAX_CHUNK_HEIGHT / SECTION_SIZE
+    } else {
+        sections
+    }
+}
+
+/// Y of the topmost non-air block in each column of a chunk, indexed
+/// `[x][z]`. Empty columns have height 0.
+pub type Heightmap = [[u16; SECTION_SIZE]; SECTION_SIZE];
+
+// Palette indices never need more than 8 bits
+const _: () = assert!(MAX_BLOCKS <= 256);
+
+/// Most palette entries that fit in 4-bit indices.
+const NARROW_PALETTE_LEN: usize = 16;
+
+/// A section's blocks as a palette of the block types used, plus each
+/// block's palette index.
+///
+/// Indices take 4 bits (two per byte, low nibble first) while the palette
+/// has at most 16 entries, and are widened to 8 bits when it outgrows that.
+/// Palette entries are never removed, so the width never shrinks.
+#[derive(Clone)]
+struct PalettedBlocks {
+    /// Block types, in order of first use.
+    palette: Vec<Block>,
+    /// Packed palette indices, in storage index order.
+    indices: Box<[u8]>,
+}
+
+impl PalettedBlocks {
+    /// Creates storage with every block set to `block`.
+    fn filled(block: Block) -> Self {
+        Self {
+            palette: vec![block],
+            indices: vec![0; SECTION_VOLUME / 2].into_boxed_slice(),
+        }
+    }
+
+    /// Returns true if indices take 8 bits.
+    fn is_wide(&self) -> bool {
+        self.indices.len() == SECTION_VOLUME
+    }
+
+    /// Returns the palette index of the block at `idx`.
+    #[inline]
+    fn palette_index(&self, idx: usize) -> usize {
+        if self.is_wide() {
+            usize::from(self.indices[idx])
+        } else {
+            usize::from((self.indices[idx / 2] >> ((idx % 2) * 4)) & 0x0F)
+        }
+    }
+
+    /// Returns the block at `idx`.
+    #[inline]
+    fn get(&self, idx: usize) -> Block {
+        self.palette[self.palette_index(idx)]
+    }
+
+    /// Sets the block at `idx`, adding it to the palette if needed.
+    fn set(&mut self, idx: usize, block: Block) {
+        let entry = if let Some(entry) = self.palette.iter().position(|&b| b == block) {
+            entry
+        } else {
+            self.palette.push(block);
+            if self.palette.len() > NARROW_PALETTE_LEN && !self.is_wide() {
+                self.widen();
+            }
+            self.palette.len() - 1
+        };
+        // Holds by the MAX_BLOCKS assertion
+        let entry = u8::try_from(entry).expect("palette index fits in 8 bits");
+
+        if self.is_wide() {
+            self.indices[idx] = entry;
+        } else {
+            let shift = (idx % 2) * 4;
+            let byte = &mut self.indices[idx / 2];
+            *byte = (*byte & !(0x0F << shift)) | (entry << shift);
+        }
+    }
+
+    /// Switches from 4-bit to 8-bit indices.
+    #[allow(clippy::cast_possible_truncation)] // narrow indices are below 16
+    fn widen(&mut self) {
+        let wide: Vec<u8> = (0..SECTION_VOLUME)
+            .map(|idx| self.palette_index(idx) as u8)
+            .collect();
+        self.indices = wide.into_boxed_slice();
+    }
+
+    /// Returns the blocks in storage index order.
+    fn iter(&self) -> impl Iterator<Item = Block> + '_ {
+        (0..SECTION_VOLUME).map(|idx| self.get(idx))
+    }
+
+    /// Returns the heap memory used, in bytes.
+    fn heap_size(&self) -> usize {
+        self.palette.capacity() * size_of::<Block>() + self.indices.len()
+    }
+}
+
 /// A 16x16x16 section of blocks within a chunk.
 #[derive(Clone)]
 pub struct ChunkSection {
-    /// Block data stored as a flat array.
+    /// Block data, palette-compressed.
     /// Index = x + z * 16 + y * 256
-    blocks: Box<[Block; SECTION_VOLUME]>,
+    blocks: PalettedBlocks,
+    /// Per-block metadata nibbles (e.g. liquid level), two per byte.
+    /// The low nibble holds the even index, the high nibble the odd one.
+    metadata: Box<[u8; SECTION_VOLUME / 2]>,
     /// Number of non-air blocks in this section.
     solid_count: u32,
 }
@@ -37,7 +168,8 @@ impl ChunkSection {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            blocks: Box::new([Block::Air; SECTION_VOLUME]),
+            blocks: PalettedBlocks::filled(Block::Air),
+            metadata: Box::new([0; SECTION_VOLUME / 2]),
             solid_count: 0,
         }
     }
@@ -51,7 +183,8 @@ impl ChunkSection {
             SECTION_VOLUME as u32
         };
         Self {
-            blocks: Box::new([block; SECTION_VOLUME]),
+            blocks: PalettedBlocks::filled(block),
+            metadata: Box::new([0; SECTION_VOLUME / 2]),
             solid_count,
         }
     }
@@ -69,14 +202,20 @@ impl ChunkSection {
     #[inline]
     #[must_use]
     pub fn get(&self, x: usize, y: usize, z: usize) -> Block {
-        self.blocks[Self::index(x, y, z)]
+        self.blocks.get(Self::index(x, y, z))
     }
 
     /// Sets the block at the given local coordinates.
+    ///
+    /// Changing the block type resets its metadata to 0.
     #[inline]
     pub fn set(&mut self, x: usize, y: usize, z: usize, block: Block) {
         let idx = Self::index(x, y, z);
-        let old = self.blocks[idx];
+        let old = self.blocks.get(idx);
+        if old == block {
+            return;
+        }
+        self.write_metadata(idx, 0);
 
         // Update solid count
         if old.is_air() && !block.is_air() {
@@ -85,7 +224,32 @@ impl ChunkSection {
             self.solid_count -= 1;
         }
 
-        self.blocks[idx] = block;
+        self.blocks.set(idx, block);
+    }
+
+    /// Gets the metadata nibble (0-15) at the given local coordinates.
+    #[inline]
+    #[must_use]
+    pub fn get_metadata(&self, x: usize, y: usize, z: usize) -> u8 {
+        let idx = Self::index(x, y, z);
+        (self.metadata[idx / 2] >> ((idx % 2) * 4)) & 0x0F
+    }
+
+    /// Sets the metadata nibble at the given local coordinates, returning
+    /// true if it changed. Only the low 4 bits of `value` are stored.
+    #[inline]
+    pub fn set_metadata(&mut self, x: usize, y: usize, z: usize, value: u8) -> bool {
+        self.write_metadata(Self::index(x, y, z), value)
+    }
+
+    /// Writes a metadata nibble by array index, returning true if it changed.
+    #[inline]
+    fn write_metadata(&mut self, idx: usize, value: u8) -> bool {
+        let shift = (idx % 2) * 4;
+        let byte = &mut self.metadata[idx / 2];
+        let old = *byte;
+        *byte = (old & !(0x0F << shift)) | ((value & 0x0F) << shift);
+        *byte != old
     }
 
     /// Returns true if this section is empty (all air).
@@ -100,9 +264,27 @@ impl ChunkSection {
         self.solid_count
     }
 
+    /// Counts the non-air blocks from the block data, ignoring the cached
+    /// [`solid_count`](Self::solid_count). Used to validate the cache.
+    #[must_use]
+    pub fn count_solid(&self) -> u32 {
+        let count = self.blocks.iter().filter(|block| !block.is_air()).count();
+        u32::try_from(count).unwrap_or(u32::MAX)
+    }
+
+    /// Overwrites the cached solid count, for testing corruption detection.
+    #[cfg(test)]
+    pub(crate) const fn corrupt_solid_count(&mut self, count: u32) {
+        self.solid_count = count;
+    }
+
     /// Returns an iterator over all blocks with their local coordinates.
+    ///
+    /// Blocks are yielded in storage index order, `x + z * 16 + y * 256`:
+    /// X varies fastest, then Z, then Y. Serialization and meshing rely on
+    /// this order, so any change to the storage layout must preserve it.
     pub fn iter(&self) -> impl Iterator<Item = (usize, usize, usize, Block)> + '_ {
-        self.blocks.iter().enumerate().map(|(idx, &block)| {
+        self.blocks.iter().enumerate().map(|(idx, block)| {
             let x = idx % SECTION_SIZE;
             let z = (idx / SECTION_SIZE) % SECTION_SIZE;
             let y = idx / (SECTION_SIZE * SECTION_SIZE);
@@ -110,10 +292,48 @@ impl ChunkSection {
         })
     }
 
-    /// Returns a reference to the raw block data.
+    /// Returns the blocks in storage index order (see [`iter`](Self::iter)).
+    pub fn blocks(&self) -> impl Iterator<Item = Block> + '_ {
+        self.blocks.iter()
+    }
+
+    /// Returns the number of distinct block types the section has held.
+    #[must_use]
+    pub const fn palette_len(&self) -> usize {
+        self.blocks.palette.len()
+    }
+
+    /// Returns the heap memory used by the block and metadata storage, in
+    /// bytes.
+    #[must_use]
+    pub fn heap_size(&self) -> usize {
+        self.blocks.heap_size() + self.metadata.len()
+    }
+
+    /// Returns a reference to the raw metadata nibbles, two per byte.
+    #[must_use]
+    pub fn metadata(&self) -> &[u8; SECTION_VOLUME / 2] {
+        &self.metadata
+    }
+
+    /// Creates a section from raw block and metadata data, as returned by
+    /// [`blocks`](Self::blocks) and [`metadata`](Self::metadata).
     #[must_use]
-    pub fn blocks(&self) -> &[Block; SECTION_VOLUME] {
-        &self.blocks
+    pub fn from_raw(
+        blocks: &[Block; SECTION_VOLUME],
+        metadata: Box<[u8; SECTION_VOLUME / 2]>,
+    ) -> Self {
+        let mut storage = PalettedBlocks::filled(blocks[0]);
+        for (idx, &block) in blocks.iter().enumerate().skip(1) {
+            storage.set(idx, block);
+        }
+        let mut section = Self {
+            blocks: storage,
+            metadata,
+            solid_count: 0,
+        };
+        section.solid_count = section.count_solid();
+        section
     }
 }
 
@@ -154,6 +374,20 @@ impl ChunkPos {
         (self.x * SECTION_SIZE as i32, self.z * SECTION_SIZE as i32)
     }
 
+    /// Returns the squared distance to another chunk, in chunks.
+    #[must_use]
+    pub const fn distance_squared(&self, other: Self) -> i32 {
+        let (dx, dz) = (self.x - other.x, self.z - other.z);
+        dx * dx + dz * dz
+    }
+
+    /// Returns the smallest sphere containing the whole chunk column,
+    /// `height` blocks tall, as `(center, radius)`, for cheap culling.
+    #[must_use]
+    pub fn bounding_sphere(&self, height: usize) -> (Vec3, f32) {
+        Aabb::from_chunk(self.x, self.z, height).bounding_sphere()
+    }
+
     /// Returns neighboring chunk positions.
     #[must_use]
     pub const fn neighbors(&self) -> [ChunkPos; 4] {
@@ -175,19 +409,42 @@ pub struct Chunk {
     sections: Vec<Option<ChunkSection>>,
     /// Whether the chunk mesh needs to be rebuilt.
     dirty: bool,
+    /// Column heights, computed on first use and cleared when blocks change.
+    heightmap: OnceLock<Heightmap>,
 }
 
 impl Chunk {
-    /// Creates a new empty chunk at the given position.
+    /// Creates a new empty chunk at the given position, [`CHUNK_HEIGHT`]
+    /// blocks tall.
     #[must_use]
     pub fn new(position: ChunkPos) -> Self {
+        Self::with_height(position, CHUNK_HEIGHT)
+    }
+
+    /// Creates a new empty chunk `height` blocks tall, rounded as by
+    /// [`sections_for_height`].
+    #[must_use]
+    pub fn with_height(position: ChunkPos, height: usize) -> Self {
         Self {
             position,
-            sections: vec![None; SECTIONS_PER_CHUNK],
+            sections: vec![None; sections_for_height(height)],
             dirty: true,
+            heightmap: OnceLock::new(),
         }
     }
 
+    /// Returns the height of the chunk in blocks.
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.sections.len() * SECTION_SIZE
+    }
+
+    /// Returns the number of sections in the chunk.
+    #[must_use]
+    pub const fn section_count(&self) -> usize {
+        self.sections.len()
+    }
+
     /// Returns the chunk position.
     #[must_use]
     pub const fn position(&self) -> ChunkPos {
@@ -196,11 +453,12 @@ impl Chunk {
 
     /// Gets the block at world-relative coordinates within this chunk.
     ///
-    /// Coordinates are relative to the chunk (0-15 for x/z, 0-255 for y).
+    /// Coordinates are relative to the chunk (0-15 for x/z, 0 to
+    /// [`height`](Self::height) - 1 for y).
     #[must_use]
     pub fn get_block(&self, x: usize, y: usize, z: usize) -> Block {
         debug_assert!(x < SECTION_SIZE);
-        debug_assert!(y < CHUNK_HEIGHT);
+        debug_assert!(y < self.height());
         debug_assert!(z < SECTION_SIZE);
 
         let section_y = y / SECTION_SIZE;
@@ -212,20 +470,30 @@ impl Chunk {
             .map_or(Block::Air, |section| section.get(x, local_y, z))
     }
 
-    /// Sets the block at chunk-relative coordinates.
-    pub fn set_block(&mut self, x: usize, y: usize, z: usize, block: Block) {
+    /// Gets the block at chunk-relative coordinates like
+    /// [`get_block`](Self::get_block), or `None` if they're outside the chunk.
+    #[must_use]
+    pub fn try_get_block(&self, x: usize, y: usize, z: usize) -> Option<Block> {
+        (x < SECTION_SIZE && y < self.height() && z < SECTION_SIZE).then(|| self.get_block(x, y, z))
+    }
+
+    /// Sets the block at chunk-relative coordinates. Returns true if it
+    /// changed; setting a block to what's already there leaves the chunk as
+    /// it was, without dirtying it.
+    pub fn set_block(&mut self, x: usize, y: usize, z: usize, block: Block) -> bool {
         debug_assert!(x < SECTION_SIZE);
-        debug_assert!(y < CHUNK_HEIGHT);
+        debug_assert!(y < self.height());
         debug_assert!(z < SECTION_SIZE);
 
+        if self.get_block(x, y, z) == block {
+            return false;
+        }
+
         let section_y = y / SECTION_SIZE;
         let local_y = y % SECTION_SIZE;
 
         // Create section if it doesn't exist and we're placing a non-air block
         if self.sections[section_y].is_none() {
-            if block.is_air() {
-                return; // No need to create section for air
-            }
             self.sections[section_y] = Some(ChunkSection::new());
         }
 
@@ -239,6 +507,104 @@ impl Chunk {
         }
 
         self.dirty = true;
+        self.heightmap.take();
+        true
+    }
+
+    /// Returns the Y of the topmost non-air block in each column.
+    ///
+    /// Computed on first use and cached until a block changes.
+    #[must_use]
+    pub fn heightmap(&self) -> Heightmap {
+        *self.heightmap.get_or_init(|| {
+            let mut heights = [[0; SECTION_SIZE]; SECTION_SIZE];
+            for (x, column) in heights.iter_mut().enumerate() {
+                for (z, height) in column.iter_mut().enumerate() {
+                    *height = self.column_height(x, z);
+                }
+            }
+            heights
+        })
+    }
+
+    /// Finds the topmost non-air block in a column, skipping empty sections.
+    #[allow(clippy::cast_possible_truncation)] // MAX_CHUNK_HEIGHT fits in u16
+    fn column_height(&self, x: usize, z: usize) -> u16 {
+        for (section_y, section) in self.sections.iter().enumerate().rev() {
+            let Some(section) = section else {
+                continue;
+            };
+            if let Some(local_y) = (0..SECTION_SIZE)
+                .rev()
+                .find(|&y| !section.get(x, y, z).is_air())
+            {
+                return (section_y * SECTION_SIZE + local_y) as u16;
+            }
+        }
+        0
+    }
+
+    /// Returns world-space AABBs of the movement-blocking blocks within
+    /// `radius` blocks of `pos` (a cube, not a sphere) that lie in this chunk,
+    /// one per box of each block's [shape](Block::shape).
+    ///
+    /// This is a cheap collision representation for physics, independent of
+    /// the render mesh.
+    #[must_use]
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    pub fn collision_aabbs_near(&self, pos: BlockPos, radius: i32) -> Vec<Aabb> {
+        let (ox, oz) = self.position.block_origin();
+        let size = SECTION_SIZE as i32;
+        let x_range = (pos.x - radius).max(ox)..=(pos.x + radius).min(ox + size - 1);
+        let y_range = (pos.y - radius).max(0)..=(pos.y + radius).min(self.height() as i32 - 1);
+        let z_range = (pos.z - radius).max(oz)..=(pos.z + radius).min(oz + size - 1);
+
+        let mut aabbs = Vec::new();
+        for y in y_range {
+            for z in z_range.clone() {
+                for x in x_range.clone() {
+                    // Ranges are clamped to this chunk, so the offsets are in bounds
+                    let block = self.get_block((x - ox) as usize, y as usize, (z - oz) as usize);
+                    if block.blocks_movement() {
+                        aabbs.extend(block.shape().world_boxes(BlockPos::new(x, y, z)));
+                    }
+                }
+            }
+        }
+        aabbs
+    }
+
+    /// Gets the metadata nibble at chunk-relative coordinates.
+    ///
+    /// Returns 0 for blocks in empty sections.
+    #[must_use]
+    pub fn get_metadata(&self, x: usize, y: usize, z: usize) -> u8 {
+        debug_assert!(y < self.height());
+
+        self.get_section(y / SECTION_SIZE)
+            .map_or(0, |section| section.get_metadata(x, y % SECTION_SIZE, z))
+    }
+
+    /// Sets the metadata nibble at chunk-relative coordinates. Returns true
+    /// if it changed; like [`set_block`](Self::set_block), writing the value
+    /// that's already there doesn't dirty the chunk.
+    ///
+    /// Has no effect in empty sections, since air carries no metadata.
+    pub fn set_metadata(&mut self, x: usize, y: usize, z: usize, value: u8) -> bool {
+        debug_assert!(y < self.height());
+
+        let changed = self
+            .get_section_mut(y / SECTION_SIZE)
+            .is_some_and(|section| section.set_metadata(x, y % SECTION_SIZE, z, value));
+        if changed {
+            self.dirty = true;
+        }
+        changed
     }
 
     /// Returns true if the chunk mesh needs to be rebuilt.
@@ -257,17 +623,62 @@ impl Chunk {
         self.dirty = true;
     }
 
-    /// Returns the section at the given Y index (0-15).
+    /// Returns the section at the given Y index, counting from 0 at the
+    /// bottom.
     #[must_use]
     pub fn get_section(&self, section_y: usize) -> Option<&ChunkSection> {
         self.sections.get(section_y).and_then(|s| s.as_ref())
     }
 
+    /// Returns an iterator over the chunk's non-air blocks with their
+    /// chunk-local coordinates, bottom section first and in
+    /// [`ChunkSection::iter`] order within a section.
+    ///
+    /// Empty sections are skipped outright, and each section stops once its
+    /// [`solid_count`](ChunkSection::solid_count) blocks have been found.
+    pub fn iter_solid(&self) -> impl Iterator<Item = (usize, usize, usize, Block)> + '_ {
+        self.sections
+            .iter()
+            .enumerate()
+            .filter_map(|(section_y, section)| Some((section_y, section.as_ref()?)))
+            .filter(|(_, section)| !section.is_empty())
+            .flat_map(|(section_y, section)| {
+                let base_y = section_y * SECTION_SIZE;
+                section
+                    .iter()
+                    .filter(|&(.., block)| !block.is_air())
+                    .take(section.solid_count() as usize)
+                    .map(move |(x, y, z, block)| (x, base_y + y, z, block))
+            })
+    }
+
     /// Returns a mutable reference to the section at the given Y index.
+    ///
+    /// Clears the cached heightmap, since the caller may change blocks.
     pub fn get_section_mut(&mut self, section_y: usize) -> Option<&mut ChunkSection> {
+        self.heightmap.take();
         self.sections.get_mut(section_y).and_then(|s| s.as_mut())
     }
 
+    /// Returns the heap memory used by the chunk's block storage, in bytes.
+    #[must_use]
+    pub fn heap_size(&self) -> usize {
+        self.sections
+            .iter()
+            .flatten()
+            .map(ChunkSection::heap_size)
+            .sum()
+    }
+
+    /// Replaces the section at the given Y index. Out-of-range indices are ignored.
+    pub fn set_section(&mut self, section_y: usize, section: Option<ChunkSection>) {
+        if let Some(slot) = self.sections.get_mut(section_y) {
+            *slot = section;
+            self.dirty = true;
+            self.heightmap.take();
+        }
+    }
+
     /// Fills the chunk with a simple test pattern.
     pub fn fill_test_pattern(&mut self) {
         // Create a flat grass surface at y=64
@@ -297,6 +708,122 @@ impl Chunk {
 mod tests {
     use super::*;
 
+    #[test]
+    fn collision_aabbs_cover_solid_blocks_only() {
+        // Chunk (1, -1) spans x 16..32 and z -16..0
+        let mut chunk = Chunk::new(ChunkPos::new(1, -1));
+        let center = BlockPos::new(20, 64, -5);
+
+        assert!(chunk.collision_aabbs_near(center, 2).is_empty());
+
+        chunk.set_block(5, 65, 10, Block::Stone); // World (21, 65, -6)
+        chunk.set_block(4, 64, 11, Block::Water); // Liquids don't collide
+        let aabbs = chunk.collision_aabbs_near(center, 2);
+        assert_eq!(aabbs.len(), 1);
+        assert_eq!(aabbs[0].min, Vec3::new(21.0, 65.0, -6.0));
+        assert_eq!(aabbs[0].max, Vec3::new(22.0, 66.0, -5.0));
+
+        // Out of range
+        assert!(
+            chunk
+                .collision_aabbs_near(BlockPos::new(26, 64, -5), 2)
+                .is_empty()
+        );
+
+        // Slabs only fill the lower half of their block
+        chunk.set_block(4, 64, 10, Block::StoneSlab); // World (20, 64, -6)
+        let aabbs = chunk.collision_aabbs_near(center, 2);
+        assert_eq!(aabbs.len(), 2);
+        assert_eq!(aabbs[0].min, Vec3::new(20.0, 64.0, -6.0));
+        assert_eq!(aabbs[0].max, Vec3::new(21.0, 64.5, -5.0));
+    }
+
+    #[test]
+    fn heightmap_follows_the_top_block() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.fill_test_pattern();
+        assert_eq!(chunk.heightmap()[3][5], 64);
+
+        chunk.set_block(3, 100, 5, Block::Stone);
+        assert_eq!(chunk.heightmap()[3][5], 100);
+        assert_eq!(chunk.heightmap()[5][3], 64);
+
+        chunk.set_block(3, 100, 5, Block::Air);
+        chunk.set_block(3, 64, 5, Block::Air);
+        assert_eq!(chunk.heightmap()[3][5], 63);
+
+        chunk.set_section(3, None); // y 48..64
+        assert_eq!(chunk.heightmap()[3][5], 47);
+        assert_eq!(Chunk::new(ChunkPos::new(0, 0)).heightmap()[0][0], 0);
+    }
+
+    #[test]
+    fn iter_solid_yields_every_non_air_block() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        assert_eq!(chunk.iter_solid().count(), 0);
+
+        // Bedrock, stone, dirt and grass up to y = 64 in every column
+        chunk.fill_test_pattern();
+        let columns = SECTION_SIZE * SECTION_SIZE;
+        assert_eq!(chunk.iter_solid().count(), columns * 65);
+
+        chunk.set_block(3, 200, 7, Block::Glass);
+        chunk.set_block(3, 64, 7, Block::Air);
+        let solid: Vec<_> = chunk.iter_solid().collect();
+        assert_eq!(solid.len(), columns * 65);
+        assert!(solid.contains(&(3, 200, 7, Block::Glass)));
+        assert!(!solid.iter().any(|&(x, y, z, _)| (x, y, z) == (3, 64, 7)));
+        assert!(
+            solid
+                .iter()
+                .all(|&(x, y, z, block)| chunk.get_block(x, y, z) == block)
+        );
+        assert_eq!(solid[0], (0, 0, 0, Block::Bedrock));
+        assert_eq!(solid.last(), Some(&(3, 200, 7, Block::Glass)));
+    }
+
+    #[test]
+    fn try_get_block_is_none_outside_the_chunk() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        let last = SECTION_SIZE - 1;
+        let top = CHUNK_HEIGHT - 1;
+        chunk.set_block(last, top, last, Block::Stone);
+        chunk.set_block(0, 0, 0, Block::Dirt);
+
+        assert_eq!(chunk.try_get_block(last, top, last), Some(Block::Stone));
+        assert_eq!(chunk.try_get_block(0, 0, 0), Some(Block::Dirt));
+        assert_eq!(chunk.try_get_block(last, 64, 0), Some(Block::Air));
+
+        assert_eq!(chunk.try_get_block(SECTION_SIZE, 64, 0), None);
+        assert_eq!(chunk.try_get_block(0, CHUNK_HEIGHT, 0), None);
+        assert_eq!(chunk.try_get_block(0, 64, SECTION_SIZE), None);
+        assert_eq!(chunk.try_get_block(usize::MAX, 64, 0), None);
+    }
+
+    #[test]
+    fn chunk_height_rounds_up_to_whole_sections() {
+        let pos = ChunkPos::new(0, 0);
+        assert_eq!(Chunk::new(pos).height(), CHUNK_HEIGHT);
+        assert_eq!(Chunk::with_height(pos, 0).height(), SECTION_SIZE);
+        assert_eq!(
+            Chunk::with_height(pos, usize::MAX).height(),
+            MAX_CHUNK_HEIGHT
+        );
+
+        let mut short = Chunk::with_height(pos, 40);
+        assert_eq!((short.height(), short.section_count()), (48, 3));
+        short.set_block(3, 47, 5, Block::Stone);
+        assert_eq!(short.try_get_block(3, 47, 5), Some(Block::Stone));
+        assert_eq!(short.try_get_block(3, 48, 5), None);
+        assert_eq!(short.heightmap()[3][5], 47);
+
+        let mut tall = Chunk::with_height(pos, 512);
+        tall.set_block(3, 400, 5, Block::Stone);
+        assert_eq!(tall.get_block(3, 400, 5), Block::Stone);
+        assert_eq!(tall.heightmap()[3][5], 400);
+        assert_eq!(tall.iter_solid().count(), 1);
+    }
+
     #[test]
     fn section_index_calculation() {
         // Test corner cases
@@ -324,6 +851,23 @@ mod tests {
         assert!(section.is_empty());
     }
 
+    #[test]
+    fn section_metadata_nibbles_are_independent() {
+        let mut section = ChunkSection::new();
+        section.set(0, 0, 0, Block::Water);
+        section.set(1, 0, 0, Block::Water);
+
+        section.set_metadata(0, 0, 0, 5);
+        section.set_metadata(1, 0, 0, 0xFA); // Only the low nibble is kept
+        assert_eq!(section.get_metadata(0, 0, 0), 5);
+        assert_eq!(section.get_metadata(1, 0, 0), 0x0A);
+
+        // Replacing the block clears its metadata, but not its neighbor's
+        section.set(0, 0, 0, Block::Stone);
+        assert_eq!(section.get_metadata(0, 0, 0), 0);
+        assert_eq!(section.get_metadata(1, 0, 0), 0x0A);
+    }
+
     #[test]
     fn section_filled() {
         let section = ChunkSection::filled(Block::Stone);
@@ -359,6 +903,44 @@ mod tests {
         assert!(chunk.is_dirty());
     }
 
+    #[test]
+    fn setting_the_same_block_leaves_the_chunk_clean() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.mark_clean();
+
+        assert!(chunk.set_block(5, 100, 5, Block::Stone));
+        assert!(chunk.is_dirty());
+        chunk.mark_clean();
+        assert!(!chunk.set_block(5, 100, 5, Block::Stone));
+        assert!(!chunk.is_dirty());
+
+        // Clearing an empty section doesn't create it
+        assert!(!chunk.set_block(5, 20, 5, Block::Air));
+        assert!(chunk.get_section(20 / SECTION_SIZE).is_none());
+        assert!(!chunk.is_dirty());
+
+        // Removing the last block drops its section again
+        assert!(chunk.set_block(5, 100, 5, Block::Air));
+        assert!(chunk.get_section(100 / SECTION_SIZE).is_none());
+    }
+
+    #[test]
+    fn setting_the_same_metadata_leaves_the_chunk_clean() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.set_block(5, 100, 5, Block::Water);
+        chunk.mark_clean();
+
+        assert!(chunk.set_metadata(5, 100, 5, 3));
+        assert!(chunk.is_dirty());
+        chunk.mark_clean();
+        assert!(!chunk.set_metadata(5, 100, 5, 3));
+        assert!(!chunk.is_dirty());
+
+        // Air in an empty section has nowhere to store metadata
+        assert!(!chunk.set_metadata(5, 20, 5, 3));
+        assert!(!chunk.is_dirty());
+    }
+
     #[test]
     fn chunk_lazy_section_creation() {
         let mut chunk = Chunk::new(ChunkPos::new(0, 0));
@@ -384,6 +966,17 @@ mod tests {
         assert_eq!(bz, -32);
     }
 
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn chunk_bounding_sphere_holds_the_column() {
+        let (center, radius) = ChunkPos::new(3, -2).bounding_sphere(CHUNK_HEIGHT);
+        assert!((center - Vec3::new(56.0, CHUNK_HEIGHT as f32 / 2.0, -24.0)).length() < 1e-4);
+
+        for corner in Aabb::from_chunk(3, -2, CHUNK_HEIGHT).corners() {
+            assert!(corner.distance(center) <= radius + 1e-4);
+        }
+    }
+
     #[test]
     fn chunk_pos_neighbors() {
         let pos = ChunkPos::new(5, 10);
@@ -419,11 +1012,70 @@ mod tests {
     }
 
     #[test]
-    fn section_blocks_reference() {
-        let section = ChunkSection::filled(Block::Grass);
-        let blocks = section.blocks();
+    fn section_iterator_order_is_index_order() {
+        let mut section = ChunkSection::new();
+        // Set out of order to make sure the iterator doesn't follow insertion
+        section.set(3, 7, 1, Block::Stone);
+        section.set(15, 0, 0, Block::Dirt);
+        section.set(0, 0, 1, Block::Grass);
+        section.set(0, 1, 0, Block::Sand);
+        section.set(1, 0, 0, Block::Log);
+
+        let coords: Vec<_> = section
+            .iter()
+            .filter(|(_, _, _, b)| !b.is_air())
+            .map(|(x, y, z, _)| (x, y, z))
+            .collect();
+
+        assert_eq!(
+            coords,
+            vec![(1, 0, 0), (15, 0, 0), (0, 0, 1), (0, 1, 0), (3, 7, 1)]
+        );
+
+        // Every yielded coordinate maps back to its position in the sequence
+        for (i, (x, y, z, _)) in section.iter().enumerate() {
+            assert_eq!(x + z * SECTION_SIZE + y * SECTION_SIZE * SECTION_SIZE, i);
+        }
+    }
+
+    #[test]
+    fn section_blocks_in_storage_order() {
+        let mut section = ChunkSection::filled(Block::Grass);
+        section.set(1, 0, 0, Block::Dirt);
+        let blocks: Vec<_> = section.blocks().collect();
         assert_eq!(blocks.len(), SECTION_VOLUME);
-        assert_eq!(blocks[0], Block::Grass);
+        assert_eq!(blocks[..3], [Block::Grass, Block::Dirt, Block::Grass]);
+    }
+
+    #[test]
+    fn palette_widens_past_sixteen_block_types() {
+        let mut section = ChunkSection::new();
+        let narrow = section.heap_size();
+        let types: Vec<Block> = Block::all().iter().copied().skip(1).take(20).collect();
+        for (i, &block) in types.iter().enumerate() {
+            section.set(i % SECTION_SIZE, 3, i / SECTION_SIZE, block);
+        }
+
+        // Air plus 20 types no longer fit 4-bit indices
+        assert_eq!(section.palette_len(), 21);
+        assert!(section.heap_size() >= narrow + SECTION_VOLUME / 2);
+        for (i, &block) in types.iter().enumerate() {
+            assert_eq!(section.get(i % SECTION_SIZE, 3, i / SECTION_SIZE), block);
+        }
+        assert_eq!(section.get(15, 15, 15), Block::Air);
+        assert_eq!(section.solid_count(), 20);
+        assert_eq!(section.count_solid(), 20);
+    }
+
+    #[test]
+    fn terrain_section_is_smaller_than_a_flat_array() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.fill_test_pattern();
+        let section = chunk.get_section(3).unwrap(); // stone and dirt
+        assert_eq!(section.palette_len(), 3); // plus the initial air
+
+        let flat = size_of::<[Block; SECTION_VOLUME]>() + SECTION_VOLUME / 2;
+        assert!(section.heap_size() < flat / 2, "{}", section.heap_size());
     }
 
     #[test]
@@ -434,12 +1086,12 @@ mod tests {
         chunk.set_block(0, 0, 0, Block::Bedrock); // Section 0
         chunk.set_block(0, 16, 0, Block::Stone); // Section 1
         chunk.set_block(0, 64, 0, Block::Grass); // Section 4
-        chunk.set_block(0, 255, 0, Block::Glass); // Section 15
+        chunk.set_block(0, CHUNK_HEIGHT - 1, 0, Block::Glass); // Top section
 
         assert_eq!(chunk.get_block(0, 0, 0), Block::Bedrock);
         assert_eq!(chunk.get_block(0, 16, 0), Block::Stone);
         assert_eq!(chunk.get_block(0, 64, 0), Block::Grass);
-        assert_eq!(chunk.get_block(0, 255, 0), Block::Glass);
+        assert_eq!(chunk.get_block(0, CHUNK_HEIGHT - 1, 0), Block::Glass);
 
         // Intermediate sections should still be empty
         assert!(chunk.get_section(2).is_none());
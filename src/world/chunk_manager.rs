@@ -3,15 +3,27 @@
 //! Handles chunk lifecycle based on player position and render distance.
 
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::thread;
 
 use glam::Vec3;
+use parking_lot::RwLock;
 use rayon::prelude::*;
-
-use super::block::Block;
-use super::chunk::{Chunk, ChunkPos, SECTION_SIZE};
-use super::mesh::{ChunkMesh, MeshGenerator};
+use tracing::warn;
+
+use super::block::{Block, ColorOverrides};
+use super::block_update::BlockUpdateQueue;
+use super::chunk::{CHUNK_HEIGHT, Chunk, ChunkPos, SECTION_SIZE, sections_for_height};
+use super::mesh::{ChunkMesh, MeshGenerator, MeshLayer};
+use super::raycast::{BlockPos, HitFace, RaycastHit, raycast_shapes};
+use super::shape::BlockShape;
+use super::storage::{ChunkSaver, ChunkStore, StorageError};
+use super::structure::StructureBlock;
+use super::terrain::TerrainGenerator;
+use super::validate::{WorldIssue, validate_chunk, validate_mesh};
+use crate::math::Frustum;
 
 /// Configuration for chunk management.
 #[derive(Debug, Clone)]
@@ -22,6 +34,40 @@ pub struct ChunkManagerConfig {
     pub max_chunks_per_frame: usize,
     /// Maximum chunks to unload per frame.
     pub max_unloads_per_frame: usize,
+    /// Maximum rebuilt chunk meshes to hand back per frame.
+    pub max_mesh_uploads_per_frame: usize,
+    /// World seed; each seed generates different terrain.
+    pub seed: u32,
+    /// Directory to save chunks and their cached meshes to, if any.
+    ///
+    /// Saved chunks are loaded instead of generated, and skip meshing while
+    /// their cached mesh is still valid. Edited chunks are saved on unload,
+    /// on a background thread. Without a save dir, edited chunks are kept
+    /// in memory after unloading instead, so their edits still survive.
+    pub save_dir: Option<PathBuf>,
+    /// Distance in chunks beyond which chunks are meshed at a lower level
+    /// of detail, if any.
+    ///
+    /// Chunks past it are meshed at LOD 2, and past twice it at LOD 4, then
+    /// re-meshed in more detail as the player approaches.
+    pub lod_distance: Option<i32>,
+    /// Height of the world in blocks, rounded up to whole sections.
+    ///
+    /// Generated chunks are this tall, and block access, raycasts and
+    /// culling stop at it.
+    pub world_height: usize,
+    /// Maximum block updates to run per tick; the rest wait for later
+    /// ticks, so a flood of flowing water can't stall a frame.
+    pub max_block_updates_per_tick: usize,
+}
+
+impl ChunkManagerConfig {
+    /// Returns the terrain generator for the configured seed and world
+    /// height.
+    #[must_use]
+    pub const fn terrain(&self) -> TerrainGenerator {
+        TerrainGenerator::new(self.seed).with_height(self.world_height)
+    }
 }
 
 impl Default for ChunkManagerConfig {
@@ -30,10 +76,23 @@ impl Default for ChunkManagerConfig {
             render_distance: 4, // 9x9 chunks = 81 chunks
             max_chunks_per_frame: 4,
             max_unloads_per_frame: 8,
+            max_mesh_uploads_per_frame: 8,
+            seed: 0,
+            save_dir: None,
+            lod_distance: None,
+            world_height: CHUNK_HEIGHT,
+            max_block_updates_per_tick: 1024,
         }
     }
 }
 
+/// How many times farther chunks outside the view frustum count as when
+/// ordering the generation queue.
+const HIDDEN_DISTANCE_FACTOR: i32 = 2;
+
+/// Coarsest level of detail distant chunks are meshed at.
+pub const MAX_LOD: usize = 4;
+
 /// State of a chunk in the manager.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ChunkState {
@@ -41,20 +100,50 @@ pub enum ChunkState {
     Queued,
     /// Chunk is being generated.
     Generating,
-    /// Chunk is loaded and ready.
+    /// Chunk data is loaded and meshed, but not rendered until its four
+    /// horizontal neighbors are loaded too.
+    AwaitingNeighbors,
+    /// Chunk is loaded and ready to render.
     Loaded,
     /// Chunk is marked for unloading.
     Unloading,
 }
 
+/// A change in which chunks are loaded, for systems that react to chunks
+/// arriving or going away. Collected with
+/// [`take_events`](ChunkManager::take_events).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChunkEvent {
+    /// The chunk's data was loaded and can be read with
+    /// [`get_chunk`](ChunkManager::get_chunk). It may not be rendered yet.
+    Loaded(ChunkPos),
+    /// The chunk was unloaded and its data can no longer be read. Edits to
+    /// it are kept, and come back when it loads again.
+    Unloaded(ChunkPos),
+}
+
+impl ChunkEvent {
+    /// Returns the position of the chunk the event is about.
+    #[must_use]
+    pub const fn pos(self) -> ChunkPos {
+        match self {
+            Self::Loaded(pos) | Self::Unloaded(pos) => pos,
+        }
+    }
+}
+
 /// A generated chunk ready to be uploaded to GPU.
 pub struct GeneratedChunk {
     /// Chunk position.
     pub pos: ChunkPos,
-    /// Generated mesh (may be empty for air-only chunks).
+    /// Generated opaque mesh (may be empty for air-only chunks).
     pub mesh: ChunkMesh,
+    /// Generated translucent mesh (glass, water), drawn in the transparent pass.
+    pub transparent_mesh: ChunkMesh,
     /// The chunk data (for block queries and modification).
     pub chunk: Chunk,
+    /// Level of detail the meshes were built at, 1 for full detail.
+    pub lod: usize,
 }
 
 /// Background worker result.
@@ -63,6 +152,14 @@ enum WorkerResult {
     ChunkReady(GeneratedChunk),
 }
 
+/// A chunk mesh rebuilt on the thread pool.
+struct MeshJobResult {
+    /// Job number, matched against the chunk's latest job.
+    job: u64,
+    /// The rebuilt meshes and the chunk snapshot they were built from.
+    generated: GeneratedChunk,
+}
+
 /// Manages chunk loading, generation, and unloading.
 pub struct ChunkManager {
     /// Configuration.
@@ -71,28 +168,78 @@ pub struct ChunkManager {
     chunk_states: HashMap<ChunkPos, ChunkState>,
     /// Actual chunk data for loaded chunks.
     chunk_data: HashMap<ChunkPos, Chunk>,
-    /// Queue of chunks to generate.
+    /// Queue of chunks to generate, most urgent first.
     generation_queue: VecDeque<ChunkPos>,
-    /// Sender for work requests.
-    work_sender: Sender<ChunkPos>,
+    /// Chunk and view frustum the generation queue was last sorted for.
+    queue_sorted_for: Option<(ChunkPos, Frustum)>,
+    /// Sender for work requests, with the level of detail to mesh at.
+    work_sender: Sender<(ChunkPos, usize)>,
     /// Receiver for completed chunks.
     result_receiver: Receiver<WorkerResult>,
     /// Set of chunks being generated by workers.
     in_progress: HashSet<ChunkPos>,
+    /// Generated chunks held back until their neighbors are loaded.
+    awaiting_neighbors: HashMap<ChunkPos, GeneratedChunk>,
     /// Chunks that need their mesh rebuilt (after block modification).
     dirty_chunks: HashSet<ChunkPos>,
+    /// Latest mesh job dispatched for each chunk still being re-meshed.
+    mesh_jobs: HashMap<ChunkPos, u64>,
+    /// Number of the next mesh job.
+    next_mesh_job: u64,
+    /// Sender handed to mesh jobs.
+    mesh_sender: Sender<MeshJobResult>,
+    /// Receiver for rebuilt meshes.
+    mesh_receiver: Receiver<MeshJobResult>,
+    /// Level of detail of each chunk's latest mesh.
+    mesh_lods: HashMap<ChunkPos, usize>,
+    /// The player's chunk as of the last update, deciding levels of detail.
+    player_chunk: ChunkPos,
+    /// Chunks edited since they were loaded or last saved.
+    edited_chunks: HashSet<ChunkPos>,
+    /// Edited chunks unloaded with no save dir to write them to, kept so
+    /// they come back with their edits rather than being regenerated.
+    unloaded_edits: HashMap<ChunkPos, Chunk>,
+    /// Structure blocks waiting for their unloaded chunks to load.
+    pending_structure_blocks: HashMap<ChunkPos, Vec<StructureBlock>>,
+    /// Where chunks are saved, if anywhere.
+    store: Option<ChunkStore>,
+    /// Background thread saving unloaded chunks, if there's a store.
+    saver: Option<ChunkSaver>,
+    /// Scheduled block updates (liquids, etc.).
+    block_updates: BlockUpdateQueue,
+    /// Block colors used for meshing, shared with the worker thread.
+    color_overrides: Arc<RwLock<ColorOverrides>>,
+    /// Chunks of the first render-distance region not yet ready to render.
+    initial_pending: HashSet<ChunkPos>,
+    /// Size of the first render-distance region; 0 before the first update.
+    initial_total: usize,
+    /// Load and unload events not yet taken, oldest first.
+    events: Vec<ChunkEvent>,
 }
 
 impl ChunkManager {
     /// Creates a new chunk manager with background workers.
     #[must_use]
     pub fn new(config: ChunkManagerConfig) -> Self {
-        let (work_sender, work_receiver) = mpsc::channel::<ChunkPos>();
+        let (work_sender, work_receiver) = mpsc::channel::<(ChunkPos, usize)>();
         let (result_sender, result_receiver) = mpsc::channel::<WorkerResult>();
+        let (mesh_sender, mesh_receiver) = mpsc::channel::<MeshJobResult>();
+        let store = config.save_dir.clone().map(ChunkStore::new);
+        let saver = store.clone().map(ChunkSaver::new);
+        let color_overrides = Arc::new(RwLock::new(ColorOverrides::new()));
 
         // Spawn background worker thread
+        let worker_store = store.clone();
+        let worker_colors = Arc::clone(&color_overrides);
+        let terrain = config.terrain();
         let _worker = thread::spawn(move || {
-            Self::worker_loop(work_receiver, result_sender);
+            Self::worker_loop(
+                &work_receiver,
+                &result_sender,
+                terrain,
+                worker_store.as_ref(),
+                &worker_colors,
+            );
         });
 
         Self {
@@ -100,15 +247,39 @@ impl ChunkManager {
             chunk_states: HashMap::new(),
             chunk_data: HashMap::new(),
             generation_queue: VecDeque::new(),
+            queue_sorted_for: None,
             work_sender,
             result_receiver,
             in_progress: HashSet::new(),
+            awaiting_neighbors: HashMap::new(),
             dirty_chunks: HashSet::new(),
+            mesh_jobs: HashMap::new(),
+            next_mesh_job: 0,
+            mesh_sender,
+            mesh_receiver,
+            mesh_lods: HashMap::new(),
+            player_chunk: ChunkPos::new(0, 0),
+            edited_chunks: HashSet::new(),
+            unloaded_edits: HashMap::new(),
+            pending_structure_blocks: HashMap::new(),
+            store,
+            saver,
+            block_updates: BlockUpdateQueue::new(),
+            color_overrides,
+            initial_pending: HashSet::new(),
+            initial_total: 0,
+            events: Vec::new(),
         }
     }
 
     /// Worker thread loop - generates chunks in parallel.
-    fn worker_loop(receiver: Receiver<ChunkPos>, sender: Sender<WorkerResult>) {
+    fn worker_loop(
+        receiver: &Receiver<(ChunkPos, usize)>,
+        sender: &Sender<WorkerResult>,
+        terrain: TerrainGenerator,
+        store: Option<&ChunkStore>,
+        colors: &RwLock<ColorOverrides>,
+    ) {
         // Batch chunks for parallel processing
         let mut batch = Vec::new();
         const BATCH_SIZE: usize = 8;
@@ -116,29 +287,22 @@ impl ChunkManager {
         loop {
             // Try to receive work
             match receiver.recv() {
-                Ok(pos) => {
-                    batch.push(pos);
+                Ok(work) => {
+                    batch.push(work);
 
                     // Drain any additional queued work
                     while batch.len() < BATCH_SIZE {
                         match receiver.try_recv() {
-                            Ok(pos) => batch.push(pos),
+                            Ok(work) => batch.push(work),
                             Err(_) => break,
                         }
                     }
 
                     // Process batch in parallel
+                    let colors = colors.read().clone();
                     let results: Vec<GeneratedChunk> = batch
                         .par_drain(..)
-                        .map(|pos| {
-                            let chunk = Self::generate_chunk(pos);
-                            let generator = MeshGenerator::new(chunk.clone());
-                            GeneratedChunk {
-                                pos,
-                                mesh: generator.generate(),
-                                chunk,
-                            }
-                        })
+                        .map(|(pos, lod)| Self::load_or_generate(store, pos, lod, terrain, &colors))
                         .collect();
 
                     // Send results back
@@ -153,103 +317,86 @@ impl ChunkManager {
         }
     }
 
-    /// Generates terrain for a chunk.
-    fn generate_chunk(pos: ChunkPos) -> Chunk {
-        let mut chunk = Chunk::new(pos);
-        let (cx, cz) = (pos.x, pos.z);
-
-        for x in 0..SECTION_SIZE {
-            for z in 0..SECTION_SIZE {
-                let wx = cx * SECTION_SIZE as i32 + x as i32;
-                let wz = cz * SECTION_SIZE as i32 + z as i32;
-
-                // Noise-based height
-                let height = 64
-                    + ((wx as f32 * 0.05).sin() * 8.0) as usize
-                    + ((wz as f32 * 0.07).cos() * 6.0) as usize
-                    + (((wx + wz) as f32 * 0.03).sin() * 4.0) as usize;
-
-                // Bedrock
-                chunk.set_block(x, 0, z, Block::Bedrock);
-
-                // Stone
-                for y in 1..height.saturating_sub(4) {
-                    chunk.set_block(x, y, z, Block::Stone);
-                }
-
-                // Dirt
-                for y in height.saturating_sub(4)..height {
-                    chunk.set_block(x, y, z, Block::Dirt);
-                }
-
-                // Grass
-                if height < 255 {
-                    chunk.set_block(x, height, z, Block::Grass);
-                }
-
-                // Trees
-                if wx % 11 == 0 && wz % 13 == 0 && wx.abs() > 3 && wz.abs() > 3 {
-                    let trunk_height = 4 + (wx.abs() % 3) as usize;
-                    for ty in 1..=trunk_height {
-                        let y = height + ty;
-                        if y < 255 {
-                            chunk.set_block(x, y, z, Block::Log);
-                        }
-                    }
-
-                    // Leaves (simple sphere)
-                    let top = height + trunk_height;
-                    for ly in -2i32..=2 {
-                        for lx in -2i32..=2 {
-                            for lz in -2i32..=2 {
-                                if lx * lx + ly * ly + lz * lz <= 6 {
-                                    let bx = x as i32 + lx;
-                                    let by = top as i32 + ly;
-                                    let bz = z as i32 + lz;
-
-                                    if bx >= 0
-                                        && bx < SECTION_SIZE as i32
-                                        && by > 0
-                                        && by < 255
-                                        && bz >= 0
-                                        && bz < SECTION_SIZE as i32
-                                    {
-                                        let block =
-                                            chunk.get_block(bx as usize, by as usize, bz as usize);
-                                        if block.is_air() {
-                                            chunk.set_block(
-                                                bx as usize,
-                                                by as usize,
-                                                bz as usize,
-                                                Block::Leaves,
-                                            );
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+    /// Loads a chunk (and its cached mesh) from the store, falling back to
+    /// generating and meshing it, then saving the result for next time.
+    ///
+    /// Cached meshes have the built-in block colors baked in at full
+    /// detail, so they are neither read nor written while any color is
+    /// overridden or when meshing at a lower level of detail.
+    fn load_or_generate(
+        store: Option<&ChunkStore>,
+        pos: ChunkPos,
+        lod: usize,
+        terrain: TerrainGenerator,
+        colors: &ColorOverrides,
+    ) -> GeneratedChunk {
+        let mesh = |chunk: &Chunk| {
+            MeshGenerator::new(chunk.clone())
+                .with_color_overrides(colors)
+                .with_biomes(&terrain)
+                .with_lod(lod)
+                .generate_layers()
+        };
+        let use_mesh_cache = colors.is_empty() && lod == 1;
+
+        let loaded = store.and_then(|store| {
+            let loaded = if use_mesh_cache {
+                store.load_meshed(pos, mesh)
+            } else {
+                store.load(pos).map(|stored| {
+                    stored.map(|stored| {
+                        let layers = mesh(&stored.chunk);
+                        (stored.chunk, layers)
+                    })
+                })
+            };
+            loaded
+                .inspect_err(|err| warn!("Failed to load chunk {pos:?}: {err}"))
+                .ok()
+                .flatten()
+        });
+        let (chunk, layers) = loaded.unwrap_or_else(|| {
+            let chunk = terrain.generate(pos);
+            let layers = mesh(&chunk);
+            let cached = use_mesh_cache.then_some(&layers);
+            if let Some(Err(err)) = store.map(|store| store.save(&chunk, cached)) {
+                warn!("Failed to save chunk {pos:?}: {err}");
             }
-        }
+            (chunk, layers)
+        });
 
-        chunk
+        GeneratedChunk {
+            pos,
+            mesh: layers.opaque,
+            transparent_mesh: layers.transparent,
+            chunk,
+            lod,
+        }
     }
 
     /// Updates chunk loading based on player position.
     /// Returns chunks that need GPU buffer creation and positions to unload.
-    pub fn update(&mut self, player_pos: Vec3) -> (Vec<GeneratedChunk>, Vec<ChunkPos>) {
+    ///
+    /// A generated chunk is only returned once its four horizontal neighbors
+    /// are loaded (or outside the render distance), so it is never drawn
+    /// with boundary faces that change when a neighbor arrives.
+    ///
+    /// Chunks inside `frustum` are generated before equally distant ones
+    /// outside it.
+    pub fn update(
+        &mut self,
+        player_pos: Vec3,
+        frustum: &Frustum,
+    ) -> (Vec<GeneratedChunk>, Vec<ChunkPos>) {
         let player_chunk = ChunkPos::from_world_pos(player_pos.x, player_pos.z);
 
         // Determine which chunks should be loaded
         let needed = self.calculate_needed_chunks(player_chunk);
 
-        // Queue new chunks for generation
-        for pos in &needed {
-            if !self.chunk_states.contains_key(pos) {
-                self.chunk_states.insert(*pos, ChunkState::Queued);
-                self.generation_queue.push_back(*pos);
-            }
+        self.queue_needed_chunks(player_chunk, frustum, &needed);
+        if player_chunk != self.player_chunk {
+            self.player_chunk = player_chunk;
+            self.refresh_lods();
         }
 
         // Send work to background thread
@@ -259,7 +406,7 @@ impl ChunkManager {
                 if self.chunk_states.get(&pos) == Some(&ChunkState::Queued) {
                     self.chunk_states.insert(pos, ChunkState::Generating);
                     self.in_progress.insert(pos);
-                    let _ = self.work_sender.send(pos);
+                    let _ = self.work_sender.send((pos, self.lod_for(pos)));
                     sent += 1;
                 }
             } else {
@@ -268,19 +415,13 @@ impl ChunkManager {
         }
 
         // Collect completed chunks
-        let mut ready = Vec::new();
         while let Ok(result) = self.result_receiver.try_recv() {
             match result {
-                WorkerResult::ChunkReady(generated) => {
-                    self.in_progress.remove(&generated.pos);
-                    self.chunk_states.insert(generated.pos, ChunkState::Loaded);
-                    // Store the chunk data
-                    self.chunk_data
-                        .insert(generated.pos, generated.chunk.clone());
-                    ready.push(generated);
-                }
+                WorkerResult::ChunkReady(generated) => self.accept_generated(generated),
             }
         }
+        let ready = self.take_renderable(&needed);
+        self.track_initial_load(&needed, &ready);
 
         // Find chunks to unload
         let mut to_unload = Vec::new();
@@ -288,10 +429,19 @@ impl ChunkManager {
 
         for pos in chunks_to_check {
             if !needed.contains(&pos) && to_unload.len() < self.config.max_unloads_per_frame {
-                if self.chunk_states.get(&pos) == Some(&ChunkState::Loaded) {
+                if matches!(
+                    self.chunk_states.get(&pos),
+                    Some(ChunkState::Loaded | ChunkState::AwaitingNeighbors)
+                ) {
                     to_unload.push(pos);
+                    self.events.push(ChunkEvent::Unloaded(pos));
                     self.chunk_states.remove(&pos);
-                    self.chunk_data.remove(&pos);
+                    self.awaiting_neighbors.remove(&pos);
+                    self.mesh_jobs.remove(&pos);
+                    self.mesh_lods.remove(&pos);
+                    if let Some(chunk) = self.chunk_data.remove(&pos) {
+                        self.keep_if_edited(chunk);
+                    }
                 }
             }
         }
@@ -299,6 +449,234 @@ impl ChunkManager {
         (ready, to_unload)
     }
 
+    /// Queues needed chunks that aren't loaded yet for generation, and
+    /// sorts the queue nearest-first around the player's chunk.
+    ///
+    /// Chunks outside `frustum` count as [`HIDDEN_DISTANCE_FACTOR`] times
+    /// farther, so turning around doesn't stall the view behind chunks
+    /// that aren't on screen. A chunk still being saved waits, so it isn't
+    /// loaded from its old file, and an edited chunk kept in memory is
+    /// loaded straight away, only needing a new mesh.
+    fn queue_needed_chunks(
+        &mut self,
+        player_chunk: ChunkPos,
+        frustum: &Frustum,
+        needed: &HashSet<ChunkPos>,
+    ) {
+        let mut queued = false;
+        for pos in needed {
+            if !self.chunk_states.contains_key(pos) && !self.is_saving(*pos) {
+                if let Some(chunk) = self.unloaded_edits.remove(pos) {
+                    self.insert_chunk(chunk);
+                    continue;
+                }
+                self.chunk_states.insert(*pos, ChunkState::Queued);
+                self.generation_queue.push_back(*pos);
+                queued = true;
+            }
+        }
+
+        // Re-sort when chunks were added, or the player crossed into another
+        // chunk or turned
+        let unchanged = self
+            .queue_sorted_for
+            .as_ref()
+            .is_some_and(|(center, sorted)| *center == player_chunk && sorted == frustum);
+        if !queued && unchanged {
+            return;
+        }
+        self.queue_sorted_for = Some((player_chunk, frustum.clone()));
+        self.generation_queue
+            .make_contiguous()
+            .sort_by_cached_key(|pos| {
+                let distance = pos.distance_squared(player_chunk);
+                if frustum.is_chunk_visible(pos.x, pos.z) {
+                    distance
+                } else {
+                    distance * HIDDEN_DISTANCE_FACTOR * HIDDEN_DISTANCE_FACTOR
+                }
+            });
+    }
+
+    /// Returns the level of detail to mesh a chunk at, from its distance to
+    /// the player: 1 (full detail) up to [`MAX_LOD`].
+    #[must_use]
+    pub fn lod_for(&self, pos: ChunkPos) -> usize {
+        let Some(lod_distance) = self.config.lod_distance else {
+            return 1;
+        };
+        let distance = pos.distance_squared(self.player_chunk);
+        let mut lod = 1;
+        let mut threshold = lod_distance.max(1);
+        while lod < MAX_LOD && distance > threshold * threshold {
+            lod *= 2;
+            threshold *= 2;
+        }
+        lod
+    }
+
+    /// Marks chunks dirty whose mesh is at a different level of detail than
+    /// their distance to the player now calls for.
+    fn refresh_lods(&mut self) {
+        let stale: Vec<ChunkPos> = self
+            .mesh_lods
+            .iter()
+            .filter(|&(&pos, &lod)| lod != self.lod_for(pos))
+            .map(|(&pos, _)| pos)
+            .collect();
+        self.dirty_chunks.extend(stale);
+    }
+
+    /// Stores a chunk from the workers, holding it back from rendering until
+    /// its neighbors are loaded.
+    fn accept_generated(&mut self, generated: GeneratedChunk) {
+        let pos = generated.pos;
+        self.in_progress.remove(&pos);
+        self.mesh_lods.insert(pos, generated.lod);
+        self.chunk_states.insert(pos, ChunkState::AwaitingNeighbors);
+        self.chunk_data.insert(pos, generated.chunk.clone());
+        self.awaiting_neighbors.insert(pos, generated);
+        self.events.push(ChunkEvent::Loaded(pos));
+        self.place_pending_structure_blocks(pos);
+    }
+
+    /// Returns the held-back chunks whose neighbors are now all loaded or
+    /// not needed, marking them loaded.
+    fn take_renderable(&mut self, needed: &HashSet<ChunkPos>) -> Vec<GeneratedChunk> {
+        let chunk_data = &self.chunk_data;
+        let ready: Vec<GeneratedChunk> = self
+            .awaiting_neighbors
+            .extract_if(|pos, _| {
+                pos.neighbors()
+                    .iter()
+                    .all(|n| chunk_data.contains_key(n) || !needed.contains(n))
+            })
+            .map(|(_, generated)| generated)
+            .collect();
+
+        for generated in &ready {
+            self.chunk_states.insert(generated.pos, ChunkState::Loaded);
+        }
+        ready
+    }
+
+    /// Records which chunks of the first render-distance region are ready.
+    ///
+    /// The region is captured on the first call. Chunks that leave the render
+    /// distance before loading stop counting, so progress can't stall.
+    fn track_initial_load(&mut self, needed: &HashSet<ChunkPos>, ready: &[GeneratedChunk]) {
+        if self.initial_total == 0 {
+            self.initial_total = needed.len();
+            self.initial_pending.clone_from(needed);
+        }
+        for generated in ready {
+            self.initial_pending.remove(&generated.pos);
+        }
+        self.initial_pending.retain(|pos| needed.contains(pos));
+    }
+
+    /// Returns the fraction of the initial render-distance region that is
+    /// ready to render, from 0.0 before the first update to 1.0 once the
+    /// whole region has loaded. Useful for drawing a loading screen.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn initial_load_progress(&self) -> f32 {
+        if self.initial_total == 0 {
+            return 0.0;
+        }
+        1.0 - self.initial_pending.len() as f32 / self.initial_total as f32
+    }
+
+    /// Keeps the edits of an unloading chunk if it was edited since it was
+    /// loaded: queues it to be saved, or holds on to it in memory when
+    /// there's no save dir.
+    fn keep_if_edited(&mut self, chunk: Chunk) {
+        let pos = chunk.position();
+        if !self.edited_chunks.contains(&pos) {
+            return;
+        }
+        if let Some(saver) = &self.saver {
+            self.edited_chunks.remove(&pos);
+            saver.save(chunk);
+        } else {
+            self.unloaded_edits.insert(pos, chunk);
+        }
+    }
+
+    /// Returns true if a save of this chunk is still being written.
+    fn is_saving(&self, pos: ChunkPos) -> bool {
+        self.saver
+            .as_ref()
+            .is_some_and(|saver| saver.is_pending(pos))
+    }
+
+    /// Saves every loaded chunk edited since it was loaded, e.g. before exit,
+    /// and waits for background saves of unloaded chunks to finish.
+    /// Returns how many loaded chunks were saved; does nothing without a
+    /// save dir.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`StorageError`]; chunks that failed stay marked
+    /// as edited.
+    pub fn save_edited(&mut self) -> Result<usize, StorageError> {
+        if let Some(saver) = &self.saver {
+            saver.flush()?;
+        }
+        let Some(store) = &self.store else {
+            return Ok(0);
+        };
+        let mut saved = 0;
+        for pos in self.edited_chunks.clone() {
+            if let Some(chunk) = self.chunk_data.get(&pos) {
+                store.save(chunk, None)?;
+                saved += 1;
+            }
+            self.edited_chunks.remove(&pos);
+        }
+        Ok(saved)
+    }
+
+    /// Saves a loaded chunk to the save dir, whether or not it was edited.
+    /// Returns false if the chunk isn't loaded or there's no save dir.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StorageError`] if the file can't be written; the chunk
+    /// then stays marked as edited.
+    pub fn save_chunk(&mut self, pos: ChunkPos) -> Result<bool, StorageError> {
+        let (Some(store), Some(chunk)) = (&self.store, self.chunk_data.get(&pos)) else {
+            return Ok(false);
+        };
+        store.save(chunk, None)?;
+        self.edited_chunks.remove(&pos);
+        Ok(true)
+    }
+
+    /// Loads a chunk from the save dir, replacing any loaded data for it
+    /// (including unsaved edits). Returns false if there's no save dir or
+    /// no file for the chunk.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StorageError`] if a pending background save fails or the
+    /// file can't be read or is corrupt.
+    pub fn load_chunk(&mut self, pos: ChunkPos) -> Result<bool, StorageError> {
+        // The file may still be waiting to be written by the saver
+        if let Some(saver) = &self.saver {
+            saver.flush()?;
+        }
+        let Some(store) = &self.store else {
+            return Ok(false);
+        };
+        let Some(stored) = store.load(pos)? else {
+            return Ok(false);
+        };
+        self.insert_chunk(stored.chunk);
+        self.edited_chunks.remove(&pos);
+        Ok(true)
+    }
+
     /// Calculates which chunks should be loaded based on player position.
     fn calculate_needed_chunks(&self, center: ChunkPos) -> HashSet<ChunkPos> {
         let r = self.config.render_distance;
@@ -348,44 +726,245 @@ impl ChunkManager {
         self.config.render_distance = distance.max(1).min(32);
     }
 
+    /// Returns the height of the world in blocks.
+    #[must_use]
+    pub const fn world_height(&self) -> usize {
+        sections_for_height(self.config.world_height) * SECTION_SIZE
+    }
+
+    /// Inserts an already generated chunk, marking it as loaded.
+    ///
+    /// Replaces any existing data at that position. The chunk is marked dirty
+    /// so its mesh is built on the next rebuild.
+    pub fn insert_chunk(&mut self, chunk: Chunk) {
+        let pos = chunk.position();
+        self.chunk_states.insert(pos, ChunkState::Loaded);
+        self.awaiting_neighbors.remove(&pos);
+        self.unloaded_edits.remove(&pos);
+        if self.chunk_data.insert(pos, chunk).is_none() {
+            self.events.push(ChunkEvent::Loaded(pos));
+        }
+        self.dirty_chunks.insert(pos);
+        self.place_pending_structure_blocks(pos);
+    }
+
+    /// Places a block of a [`Structure`](super::Structure), unless the block
+    /// there is one it may not replace. Returns true if it was placed.
+    ///
+    /// Blocks in unloaded chunks are held until the chunk loads, and then
+    /// placed over whatever it generated with. Blocks outside the world are
+    /// dropped.
+    pub fn place_structure_block(&mut self, block: StructureBlock) -> bool {
+        let BlockPos { x, y, z } = block.pos;
+        let Some((chunk_pos, ..)) = self.locate(x, y, z) else {
+            return false;
+        };
+        match self.get_block(x, y, z) {
+            Some(existing) if block.can_replace(existing) => {
+                self.set_block_with_metadata(x, y, z, block.block, 0)
+            }
+            Some(_) => false,
+            None => {
+                self.pending_structure_blocks
+                    .entry(chunk_pos)
+                    .or_default()
+                    .push(block);
+                false
+            }
+        }
+    }
+
+    /// Places the structure blocks held for the chunk at `pos`, now that
+    /// it's loaded.
+    fn place_pending_structure_blocks(&mut self, pos: ChunkPos) {
+        for block in self
+            .pending_structure_blocks
+            .remove(&pos)
+            .unwrap_or_default()
+        {
+            self.place_structure_block(block);
+        }
+    }
+
+    /// Splits a world position into its chunk and chunk-local coordinates.
+    /// Returns None if `y` is outside the world.
+    fn locate(&self, x: i32, y: i32, z: i32) -> Option<(ChunkPos, usize, usize, usize)> {
+        let y = usize::try_from(y)
+            .ok()
+            .filter(|&y| y < self.world_height())?;
+        let chunk_pos = ChunkPos::from_block(x, z);
+        let (origin_x, origin_z) = chunk_pos.block_origin();
+        let local_x = (x - origin_x).unsigned_abs() as usize;
+        let local_z = (z - origin_z).unsigned_abs() as usize;
+        Some((chunk_pos, local_x, y, local_z))
+    }
+
+    /// Returns the loaded chunk at `pos`, for reading many of its blocks
+    /// without a lookup per block.
+    ///
+    /// The chunk reflects every edit made so far, even ones whose mesh hasn't
+    /// been rebuilt yet. Chunks still being generated or loaded on the
+    /// worker thread aren't available until [`update`](Self::update) accepts
+    /// them, and structure blocks waiting on a neighbor to load are placed
+    /// later, so the data may still change without an edit.
+    #[must_use]
+    pub fn get_chunk(&self, pos: ChunkPos) -> Option<&Chunk> {
+        self.chunk_data.get(&pos)
+    }
+
     /// Gets a block at the given world position.
     /// Returns None if the chunk is not loaded.
     #[must_use]
     pub fn get_block(&self, x: i32, y: i32, z: i32) -> Option<Block> {
-        if y < 0 || y >= 256 {
-            return None;
-        }
-
-        let chunk_pos = ChunkPos::from_world_pos(x as f32, z as f32);
+        let (chunk_pos, local_x, local_y, local_z) = self.locate(x, y, z)?;
         let chunk = self.chunk_data.get(&chunk_pos)?;
+        chunk.try_get_block(local_x, local_y, local_z)
+    }
 
-        let local_x = x.rem_euclid(16) as usize;
-        let local_z = z.rem_euclid(16) as usize;
-
-        Some(chunk.get_block(local_x, y as usize, local_z))
+    /// Gets the metadata nibble of the block at the given world position.
+    /// Returns None if the chunk is not loaded.
+    #[must_use]
+    pub fn get_metadata(&self, x: i32, y: i32, z: i32) -> Option<u8> {
+        let (chunk_pos, local_x, local_y, local_z) = self.locate(x, y, z)?;
+        let chunk = self.chunk_data.get(&chunk_pos)?;
+        (local_y < chunk.height()).then(|| chunk.get_metadata(local_x, local_y, local_z))
     }
 
     /// Sets a block at the given world position.
-    /// Returns true if successful, false if chunk not loaded.
+    /// Returns true if it changed, false if it was already there or the
+    /// chunk is not loaded.
+    ///
+    /// Liquids at or next to the position get a block update, so water
+    /// starts flowing when placed or when a wall holding it back is removed.
     pub fn set_block(&mut self, x: i32, y: i32, z: i32, block: Block) -> bool {
-        if y < 0 || y >= 256 {
+        self.place_block(x, y, z, block, 0)
+    }
+
+    /// Sets a block and its metadata (e.g. orientation), notifying nearby
+    /// liquids like [`set_block`](Self::set_block).
+    /// Returns true if either changed, false if neither did or the chunk is
+    /// not loaded.
+    pub fn place_block(&mut self, x: i32, y: i32, z: i32, block: Block, metadata: u8) -> bool {
+        if !self.set_block_with_metadata(x, y, z, block, metadata) {
             return false;
         }
 
-        let chunk_pos = ChunkPos::from_world_pos(x as f32, z as f32);
-
-        if let Some(chunk) = self.chunk_data.get_mut(&chunk_pos) {
-            let local_x = x.rem_euclid(16) as usize;
-            let local_z = z.rem_euclid(16) as usize;
+        let pos = BlockPos::new(x, y, z);
+        let neighbors = HitFace::ALL.map(|face| pos.offset(face));
+        for p in std::iter::once(pos).chain(neighbors) {
+            if self
+                .get_block(p.x, p.y, p.z)
+                .is_some_and(|b| b.properties().is_liquid)
+            {
+                self.schedule_update(p, 1);
+            }
+        }
+        true
+    }
 
-            chunk.set_block(local_x, y as usize, local_z, block);
+    /// Sets a block and its metadata without scheduling any block updates.
+    /// Returns true if either changed, false if neither did or the chunk is
+    /// not loaded. Only changes dirty any meshes.
+    ///
+    /// Used by block update handlers, which schedule their own follow-ups.
+    pub fn set_block_with_metadata(
+        &mut self,
+        x: i32,
+        y: i32,
+        z: i32,
+        block: Block,
+        metadata: u8,
+    ) -> bool {
+        let Some((chunk_pos, local_x, local_y, local_z)) = self.locate(x, y, z) else {
+            return false;
+        };
+
+        if let Some(chunk) = self
+            .chunk_data
+            .get_mut(&chunk_pos)
+            .filter(|chunk| local_y < chunk.height())
+        {
+            let block_changed = chunk.set_block(local_x, local_y, local_z, block);
+            let metadata_changed = chunk.set_metadata(local_x, local_y, local_z, metadata);
+            if !block_changed && !metadata_changed {
+                return false;
+            }
             self.dirty_chunks.insert(chunk_pos);
+            self.edited_chunks.insert(chunk_pos);
+
+            // Neighbor meshes hide or shade their border faces by this block
+            let last = SECTION_SIZE - 1;
+            let [pos_x, neg_x, pos_z, neg_z] = chunk_pos.neighbors();
+            let touched = [
+                (local_x == last, pos_x),
+                (local_x == 0, neg_x),
+                (local_z == last, pos_z),
+                (local_z == 0, neg_z),
+            ];
+            for (on_edge, neighbor) in touched {
+                if on_edge && self.chunk_data.contains_key(&neighbor) {
+                    self.dirty_chunks.insert(neighbor);
+                }
+            }
             true
         } else {
             false
         }
     }
 
+    /// Sets every block in the box between `min` and `max` (inclusive, in
+    /// any order) to `block`. Returns the number of blocks changed.
+    ///
+    /// Only changed blocks dirty their chunk, and their neighbor on a chunk
+    /// border, like [`set_block_with_metadata`](Self::set_block_with_metadata);
+    /// no block updates are scheduled. Unloaded chunks and positions outside
+    /// the world are skipped.
+    pub fn fill_box(&mut self, min: BlockPos, max: BlockPos, block: Block) -> usize {
+        self.edit_box(min, max, |current, metadata| {
+            (current != block || metadata != 0).then_some(block)
+        })
+    }
+
+    /// Replaces every `from` block in the box between `min` and `max`
+    /// (inclusive, in any order) with `to`, like [`fill_box`](Self::fill_box).
+    /// Returns the number of blocks replaced.
+    pub fn replace_in_box(
+        &mut self,
+        min: BlockPos,
+        max: BlockPos,
+        from: Block,
+        to: Block,
+    ) -> usize {
+        self.edit_box(min, max, |current, _| (current == from).then_some(to))
+    }
+
+    /// Visits every loaded block in a box, setting it to what `edit` returns
+    /// for its block and metadata. Returns the number of blocks set.
+    fn edit_box<F>(&mut self, min: BlockPos, max: BlockPos, mut edit: F) -> usize
+    where
+        F: FnMut(Block, u8) -> Option<Block>,
+    {
+        let max_y = i32::try_from(self.world_height()).unwrap_or(i32::MAX) - 1;
+        let (min_y, top_y) = (min.y.min(max.y).max(0), min.y.max(max.y).min(max_y));
+        let mut changed = 0;
+        for x in min.x.min(max.x)..=min.x.max(max.x) {
+            for z in min.z.min(max.z)..=min.z.max(max.z) {
+                for y in min_y..=top_y {
+                    let (Some(current), Some(metadata)) =
+                        (self.get_block(x, y, z), self.get_metadata(x, y, z))
+                    else {
+                        continue;
+                    };
+                    if let Some(block) = edit(current, metadata) {
+                        self.set_block_with_metadata(x, y, z, block, 0);
+                        changed += 1;
+                    }
+                }
+            }
+        }
+        changed
+    }
+
     /// Checks if a block at the given position is solid.
     /// Returns false for unloaded chunks.
     #[must_use]
@@ -395,21 +974,253 @@ impl ChunkManager {
             .unwrap_or(false)
     }
 
+    /// Returns the shape of the block at the given position if entities
+    /// collide with it. Returns `None` for unloaded chunks.
+    #[must_use]
+    pub fn collision_shape(&self, x: i32, y: i32, z: i32) -> Option<BlockShape> {
+        self.get_block(x, y, z)
+            .filter(|block| block.blocks_movement())
+            .map(Block::shape)
+    }
+
+    /// Casts a ray through the loaded chunks, stopping at the first block
+    /// `stop_at` accepts, against the boxes of its shape. Unloaded chunks
+    /// are passed through.
+    ///
+    /// `Block::is_solid` targets what the player collides with; a wider
+    /// predicate such as "not air" also selects liquids and other
+    /// non-solid blocks.
+    pub fn raycast_where<P>(
+        &self,
+        origin: Vec3,
+        direction: Vec3,
+        max_distance: f32,
+        stop_at: P,
+    ) -> Option<RaycastHit>
+    where
+        P: Fn(Block) -> bool,
+    {
+        raycast_shapes(
+            origin,
+            direction,
+            max_distance,
+            self.world_height(),
+            |x, y, z| {
+                self.get_block(x, y, z)
+                    .filter(|&block| stop_at(block))
+                    .map(Block::shape)
+            },
+        )
+    }
+
+    /// Returns and clears the chunk load and unload events since the last
+    /// call, oldest first.
+    ///
+    /// Chunks are loaded when generated or read back from disk during
+    /// [`update`](Self::update), or by [`insert_chunk`](Self::insert_chunk)
+    /// (replacing an already loaded chunk isn't an event), and unloaded by
+    /// [`update`](Self::update) once they're out of range.
+    pub fn take_events(&mut self) -> Vec<ChunkEvent> {
+        std::mem::take(&mut self.events)
+    }
+
     /// Returns and clears the list of dirty chunks that need mesh rebuilding.
+    ///
+    /// Chunks still waiting for their neighbors stay dirty, so they aren't
+    /// rendered early and are rebuilt once they're ready.
     pub fn take_dirty_chunks(&mut self) -> Vec<ChunkPos> {
-        self.dirty_chunks.drain().collect()
+        let (waiting, dirty) = self
+            .dirty_chunks
+            .drain()
+            .partition(|pos| self.awaiting_neighbors.contains_key(pos));
+        self.dirty_chunks = waiting;
+        dirty.into_iter().collect()
+    }
+
+    /// Marks every loaded chunk dirty so all meshes are rebuilt, e.g. after
+    /// changing the mesher. Returns the number of chunks marked.
+    pub fn mark_all_dirty(&mut self) -> usize {
+        self.dirty_chunks.extend(self.chunk_data.keys().copied());
+        self.chunk_data.len()
+    }
+
+    /// Returns the block color overrides used for meshing.
+    #[must_use]
+    pub fn color_overrides(&self) -> ColorOverrides {
+        self.color_overrides.read().clone()
+    }
+
+    /// Replaces the block color overrides and marks every loaded chunk dirty
+    /// so it is re-meshed with the new colors. Returns the number of chunks
+    /// marked.
+    pub fn set_color_overrides(&mut self, overrides: ColorOverrides) -> usize {
+        *self.color_overrides.write() = overrides;
+        self.mark_all_dirty()
+    }
+
+    /// Returns a mesh generator for a chunk using the color overrides, the
+    /// loaded neighbor chunks and the chunk's level of detail.
+    fn mesh_generator(&self, chunk: &Chunk) -> MeshGenerator {
+        let pos = chunk.position();
+        let neighbors = pos.neighbors().map(|pos| self.chunk_data.get(&pos));
+        MeshGenerator::new(chunk.clone())
+            .with_color_overrides(&self.color_overrides.read())
+            .with_biomes(&self.config.terrain())
+            .with_neighbors(neighbors)
+            .with_lod(self.lod_for(pos))
+    }
+
+    /// Schedules a block update at `pos` after `delay_ticks` ticks.
+    pub fn schedule_update(&mut self, pos: BlockPos, delay_ticks: u32) {
+        self.block_updates.schedule(pos, delay_ticks);
+    }
+
+    /// Returns the number of pending block updates.
+    #[must_use]
+    pub fn pending_updates(&self) -> usize {
+        self.block_updates.len()
+    }
+
+    /// Advances one game tick, calling `handler` for each block update due.
+    ///
+    /// The handler may modify blocks and schedule further updates; those
+    /// always land on a later tick. Updates beyond
+    /// [`max_block_updates_per_tick`](ChunkManagerConfig::max_block_updates_per_tick)
+    /// are put off to the next tick.
+    pub fn tick<F>(&mut self, mut handler: F)
+    where
+        F: FnMut(&mut Self, BlockPos),
+    {
+        let limit = self.config.max_block_updates_per_tick;
+        for pos in self.block_updates.advance_at_most(limit) {
+            handler(self, pos);
+        }
+    }
+
+    /// Returns true if the chunk's data is loaded.
+    #[must_use]
+    pub fn is_loaded(&self, pos: ChunkPos) -> bool {
+        self.chunk_data.contains_key(&pos)
+    }
+
+    /// Checks every loaded chunk for corruption, rebuilding meshes to do so.
+    ///
+    /// `has_buffers(pos, layer)` reports whether the renderer holds GPU
+    /// buffers for a layer of a chunk's mesh. Clean chunks
+    /// must have buffers exactly when that mesh is non-empty; dirty chunks,
+    /// chunks being re-meshed and chunks waiting for their neighbors are
+    /// skipped for this check since their upload is still pending.
+    #[must_use]
+    pub fn validate<F>(&self, has_buffers: F) -> Vec<WorldIssue>
+    where
+        F: Fn(ChunkPos, MeshLayer) -> bool,
+    {
+        let mut issues = Vec::new();
+        for (&pos, chunk) in &self.chunk_data {
+            issues.extend(validate_chunk(chunk));
+
+            let layers = self.mesh_generator(chunk).generate_layers();
+            for layer in MeshLayer::ALL {
+                let mesh = layers.layer(layer);
+                issues.extend(validate_mesh(pos, layer, mesh));
+
+                let buffered = has_buffers(pos, layer);
+                let pending = self.dirty_chunks.contains(&pos)
+                    || self.mesh_jobs.contains_key(&pos)
+                    || self.awaiting_neighbors.contains_key(&pos);
+                if !pending && buffered == mesh.is_empty() {
+                    issues.push(WorldIssue::BufferMismatch {
+                        chunk: pos,
+                        layer,
+                        has_buffers: buffered,
+                        mesh_empty: mesh.is_empty(),
+                    });
+                }
+            }
+        }
+        issues
     }
 
-    /// Rebuilds the mesh for a specific chunk.
+    /// Re-meshes every dirty chunk on the rayon thread pool. Returns the
+    /// number of jobs dispatched.
+    ///
+    /// Each job meshes a snapshot of the chunk and its neighbors' edges, so
+    /// later edits can't race with it; they dirty the chunk again, and the
+    /// older job's result is dropped. Collect results with
+    /// [`take_rebuilt_meshes`](Self::take_rebuilt_meshes).
+    pub fn dispatch_dirty_meshes(&mut self) -> usize {
+        let dirty = self.take_dirty_chunks();
+        let mut dispatched = 0;
+        for pos in dirty {
+            let Some(chunk) = self.chunk_data.get(&pos) else {
+                continue;
+            };
+            let generator = self.mesh_generator(chunk);
+            let lod = self.lod_for(pos);
+            let snapshot = chunk.clone();
+            let job = self.next_mesh_job;
+            self.next_mesh_job += 1;
+            self.mesh_jobs.insert(pos, job);
+
+            let sender = self.mesh_sender.clone();
+            rayon::spawn(move || {
+                let layers = generator.generate_layers();
+                // The manager may be gone by now; the mesh isn't needed then
+                let _ = sender.send(MeshJobResult {
+                    job,
+                    generated: GeneratedChunk {
+                        pos,
+                        mesh: layers.opaque,
+                        transparent_mesh: layers.transparent,
+                        chunk: snapshot,
+                        lod,
+                    },
+                });
+            });
+            dispatched += 1;
+        }
+        dispatched
+    }
+
+    /// Returns up to `max_mesh_uploads_per_frame` meshes finished by
+    /// [`dispatch_dirty_meshes`](Self::dispatch_dirty_meshes), skipping
+    /// meshes of chunks that were edited again or unloaded since.
+    pub fn take_rebuilt_meshes(&mut self) -> Vec<GeneratedChunk> {
+        let mut rebuilt = Vec::new();
+        while rebuilt.len() < self.config.max_mesh_uploads_per_frame {
+            let Ok(result) = self.mesh_receiver.try_recv() else {
+                break;
+            };
+            let pos = result.generated.pos;
+            if self.mesh_jobs.get(&pos) == Some(&result.job) {
+                self.mesh_jobs.remove(&pos);
+                if self.chunk_data.contains_key(&pos) {
+                    self.mesh_lods.insert(pos, result.generated.lod);
+                    rebuilt.push(result.generated);
+                }
+            }
+        }
+        rebuilt
+    }
+
+    /// Returns the number of chunks being re-meshed on the thread pool.
+    #[must_use]
+    pub fn meshing_count(&self) -> usize {
+        self.mesh_jobs.len()
+    }
+
+    /// Rebuilds the mesh for a specific chunk on the calling thread.
     /// Returns the generated chunk if successful.
     #[must_use]
     pub fn rebuild_chunk_mesh(&self, pos: ChunkPos) -> Option<GeneratedChunk> {
         let chunk = self.chunk_data.get(&pos)?;
-        let generator = MeshGenerator::new(chunk.clone());
+        let layers = self.mesh_generator(chunk).generate_layers();
         Some(GeneratedChunk {
             pos,
-            mesh: generator.generate(),
+            mesh: layers.opaque,
+            transparent_mesh: layers.transparent,
             chunk: chunk.clone(),
+            lod: self.lod_for(pos),
         })
     }
 }
@@ -417,6 +1228,7 @@ impl ChunkManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::world::chunk::ChunkSection;
 
     #[test]
     fn chunk_pos_from_world_pos() {
@@ -472,6 +1284,87 @@ mod tests {
         assert_eq!(config.max_chunks_per_frame, 4);
     }
 
+    /// The view frustum of a camera in the middle of `chunk`, looking
+    /// towards -Z.
+    fn frustum_in(chunk: ChunkPos) -> Frustum {
+        let (x, z) = chunk.block_origin();
+        #[allow(clippy::cast_precision_loss)]
+        let position = Vec3::new(x as f32 + 8.0, 64.0, z as f32 + 8.0);
+        crate::engine::camera::Camera::default()
+            .at_position(position)
+            .frustum()
+    }
+
+    /// Returns where `pos` is in the generation queue.
+    fn queue_index(manager: &ChunkManager, pos: ChunkPos) -> usize {
+        manager
+            .generation_queue
+            .iter()
+            .position(|&queued| queued == pos)
+            .unwrap()
+    }
+
+    #[test]
+    fn nearest_chunks_are_queued_first() {
+        let mut manager = ChunkManager::new(ChunkManagerConfig::default());
+        let player = ChunkPos::new(10, -3);
+        manager.insert_chunk(Chunk::new(player));
+
+        let needed = manager.calculate_needed_chunks(player);
+        manager.queue_needed_chunks(player, &frustum_in(player), &needed);
+
+        // The player's own chunk is loaded, so its ring comes next
+        let first = *manager.generation_queue.front().unwrap();
+        assert_eq!(first.distance_squared(player), 1);
+        assert!(!manager.generation_queue.contains(&player));
+        let ahead = |distance| ChunkPos::new(player.x, player.z - distance);
+        assert!(queue_index(&manager, ahead(1)) < queue_index(&manager, ahead(2)));
+        assert!(queue_index(&manager, ahead(2)) < queue_index(&manager, ahead(4)));
+
+        // Crossing into another chunk re-sorts around it
+        let moved = ChunkPos::new(12, -3);
+        manager.queue_needed_chunks(moved, &frustum_in(moved), &HashSet::new());
+        assert_eq!(manager.generation_queue.front(), Some(&moved));
+    }
+
+    #[test]
+    fn queue_is_only_resorted_when_the_view_changes() {
+        let mut manager = ChunkManager::new(ChunkManagerConfig::default());
+        let player = ChunkPos::new(0, 0);
+        let needed = manager.calculate_needed_chunks(player);
+        manager.queue_needed_chunks(player, &frustum_in(player), &needed);
+        let sorted: Vec<ChunkPos> = manager.generation_queue.iter().copied().collect();
+
+        // Nothing new to queue from the same place: the order is left alone
+        manager.generation_queue.rotate_left(1);
+        manager.queue_needed_chunks(player, &frustum_in(player), &needed);
+        assert_ne!(manager.generation_queue, sorted);
+
+        // Turning around re-sorts for the new view
+        let turned = crate::engine::camera::Camera::default()
+            .at_position(Vec3::new(8.0, 64.0, 8.0))
+            .with_orientation(90.0, 0.0)
+            .frustum();
+        manager.queue_needed_chunks(player, &turned, &needed);
+        let behind = ChunkPos::new(0, 2);
+        assert!(queue_index(&manager, behind) < queue_index(&manager, ChunkPos::new(0, -2)));
+    }
+
+    #[test]
+    fn chunks_in_view_are_queued_before_equally_near_ones() {
+        let mut manager = ChunkManager::new(ChunkManagerConfig::default());
+        let player = ChunkPos::new(0, 0);
+        let in_front = ChunkPos::new(0, -2);
+        let behind = ChunkPos::new(0, 2);
+        let frustum = frustum_in(player);
+        assert!(frustum.is_chunk_visible(in_front.x, in_front.z));
+        assert!(!frustum.is_chunk_visible(behind.x, behind.z));
+
+        let needed = manager.calculate_needed_chunks(player);
+        manager.queue_needed_chunks(player, &frustum, &needed);
+        assert!(queue_index(&manager, in_front) < queue_index(&manager, behind));
+    }
+
     #[test]
     fn set_render_distance_clamps() {
         let mut manager = ChunkManager::new(ChunkManagerConfig::default());
@@ -485,4 +1378,628 @@ mod tests {
         manager.set_render_distance(8);
         assert_eq!(manager.render_distance(), 8);
     }
+
+    #[test]
+    fn mark_all_dirty_rebuilds_every_loaded_chunk() {
+        let mut manager = ChunkManager::new(ChunkManagerConfig::default());
+        for x in 0..3 {
+            let mut chunk = Chunk::new(ChunkPos::new(x, 0));
+            chunk.fill_test_pattern();
+            manager.insert_chunk(chunk);
+        }
+        manager.take_dirty_chunks();
+
+        assert_eq!(manager.mark_all_dirty(), 3);
+        let dirty = manager.take_dirty_chunks();
+        assert_eq!(dirty.len(), 3);
+        let rebuilt = dirty
+            .into_iter()
+            .filter_map(|pos| manager.rebuild_chunk_mesh(pos))
+            .filter(|generated| !generated.mesh.is_empty())
+            .count();
+        assert_eq!(rebuilt, 3);
+    }
+
+    #[test]
+    fn edge_edits_remesh_the_neighbor() {
+        let mut manager = ChunkManager::new(ChunkManagerConfig::default());
+        for x in [0, 1] {
+            let mut chunk = Chunk::new(ChunkPos::new(x, 0));
+            chunk.set_section(0, Some(ChunkSection::filled(Block::Stone)));
+            manager.insert_chunk(chunk);
+        }
+        manager.take_dirty_chunks();
+
+        // The shared wall is hidden while both chunks are loaded
+        let west = manager.rebuild_chunk_mesh(ChunkPos::new(0, 0)).unwrap();
+        let walls = |generated: &GeneratedChunk| {
+            let east_normal = [1.0, 0.0, 0.0];
+            generated
+                .mesh
+                .vertices
+                .iter()
+                .filter(|v| v.normal == east_normal && v.position[0] == 16.0)
+                .count()
+        };
+        assert_eq!(walls(&west), 0);
+
+        // Digging out the neighbor's edge exposes the wall again
+        assert!(manager.set_block(16, 5, 5, Block::Air));
+        let mut dirty = manager.take_dirty_chunks();
+        dirty.sort_by_key(|pos| pos.x);
+        assert_eq!(dirty, vec![ChunkPos::new(0, 0), ChunkPos::new(1, 0)]);
+        let west = manager.rebuild_chunk_mesh(ChunkPos::new(0, 0)).unwrap();
+        assert_eq!(walls(&west), 4);
+
+        // Interior edits leave the neighbor alone
+        assert!(manager.set_block(20, 5, 5, Block::Air));
+        assert_eq!(manager.take_dirty_chunks(), vec![ChunkPos::new(1, 0)]);
+
+        // Setting a block to what's already there dirties nothing
+        assert!(!manager.set_block(20, 5, 5, Block::Air));
+        assert!(manager.take_dirty_chunks().is_empty());
+        // A metadata change alone still counts
+        assert!(manager.set_block_with_metadata(20, 6, 5, Block::Stone, 3));
+        assert_eq!(manager.take_dirty_chunks(), vec![ChunkPos::new(1, 0)]);
+        assert!(!manager.set_block_with_metadata(20, 6, 5, Block::Stone, 3));
+    }
+
+    #[test]
+    fn raycast_where_can_stop_at_liquids() {
+        let mut manager = stone_floor_manager();
+        manager.set_block(8, 20, 8, Block::Water);
+        let origin = Vec3::new(8.5, 30.5, 8.5);
+
+        // Solid-only targeting looks through the water to the floor
+        let hit = manager.raycast_where(origin, Vec3::NEG_Y, 50.0, Block::is_solid);
+        assert_eq!(hit.map(|hit| hit.block_pos), Some(BlockPos::new(8, 15, 8)));
+
+        let not_air = |block: Block| !block.is_air();
+        let hit = manager
+            .raycast_where(origin, Vec3::NEG_Y, 50.0, not_air)
+            .unwrap();
+        assert_eq!(hit.block_pos, BlockPos::new(8, 20, 8));
+        assert_eq!(hit.face, HitFace::Top);
+
+        // Nothing in unloaded chunks
+        let far = Vec3::new(100.5, 30.5, 8.5);
+        assert_eq!(manager.raycast_where(far, Vec3::NEG_Y, 50.0, not_air), None);
+    }
+
+    #[test]
+    fn edits_on_the_positive_edge_dirty_that_neighbor() {
+        let mut manager = stone_floor_manager();
+
+        assert!(manager.set_block(15, 20, 8, Block::Stone));
+        assert_eq!(
+            sorted_dirty(&mut manager),
+            vec![ChunkPos::new(0, 0), ChunkPos::new(1, 0)]
+        );
+
+        // A corner block borders two neighbors
+        assert!(manager.set_block(15, 20, 15, Block::Stone));
+        assert_eq!(
+            sorted_dirty(&mut manager),
+            vec![
+                ChunkPos::new(0, 0),
+                ChunkPos::new(0, 1),
+                ChunkPos::new(1, 0)
+            ]
+        );
+    }
+
+    /// Loads stone-floored chunks from -1 to 2 along X and -1 to 1 along Z,
+    /// with nothing dirty.
+    fn stone_floor_manager() -> ChunkManager {
+        let mut manager = ChunkManager::new(ChunkManagerConfig::default());
+        for x in -1..=2 {
+            for z in -1..=1 {
+                let mut chunk = Chunk::new(ChunkPos::new(x, z));
+                chunk.set_section(0, Some(ChunkSection::filled(Block::Stone)));
+                manager.insert_chunk(chunk);
+            }
+        }
+        manager.take_dirty_chunks();
+        manager
+    }
+
+    fn sorted_dirty(manager: &mut ChunkManager) -> Vec<ChunkPos> {
+        let mut dirty = manager.take_dirty_chunks();
+        dirty.sort_by_key(|pos| (pos.x, pos.z));
+        dirty
+    }
+
+    #[test]
+    fn loaded_chunks_can_be_read_directly() {
+        let mut manager = ChunkManager::new(ChunkManagerConfig::default());
+        let pos = ChunkPos::new(-1, 2);
+        assert!(manager.get_chunk(pos).is_none());
+
+        let mut chunk = Chunk::new(pos);
+        chunk.fill_test_pattern();
+        manager.insert_chunk(chunk);
+        // World (-13, 70, 37) is local (3, 70, 5) in chunk (-1, 2)
+        assert!(manager.set_block(-13, 70, 37, Block::Glass));
+
+        let chunk = manager.get_chunk(pos).expect("chunk is loaded");
+        assert_eq!(chunk.position(), pos);
+        assert_eq!(chunk.get_block(3, 70, 5), Block::Glass);
+        assert_eq!(chunk.get_block(3, 64, 5), Block::Grass);
+        assert_eq!(manager.get_block(-13, 70, 37), Some(Block::Glass));
+        assert!(manager.get_chunk(ChunkPos::new(0, 2)).is_none());
+    }
+
+    #[test]
+    fn world_height_bounds_generation_and_edits() {
+        let config = ChunkManagerConfig {
+            world_height: 40,
+            ..ChunkManagerConfig::default()
+        };
+        let mut manager = ChunkManager::new(config.clone());
+        assert_eq!(manager.world_height(), 48);
+
+        let chunk = config.terrain().generate(ChunkPos::new(0, 0));
+        assert_eq!(chunk.height(), 48);
+        manager.insert_chunk(chunk);
+
+        assert!(manager.set_block(3, 47, 3, Block::Glass));
+        assert!(!manager.set_block(3, 48, 3, Block::Glass));
+        assert_eq!(manager.get_block(3, 48, 3), None);
+        let hit = manager.raycast_where(Vec3::new(3.5, 100.0, 3.5), Vec3::NEG_Y, 200.0, |block| {
+            !block.is_air()
+        });
+        assert_eq!(hit.map(|hit| hit.block_pos.y), Some(47));
+
+        let mut tall = ChunkManager::new(ChunkManagerConfig {
+            world_height: 512,
+            ..ChunkManagerConfig::default()
+        });
+        tall.insert_chunk(tall.config.terrain().generate(ChunkPos::new(0, 0)));
+        assert!(tall.set_block(3, 400, 3, Block::Glass));
+        assert_eq!(tall.get_block(3, 400, 3), Some(Block::Glass));
+    }
+
+    #[test]
+    fn fill_box_dirties_only_touched_chunks() {
+        let mut manager = stone_floor_manager();
+
+        // Inside one chunk, clear of its borders
+        let filled = manager.fill_box(
+            BlockPos::new(3, 20, 3),
+            BlockPos::new(5, 21, 4),
+            Block::Glass,
+        );
+        assert_eq!(filled, 3 * 2 * 2);
+        assert_eq!(manager.get_block(5, 21, 4), Some(Block::Glass));
+        assert_eq!(sorted_dirty(&mut manager), vec![ChunkPos::new(0, 0)]);
+
+        // Straddling the X border, and reaching the +Z edge of both chunks
+        let filled = manager.fill_box(
+            BlockPos::new(17, 12, 15),
+            BlockPos::new(14, 10, 3),
+            Block::Dirt,
+        );
+        assert_eq!(filled, 4 * 3 * 13);
+        assert_eq!(manager.get_block(14, 10, 3), Some(Block::Dirt));
+        assert_eq!(manager.get_block(17, 12, 15), Some(Block::Dirt));
+        assert_eq!(
+            sorted_dirty(&mut manager),
+            vec![
+                ChunkPos::new(0, 0),
+                ChunkPos::new(0, 1),
+                ChunkPos::new(1, 0),
+                ChunkPos::new(1, 1)
+            ]
+        );
+
+        // Filling with what's already there changes nothing
+        let filled = manager.fill_box(
+            BlockPos::new(14, 10, 3),
+            BlockPos::new(17, 12, 15),
+            Block::Dirt,
+        );
+        assert_eq!(filled, 0);
+        assert!(manager.take_dirty_chunks().is_empty());
+    }
+
+    #[test]
+    fn fill_box_skips_unloaded_chunks_and_the_world_edge() {
+        let mut manager = stone_floor_manager();
+
+        // Runs west past the loaded chunks and below the world
+        let filled = manager.fill_box(
+            BlockPos::new(-40, -5, 8),
+            BlockPos::new(-16, 1, 8),
+            Block::Air,
+        );
+        assert_eq!(filled, 2);
+        assert_eq!(manager.get_block(-16, 0, 8), Some(Block::Air));
+        assert_eq!(sorted_dirty(&mut manager), vec![ChunkPos::new(-1, 0)]);
+    }
+
+    #[test]
+    fn replace_in_box_dirties_only_chunks_with_matches() {
+        let mut manager = stone_floor_manager();
+        manager.fill_box(
+            BlockPos::new(20, 2, 20),
+            BlockPos::new(21, 2, 21),
+            Block::Dirt,
+        );
+        manager.take_dirty_chunks();
+
+        // The box spans four chunks, but only one holds dirt
+        let replaced = manager.replace_in_box(
+            BlockPos::new(8, 0, 8),
+            BlockPos::new(24, 4, 24),
+            Block::Dirt,
+            Block::Grass,
+        );
+        assert_eq!(replaced, 4);
+        assert_eq!(manager.get_block(21, 2, 21), Some(Block::Grass));
+        assert_eq!(manager.get_block(8, 2, 8), Some(Block::Stone));
+        assert_eq!(sorted_dirty(&mut manager), vec![ChunkPos::new(1, 1)]);
+
+        assert_eq!(
+            manager.replace_in_box(
+                BlockPos::new(8, 0, 8),
+                BlockPos::new(24, 4, 24),
+                Block::Dirt,
+                Block::Grass,
+            ),
+            0
+        );
+        assert!(manager.take_dirty_chunks().is_empty());
+    }
+
+    /// Collects rebuilt meshes until no mesh jobs are left, checking each
+    /// batch respects the per-frame upload cap, or panics after a few seconds.
+    fn wait_for_meshes(manager: &mut ChunkManager) -> Vec<GeneratedChunk> {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        let mut rebuilt = Vec::new();
+        while manager.meshing_count() > 0 {
+            assert!(std::time::Instant::now() < deadline, "mesh jobs timed out");
+            let batch = manager.take_rebuilt_meshes();
+            // Uploads are capped per frame
+            assert!(batch.len() <= manager.config.max_mesh_uploads_per_frame);
+            rebuilt.extend(batch);
+            thread::yield_now();
+        }
+        rebuilt
+    }
+
+    /// Updates the manager from the center of `pos` until that chunk has
+    /// loaded, or panics after a few seconds.
+    fn wait_until_loaded(manager: &mut ChunkManager, pos: ChunkPos) {
+        let (x, z) = pos.block_origin();
+        #[allow(clippy::cast_precision_loss)]
+        let position = Vec3::new(x as f32 + 8.0, 64.0, z as f32 + 8.0);
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while !manager.is_loaded(pos) {
+            assert!(std::time::Instant::now() < deadline, "generation timed out");
+            manager.update(position, &frustum_in(pos));
+            thread::yield_now();
+        }
+    }
+
+    #[test]
+    fn dirty_chunks_are_meshed_on_the_thread_pool() {
+        let mut manager = ChunkManager::new(ChunkManagerConfig {
+            max_mesh_uploads_per_frame: 2,
+            ..ChunkManagerConfig::default()
+        });
+        for x in 0..5 {
+            let mut chunk = Chunk::new(ChunkPos::new(x, 0));
+            chunk.fill_test_pattern();
+            manager.insert_chunk(chunk);
+        }
+
+        assert_eq!(manager.dispatch_dirty_meshes(), 5);
+        assert_eq!(manager.dispatch_dirty_meshes(), 0);
+        let rebuilt = wait_for_meshes(&mut manager);
+        assert_eq!(rebuilt.len(), 5);
+        assert!(rebuilt.iter().all(|generated| !generated.mesh.is_empty()));
+    }
+
+    #[test]
+    fn distant_chunks_are_meshed_coarser() {
+        let mut manager = ChunkManager::new(ChunkManagerConfig {
+            lod_distance: Some(2),
+            ..ChunkManagerConfig::default()
+        });
+        assert_eq!(manager.lod_for(ChunkPos::new(2, 0)), 1);
+        assert_eq!(manager.lod_for(ChunkPos::new(0, -3)), 2);
+        assert_eq!(manager.lod_for(ChunkPos::new(4, 0)), 2);
+        assert_eq!(manager.lod_for(ChunkPos::new(5, 0)), 4);
+        assert_eq!(manager.lod_for(ChunkPos::new(50, 50)), MAX_LOD);
+
+        let far = ChunkPos::new(6, 0);
+        let mut chunk = Chunk::new(far);
+        chunk.fill_test_pattern();
+        manager.insert_chunk(chunk);
+        manager.dispatch_dirty_meshes();
+        let rebuilt = wait_for_meshes(&mut manager);
+        assert_eq!(rebuilt[0].lod, 4);
+
+        // Walking up to the chunk re-meshes it in full detail
+        manager.player_chunk = ChunkPos::new(5, 0);
+        manager.refresh_lods();
+        assert_eq!(manager.dispatch_dirty_meshes(), 1);
+        let rebuilt = wait_for_meshes(&mut manager);
+        assert_eq!(rebuilt[0].lod, 1);
+        assert!(rebuilt[0].mesh.triangle_count() > 0);
+
+        // Nothing is left to re-mesh at the same distance
+        manager.refresh_lods();
+        assert_eq!(manager.dispatch_dirty_meshes(), 0);
+    }
+
+    #[test]
+    fn edits_during_meshing_supersede_the_older_job() {
+        let mut manager = ChunkManager::new(ChunkManagerConfig::default());
+        let pos = ChunkPos::new(0, 0);
+        let mut chunk = Chunk::new(pos);
+        chunk.fill_test_pattern();
+        manager.insert_chunk(chunk);
+        manager.dispatch_dirty_meshes();
+
+        // The first job meshed a snapshot without the glass
+        assert!(manager.set_block(3, 65, 3, Block::Glass));
+        manager.dispatch_dirty_meshes();
+
+        let rebuilt = wait_for_meshes(&mut manager);
+        assert_eq!(rebuilt.len(), 1);
+        assert_eq!(rebuilt[0].chunk.get_block(3, 65, 3), Block::Glass);
+        assert!(!rebuilt[0].transparent_mesh.is_empty());
+    }
+
+    #[test]
+    fn saved_chunk_loads_back_over_edits() {
+        let dir = std::env::temp_dir().join(format!("voxel-forge-manager-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut manager = ChunkManager::new(ChunkManagerConfig {
+            save_dir: Some(dir.clone()),
+            ..ChunkManagerConfig::default()
+        });
+        let pos = ChunkPos::new(0, 0);
+        assert!(!manager.save_chunk(pos).unwrap());
+        assert!(!manager.load_chunk(pos).unwrap());
+
+        let mut chunk = Chunk::new(pos);
+        chunk.fill_test_pattern();
+        manager.insert_chunk(chunk);
+        assert!(manager.set_block(3, 64, 3, Block::Sand));
+        assert!(manager.save_chunk(pos).unwrap());
+
+        // Unsaved edits are replaced by the saved chunk
+        assert!(manager.set_block(3, 64, 3, Block::Water));
+        manager.take_dirty_chunks();
+        assert!(manager.load_chunk(pos).unwrap());
+        assert_eq!(manager.get_block(3, 64, 3), Some(Block::Sand));
+        assert_eq!(manager.take_dirty_chunks(), vec![pos]);
+        assert_eq!(manager.save_edited().unwrap(), 0);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn color_overrides_remesh_loaded_chunks() {
+        let mut manager = ChunkManager::new(ChunkManagerConfig::default());
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.set_block(1, 1, 1, Block::Stone);
+        manager.insert_chunk(chunk);
+        manager.take_dirty_chunks();
+
+        let mut overrides = ColorOverrides::new();
+        overrides.set(Block::Stone, [0.0, 0.45, 0.7]);
+        assert_eq!(manager.set_color_overrides(overrides.clone()), 1);
+        assert_eq!(manager.color_overrides(), overrides);
+
+        let dirty = manager.take_dirty_chunks();
+        assert_eq!(dirty, vec![ChunkPos::new(0, 0)]);
+        let rebuilt = manager.rebuild_chunk_mesh(dirty[0]).unwrap();
+        assert!(
+            rebuilt
+                .mesh
+                .vertices
+                .iter()
+                .all(|v| v.color == [0.0, 0.45, 0.7])
+        );
+    }
+
+    #[test]
+    fn chunk_waits_for_neighbors_before_rendering() {
+        let mut manager = ChunkManager::new(ChunkManagerConfig::default());
+        let center = ChunkPos::new(0, 0);
+        let needed = manager.calculate_needed_chunks(center);
+        let generate = |pos| {
+            ChunkManager::load_or_generate(
+                None,
+                pos,
+                1,
+                TerrainGenerator::new(0),
+                &ColorOverrides::new(),
+            )
+        };
+
+        manager.accept_generated(generate(center));
+        assert!(manager.take_renderable(&needed).is_empty());
+        assert_eq!(manager.chunk_states[&center], ChunkState::AwaitingNeighbors);
+        // Its data can already be queried
+        assert!(manager.is_loaded(center));
+
+        let [first, rest @ ..] = center.neighbors();
+        for pos in rest {
+            manager.accept_generated(generate(pos));
+        }
+        assert!(manager.take_renderable(&needed).is_empty());
+
+        // The last neighbor completes the center; the neighbors still wait
+        // for their own outer neighbors
+        manager.accept_generated(generate(first));
+        let ready: Vec<ChunkPos> = manager
+            .take_renderable(&needed)
+            .into_iter()
+            .map(|generated| generated.pos)
+            .collect();
+        assert_eq!(ready, vec![center]);
+        assert_eq!(manager.chunk_states[&center], ChunkState::Loaded);
+        assert_eq!(manager.loaded_count(), 1);
+    }
+
+    #[test]
+    fn moving_away_unloads_what_was_loaded() {
+        let mut manager = ChunkManager::new(ChunkManagerConfig {
+            render_distance: 1,
+            ..ChunkManagerConfig::default()
+        });
+        let home = ChunkPos::new(0, 0);
+        wait_until_loaded(&mut manager, home);
+
+        // Far enough that none of the old chunks are needed
+        let away = ChunkPos::new(100, 0);
+        manager.update(Vec3::new(1608.0, 64.0, 8.0), &frustum_in(away));
+        let events: Vec<ChunkEvent> = manager
+            .take_events()
+            .into_iter()
+            .filter(|event| event.pos() == home)
+            .collect();
+        assert_eq!(
+            events,
+            vec![ChunkEvent::Loaded(home), ChunkEvent::Unloaded(home)]
+        );
+        assert!(manager.take_events().is_empty());
+        assert!(manager.get_chunk(home).is_none());
+    }
+
+    #[test]
+    fn edits_survive_unloading_without_a_save_dir() {
+        let mut manager = ChunkManager::new(ChunkManagerConfig {
+            render_distance: 1,
+            ..ChunkManagerConfig::default()
+        });
+        let home = ChunkPos::new(0, 0);
+        wait_until_loaded(&mut manager, home);
+        assert!(manager.set_block(8, 120, 8, Block::Lamp));
+        manager.take_dirty_chunks();
+
+        let away = ChunkPos::new(100, 0);
+        manager.update(Vec3::new(1608.0, 64.0, 8.0), &frustum_in(away));
+        assert!(!manager.is_loaded(home));
+        manager.take_events();
+
+        // Back in range, the edited chunk loads at once and only needs meshing
+        manager.update(Vec3::new(8.0, 64.0, 8.0), &frustum_in(home));
+        assert_eq!(manager.get_block(8, 120, 8), Some(Block::Lamp));
+        assert!(manager.take_dirty_chunks().contains(&home));
+        assert!(manager.take_events().contains(&ChunkEvent::Loaded(home)));
+    }
+
+    #[test]
+    fn initial_load_progress_tracks_ready_chunks() {
+        let mut manager = ChunkManager::new(ChunkManagerConfig {
+            render_distance: 1,
+            ..ChunkManagerConfig::default()
+        });
+        let center = ChunkPos::new(0, 0);
+        let needed = manager.calculate_needed_chunks(center);
+        assert_eq!(needed.len(), 5);
+        assert!(manager.initial_load_progress().abs() < f32::EPSILON);
+
+        let mut progress = Vec::new();
+        for pos in std::iter::once(center).chain(center.neighbors()) {
+            let generated = ChunkManager::load_or_generate(
+                None,
+                pos,
+                1,
+                TerrainGenerator::new(0),
+                &ColorOverrides::new(),
+            );
+            manager.accept_generated(generated);
+            let ready = manager.take_renderable(&needed);
+            manager.track_initial_load(&needed, &ready);
+            progress.push(manager.initial_load_progress());
+        }
+
+        // Outer chunks are ready as soon as they load (their other neighbors
+        // are out of range); the center waits for the last of them
+        let expected = [0.0, 0.2, 0.4, 0.6, 1.0];
+        for (p, e) in progress.iter().zip(expected) {
+            assert!((p - e).abs() < 1e-6, "progress {progress:?}");
+        }
+    }
+
+    #[test]
+    fn tick_drains_scheduled_updates() {
+        let mut manager = ChunkManager::new(ChunkManagerConfig::default());
+        let pos = BlockPos::new(3, 70, -2);
+        manager.schedule_update(pos, 3);
+
+        let mut fired = Vec::new();
+        for tick in 1..=5 {
+            manager.tick(|_, p| fired.push((tick, p)));
+        }
+
+        assert_eq!(fired, vec![(3, pos)]);
+        assert_eq!(manager.pending_updates(), 0);
+    }
+
+    #[test]
+    fn tick_runs_a_bounded_number_of_updates() {
+        let mut manager = ChunkManager::new(ChunkManagerConfig {
+            max_block_updates_per_tick: 2,
+            ..ChunkManagerConfig::default()
+        });
+        for x in 0..5 {
+            manager.schedule_update(BlockPos::new(x, 70, 0), 1);
+        }
+
+        let mut per_tick = Vec::new();
+        for _ in 0..4 {
+            let mut fired = 0;
+            manager.tick(|_, _| fired += 1);
+            per_tick.push(fired);
+        }
+        assert_eq!(per_tick, vec![2, 2, 1, 0]);
+    }
+
+    #[test]
+    fn validate_reports_corruption_and_missing_buffers() {
+        let mut manager = ChunkManager::new(ChunkManagerConfig::default());
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.fill_test_pattern();
+        manager.insert_chunk(chunk);
+
+        // Freshly inserted chunks are dirty, so missing buffers are expected
+        assert!(manager.validate(|_, _| false).is_empty());
+
+        // Once clean, the opaque layer must be uploaded
+        manager.take_dirty_chunks();
+        assert_eq!(
+            manager.validate(|_, _| false),
+            vec![WorldIssue::BufferMismatch {
+                chunk: ChunkPos::new(0, 0),
+                layer: MeshLayer::Opaque,
+                has_buffers: false,
+                mesh_empty: false,
+            }]
+        );
+        assert!(
+            manager
+                .validate(|_, layer| layer == MeshLayer::Opaque)
+                .is_empty()
+        );
+
+        // Corrupt a section's cached count
+        let chunk = manager.chunk_data.get_mut(&ChunkPos::new(0, 0)).unwrap();
+        chunk.get_section_mut(0).unwrap().corrupt_solid_count(1);
+        let issues = manager.validate(|_, layer| layer == MeshLayer::Opaque);
+        assert!(matches!(
+            issues.as_slice(),
+            [WorldIssue::SolidCountMismatch {
+                section: 0,
+                stored: 1,
+                ..
+            }]
+        ));
+    }
 }
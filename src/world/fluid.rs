@@ -0,0 +1,218 @@
+//! Liquid flow.
+//!
+//! Water spreads through scheduled block updates. The block metadata nibble
+//! holds the flow level: 0 is a source, and each step away from the source
+//! adds one. Water falls straight down into air; otherwise it spreads
+//! sideways until it reaches `max_spread`.
+//!
+//! Flow only ever fills air or lowers a level, so every update does a
+//! bounded amount of work and a pool settles after finitely many ticks.
+//! [`ChunkManager::tick`] also caps the updates run per tick.
+//!
+//! The mesher lowers the open surface of water by its level; see
+//! [`surface_height`].
+
+use super::block::Block;
+use super::chunk_manager::ChunkManager;
+use super::raycast::{BlockPos, HitFace};
+
+/// Liquid flow settings.
+#[derive(Debug, Clone)]
+pub struct FluidConfig {
+    /// Highest flow level; water at this level spreads no further.
+    pub max_spread: u8,
+    /// Ticks between a water block updating and its neighbors updating.
+    pub tick_delay: u32,
+}
+
+impl Default for FluidConfig {
+    fn default() -> Self {
+        Self {
+            max_spread: 7,
+            tick_delay: 5,
+        }
+    }
+}
+
+/// Returns the height of the open surface of liquid at flow `level`, as a
+/// fraction of a block: just below the top for a source, dropping by a
+/// ninth per level, down to a thin film from level 7 on.
+#[must_use]
+pub fn surface_height(level: u8) -> f32 {
+    f32::from(8 - level.min(7)) / 9.0
+}
+
+/// Handles a scheduled block update for water at `pos`.
+///
+/// Does nothing if `pos` no longer holds water. Intended as the handler for
+/// [`ChunkManager::tick`].
+pub fn update_water(world: &mut ChunkManager, pos: BlockPos, config: &FluidConfig) {
+    if world.get_block(pos.x, pos.y, pos.z) != Some(Block::Water) {
+        return;
+    }
+    let level = world.get_metadata(pos.x, pos.y, pos.z).unwrap_or(0);
+
+    // Falling takes priority over spreading
+    let below = pos.offset(HitFace::Bottom);
+    match world.get_block(below.x, below.y, below.z) {
+        Some(Block::Air) => {
+            // Falling water keeps spreading at most one level below a source
+            flow_into(world, below, level.max(1), config);
+            return;
+        }
+        Some(Block::Water) => return, // Resting on water: nothing to spread
+        _ => {}
+    }
+
+    if level >= config.max_spread {
+        return;
+    }
+    for face in HitFace::HORIZONTAL {
+        flow_into(world, pos.offset(face), level + 1, config);
+    }
+}
+
+/// Puts water of `level` at `pos` if it is air or weaker water.
+fn flow_into(world: &mut ChunkManager, pos: BlockPos, level: u8, config: &FluidConfig) {
+    let changed = match world.get_block(pos.x, pos.y, pos.z) {
+        Some(Block::Air) => true,
+        Some(Block::Water) => world
+            .get_metadata(pos.x, pos.y, pos.z)
+            .is_some_and(|current| current > level),
+        _ => false,
+    };
+
+    if changed && world.set_block_with_metadata(pos.x, pos.y, pos.z, Block::Water, level) {
+        world.schedule_update(pos, config.tick_delay);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::chunk::{Chunk, ChunkPos, SECTION_SIZE};
+    use crate::world::chunk_manager::ChunkManagerConfig;
+
+    const FLOOR_Y: i32 = 10;
+
+    /// A 3x3 grid of chunks with a stone floor at `FLOOR_Y`.
+    fn flat_world() -> ChunkManager {
+        let mut world = ChunkManager::new(ChunkManagerConfig::default());
+        for cx in -1..=1 {
+            for cz in -1..=1 {
+                let mut chunk = Chunk::new(ChunkPos::new(cx, cz));
+                for x in 0..SECTION_SIZE {
+                    for z in 0..SECTION_SIZE {
+                        chunk.set_block(x, FLOOR_Y as usize, z, Block::Stone);
+                    }
+                }
+                world.insert_chunk(chunk);
+            }
+        }
+        world
+    }
+
+    fn run_until_settled(world: &mut ChunkManager, config: &FluidConfig) -> u32 {
+        let mut ticks = 0;
+        while world.pending_updates() > 0 {
+            world.tick(|w, p| update_water(w, p, config));
+            ticks += 1;
+            assert!(ticks < 1000, "water never settled");
+        }
+        ticks
+    }
+
+    #[test]
+    fn source_spreads_configured_levels_then_settles() {
+        let config = FluidConfig {
+            max_spread: 4,
+            tick_delay: 2,
+        };
+        let mut world = flat_world();
+        let y = FLOOR_Y + 1;
+        assert!(world.set_block(0, y, 0, Block::Water));
+
+        let ticks = run_until_settled(&mut world, &config);
+        assert!(ticks > 0);
+
+        // Level grows by one per block of Manhattan distance from the source
+        for x in -6i32..=6 {
+            for z in -6i32..=6 {
+                let distance = u8::try_from(x.abs() + z.abs()).unwrap();
+                let block = world.get_block(x, y, z);
+                if distance <= config.max_spread {
+                    assert_eq!(block, Some(Block::Water), "at ({x}, {z})");
+                    assert_eq!(world.get_metadata(x, y, z), Some(distance));
+                } else {
+                    assert_eq!(block, Some(Block::Air), "at ({x}, {z})");
+                }
+            }
+        }
+
+        // Stable: more ticks change nothing
+        world.tick(|w, p| update_water(w, p, &config));
+        assert_eq!(world.pending_updates(), 0);
+    }
+
+    #[test]
+    fn source_spreads_one_level_per_tick() {
+        let config = FluidConfig {
+            tick_delay: 1,
+            ..FluidConfig::default()
+        };
+        let mut world = flat_world();
+        let y = FLOOR_Y + 1;
+        assert!(world.set_block(0, y, 0, Block::Water));
+        assert_eq!(world.get_block(1, y, 0), Some(Block::Air));
+
+        world.tick(|w, p| update_water(w, p, &config));
+        assert_eq!(world.get_block(1, y, 0), Some(Block::Water));
+        assert_eq!(world.get_metadata(1, y, 0), Some(1));
+        assert_eq!(world.get_block(2, y, 0), Some(Block::Air));
+
+        world.tick(|w, p| update_water(w, p, &config));
+        assert_eq!(world.get_metadata(2, y, 0), Some(2));
+    }
+
+    #[test]
+    fn surface_drops_with_level() {
+        assert!(surface_height(0) < 1.0);
+        for level in 0..7 {
+            assert!(surface_height(level + 1) < surface_height(level));
+        }
+        assert!(surface_height(7) > 0.0);
+        assert!((surface_height(15) - surface_height(7)).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn water_falls_before_spreading() {
+        let config = FluidConfig::default();
+        let mut world = flat_world();
+        let top = FLOOR_Y + 4;
+        assert!(world.set_block(0, top, 0, Block::Water));
+
+        run_until_settled(&mut world, &config);
+
+        // The column falls to the floor without spreading mid-air
+        for y in FLOOR_Y + 1..top {
+            assert_eq!(world.get_block(0, y, 0), Some(Block::Water));
+        }
+        assert_eq!(world.get_block(1, top - 1, 0), Some(Block::Air));
+        // Then spreads along the floor
+        assert_eq!(world.get_block(1, FLOOR_Y + 1, 0), Some(Block::Water));
+    }
+
+    #[test]
+    fn water_stops_at_solid_blocks() {
+        let config = FluidConfig::default();
+        let mut world = flat_world();
+        let y = FLOOR_Y + 1;
+        world.set_block(1, y, 0, Block::Stone);
+        world.set_block(0, y, 0, Block::Water);
+
+        run_until_settled(&mut world, &config);
+
+        assert_eq!(world.get_block(1, y, 0), Some(Block::Stone));
+        assert_eq!(world.get_block(-1, y, 0), Some(Block::Water));
+    }
+}
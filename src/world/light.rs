@@ -0,0 +1,165 @@
+//! Block light.
+//!
+//! Light-emitting blocks flood their light outward through transparent
+//! blocks and partial ones like slabs, losing one level per step, so a
+//! level 15 lamp lights the air next to it at 14 and reaches 14 blocks
+//! away. Opaque full blocks stop the light and stay dark; their faces are
+//! lit by the block in front of them.
+//!
+//! Light is computed per chunk, and doesn't yet cross into neighbor chunks.
+//!
+//! Sky light is simpler: anything above a column's topmost block sees the
+//! sky, and anything below it is under cover.
+
+use std::collections::VecDeque;
+
+use super::chunk::{Chunk, SECTION_SIZE};
+
+/// Brightest light level, as emitted by a full-brightness light source.
+pub const MAX_LIGHT: u8 = 15;
+
+/// Sky light level under cover, below a column's
+/// [heightmap](super::Chunk::heightmap); open sky is [`MAX_LIGHT`].
+pub const COVERED_SKY_LIGHT: u8 = 8;
+
+/// Block light level of every block in a chunk.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LightMap {
+    /// Levels indexed by [`index`](Self::index); empty when the chunk has
+    /// no light sources, so unlit chunks don't allocate.
+    levels: Vec<u8>,
+    /// Height of the chunk in blocks.
+    height: usize,
+}
+
+impl LightMap {
+    /// Floods light from every light-emitting block in `chunk`.
+    #[must_use]
+    pub fn compute(chunk: &Chunk) -> Self {
+        let mut map = Self {
+            levels: Vec::new(),
+            height: chunk.height(),
+        };
+        let mut queue = VecDeque::new();
+
+        for (x, y, z, block) in chunk.iter_solid() {
+            let emission = block.properties().light_emission.min(MAX_LIGHT);
+            if emission > 0 {
+                map.raise([x, y, z], emission);
+                queue.push_back([x, y, z]);
+            }
+        }
+
+        while let Some(pos) = queue.pop_front() {
+            let level = map.get(pos[0], pos[1], pos[2]);
+            if level <= 1 {
+                continue;
+            }
+            for next in map.neighbors(pos).into_iter().flatten() {
+                let open = !chunk.get_block(next[0], next[1], next[2]).occludes();
+                if open && map.raise(next, level - 1) {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        map
+    }
+
+    /// Returns the light level at a chunk-local position, or 0 outside the
+    /// chunk.
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize, z: usize) -> u8 {
+        if x >= SECTION_SIZE || y >= self.height || z >= SECTION_SIZE {
+            return 0;
+        }
+        self.levels
+            .get(Self::index([x, y, z]))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Returns true if no block in the chunk is lit.
+    #[must_use]
+    pub fn is_dark(&self) -> bool {
+        self.levels.iter().all(|&level| level == 0)
+    }
+
+    /// Raises the level at `pos` to `level`, returning true if it was darker.
+    fn raise(&mut self, pos: [usize; 3], level: u8) -> bool {
+        if self.levels.is_empty() {
+            self.levels = vec![0; SECTION_SIZE * SECTION_SIZE * self.height];
+        }
+        let current = &mut self.levels[Self::index(pos)];
+        if *current >= level {
+            return false;
+        }
+        *current = level;
+        true
+    }
+
+    /// Index of a chunk-local position in `levels`.
+    const fn index([x, y, z]: [usize; 3]) -> usize {
+        x + z * SECTION_SIZE + y * SECTION_SIZE * SECTION_SIZE
+    }
+
+    /// Returns the six neighbors of `pos` that are inside the chunk.
+    fn neighbors(&self, [x, y, z]: [usize; 3]) -> [Option<[usize; 3]>; 6] {
+        [
+            (x + 1 < SECTION_SIZE).then(|| [x + 1, y, z]),
+            x.checked_sub(1).map(|x| [x, y, z]),
+            (y + 1 < self.height).then(|| [x, y + 1, z]),
+            y.checked_sub(1).map(|y| [x, y, z]),
+            (z + 1 < SECTION_SIZE).then(|| [x, y, z + 1]),
+            z.checked_sub(1).map(|z| [x, y, z]),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::{Block, ChunkPos};
+
+    #[test]
+    fn chunk_without_sources_is_dark() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.set_block(8, 64, 8, Block::Stone);
+
+        let light = LightMap::compute(&chunk);
+        assert!(light.is_dark());
+        assert_eq!(light.get(8, 65, 8), 0);
+    }
+
+    #[test]
+    fn light_fades_one_level_per_block() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.set_block(8, 64, 8, Block::Lamp);
+        let emission = Block::Lamp.properties().light_emission;
+
+        let light = LightMap::compute(&chunk);
+        assert_eq!(light.get(8, 64, 8), emission);
+        assert_eq!(light.get(9, 64, 8), emission - 1);
+        assert_eq!(light.get(8, 66, 8), emission - 2);
+        assert_eq!(light.get(11, 65, 9), emission - 5);
+        // Out of reach
+        assert_eq!(light.get(8, 64 + usize::from(emission), 8), 0);
+    }
+
+    #[test]
+    fn opaque_blocks_stop_light() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.set_block(8, 64, 8, Block::Lamp);
+        // A stone shell around the lamp, with a glass window to the east
+        for (x, y, z) in [(7, 64, 8), (8, 63, 8), (8, 65, 8), (8, 64, 7), (8, 64, 9)] {
+            chunk.set_block(x, y, z, Block::Stone);
+        }
+        chunk.set_block(9, 64, 8, Block::Glass);
+
+        let light = LightMap::compute(&chunk);
+        assert_eq!(light.get(8, 65, 8), 0);
+        assert_eq!(light.get(9, 64, 8), MAX_LIGHT - 1);
+        // Light leaks around through the window, taking the long way
+        assert_eq!(light.get(6, 64, 8), MAX_LIGHT - 8);
+    }
+}
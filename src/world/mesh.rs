@@ -3,11 +3,26 @@
 //! Converts chunk block data into renderable mesh geometry using
 //! greedy meshing to minimize triangle count.
 
-use bytemuck::{Pod, Zeroable};
+use std::collections::HashMap;
+use std::fmt;
 
-use super::block::Block;
-use super::chunk::{CHUNK_HEIGHT, Chunk, SECTION_SIZE};
+use bytemuck::{Pod, Zeroable};
+use glam::Vec3;
+use thiserror::Error;
+
+use super::biome::Biome;
+use super::block::{Block, ColorOverrides};
+use super::block_registry::TextureSide;
+use super::chunk::{Chunk, Heightmap, SECTION_SIZE};
+use super::fluid::surface_height;
+use super::light::{COVERED_SKY_LIGHT, LightMap, MAX_LIGHT};
+use super::shape::BlockShape;
+use super::terrain::TerrainGenerator;
 use super::texture_atlas::TextureAtlas;
+use crate::math::Aabb;
+
+/// Size of a [`ChunkVertex`] in 32-bit words, for hashing its bits.
+const VERTEX_WORDS: usize = std::mem::size_of::<ChunkVertex>() / 4;
 
 /// A vertex in the chunk mesh.
 #[repr(C)]
@@ -25,6 +40,12 @@ pub struct ChunkVertex {
     pub local_uv: [f32; 2],
     /// Atlas UV base position (top-left of texture in atlas).
     pub atlas_uv: [f32; 2],
+    /// Block light and sky light reaching the face (0.0 = unlit, 1.0 =
+    /// full brightness).
+    pub light: [f32; 2],
+    /// Animation frames following the texture at `atlas_uv`, 1 for a still
+    /// texture.
+    pub frames: f32,
 }
 
 impl ChunkVertex {
@@ -37,6 +58,7 @@ impl ChunkVertex {
         ao: f32,
         local_uv: [f32; 2],
         atlas_uv: [f32; 2],
+        light: [f32; 2],
     ) -> Self {
         Self {
             position,
@@ -45,9 +67,20 @@ impl ChunkVertex {
             ao,
             local_uv,
             atlas_uv,
+            light,
+            frames: 1.0,
         }
     }
 
+    /// Animates the texture through `frames` frames, laid out along the
+    /// atlas row from `atlas_uv`.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub const fn with_frames(mut self, frames: u32) -> Self {
+        self.frames = frames as f32;
+        self
+    }
+
     /// Returns the vertex buffer layout for wgpu.
     #[must_use]
     pub fn layout() -> wgpu::VertexBufferLayout<'static> {
@@ -91,6 +124,18 @@ impl ChunkVertex {
                     shader_location: 5,
                     format: wgpu::VertexFormat::Float32x2,
                 },
+                // light
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 14]>() as wgpu::BufferAddress,
+                    shader_location: 6,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                // frames
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 16]>() as wgpu::BufferAddress,
+                    shader_location: 7,
+                    format: wgpu::VertexFormat::Float32,
+                },
             ],
         }
     }
@@ -127,6 +172,67 @@ impl Face {
         }
     }
 
+    /// Returns which of a block's textures is drawn on this face.
+    #[must_use]
+    pub const fn texture_side(self) -> TextureSide {
+        match self {
+            Self::PosY => TextureSide::Top,
+            Self::NegY => TextureSide::Bottom,
+            Self::PosX | Self::NegX | Self::PosZ | Self::NegZ => TextureSide::Side,
+        }
+    }
+
+    /// Returns the `(axis, u_axis, v_axis)` indices for sweeping this face:
+    /// the axis along its normal, then the two axes spanning the face.
+    const fn sweep_axes(self) -> (usize, usize, usize) {
+        match self {
+            Self::PosY | Self::NegY => (1, 0, 2), // iterate Y, sweep XZ
+            Self::PosX | Self::NegX => (0, 2, 1), // iterate X, sweep ZY
+            Self::PosZ | Self::NegZ => (2, 0, 1), // iterate Z, sweep XY
+        }
+    }
+
+    /// Returns the face on the other side of a cube.
+    #[must_use]
+    pub const fn opposite(self) -> Self {
+        match self {
+            Self::PosX => Self::NegX,
+            Self::NegX => Self::PosX,
+            Self::PosY => Self::NegY,
+            Self::NegY => Self::PosY,
+            Self::PosZ => Self::NegZ,
+            Self::NegZ => Self::PosZ,
+        }
+    }
+
+    /// Returns true for the faces looking along a positive axis.
+    const fn is_positive(self) -> bool {
+        matches!(self, Self::PosX | Self::PosY | Self::PosZ)
+    }
+
+    /// Order a quad's corners `(0, 0)`, `(1, 0)`, `(1, 1)`, `(0, 1)` are
+    /// emitted in, so they wind counter-clockwise seen from the front.
+    const fn corner_order(self) -> [usize; 4] {
+        if self.is_positive() {
+            [0, 1, 2, 3]
+        } else {
+            [0, 3, 2, 1]
+        }
+    }
+
+    /// Indices of a quad's two triangles into its four emitted vertices.
+    /// `flip` splits the quad along the other diagonal.
+    ///
+    /// X and Y faces use one winding, Z faces use the opposite.
+    const fn quad_indices(self, flip: bool) -> [u32; 6] {
+        match (self, flip) {
+            (Self::PosX | Self::NegX | Self::PosY | Self::NegY, true) => [0, 3, 1, 1, 3, 2],
+            (Self::PosZ | Self::NegZ, true) => [0, 1, 3, 1, 2, 3],
+            (Self::PosX | Self::NegX | Self::PosY | Self::NegY, false) => [0, 2, 1, 0, 3, 2],
+            (Self::PosZ | Self::NegZ, false) => [0, 1, 2, 0, 2, 3],
+        }
+    }
+
     /// Returns all six faces.
     pub const ALL: [Face; 6] = [
         Self::PosX,
@@ -138,6 +244,51 @@ impl Face {
     ];
 }
 
+/// A problem found by [`ChunkMesh::validate`].
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum MeshError {
+    /// The index count isn't a multiple of three.
+    #[error("{index_count} indices don't form whole triangles")]
+    IncompleteTriangle {
+        /// Number of indices in the mesh.
+        index_count: usize,
+    },
+
+    /// A triangle refers to a vertex that doesn't exist.
+    #[error("triangle {triangle}: index {index} is out of range for {vertex_count} vertices")]
+    IndexOutOfRange {
+        /// Triangle number.
+        triangle: usize,
+        /// The offending index.
+        index: u32,
+        /// Number of vertices in the mesh.
+        vertex_count: usize,
+    },
+
+    /// A vertex position is NaN or infinite.
+    #[error("vertex {vertex} has a non-finite position {position:?}")]
+    NonFinitePosition {
+        /// Vertex number.
+        vertex: usize,
+        /// The bad position.
+        position: [f32; 3],
+    },
+
+    /// A triangle's winding faces away from its stored normal, so it would
+    /// be back-face culled when seen from the front.
+    #[error(
+        "triangle {triangle}: winding normal {winding:?} disagrees with stored normal {normal:?}"
+    )]
+    WrongWinding {
+        /// Triangle number.
+        triangle: usize,
+        /// Normal implied by the counter-clockwise winding (unnormalized).
+        winding: [f32; 3],
+        /// Normal stored on the triangle's first vertex.
+        normal: [f32; 3],
+    },
+}
+
 /// Generated mesh data for a chunk.
 pub struct ChunkMesh {
     /// Vertex data.
@@ -156,6 +307,15 @@ impl ChunkMesh {
         }
     }
 
+    /// Creates an empty mesh with room for the given number of vertices and indices.
+    #[must_use]
+    pub fn with_capacity(vertices: usize, indices: usize) -> Self {
+        Self {
+            vertices: Vec::with_capacity(vertices),
+            indices: Vec::with_capacity(indices),
+        }
+    }
+
     /// Returns true if the mesh is empty.
     #[must_use]
     pub fn is_empty(&self) -> bool {
@@ -167,6 +327,96 @@ impl ChunkMesh {
     pub fn triangle_count(&self) -> usize {
         self.indices.len() / 3
     }
+
+    /// Checks that the mesh is well formed: indices form whole triangles and
+    /// stay in range, positions are finite, and every triangle's
+    /// counter-clockwise winding agrees with its stored normal.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`MeshError`] describing the first problem found.
+    pub fn validate(&self) -> Result<(), MeshError> {
+        if !self.indices.len().is_multiple_of(3) {
+            return Err(MeshError::IncompleteTriangle {
+                index_count: self.indices.len(),
+            });
+        }
+
+        if let Some((vertex, v)) = self
+            .vertices
+            .iter()
+            .enumerate()
+            .find(|(_, v)| !Vec3::from_array(v.position).is_finite())
+        {
+            return Err(MeshError::NonFinitePosition {
+                vertex,
+                position: v.position,
+            });
+        }
+
+        for (triangle, indices) in self.indices.chunks_exact(3).enumerate() {
+            let vertex = |index: u32| {
+                usize::try_from(index)
+                    .ok()
+                    .and_then(|i| self.vertices.get(i))
+                    .ok_or(MeshError::IndexOutOfRange {
+                        triangle,
+                        index,
+                        vertex_count: self.vertices.len(),
+                    })
+            };
+            let first = vertex(indices[0])?;
+            let v0 = Vec3::from_array(first.position);
+            let v1 = Vec3::from_array(vertex(indices[1])?.position);
+            let v2 = Vec3::from_array(vertex(indices[2])?.position);
+
+            let winding = (v1 - v0).cross(v2 - v0);
+            let normal = first.normal;
+            if winding.dot(Vec3::from_array(normal)) <= 0.0 {
+                return Err(MeshError::WrongWinding {
+                    triangle,
+                    winding: winding.to_array(),
+                    normal,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Merges identical vertices into one, so triangles share them through
+    /// the index buffer. Vertices keep the order they're first used in.
+    #[allow(clippy::cast_possible_truncation)]
+    fn dedup_vertices(&mut self) {
+        let mut shared: HashMap<[u32; VERTEX_WORDS], u32> = HashMap::new();
+        let mut vertices = Vec::with_capacity(self.vertices.len());
+        let remap: Vec<u32> = self
+            .vertices
+            .iter()
+            .map(|&vertex| {
+                *shared.entry(bytemuck::cast(vertex)).or_insert_with(|| {
+                    vertices.push(vertex);
+                    vertices.len() as u32 - 1
+                })
+            })
+            .collect();
+        for index in &mut self.indices {
+            *index = remap[*index as usize];
+        }
+        self.vertices = vertices;
+    }
+
+    /// Appends another mesh, offsetting its indices past this mesh's vertices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the combined mesh has more vertices than `u32` indices can
+    /// address.
+    pub fn append(&mut self, other: &Self) {
+        let base = u32::try_from(self.vertices.len()).expect("mesh exceeds u32 indices");
+        self.vertices.extend_from_slice(&other.vertices);
+        self.indices.extend(other.indices.iter().map(|i| i + base));
+    }
 }
 
 impl Default for ChunkMesh {
@@ -175,17 +425,143 @@ impl Default for ChunkMesh {
     }
 }
 
+/// One of the render passes a chunk mesh is split into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MeshLayer {
+    /// Opaque and cutout geometry.
+    Opaque,
+    /// Translucent geometry.
+    Transparent,
+}
+
+impl MeshLayer {
+    /// Both layers, in draw order.
+    pub const ALL: [Self; 2] = [Self::Opaque, Self::Transparent];
+}
+
+impl fmt::Display for MeshLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Opaque => "opaque",
+            Self::Transparent => "transparent",
+        })
+    }
+}
+
+/// Chunk mesh split by render pass.
+#[derive(Default)]
+pub struct MeshLayers {
+    /// Opaque and cutout geometry (drawn first, writes depth).
+    pub opaque: ChunkMesh,
+    /// Translucent geometry like glass and water (alpha blended, drawn last).
+    pub transparent: ChunkMesh,
+}
+
+impl MeshLayers {
+    /// Returns true if both layers are empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.opaque.is_empty() && self.transparent.is_empty()
+    }
+
+    /// Returns the mesh of one layer.
+    #[must_use]
+    pub const fn layer(&self, layer: MeshLayer) -> &ChunkMesh {
+        match layer {
+            MeshLayer::Opaque => &self.opaque,
+            MeshLayer::Transparent => &self.transparent,
+        }
+    }
+
+    /// Returns the mesh layer a block's faces belong to.
+    fn layer_mut(&mut self, block: Block) -> &mut ChunkMesh {
+        if block.is_translucent() {
+            &mut self.transparent
+        } else {
+            &mut self.opaque
+        }
+    }
+}
+
+/// A rectangle of identical block faces merged by greedy meshing.
+///
+/// The quad spans `width` blocks along the face's first in-plane axis and
+/// `height` blocks along the second: X and Z for top and bottom faces, Z and
+/// Y for east and west faces, X and Y for south and north faces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GreedyQuad {
+    /// Direction the quad faces.
+    pub face: Face,
+    /// Chunk-local position of the block at the quad's minimum corner.
+    pub origin: [usize; 3],
+    /// Size in blocks along the first in-plane axis.
+    pub width: usize,
+    /// Size in blocks along the second in-plane axis.
+    pub height: usize,
+    /// Block the faces belong to.
+    pub block: Block,
+    /// Ambient occlusion level at each corner, from 0 (darkest) to 3
+    /// (unoccluded), in the order `(0, 0)`, `(width, 0)`,
+    /// `(width, height)`, `(0, height)`.
+    pub ao: [u8; 4],
+    /// Block light level in front of the faces, from 0 to [`MAX_LIGHT`].
+    pub light: u8,
+    /// Sky light level in front of the faces: [`MAX_LIGHT`] in the open, or
+    /// [`COVERED_SKY_LIGHT`] below the heightmap.
+    pub sky_light: u8,
+    /// Flow level of liquid with no liquid above it, whose top edge is
+    /// lowered to its [surface](super::fluid::surface_height); `None` for
+    /// full-height faces.
+    pub liquid_level: Option<u8>,
+}
+
+/// Vertex brightness for each ambient occlusion level.
+const AO_BRIGHTNESS: [f32; 4] = [0.4, 0.6, 0.8, 1.0];
+
 /// Face mask entry for greedy meshing.
 /// Stores the block type and whether it's been processed.
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
 struct FaceMask {
     block: Option<Block>,
+    /// Ambient occlusion level at each corner of the face.
+    ao: [u8; 4],
+    /// Block light level in front of the face.
+    light: u8,
+    /// Sky light level in front of the face.
+    sky_light: u8,
+    /// Flow level of an open liquid surface.
+    liquid_level: Option<u8>,
+}
+
+impl FaceMask {
+    /// Returns true if every corner has the same occlusion, so the face can
+    /// merge with its neighbors without changing the shading.
+    fn uniform_ao(self) -> bool {
+        self.ao.iter().all(|&level| level == self.ao[0])
+    }
 }
 
 /// Greedy mesh generator - optimized mesh generation.
 pub struct MeshGenerator {
     chunk: Chunk,
     world_offset: [f32; 3],
+    /// Block colors replacing the built-in ones.
+    colors: ColorOverrides,
+    /// Block light of the chunk, lighting the faces next to lit blocks.
+    light: LightMap,
+    /// Column heights of the chunk, shading faces under cover.
+    heightmap: Heightmap,
+    /// The facing edge of each neighbor chunk, in
+    /// [`ChunkPos::neighbors`](super::ChunkPos::neighbors) order; `None` where the neighbor isn't loaded.
+    neighbor_edges: [Option<Vec<Block>>; 4],
+    /// Level of detail: the size in blocks of the cells the chunk was
+    /// coarsened into, 1 for full detail.
+    lod: usize,
+    /// Biome tint at each column corner of the chunk, indexed by
+    /// `x + z * (SECTION_SIZE + 1)`; `None` leaves blocks untinted.
+    biome_tints: Option<Vec<[f32; 3]>>,
+    /// Whether quads share their identical vertices.
+    dedup_vertices: bool,
 }
 
 impl MeshGenerator {
@@ -194,128 +570,471 @@ impl MeshGenerator {
     pub fn new(chunk: Chunk) -> Self {
         let (ox, oz) = chunk.position().block_origin();
         Self {
+            light: LightMap::compute(&chunk),
+            heightmap: chunk.heightmap(),
             chunk,
             world_offset: [ox as f32, 0.0, oz as f32],
+            colors: ColorOverrides::new(),
+            neighbor_edges: [None, None, None, None],
+            lod: 1,
+            biome_tints: None,
+            dedup_vertices: false,
+        }
+    }
+
+    /// Hides faces on the chunk border that are covered by a neighbor chunk.
+    ///
+    /// `neighbors` are the chunks at
+    /// [`ChunkPos::neighbors`](super::ChunkPos::neighbors), in that order.
+    /// Faces towards a missing (unloaded) neighbor stay visible.
+    #[must_use]
+    pub fn with_neighbors(mut self, neighbors: [Option<&Chunk>; 4]) -> Self {
+        let last = SECTION_SIZE - 1;
+        let height = self.chunk.height();
+        for (side, neighbor) in neighbors.into_iter().enumerate() {
+            self.neighbor_edges[side] = neighbor.map(|chunk| {
+                // Edge blocks indexed by `i + y * SECTION_SIZE`, where `i`
+                // runs along the shared border. Above a shorter neighbor is air.
+                let mut edge = Vec::with_capacity(SECTION_SIZE * height);
+                for y in 0..height {
+                    for i in 0..SECTION_SIZE {
+                        let (x, z) = match side {
+                            0 => (0, i),
+                            1 => (last, i),
+                            2 => (i, 0),
+                            _ => (i, last),
+                        };
+                        edge.push(chunk.try_get_block(x, y, z).unwrap_or(Block::Air));
+                    }
+                }
+                edge
+            });
+        }
+        self
+    }
+
+    /// Meshes the chunk at a lower level of detail, for distant chunks.
+    ///
+    /// Each `lod`-sized cube of blocks is merged into one cell of a single
+    /// representative block, so greedy meshing emits far fewer, larger
+    /// quads. `lod` is rounded up to a power of two, at most
+    /// `SECTION_SIZE`; 1 keeps full detail. Coarse meshes ignore neighbor
+    /// chunks and keep their border faces, so seams between detail levels
+    /// don't open holes.
+    #[must_use]
+    pub fn with_lod(mut self, lod: usize) -> Self {
+        let lod = lod.clamp(1, SECTION_SIZE).next_power_of_two();
+        if lod > 1 {
+            self.chunk = coarsen(&self.chunk, lod);
+            self.light = LightMap::compute(&self.chunk);
+            self.heightmap = self.chunk.heightmap();
+        }
+        self.lod = lod;
+        self
+    }
+
+    /// Colors vertices with `overrides` in place of the built-in block colors.
+    #[must_use]
+    pub fn with_color_overrides(mut self, overrides: &ColorOverrides) -> Self {
+        self.colors = overrides.clone();
+        self
+    }
+
+    /// Tints grass and leaves with the biomes `terrain` generates around
+    /// the chunk.
+    ///
+    /// Each vertex takes the average tint of the four columns meeting at
+    /// its corner, so the color blends across biome borders.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub fn with_biomes(mut self, terrain: &TerrainGenerator) -> Self {
+        let (ox, oz) = self.chunk.position().block_origin();
+        let corners = SECTION_SIZE as i32 + 1;
+        let column = |x, z| terrain.biome_at(ox + x, oz + z).tint();
+
+        let mut tints = Vec::with_capacity((SECTION_SIZE + 1) * (SECTION_SIZE + 1));
+        for z in 0..corners {
+            for x in 0..corners {
+                let around = [
+                    column(x - 1, z - 1),
+                    column(x, z - 1),
+                    column(x - 1, z),
+                    column(x, z),
+                ];
+                tints.push(std::array::from_fn(|i| {
+                    around.iter().map(|tint| tint[i]).sum::<f32>() / 4.0
+                }));
+            }
         }
+        self.biome_tints = Some(tints);
+        self
+    }
+
+    /// Shares vertices between quads that meet with identical attributes,
+    /// such as the pieces of a flat surface greedy meshing couldn't merge
+    /// into one rectangle, shrinking the vertex buffer.
+    ///
+    /// Texture coordinates are aligned to the chunk rather than to each
+    /// quad, so neighboring quads agree at their shared corners; textures
+    /// tile the same either way. Off by default, since hashing every vertex
+    /// makes meshing slower.
+    #[must_use]
+    pub const fn with_vertex_dedup(mut self, dedup: bool) -> Self {
+        self.dedup_vertices = dedup;
+        self
+    }
+
+    /// Returns `color` with the biome tint at a world space `corner`
+    /// applied, if `block` is tinted.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn tinted(&self, block: Block, color: [f32; 3], corner: [f32; 3]) -> [f32; 3] {
+        let x = (corner[0] - self.world_offset[0]) as usize;
+        let z = (corner[2] - self.world_offset[2]) as usize;
+        self.biome_tints
+            .as_ref()
+            .filter(|_| Biome::is_tinted(block))
+            .and_then(|tints| tints.get(x + z * (SECTION_SIZE + 1)))
+            .map_or(color, |tint| std::array::from_fn(|c| color[c] * tint[c]))
     }
 
     /// Generates the mesh using greedy meshing algorithm.
+    ///
+    /// Opaque and translucent geometry are combined into a single mesh. Use
+    /// [`generate_layers`](Self::generate_layers) to render them in separate passes.
     #[must_use]
     pub fn generate(self) -> ChunkMesh {
-        let mut mesh = ChunkMesh::new();
+        let mut layers = self.generate_layers();
+        layers.opaque.append(&layers.transparent);
+        layers.opaque
+    }
+
+    /// Generates the mesh split into opaque and transparent layers.
+    #[must_use]
+    pub fn generate_layers(self) -> MeshLayers {
+        let quad_estimate = self.estimate_quad_count();
+        let mut layers = MeshLayers {
+            opaque: ChunkMesh::with_capacity(quad_estimate * 4, quad_estimate * 6),
+            transparent: ChunkMesh::new(),
+        };
+
+        for quad in self.quads() {
+            // Each quad goes in the layer matching the block's render pass
+            self.add_greedy_quad(layers.layer_mut(quad.block), &quad);
+        }
+        self.add_shaped_blocks(&mut layers);
+        if self.dedup_vertices {
+            layers.opaque.dedup_vertices();
+            layers.transparent.dedup_vertices();
+        }
+
+        layers
+    }
+
+    /// Returns the merged quads without expanding them into vertices.
+    ///
+    /// Useful for building a custom vertex format or collision geometry.
+    /// Only full cubes are merged into quads; blocks with another
+    /// [shape](Block::shape) aren't included.
+    /// Quads are grouped by face: top, bottom, east, west, south, north.
+    #[must_use]
+    pub fn quads(&self) -> Vec<GreedyQuad> {
+        let mut quads = Vec::with_capacity(self.estimate_quad_count());
+
+        // Interior blocks are found once and skipped by every face pass
+        let interior = self.interior_blocks();
 
         // Process each face direction
-        self.generate_faces(&mut mesh, Face::PosY); // Top
-        self.generate_faces(&mut mesh, Face::NegY); // Bottom
-        self.generate_faces(&mut mesh, Face::PosX); // East
-        self.generate_faces(&mut mesh, Face::NegX); // West
-        self.generate_faces(&mut mesh, Face::PosZ); // South
-        self.generate_faces(&mut mesh, Face::NegZ); // North
+        self.generate_faces(&mut quads, &interior, Face::PosY); // Top
+        self.generate_faces(&mut quads, &interior, Face::NegY); // Bottom
+        self.generate_faces(&mut quads, &interior, Face::PosX); // East
+        self.generate_faces(&mut quads, &interior, Face::NegX); // West
+        self.generate_faces(&mut quads, &interior, Face::PosZ); // South
+        self.generate_faces(&mut quads, &interior, Face::NegZ); // North
+
+        quads
+    }
+
+    /// Estimates how many quads the mesh will need, to pre-reserve buffers.
+    ///
+    /// A full section exposes at most `6 * 16 * 16` unit faces on its surface,
+    /// so each section contributes proportionally to its solid block count.
+    /// Greedy merging usually produces far fewer quads than this.
+    fn estimate_quad_count(&self) -> usize {
+        (0..self.chunk.section_count())
+            .filter_map(|y| self.chunk.get_section(y))
+            .map(|section| section.solid_count() as usize * 6 / SECTION_SIZE)
+            .sum()
+    }
+
+    /// Returns whether each section of the chunk holds any non-air block,
+    /// bottom first, and the height just above the topmost such section.
+    ///
+    /// Air-only sections have no faces, so meshing skips them outright.
+    fn solid_sections(&self) -> (Vec<bool>, usize) {
+        let solid: Vec<bool> = (0..self.chunk.section_count())
+            .map(|y| {
+                self.chunk
+                    .get_section(y)
+                    .is_some_and(|section| !section.is_empty())
+            })
+            .collect();
+        let top = solid
+            .iter()
+            .rposition(|&solid| solid)
+            .map_or(0, |y| (y + 1) * SECTION_SIZE);
+        (solid, top)
+    }
+
+    /// Index of a block in the mask returned by [`interior_blocks`](Self::interior_blocks).
+    const fn interior_index(x: usize, y: usize, z: usize) -> usize {
+        x + z * SECTION_SIZE + y * SECTION_SIZE * SECTION_SIZE
+    }
+
+    /// Flags non-air blocks whose six neighbors are all opaque.
+    ///
+    /// Such blocks have no visible face, so the face passes can skip them
+    /// without checking neighbors. Blocks on the chunk border are never
+    /// interior, since border faces are always emitted.
+    fn interior_blocks(&self) -> Vec<bool> {
+        let height = self.chunk.height();
+        let mut interior = vec![false; SECTION_SIZE * SECTION_SIZE * height];
+        let opaque = |x: usize, y: usize, z: usize| self.chunk.get_block(x, y, z).occludes();
+        let (solid, top) = self.solid_sections();
+
+        for y in 1..top.min(height - 1) {
+            if !solid[y / SECTION_SIZE] {
+                continue; // All air
+            }
+            for z in 1..SECTION_SIZE - 1 {
+                for x in 1..SECTION_SIZE - 1 {
+                    if self.chunk.get_block(x, y, z).is_air() {
+                        continue;
+                    }
+                    let opaque_neighbors = [
+                        opaque(x + 1, y, z),
+                        opaque(x - 1, y, z),
+                        opaque(x, y + 1, z),
+                        opaque(x, y - 1, z),
+                        opaque(x, y, z + 1),
+                        opaque(x, y, z - 1),
+                    ]
+                    .into_iter()
+                    .filter(|&o| o)
+                    .count();
+                    interior[Self::interior_index(x, y, z)] = opaque_neighbors == 6;
+                }
+            }
+        }
 
-        mesh
+        interior
     }
 
     /// Generates faces for one direction using greedy meshing.
-    fn generate_faces(&self, mesh: &mut ChunkMesh, face: Face) {
+    ///
+    /// Each slice's mask spans the whole chunk along both face axes (16 for
+    /// X/Z, up to the topmost non-empty section for Y), so quads merge
+    /// across section boundaries and only stop at the chunk's edges.
+    /// Air-only sections are skipped, and slices without a visible face
+    /// aren't merged.
+    fn generate_faces(&self, quads: &mut Vec<GreedyQuad>, interior: &[bool], face: Face) {
         // Determine axis and iteration order based on face
-        let (axis, u_axis, v_axis, positive) = match face {
-            Face::PosY => (1, 0, 2, true),  // Y+: iterate Y, sweep XZ
-            Face::NegY => (1, 0, 2, false), // Y-: iterate Y, sweep XZ
-            Face::PosX => (0, 2, 1, true),  // X+: iterate X, sweep ZY
-            Face::NegX => (0, 2, 1, false), // X-: iterate X, sweep ZY
-            Face::PosZ => (2, 0, 1, true),  // Z+: iterate Z, sweep XY
-            Face::NegZ => (2, 0, 1, false), // Z-: iterate Z, sweep XY
-        };
+        let (axis, u_axis, v_axis) = face.sweep_axes();
+        let positive = matches!(face, Face::PosX | Face::PosY | Face::PosZ);
 
-        let axis_size = if axis == 1 {
-            CHUNK_HEIGHT
-        } else {
-            SECTION_SIZE
-        };
-        let u_size = if u_axis == 1 {
-            CHUNK_HEIGHT
-        } else {
-            SECTION_SIZE
-        };
-        let v_size = if v_axis == 1 {
-            CHUNK_HEIGHT
-        } else {
-            SECTION_SIZE
-        };
+        // Nothing above the topmost solid section has a face
+        let (solid, top) = self.solid_sections();
+        let size = |axis| if axis == 1 { top } else { SECTION_SIZE };
+        let (axis_size, u_size, v_size) = (size(axis), size(u_axis), size(v_axis));
+        let in_air = |axis, coord: usize| axis == 1 && !solid[coord / SECTION_SIZE];
 
         // For each slice along the axis
         for d in 0..axis_size {
+            if in_air(axis, d) {
+                continue;
+            }
+
             // Build face mask for this slice
             let mut mask = vec![FaceMask::default(); u_size * v_size];
+            let mut any_visible = false;
 
             for v in 0..v_size {
+                if in_air(v_axis, v) {
+                    continue;
+                }
                 for u in 0..u_size {
                     let mut pos = [0usize; 3];
                     pos[axis] = d;
                     pos[u_axis] = u;
                     pos[v_axis] = v;
 
+                    // Fully enclosed blocks have no visible faces
+                    if interior[Self::interior_index(pos[0], pos[1], pos[2])] {
+                        continue;
+                    }
+
                     let block = self.chunk.get_block(pos[0], pos[1], pos[2]);
 
-                    // Skip air blocks
-                    if block.is_air() {
+                    // Skip air, and shaped blocks meshed on their own
+                    if block.is_air() || !block.shape().is_full_cube() {
                         continue;
                     }
 
-                    // Check if face is visible (neighbor is transparent)
-                    let neighbor_pos = if positive {
-                        if d + 1 >= axis_size {
-                            // Chunk boundary - face is visible
-                            None
-                        } else {
-                            let mut np = pos;
-                            np[axis] = d + 1;
-                            Some(np)
-                        }
-                    } else if d == 0 {
-                        // Chunk boundary - face is visible
-                        None
-                    } else {
-                        let mut np = pos;
-                        np[axis] = d - 1;
-                        Some(np)
-                    };
-
-                    let face_visible = match neighbor_pos {
-                        None => true, // Chunk boundary
-                        Some(np) => {
-                            let neighbor = self.chunk.get_block(np[0], np[1], np[2]);
-                            neighbor.is_transparent()
-                        }
-                    };
+                    // Check if face is visible (neighbor is transparent).
+                    // There's no neighbor in this chunk on its boundary
+                    let mut np = pos;
+                    np[axis] = if positive { d + 1 } else { d.wrapping_sub(1) };
+                    let neighbor = self.chunk.try_get_block(np[0], np[1], np[2]);
+                    let neighbor_pos = neighbor.map(|_| np);
+
+                    // On the chunk boundary, look across into the neighbor chunk
+                    let face_visible = neighbor
+                        .or_else(|| self.border_neighbor(pos, face))
+                        .is_none_or(|neighbor| !Self::hides(neighbor, face));
 
                     if face_visible {
-                        mask[u + v * u_size] = FaceMask { block: Some(block) };
+                        any_visible = true;
+                        mask[u + v * u_size] = FaceMask {
+                            block: Some(block),
+                            ao: self.face_ao(pos, face),
+                            light: neighbor_pos.map_or(0, |np| self.light.get(np[0], np[1], np[2])),
+                            sky_light: self.sky_light(neighbor_pos),
+                            liquid_level: self.liquid_level(block, pos, face),
+                        };
                     }
                 }
             }
 
-            // Greedy merge and generate quads
-            self.greedy_merge(
-                mesh, &mut mask, u_size, v_size, d, face, u_axis, v_axis, axis,
-            );
+            // Greedy merge into quads
+            if any_visible {
+                Self::greedy_merge(quads, &mut mask, u_size, v_size, d, face);
+            }
+        }
+    }
+
+    /// Returns true if `neighbor`, lying beyond a block's `face`, hides that
+    /// face: it's opaque and its shape fills the side facing the block.
+    fn hides(neighbor: Block, face: Face) -> bool {
+        !neighbor.is_transparent() && neighbor.shape().covers(face.opposite())
+    }
+
+    /// Returns the flow level of `block` at `pos` if it's a liquid with no
+    /// liquid above, so `face` reaches up to its lowered surface. Bottom
+    /// faces aren't lowered.
+    fn liquid_level(&self, block: Block, [x, y, z]: [usize; 3], face: Face) -> Option<u8> {
+        let covered = self
+            .chunk
+            .try_get_block(x, y + 1, z)
+            .is_some_and(|above| above.properties().is_liquid);
+        (block.properties().is_liquid && !covered && face != Face::NegY)
+            .then(|| self.chunk.get_metadata(x, y, z))
+    }
+
+    /// Returns the sky light in front of a face: open sky above the column's
+    /// heightmap, cover below it. Faces looking out of the chunk count as
+    /// open.
+    fn sky_light(&self, front: Option<[usize; 3]>) -> u8 {
+        match front {
+            Some([x, y, z]) if y <= usize::from(self.heightmap[x][z]) => COVERED_SKY_LIGHT,
+            _ => MAX_LIGHT,
+        }
+    }
+
+    /// Returns the block in a neighbor chunk that a border face looks out
+    /// onto, or `None` if the neighbor isn't loaded or the face is on the top
+    /// or bottom of the world.
+    fn border_neighbor(&self, pos: [usize; 3], face: Face) -> Option<Block> {
+        if self.lod > 1 {
+            return None;
+        }
+        let (side, i) = match face {
+            Face::PosX => (0, pos[2]),
+            Face::NegX => (1, pos[2]),
+            Face::PosZ => (2, pos[0]),
+            Face::NegZ => (3, pos[0]),
+            Face::PosY | Face::NegY => return None,
+        };
+        self.neighbor_edges[side]
+            .as_ref()
+            .map(|edge| edge[i + pos[1] * SECTION_SIZE])
+    }
+
+    /// Returns the block at a chunk-local position, looking one block into
+    /// loaded neighbor chunks. Other positions outside the chunk are `None`.
+    fn block_at(&self, pos: [i32; 3]) -> Option<Block> {
+        let coord = |c: i32, size: usize| usize::try_from(c).ok().filter(|&c| c < size);
+        let y = coord(pos[1], self.chunk.height())?;
+        match (coord(pos[0], SECTION_SIZE), coord(pos[2], SECTION_SIZE)) {
+            (Some(x), Some(z)) => self.chunk.try_get_block(x, y, z),
+            (None, Some(z)) => {
+                let face = if pos[0] < 0 { Face::NegX } else { Face::PosX };
+                let edge_x = if pos[0] < 0 { 0 } else { SECTION_SIZE - 1 };
+                (pos[0] == -1 || usize::try_from(pos[0]) == Ok(SECTION_SIZE))
+                    .then(|| self.border_neighbor([edge_x, y, z], face))
+                    .flatten()
+            }
+            (Some(x), None) => {
+                let face = if pos[2] < 0 { Face::NegZ } else { Face::PosZ };
+                let edge_z = if pos[2] < 0 { 0 } else { SECTION_SIZE - 1 };
+                (pos[2] == -1 || usize::try_from(pos[2]) == Ok(SECTION_SIZE))
+                    .then(|| self.border_neighbor([x, y, edge_z], face))
+                    .flatten()
+            }
+            (None, None) => None,
         }
     }
 
-    /// Performs greedy merging on the mask and generates quads.
-    #[allow(clippy::too_many_arguments)]
+    /// Returns true if the block at a chunk-local position darkens the
+    /// corners next to it. Unknown blocks outside the chunk never do.
+    fn occludes(&self, pos: [i32; 3]) -> bool {
+        self.block_at(pos).is_some_and(Block::occludes)
+    }
+
+    /// Computes the ambient occlusion level of each corner of a block face.
+    ///
+    /// Each corner looks at the two blocks beside it and the one diagonal to
+    /// it, in the layer the face looks out into. Two occluding sides darken
+    /// the corner fully, whatever the diagonal is.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn face_ao(&self, pos: [usize; 3], face: Face) -> [u8; 4] {
+        let (axis, u_axis, v_axis) = face.sweep_axes();
+        let mut front = pos.map(|coord| coord as i32);
+        front[axis] += if matches!(face, Face::PosX | Face::PosY | Face::PosZ) {
+            1
+        } else {
+            -1
+        };
+        let offset = |du: i32, dv: i32| {
+            let mut sample = front;
+            sample[u_axis] += du;
+            sample[v_axis] += dv;
+            self.occludes(sample)
+        };
+
+        [(-1, -1), (1, -1), (1, 1), (-1, 1)].map(|(du, dv)| {
+            let side_u = offset(du, 0);
+            let side_v = offset(0, dv);
+            if side_u && side_v {
+                0
+            } else {
+                3 - u8::from(side_u) - u8::from(side_v) - u8::from(offset(du, dv))
+            }
+        })
+    }
+
+    /// Performs greedy merging on the mask and collects the quads.
+    ///
+    /// Faces only merge when their blocks and corner occlusion match, and
+    /// faces with uneven occlusion are kept as single quads so each corner
+    /// keeps its own shading.
     fn greedy_merge(
-        &self,
-        mesh: &mut ChunkMesh,
+        quads: &mut Vec<GreedyQuad>,
         mask: &mut [FaceMask],
         u_size: usize,
         v_size: usize,
         d: usize,
         face: Face,
-        u_axis: usize,
-        v_axis: usize,
-        axis: usize,
     ) {
+        let (axis, u_axis, v_axis) = face.sweep_axes();
         for v in 0..v_size {
             let mut u = 0;
             while u < u_size {
@@ -328,12 +1047,13 @@ impl MeshGenerator {
                 }
 
                 let block = current.block.unwrap();
+                let mergeable = current.uniform_ao();
 
                 // Find width (how far we can extend in U direction)
                 let mut width = 1;
-                while u + width < u_size {
+                while mergeable && u + width < u_size {
                     let next = mask[u + width + v * u_size];
-                    if next.block != current.block {
+                    if next != current {
                         break;
                     }
                     width += 1;
@@ -341,10 +1061,10 @@ impl MeshGenerator {
 
                 // Find height (how far we can extend in V direction)
                 let mut height = 1;
-                'height: while v + height < v_size {
+                'height: while mergeable && v + height < v_size {
                     for w in 0..width {
                         let next = mask[u + w + (v + height) * u_size];
-                        if next.block != current.block {
+                        if next != current {
                             break 'height;
                         }
                     }
@@ -358,51 +1078,191 @@ impl MeshGenerator {
                     }
                 }
 
-                // Generate quad
-                self.add_greedy_quad(
-                    mesh, d, u, v, width, height, face, u_axis, v_axis, axis, block,
-                );
+                let mut origin = [0usize; 3];
+                origin[axis] = d;
+                origin[u_axis] = u;
+                origin[v_axis] = v;
+                quads.push(GreedyQuad {
+                    face,
+                    origin,
+                    width,
+                    height,
+                    block,
+                    ao: current.ao,
+                    light: current.light,
+                    sky_light: current.sky_light,
+                    liquid_level: current.liquid_level,
+                });
 
                 u += width;
             }
         }
     }
 
-    /// Adds a quad from greedy meshing.
-    #[allow(clippy::too_many_arguments)]
-    fn add_greedy_quad(
+    /// Adds the faces of blocks that don't fill their cell, such as slabs,
+    /// box by box. These aren't merged with their neighbors.
+    fn add_shaped_blocks(&self, layers: &mut MeshLayers) {
+        for (x, y, z, block) in self.chunk.iter_solid() {
+            let shape = block.shape();
+            if shape.is_full_cube() {
+                continue;
+            }
+            for part in shape.boxes() {
+                for face in Face::ALL {
+                    if !self.box_face_hidden(shape, part, [x, y, z], face) {
+                        self.add_box_face(layers.layer_mut(block), block, [x, y, z], part, face);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns true if `face` of `part`, one of the boxes of a block of
+    /// `shape` at `pos`, can't be seen. A face on the side of the cell is
+    /// hidden by a neighbor covering that side; one inside the cell, by
+    /// another box of the shape lying against all of it.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    fn box_face_hidden(&self, shape: BlockShape, part: &Aabb, pos: [usize; 3], face: Face) -> bool {
+        let (axis, u_axis, v_axis) = face.sweep_axes();
+        let plane = |b: &Aabb, positive| if positive { b.max[axis] } else { b.min[axis] };
+        let at = plane(part, face.is_positive());
+        let side = if face.is_positive() { 1.0 } else { 0.0 };
+
+        if (at - side).abs() < f32::EPSILON {
+            let mut front = pos.map(|coord| coord as i32);
+            front[axis] += if face.is_positive() { 1 } else { -1 };
+            return self
+                .block_at(front)
+                .is_some_and(|neighbor| Self::hides(neighbor, face));
+        }
+
+        shape.boxes().iter().any(|other| {
+            let spans = |a: usize| other.min[a] <= part.min[a] && other.max[a] >= part.max[a];
+            (plane(other, !face.is_positive()) - at).abs() < f32::EPSILON
+                && spans(u_axis)
+                && spans(v_axis)
+        })
+    }
+
+    /// Adds one face of `part`, a box of the block at `pos`, lit by the
+    /// block it looks out onto. Shaped faces get no ambient occlusion.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+    fn add_box_face(
         &self,
         mesh: &mut ChunkMesh,
-        d: usize,
-        u: usize,
-        v: usize,
-        width: usize,
-        height: usize,
-        face: Face,
-        u_axis: usize,
-        v_axis: usize,
-        axis: usize,
         block: Block,
+        pos: [usize; 3],
+        part: &Aabb,
+        face: Face,
     ) {
+        let (axis, u_axis, v_axis) = face.sweep_axes();
+        let base_idx = mesh.vertices.len() as u32;
+        let color = self.colors.color(block);
+        let (atlas_u, atlas_v, _, _) = TextureAtlas::block_face_uvs(block, face);
+        let frames = block.def().frames;
+
+        let mut front = pos;
+        front[axis] = if face.is_positive() {
+            pos[axis] + 1
+        } else {
+            pos[axis].wrapping_sub(1)
+        };
+        let front = self
+            .chunk
+            .try_get_block(front[0], front[1], front[2])
+            .map(|_| front);
+        let light = [
+            front.map_or(0, |[x, y, z]| self.light.get(x, y, z)),
+            self.sky_light(front),
+        ]
+        .map(|level| f32::from(level) / f32::from(MAX_LIGHT));
+
+        // Corners (0, 0), (1, 0), (1, 1), (0, 1) of the face, in the block
+        let uv = [(false, false), (true, false), (true, true), (false, true)].map(|(u, v)| {
+            let pick = |a: usize, far: bool| if far { part.max[a] } else { part.min[a] };
+            [pick(u_axis, u), pick(v_axis, v)]
+        });
+        for i in face.corner_order() {
+            let mut corner = [0.0; 3];
+            corner[axis] = if face.is_positive() {
+                part.max[axis]
+            } else {
+                part.min[axis]
+            };
+            corner[u_axis] = uv[i][0];
+            corner[v_axis] = uv[i][1];
+            let corner: [f32; 3] =
+                std::array::from_fn(|a| corner[a] + pos[a] as f32 + self.world_offset[a]);
+            mesh.vertices.push(
+                ChunkVertex::new(
+                    corner,
+                    face.normal(),
+                    self.tinted(block, color, corner),
+                    AO_BRIGHTNESS[3],
+                    uv[i],
+                    [atlas_u, atlas_v],
+                    light,
+                )
+                .with_frames(frames),
+            );
+        }
+        let indices = face.quad_indices(false).map(|i| base_idx + i);
+        mesh.indices.extend_from_slice(&indices);
+    }
+
+    /// Returns the local UV corners of a quad at `origin` in its face plane,
+    /// for tiling its texture (0 to width, 0 to height).
+    ///
+    /// Corner order: (0,0), (width,0), (width,height), (0,height). With
+    /// [vertex dedup](Self::with_vertex_dedup) they're offset by `origin`
+    /// instead, so shared corners agree between quads.
+    #[allow(clippy::cast_precision_loss)]
+    fn local_uvs(&self, origin: [usize; 2], width: usize, height: usize) -> [[f32; 2]; 4] {
+        let [u, v] = if self.dedup_vertices {
+            origin.map(|coord| coord as f32)
+        } else {
+            [0.0; 2]
+        };
+        let (w, h) = (width as f32, height as f32);
+        [
+            [u, v],         // 0: bottom-left
+            [u + w, v],     // 1: bottom-right
+            [u + w, v + h], // 2: top-right
+            [u, v + h],     // 3: top-left
+        ]
+    }
+
+    /// Adds a quad from greedy meshing.
+    fn add_greedy_quad(&self, mesh: &mut ChunkMesh, quad: &GreedyQuad) {
+        let GreedyQuad {
+            face,
+            origin,
+            width,
+            height,
+            block,
+            ao,
+            light,
+            sky_light,
+            liquid_level,
+        } = *quad;
+        let (axis, u_axis, v_axis) = face.sweep_axes();
+        let (d, u, v) = (origin[axis], origin[u_axis], origin[v_axis]);
         let base_idx = mesh.vertices.len() as u32;
         let normal = face.normal();
-        let color = block.color();
-
-        // Get texture atlas base position for this block
-        let (atlas_u, atlas_v, _, _) = TextureAtlas::block_uvs(block);
-        let atlas_uv = [atlas_u, atlas_v];
-
-        // Local UV corners for tiling (0 to width, 0 to height)
-        // Corner order: (0,0), (width,0), (width,height), (0,height)
-        let local_uv_corners = [
-            [0.0, 0.0],                    // 0: bottom-left
-            [width as f32, 0.0],           // 1: bottom-right
-            [width as f32, height as f32], // 2: top-right
-            [0.0, height as f32],          // 3: top-left
-        ];
+        let color = self.colors.color(block);
+        let light = [light, sky_light].map(|level| f32::from(level) / f32::from(MAX_LIGHT));
 
-        // Calculate the 4 corners of the quad
+        // Get texture atlas base position for this face of the block. Quads
+        // only merge within one face direction, so they share one texture.
+        let atlas_uv = atlas_origin(block, face);
+        let frames = block.def().frames;
+
+        let local_uv_corners = self.local_uvs([u, v], width, height);
+
+        // Calculate the 4 corners of the quad. An open liquid surface sits
+        // below the top of its block.
         let mut corners = [[0.0f32; 3]; 4];
+        let liquid_drop = liquid_level.map_or(0.0, |level| 1.0 - surface_height(level));
 
         // Base position
         let d_offset = match face {
@@ -424,110 +1284,102 @@ impl MeshGenerator {
             corner[u_axis] = (u + u_off) as f32;
             corner[v_axis] = (v + v_off) as f32;
 
+            if face == Face::PosY || (v_axis == 1 && v_off == height) {
+                corner[1] -= liquid_drop;
+            }
+
             // Add world offset
             corner[0] += self.world_offset[0];
             corner[1] += self.world_offset[1];
             corner[2] += self.world_offset[2];
         }
 
-        // Add vertices (winding order depends on face direction)
-        let ao = 1.0; // TODO: Compute ambient occlusion
-
-        match face {
-            Face::PosX | Face::PosY | Face::PosZ => {
-                mesh.vertices.push(ChunkVertex::new(
-                    corners[0],
-                    normal,
-                    color,
-                    ao,
-                    local_uv_corners[0],
-                    atlas_uv,
-                ));
-                mesh.vertices.push(ChunkVertex::new(
-                    corners[1],
+        for i in face.corner_order() {
+            let color = self.tinted(block, color, corners[i]);
+            mesh.vertices.push(
+                ChunkVertex::new(
+                    corners[i],
                     normal,
                     color,
-                    ao,
-                    local_uv_corners[1],
+                    AO_BRIGHTNESS[usize::from(ao[i])],
+                    local_uv_corners[i],
                     atlas_uv,
-                ));
-                mesh.vertices.push(ChunkVertex::new(
-                    corners[2],
-                    normal,
-                    color,
-                    ao,
-                    local_uv_corners[2],
-                    atlas_uv,
-                ));
-                mesh.vertices.push(ChunkVertex::new(
-                    corners[3],
-                    normal,
-                    color,
-                    ao,
-                    local_uv_corners[3],
-                    atlas_uv,
-                ));
-            }
-            Face::NegX | Face::NegY | Face::NegZ => {
-                mesh.vertices.push(ChunkVertex::new(
-                    corners[0],
-                    normal,
-                    color,
-                    ao,
-                    local_uv_corners[0],
-                    atlas_uv,
-                ));
-                mesh.vertices.push(ChunkVertex::new(
-                    corners[3],
-                    normal,
-                    color,
-                    ao,
-                    local_uv_corners[3],
-                    atlas_uv,
-                ));
-                mesh.vertices.push(ChunkVertex::new(
-                    corners[2],
-                    normal,
-                    color,
-                    ao,
-                    local_uv_corners[2],
-                    atlas_uv,
-                ));
-                mesh.vertices.push(ChunkVertex::new(
-                    corners[1],
-                    normal,
-                    color,
-                    ao,
-                    local_uv_corners[1],
-                    atlas_uv,
-                ));
-            }
+                    light,
+                )
+                .with_frames(frames),
+            );
         }
 
-        // Add indices (two triangles, CCW winding for front faces)
-        // X and Y faces use one winding, Z faces use the opposite
-        let indices = match face {
-            Face::PosX | Face::NegX | Face::PosY | Face::NegY => [
-                base_idx,
-                base_idx + 2,
-                base_idx + 1,
-                base_idx,
-                base_idx + 3,
-                base_idx + 2,
-            ],
-            Face::PosZ | Face::NegZ => [
-                base_idx,
-                base_idx + 1,
-                base_idx + 2,
-                base_idx,
-                base_idx + 2,
-                base_idx + 3,
-            ],
-        };
+        // Split along the diagonal between the brighter corners, so a single
+        // dark corner shades one triangle instead of smearing across both.
+        // Vertices 0 and 2 are corners 0 and 2 for either winding.
+        let flip = ao[0] + ao[2] < ao[1] + ao[3];
+
+        let indices = face.quad_indices(flip).map(|i| base_idx + i);
         mesh.indices.extend_from_slice(&indices);
     }
 }
 
+/// Returns the corner of the atlas tile for `face` of `block`.
+fn atlas_origin(block: Block, face: Face) -> [f32; 2] {
+    let (u, v, _, _) = TextureAtlas::block_face_uvs(block, face);
+    [u, v]
+}
+
+/// Returns a copy of `chunk` with each `lod`-sized cube of blocks filled
+/// with one representative block: the most common non-air block if at
+/// least half the cube is filled, otherwise air.
+fn coarsen(chunk: &Chunk, lod: usize) -> Chunk {
+    let mut coarse = Chunk::with_height(chunk.position(), chunk.height());
+    let mut counts: Vec<(Block, usize)> = Vec::new();
+
+    for cell_y in (0..chunk.height()).step_by(lod) {
+        // Cells never straddle sections, so empty sections stay empty
+        if chunk.get_section(cell_y / SECTION_SIZE).is_none() {
+            continue;
+        }
+        for cell_z in (0..SECTION_SIZE).step_by(lod) {
+            for cell_x in (0..SECTION_SIZE).step_by(lod) {
+                counts.clear();
+                for y in cell_y..cell_y + lod {
+                    for z in cell_z..cell_z + lod {
+                        for x in cell_x..cell_x + lod {
+                            let block = chunk.get_block(x, y, z);
+                            if block.is_air() {
+                                continue;
+                            }
+                            match counts.iter_mut().find(|(b, _)| *b == block) {
+                                Some((_, count)) => *count += 1,
+                                None => counts.push((block, 1)),
+                            }
+                        }
+                    }
+                }
+
+                let filled: usize = counts.iter().map(|&(_, count)| count).sum();
+                if filled * 2 < lod * lod * lod {
+                    continue;
+                }
+                // The earliest of equally common blocks wins, for stable output
+                let block = counts
+                    .iter()
+                    .rev()
+                    .max_by_key(|&&(_, count)| count)
+                    .map_or(Block::Air, |&(block, _)| block);
+                for y in cell_y..cell_y + lod {
+                    for z in cell_z..cell_z + lod {
+                        for x in cell_x..cell_x + lod {
+                            coarse.set_block(x, y, z, block);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    coarse
+}
+
 /// Generates a simple test mesh (single block at origin).
 /// Vertex layout matches greedy mesh generator for consistent winding.
 #[must_use]
@@ -535,10 +1387,6 @@ pub fn generate_test_cube(block: Block) -> ChunkMesh {
     let mut mesh = ChunkMesh::new();
     let color = block.color();
 
-    // Get texture atlas base position for this block
-    let (atlas_u, atlas_v, _, _) = TextureAtlas::block_uvs(block);
-    let atlas_uv = [atlas_u, atlas_v];
-
     // Vertices ordered to match greedy mesh: corners[i] at (u_off, v_off) positions
     // (0,0), (width,0), (width,height), (0,height) in the face's UV space
     let faces = [
@@ -614,16 +1462,17 @@ pub fn generate_test_cube(block: Block) -> ChunkMesh {
 
     for (face, verts) in faces {
         let base_idx = mesh.vertices.len() as u32;
-        let normal = face.normal();
+        let atlas_uv = atlas_origin(block, face);
 
         for (i, vert) in verts.iter().enumerate() {
             mesh.vertices.push(ChunkVertex::new(
                 *vert,
-                normal,
+                face.normal(),
                 color,
                 1.0,
                 local_uv_corners[i],
                 atlas_uv,
+                [0.0, 1.0],
             ));
         }
 
@@ -656,7 +1505,7 @@ pub fn generate_test_cube(block: Block) -> ChunkMesh {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::world::chunk::ChunkPos;
+    use crate::world::chunk::{CHUNK_HEIGHT, ChunkPos, ChunkSection};
 
     #[test]
     fn test_cube_has_correct_geometry() {
@@ -672,6 +1521,461 @@ mod tests {
         assert_eq!(mesh.triangle_count(), 12);
     }
 
+    #[test]
+    fn with_capacity_reserves_requested_space() {
+        let mesh = ChunkMesh::with_capacity(100, 150);
+        assert!(mesh.vertices.capacity() >= 100);
+        assert!(mesh.indices.capacity() >= 150);
+        assert!(mesh.is_empty());
+    }
+
+    #[test]
+    fn terrain_mesh_is_correct_with_reservation() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.fill_test_pattern();
+
+        let generator = MeshGenerator::new(chunk);
+        let quads = generator.estimate_quad_count();
+        let mesh = generator.generate();
+
+        // Reservation must not change the mesh shape, and the estimate
+        // should cover what greedy meshing actually produces.
+        assert!(!mesh.is_empty());
+        assert_eq!(mesh.vertices.len() % 4, 0);
+        assert_eq!(mesh.indices.len(), mesh.vertices.len() / 4 * 6);
+        assert!(mesh.vertices.len() <= quads * 4);
+        assert!(
+            mesh.indices
+                .iter()
+                .all(|&i| (i as usize) < mesh.vertices.len())
+        );
+    }
+
+    #[test]
+    fn lod_meshes_terrain_with_far_fewer_triangles() {
+        let chunk = crate::world::TerrainGenerator::new(7).generate(ChunkPos::new(3, -2));
+
+        let full = MeshGenerator::new(chunk.clone()).generate();
+        let coarse = MeshGenerator::new(chunk).with_lod(2).generate();
+
+        assert!(!coarse.is_empty());
+        assert!(coarse.validate().is_ok());
+        assert!(
+            coarse.triangle_count() * 2 < full.triangle_count(),
+            "LOD 2 has {} triangles, full detail {}",
+            coarse.triangle_count(),
+            full.triangle_count()
+        );
+    }
+
+    #[test]
+    fn lod_cells_take_the_most_common_block() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        // Five stone and one dirt fill most of the first 2x2x2 cell...
+        for (x, y, z) in [(0, 0, 0), (1, 0, 0), (0, 0, 1), (1, 0, 1), (0, 1, 0)] {
+            chunk.set_block(x, y, z, Block::Stone);
+        }
+        chunk.set_block(1, 1, 1, Block::Dirt);
+        // ...while a lone block doesn't fill the next one
+        chunk.set_block(2, 0, 0, Block::Dirt);
+
+        let coarse = coarsen(&chunk, 2);
+        for (x, y, z) in [(0, 0, 0), (1, 1, 1), (0, 1, 1)] {
+            assert_eq!(coarse.get_block(x, y, z), Block::Stone);
+        }
+        assert_eq!(coarse.get_block(2, 0, 0), Block::Air);
+
+        // One 2x2x2 cube, its faces split only where sky light changes
+        let quads = MeshGenerator::new(chunk).with_lod(2).quads();
+        let area: usize = quads.iter().map(|quad| quad.width * quad.height).sum();
+        assert_eq!(area, 6 * 4);
+        assert!(quads.iter().all(|quad| quad.block == Block::Stone));
+    }
+
+    #[test]
+    fn stained_glass_lands_in_transparent_layer_with_tint() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.set_block(4, 64, 4, Block::Stone);
+        chunk.set_block(8, 64, 8, Block::RedStainedGlass);
+
+        let layers = MeshGenerator::new(chunk).generate_layers();
+
+        // One isolated cube in each layer
+        assert_eq!(layers.opaque.vertices.len(), 24);
+        assert_eq!(layers.transparent.vertices.len(), 24);
+
+        let tint = Block::RedStainedGlass.color();
+        let is_tint = |c: [f32; 3]| c.iter().zip(tint).all(|(a, b)| (a - b).abs() < 1e-6);
+        assert!(layers.transparent.vertices.iter().all(|v| is_tint(v.color)));
+        assert!(!layers.opaque.vertices.iter().any(|v| is_tint(v.color)));
+    }
+
+    #[test]
+    fn empty_sections_are_skipped_without_changing_the_mesh() {
+        let mut bottom_only = Chunk::new(ChunkPos::new(0, 0));
+        bottom_only.set_section(0, Some(ChunkSection::filled(Block::Stone)));
+
+        // One merged quad per side of the solid section
+        let quads = MeshGenerator::new(bottom_only.clone()).quads();
+        assert_eq!(quads.len(), 6);
+        assert!(
+            quads
+                .iter()
+                .all(|quad| (quad.width, quad.height) == (16, 16))
+        );
+
+        // Allocated but air-only sections mesh like missing ones
+        let mut allocated = bottom_only.clone();
+        for y in 1..allocated.section_count() {
+            allocated.set_section(y, Some(ChunkSection::new()));
+        }
+        assert_eq!(MeshGenerator::new(allocated.clone()).quads(), quads);
+
+        let positions = |chunk: Chunk| -> Vec<[f32; 3]> {
+            let mesh = MeshGenerator::new(chunk).generate();
+            mesh.vertices.iter().map(|v| v.position).collect()
+        };
+        assert_eq!(positions(allocated), positions(bottom_only.clone()));
+
+        // A block above an air-only gap still gets all its faces
+        let mut gap = bottom_only;
+        gap.set_block(4, 100, 4, Block::Dirt);
+        let quads = MeshGenerator::new(gap).quads();
+        let dirt: Vec<_> = quads
+            .iter()
+            .filter(|quad| quad.block == Block::Dirt)
+            .collect();
+        assert_eq!(dirt.len(), 6);
+        assert!(dirt.iter().all(|quad| quad.origin == [4, 100, 4]));
+    }
+
+    #[test]
+    fn slab_has_a_half_height_top_face() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.set_block(4, 64, 4, Block::StoneSlab);
+
+        // Shaped blocks aren't merged into quads
+        assert!(MeshGenerator::new(chunk.clone()).quads().is_empty());
+
+        let mesh = MeshGenerator::new(chunk).generate();
+        assert_eq!(mesh.vertices.len(), 24);
+        assert!(mesh.validate().is_ok());
+        let top: Vec<_> = mesh.vertices.iter().filter(|v| v.normal[1] > 0.5).collect();
+        assert_eq!(top.len(), 4);
+        assert!(top.iter().all(|v| (v.position[1] - 64.5).abs() < 1e-6));
+        // The sides stop at the top face
+        let highest = mesh
+            .vertices
+            .iter()
+            .map(|v| v.position[1])
+            .fold(f32::MIN, f32::max);
+        assert!((highest - 64.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn slabs_only_hide_the_faces_they_cover() {
+        let faces_of = |chunk: &Chunk, block: Block| -> Vec<Face> {
+            MeshGenerator::new(chunk.clone())
+                .quads()
+                .into_iter()
+                .filter(|quad| quad.block == block)
+                .map(|quad| quad.face)
+                .collect()
+        };
+
+        // A slab under a full block doesn't hide the block's bottom face
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.set_block(4, 64, 4, Block::StoneSlab);
+        chunk.set_block(4, 65, 4, Block::Dirt);
+        assert!(faces_of(&chunk, Block::Dirt).contains(&Face::NegY));
+
+        // A slab resting on a full block hides the block's top face, and
+        // the slab's own bottom face
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.set_block(4, 64, 4, Block::Dirt);
+        chunk.set_block(4, 65, 4, Block::StoneSlab);
+        assert!(!faces_of(&chunk, Block::Dirt).contains(&Face::PosY));
+        let mesh = MeshGenerator::new(chunk).generate();
+        let slab_bottoms = mesh
+            .vertices
+            .iter()
+            .filter(|v| v.normal[1] < -0.5 && v.position[1] > 64.5)
+            .count();
+        assert_eq!(slab_bottoms, 0);
+    }
+
+    #[test]
+    fn water_surface_is_lowered_by_its_level() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.set_block(4, 64, 4, Block::Water);
+        chunk.set_metadata(4, 64, 4, 3);
+        // A two-block column: only its top block is lowered
+        chunk.set_block(10, 64, 10, Block::Water);
+        chunk.set_block(10, 65, 10, Block::Water);
+
+        let quads = MeshGenerator::new(chunk.clone()).quads();
+        let level_of = |origin: [usize; 3]| {
+            let levels: Vec<_> = quads
+                .iter()
+                .filter(|quad| quad.origin == origin && quad.face != Face::NegY)
+                .map(|quad| quad.liquid_level)
+                .collect();
+            assert_eq!(levels.len(), 5);
+            levels[0]
+        };
+        assert_eq!(level_of([4, 64, 4]), Some(3));
+        assert_eq!(level_of([10, 65, 10]), Some(0));
+        assert_eq!(level_of([10, 64, 10]), None);
+
+        let mesh = MeshGenerator::new(chunk).generate();
+        let top_of = |x: f32| {
+            mesh.vertices
+                .iter()
+                .filter(|v| (v.position[0] - x).abs() < 1.5)
+                .map(|v| v.position[1])
+                .fold(f32::MIN, f32::max)
+        };
+        assert!((top_of(4.5) - (64.0 + surface_height(3))).abs() < 1e-5);
+        assert!((top_of(10.5) - (65.0 + surface_height(0))).abs() < 1e-5);
+        assert!(mesh.validate().is_ok());
+    }
+
+    #[test]
+    fn vertex_dedup_shares_corners_without_changing_the_geometry() {
+        // An L-shaped slab: its top can't merge into one quad
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        for x in 0..4 {
+            for z in 0..4 {
+                if x < 2 || z < 2 {
+                    chunk.set_block(x, 10, z, Block::Stone);
+                }
+            }
+        }
+        let plain = MeshGenerator::new(chunk.clone()).generate();
+        let shared = MeshGenerator::new(chunk.clone())
+            .with_vertex_dedup(true)
+            .generate();
+        assert!(shared.vertices.len() < plain.vertices.len());
+        assert_eq!(shared.indices.len(), plain.indices.len());
+        assert!(shared.validate().is_ok());
+
+        // The same triangles, apart from where texture tiling starts
+        let triangles = |mesh: &ChunkMesh| {
+            let mut triangles: Vec<[[u32; VERTEX_WORDS]; 3]> = mesh
+                .indices
+                .chunks_exact(3)
+                .map(|triangle| {
+                    std::array::from_fn(|i| {
+                        let mut vertex = mesh.vertices[triangle[i] as usize];
+                        vertex.local_uv = [0.0; 2];
+                        bytemuck::cast(vertex)
+                    })
+                })
+                .collect();
+            triangles.sort_unstable();
+            triangles
+        };
+        assert_eq!(triangles(&shared), triangles(&plain));
+
+        let again = MeshGenerator::new(chunk).with_vertex_dedup(true).generate();
+        assert_eq!(
+            bytemuck::cast_slice::<_, u8>(&again.vertices),
+            bytemuck::cast_slice::<_, u8>(&shared.vertices)
+        );
+        assert_eq!(again.indices, shared.indices);
+    }
+
+    #[test]
+    #[allow(clippy::cast_possible_truncation)]
+    fn grass_blends_between_biome_tints() {
+        let terrain = TerrainGenerator::new(42);
+        // A chunk with a biome border running through it
+        let pos = (-256..256)
+            .map(|x| ChunkPos::new(x, 0))
+            .find(|pos| {
+                let (x, z) = pos.block_origin();
+                terrain.biome_at(x, z) != terrain.biome_at(x + 15, z + 15)
+            })
+            .unwrap();
+        let mut chunk = Chunk::new(pos);
+        for x in 0..SECTION_SIZE {
+            for z in 0..SECTION_SIZE {
+                chunk.set_block(x, 64, z, Block::Grass);
+            }
+        }
+        chunk.set_block(8, 10, 8, Block::Stone);
+
+        let mesh = MeshGenerator::new(chunk).with_biomes(&terrain).generate();
+        let mut grass_colors = Vec::new();
+        for vertex in &mesh.vertices {
+            let [x, _, z] = vertex.position.map(|c| c as i32);
+            let expected = if vertex.position[1] < 20.0 {
+                Block::Stone.color()
+            } else {
+                let around = [(x - 1, z - 1), (x, z - 1), (x - 1, z), (x, z)]
+                    .map(|(x, z)| terrain.biome_at(x, z).tint());
+                let tint = |c: usize| around.iter().map(|tint| tint[c]).sum::<f32>() / 4.0;
+                let expected = std::array::from_fn(|c| Block::Grass.color()[c] * tint(c));
+                grass_colors.push(expected);
+                expected
+            };
+            for (actual, expected) in vertex.color.iter().zip(expected) {
+                assert!((actual - expected).abs() < 1e-5);
+            }
+        }
+        let first = grass_colors[0];
+        assert!(
+            grass_colors
+                .iter()
+                .any(|color| color.iter().zip(first).any(|(a, b)| (a - b).abs() > 1e-3))
+        );
+    }
+
+    #[test]
+    fn water_vertices_carry_their_animation_frames() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.set_block(4, 64, 4, Block::Stone);
+        chunk.set_block(8, 64, 8, Block::Water);
+
+        let layers = MeshGenerator::new(chunk).generate_layers();
+        let frames = f32::from(u8::try_from(Block::Water.def().frames).unwrap());
+        assert!(frames > 1.0);
+        assert!(
+            layers
+                .transparent
+                .vertices
+                .iter()
+                .all(|v| (v.frames - frames).abs() < 1e-6)
+        );
+        assert!(
+            layers
+                .opaque
+                .vertices
+                .iter()
+                .all(|v| (v.frames - 1.0).abs() < 1e-6)
+        );
+    }
+
+    #[test]
+    fn color_override_recolors_only_that_block() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.set_block(4, 64, 4, Block::Stone);
+        chunk.set_block(8, 64, 8, Block::Dirt);
+        let mut overrides = ColorOverrides::new();
+        overrides.set(Block::Stone, [0.0, 0.45, 0.7]);
+
+        let mesh = MeshGenerator::new(chunk)
+            .with_color_overrides(&overrides)
+            .generate();
+
+        // Vertices are told apart by the atlas tile of their block
+        let colors_of = |block: Block| {
+            let (u, v, _, _) = TextureAtlas::block_uvs(block);
+            mesh.vertices
+                .iter()
+                .filter(move |vertex| vertex.atlas_uv == [u, v])
+                .map(|vertex| vertex.color)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(colors_of(Block::Stone), vec![[0.0, 0.45, 0.7]; 24]);
+        assert_eq!(colors_of(Block::Dirt), vec![Block::Dirt.color(); 24]);
+    }
+
+    #[test]
+    fn combined_mesh_includes_both_layers() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.set_block(4, 64, 4, Block::Stone);
+        chunk.set_block(8, 64, 8, Block::Glass);
+
+        let mesh = MeshGenerator::new(chunk).generate();
+
+        assert_eq!(mesh.vertices.len(), 48);
+        assert_eq!(mesh.indices.len(), 72);
+        assert!(
+            mesh.indices
+                .iter()
+                .all(|&i| (i as usize) < mesh.vertices.len())
+        );
+    }
+
+    #[test]
+    fn solid_cube_skips_interior_and_meshes_surface_only() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        for x in 5..8 {
+            for y in 10..13 {
+                for z in 5..8 {
+                    chunk.set_block(x, y, z, Block::Stone);
+                }
+            }
+        }
+
+        let generator = MeshGenerator::new(chunk);
+        let interior = generator.interior_blocks();
+        let interior_count = interior.iter().filter(|&&i| i).count();
+        assert_eq!(interior_count, 1);
+        assert!(interior[MeshGenerator::interior_index(6, 11, 6)]);
+
+        let mesh = generator.generate();
+
+        // Hand-computed surface: one merged 3x3 quad per side, given as
+        // (normal, min corner, max corner)
+        let mut expected = vec![
+            ([0, 1, 0], [5, 13, 5], [8, 13, 8]),
+            ([0, -1, 0], [5, 10, 5], [8, 10, 8]),
+            ([1, 0, 0], [8, 10, 5], [8, 13, 8]),
+            ([-1, 0, 0], [5, 10, 5], [5, 13, 8]),
+            ([0, 0, 1], [5, 10, 8], [8, 13, 8]),
+            ([0, 0, -1], [5, 10, 5], [8, 13, 5]),
+        ];
+        let mut actual: Vec<_> = mesh
+            .vertices
+            .chunks(4)
+            .map(|quad| {
+                #[allow(clippy::cast_possible_truncation)]
+                let to_i32 = |v: [f32; 3]| v.map(|c| c.round() as i32);
+                let mut min = [i32::MAX; 3];
+                let mut max = [i32::MIN; 3];
+                for vertex in quad {
+                    let p = to_i32(vertex.position);
+                    for i in 0..3 {
+                        min[i] = min[i].min(p[i]);
+                        max[i] = max[i].max(p[i]);
+                    }
+                }
+                (to_i32(quad[0].normal), min, max)
+            })
+            .collect();
+
+        expected.sort_unstable();
+        actual.sort_unstable();
+        assert_eq!(actual, expected);
+        assert_eq!(mesh.indices.len(), 36);
+    }
+
+    #[test]
+    fn interior_skip_does_not_change_terrain_mesh() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.fill_test_pattern();
+
+        // With no interior blocks flagged, every block goes through the
+        // per-face visibility test
+        let generator = MeshGenerator::new(chunk);
+        let mut reference = Vec::new();
+        let no_interior = vec![false; SECTION_SIZE * SECTION_SIZE * CHUNK_HEIGHT];
+        for face in [
+            Face::PosY,
+            Face::NegY,
+            Face::PosX,
+            Face::NegX,
+            Face::PosZ,
+            Face::NegZ,
+        ] {
+            generator.generate_faces(&mut reference, &no_interior, face);
+        }
+
+        assert_eq!(generator.quads(), reference);
+    }
+
     #[test]
     fn empty_chunk_produces_empty_mesh() {
         let chunk = Chunk::new(ChunkPos::new(0, 0));
@@ -729,6 +2033,319 @@ mod tests {
         );
     }
 
+    #[test]
+    fn flat_layer_yields_one_top_quad() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+
+        // A 5x3 layer of stone at y=64
+        for x in 2..7 {
+            for z in 4..7 {
+                chunk.set_block(x, 64, z, Block::Stone);
+            }
+        }
+
+        let quads = MeshGenerator::new(chunk).quads();
+        let top: Vec<_> = quads.iter().filter(|q| q.face == Face::PosY).collect();
+
+        assert_eq!(top.len(), 1);
+        assert_eq!(
+            *top[0],
+            GreedyQuad {
+                face: Face::PosY,
+                origin: [2, 64, 4],
+                width: 5,
+                height: 3,
+                block: Block::Stone,
+                ao: [3; 4],
+                light: 0,
+                sky_light: MAX_LIGHT,
+                liquid_level: None,
+            }
+        );
+    }
+
+    #[test]
+    fn full_grass_layer_merges_into_one_quad() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        for x in 0..SECTION_SIZE {
+            for z in 0..SECTION_SIZE {
+                chunk.set_block(x, 64, z, Block::Grass);
+            }
+        }
+
+        let quads = MeshGenerator::new(chunk).quads();
+        let top: Vec<_> = quads.iter().filter(|q| q.face == Face::PosY).collect();
+
+        assert_eq!(top.len(), 1);
+        assert_eq!((top[0].width, top[0].height), (SECTION_SIZE, SECTION_SIZE));
+    }
+
+    #[test]
+    fn grass_faces_use_their_own_textures() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        for x in 0..4 {
+            for z in 0..4 {
+                chunk.set_block(x, 10, z, Block::Grass);
+            }
+        }
+        let mesh = MeshGenerator::new(chunk).generate();
+
+        // Every vertex carries the texture of the face it belongs to
+        for face in Face::ALL {
+            let (u, v, _, _) = TextureAtlas::block_face_uvs(Block::Grass, face);
+            let on_face: Vec<_> = mesh
+                .vertices
+                .iter()
+                .filter(|vertex| vertex.normal == face.normal())
+                .collect();
+            // The whole layer merges into one quad per face
+            assert_eq!(on_face.len(), 4, "{face:?}");
+            assert!(on_face.iter().all(|vertex| vertex.atlas_uv == [u, v]));
+        }
+    }
+
+    /// Returns the quad on `face` whose origin is `origin`.
+    fn quad_at(quads: &[GreedyQuad], face: Face, origin: [usize; 3]) -> GreedyQuad {
+        *quads
+            .iter()
+            .find(|q| q.face == face && q.origin == origin)
+            .expect("quad exists")
+    }
+
+    #[test]
+    fn isolated_block_is_unoccluded() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.set_block(5, 64, 5, Block::Stone);
+        let generator = MeshGenerator::new(chunk);
+
+        assert!(generator.quads().iter().all(|q| q.ao == [3; 4]));
+        assert!(generator.generate().vertices.iter().all(|v| v.ao == 1.0));
+    }
+
+    #[test]
+    fn block_tucked_into_a_corner_is_darker() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.set_block(5, 64, 5, Block::Stone);
+        // Two walls meeting over the block's -X/-Z corner
+        for i in 4..=6 {
+            chunk.set_block(4, 65, i, Block::Stone);
+            chunk.set_block(i, 65, 4, Block::Stone);
+        }
+        let generator = MeshGenerator::new(chunk);
+
+        // Corners in order (x, z) = (5, 5), (6, 5), (6, 6), (5, 6)
+        let top = quad_at(&generator.quads(), Face::PosY, [5, 64, 5]);
+        assert_eq!(top.ao, [0, 1, 3, 1]);
+
+        let mesh = generator.generate();
+        let corner_ao = |position: [f32; 3]| {
+            mesh.vertices
+                .iter()
+                .find(|v| v.position == position && v.normal == Face::PosY.normal())
+                .map(|v| v.ao)
+        };
+        assert_eq!(corner_ao([5.0, 65.0, 5.0]), Some(AO_BRIGHTNESS[0]));
+        assert_eq!(corner_ao([6.0, 65.0, 6.0]), Some(1.0));
+    }
+
+    #[test]
+    fn lamp_lights_the_faces_around_it() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        for x in 2..10 {
+            for z in 2..10 {
+                chunk.set_block(x, 64, z, Block::Stone);
+            }
+        }
+        let floor_light = |chunk: &Chunk, x: usize, z: usize| {
+            let quads = MeshGenerator::new(chunk.clone()).quads();
+            quads
+                .iter()
+                .find(|q| {
+                    q.face == Face::PosY
+                        && (q.origin[0]..q.origin[0] + q.width).contains(&x)
+                        && (q.origin[2]..q.origin[2] + q.height).contains(&z)
+                })
+                .map(|q| q.light)
+                .unwrap()
+        };
+        assert_eq!(floor_light(&chunk, 5, 5), 0);
+
+        chunk.set_block(5, 65, 5, Block::Lamp);
+        let emission = Block::Lamp.properties().light_emission;
+        assert_eq!(floor_light(&chunk, 4, 5), emission - 1);
+        assert_eq!(floor_light(&chunk, 3, 5), emission - 2);
+
+        // The lamp's own faces and the faces across from it are lit as well
+        let quads = MeshGenerator::new(chunk).generate_layers();
+        let lit = quads
+            .opaque
+            .vertices
+            .iter()
+            .filter(|v| v.light[0] > 0.0)
+            .count();
+        assert!(lit > 4 * 6, "only {lit} lit vertices");
+    }
+
+    #[test]
+    fn faces_under_cover_get_less_sky_light() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        for x in 2..10 {
+            chunk.set_block(x, 64, 5, Block::Stone);
+        }
+        // A roof over the east half of the floor
+        for x in 6..10 {
+            chunk.set_block(x, 70, 5, Block::Stone);
+        }
+
+        let quads = MeshGenerator::new(chunk).quads();
+        let floor_sky = |x: usize| {
+            quads
+                .iter()
+                .find(|q| {
+                    q.face == Face::PosY
+                        && q.origin[1] == 64
+                        && (q.origin[0]..q.origin[0] + q.width).contains(&x)
+                })
+                .map(|q| q.sky_light)
+                .unwrap()
+        };
+        assert_eq!(floor_sky(3), MAX_LIGHT);
+        assert_eq!(floor_sky(8), COVERED_SKY_LIGHT);
+        // The roof's top is open, its underside is under itself
+        assert_eq!(quad_at(&quads, Face::PosY, [6, 70, 5]).sky_light, MAX_LIGHT);
+        assert_eq!(
+            quad_at(&quads, Face::NegY, [6, 70, 5]).sky_light,
+            COVERED_SKY_LIGHT
+        );
+    }
+
+    #[test]
+    fn uneven_occlusion_splits_merged_faces() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        for x in 2..9 {
+            for z in 2..9 {
+                chunk.set_block(x, 64, z, Block::Stone);
+            }
+        }
+        chunk.set_block(5, 65, 5, Block::Dirt);
+        let quads = MeshGenerator::new(chunk).quads();
+        let floor: Vec<_> = quads
+            .iter()
+            .filter(|q| q.face == Face::PosY && q.origin[1] == 64)
+            .collect();
+
+        // The faces touching the bump keep their own corners
+        assert!(floor.len() > 1);
+        for quad in &floor {
+            let uniform = quad.ao.iter().all(|&level| level == quad.ao[0]);
+            assert!(uniform || (quad.width, quad.height) == (1, 1), "{quad:?}");
+        }
+        assert_eq!(quad_at(&quads, Face::PosY, [4, 64, 4]).ao, [3, 3, 2, 3]);
+    }
+
+    #[test]
+    fn flipped_quads_keep_their_winding() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.set_block(5, 64, 5, Block::Stone);
+        // Only diagonal neighbors, darkening a single corner of each face
+        for (x, y, z) in [(4, 65, 4), (6, 63, 6)] {
+            chunk.set_block(x, y, z, Block::Stone);
+        }
+        let generator = MeshGenerator::new(chunk);
+        let top = quad_at(&generator.quads(), Face::PosY, [5, 64, 5]);
+        assert!(top.ao[0] + top.ao[2] < top.ao[1] + top.ao[3]);
+
+        assert_eq!(generator.generate().validate(), Ok(()));
+    }
+
+    /// Returns a chunk whose bottom section is full of stone.
+    fn full_section_chunk(pos: ChunkPos) -> Chunk {
+        let mut chunk = Chunk::new(pos);
+        chunk.set_section(0, Some(ChunkSection::filled(Block::Stone)));
+        chunk
+    }
+
+    #[test]
+    fn loaded_neighbor_hides_shared_wall() {
+        let west = full_section_chunk(ChunkPos::new(0, 0));
+        let east = full_section_chunk(ChunkPos::new(1, 0));
+        let faces_of = |quads: &[GreedyQuad], face| quads.iter().filter(|q| q.face == face).count();
+
+        // Alone, every border is a wall
+        let alone = MeshGenerator::new(west.clone()).quads();
+        assert!(faces_of(&alone, Face::PosX) > 0);
+
+        let with_east = MeshGenerator::new(west.clone())
+            .with_neighbors([Some(&east), None, None, None])
+            .quads();
+        assert_eq!(faces_of(&with_east, Face::PosX), 0);
+        // Unloaded neighbors still get walls
+        assert_eq!(
+            faces_of(&with_east, Face::NegX),
+            faces_of(&alone, Face::NegX)
+        );
+
+        let with_west = MeshGenerator::new(east)
+            .with_neighbors([None, Some(&west), None, None])
+            .quads();
+        assert_eq!(faces_of(&with_west, Face::NegX), 0);
+    }
+
+    #[test]
+    fn neighbor_blocks_shade_border_corners() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.set_block(15, 64, 5, Block::Stone);
+        let mut east = Chunk::new(ChunkPos::new(1, 0));
+        east.set_block(0, 65, 5, Block::Stone);
+
+        let quads = MeshGenerator::new(chunk)
+            .with_neighbors([Some(&east), None, None, None])
+            .quads();
+        // The +X corners of the top face sit under the neighbor's block
+        assert_eq!(quad_at(&quads, Face::PosY, [15, 64, 5]).ao, [3, 2, 2, 3]);
+    }
+
+    #[test]
+    fn side_faces_merge_across_sections() {
+        // A solid slab from y=10 to y=40 spans three sections
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        for y in 10..=40 {
+            for x in 0..SECTION_SIZE {
+                for z in 0..SECTION_SIZE {
+                    chunk.set_block(x, y, z, Block::Stone);
+                }
+            }
+        }
+
+        let quads = MeshGenerator::new(chunk).quads();
+        for face in [Face::PosX, Face::NegX, Face::PosZ, Face::NegZ] {
+            let sides: Vec<_> = quads.iter().filter(|q| q.face == face).collect();
+            assert_eq!(
+                sides.len(),
+                1,
+                "{face:?} face split into {} quads",
+                sides.len()
+            );
+            assert_eq!((sides[0].width, sides[0].height), (SECTION_SIZE, 31));
+        }
+    }
+
+    #[test]
+    fn quads_expand_to_generated_mesh() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        for x in 0..4 {
+            chunk.set_block(x, 64, 4, Block::Stone);
+        }
+        chunk.set_block(0, 65, 4, Block::Glass);
+
+        let generator = MeshGenerator::new(chunk);
+        let quad_count = generator.quads().len();
+        let mesh = generator.generate();
+
+        assert_eq!(mesh.vertices.len(), quad_count * 4);
+        assert_eq!(mesh.indices.len(), quad_count * 6);
+    }
+
     #[test]
     fn adjacent_same_blocks_merge() {
         let mut chunk = Chunk::new(ChunkPos::new(0, 0));
@@ -775,8 +2392,9 @@ mod tests {
             layout.array_stride,
             std::mem::size_of::<ChunkVertex>() as u64
         );
-        // 6 attributes: position, normal, color, ao, local_uv, atlas_uv
-        assert_eq!(layout.attributes.len(), 6);
+        // 8 attributes: position, normal, color, ao, local_uv, atlas_uv,
+        // light, frames
+        assert_eq!(layout.attributes.len(), 8);
     }
 
     #[test]
@@ -790,88 +2408,60 @@ mod tests {
 
     #[test]
     fn triangle_winding_is_ccw_for_front_faces() {
-        // Generate a simple cube and verify winding order
+        // Every triangle's winding must agree with its stored normal
         let mesh = generate_test_cube(Block::Stone);
-
-        // Check each triangle has correct CCW winding when viewed from outside
-        // For each triangle, compute cross product of edges - should point same dir as normal
-        for i in (0..mesh.indices.len()).step_by(3) {
-            let i0 = mesh.indices[i] as usize;
-            let i1 = mesh.indices[i + 1] as usize;
-            let i2 = mesh.indices[i + 2] as usize;
-
-            let v0 = mesh.vertices[i0].position;
-            let v1 = mesh.vertices[i1].position;
-            let v2 = mesh.vertices[i2].position;
-
-            // Edge vectors
-            let e1 = [v1[0] - v0[0], v1[1] - v0[1], v1[2] - v0[2]];
-            let e2 = [v2[0] - v0[0], v2[1] - v0[1], v2[2] - v0[2]];
-
-            // Cross product (gives face normal direction)
-            let cross = [
-                e1[1] * e2[2] - e1[2] * e2[1],
-                e1[2] * e2[0] - e1[0] * e2[2],
-                e1[0] * e2[1] - e1[1] * e2[0],
-            ];
-
-            // Should point same direction as stored normal (dot > 0)
-            let stored_normal = mesh.vertices[i0].normal;
-            let dot = cross[0] * stored_normal[0]
-                + cross[1] * stored_normal[1]
-                + cross[2] * stored_normal[2];
-
-            assert!(
-                dot > 0.0,
-                "Triangle {}: winding produces normal opposite to stored normal. \
-                 Cross: {:?}, Stored: {:?}, Dot: {}",
-                i / 3,
-                cross,
-                stored_normal,
-                dot
-            );
-        }
+        assert_eq!(mesh.validate(), Ok(()));
     }
 
     #[test]
     fn greedy_mesh_winding_is_ccw() {
-        // Test greedy meshing also produces correct winding
         let mut chunk = Chunk::new(ChunkPos::new(0, 0));
         chunk.set_block(8, 64, 8, Block::Stone);
+        assert_eq!(MeshGenerator::new(chunk).generate().validate(), Ok(()));
 
-        let generator = MeshGenerator::new(chunk);
-        let mesh = generator.generate();
+        // Merged quads of every size, in both layers
+        let mut chunk = Chunk::new(ChunkPos::new(3, -2));
+        chunk.fill_test_pattern();
+        chunk.set_block(4, 100, 4, Block::Glass);
+        assert_eq!(MeshGenerator::new(chunk).generate().validate(), Ok(()));
+    }
 
-        // Same winding check as above
-        for i in (0..mesh.indices.len()).step_by(3) {
-            let i0 = mesh.indices[i] as usize;
-            let i1 = mesh.indices[i + 1] as usize;
-            let i2 = mesh.indices[i + 2] as usize;
-
-            let v0 = mesh.vertices[i0].position;
-            let v1 = mesh.vertices[i1].position;
-            let v2 = mesh.vertices[i2].position;
-
-            let e1 = [v1[0] - v0[0], v1[1] - v0[1], v1[2] - v0[2]];
-            let e2 = [v2[0] - v0[0], v2[1] - v0[1], v2[2] - v0[2]];
-
-            let cross = [
-                e1[1] * e2[2] - e1[2] * e2[1],
-                e1[2] * e2[0] - e1[0] * e2[2],
-                e1[0] * e2[1] - e1[1] * e2[0],
-            ];
-
-            let stored_normal = mesh.vertices[i0].normal;
-            let dot = cross[0] * stored_normal[0]
-                + cross[1] * stored_normal[1]
-                + cross[2] * stored_normal[2];
-
-            assert!(
-                dot > 0.0,
-                "Greedy triangle {}: wrong winding. Dot: {}",
-                i / 3,
-                dot
-            );
-        }
+    #[test]
+    fn validate_reports_reversed_winding() {
+        let mut mesh = generate_test_cube(Block::Stone);
+        mesh.indices[6..9].reverse();
+
+        assert!(matches!(
+            mesh.validate(),
+            Err(MeshError::WrongWinding { triangle: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn validate_reports_bad_indices_and_positions() {
+        let mut mesh = generate_test_cube(Block::Stone);
+        mesh.indices[4] = 99;
+        assert_eq!(
+            mesh.validate(),
+            Err(MeshError::IndexOutOfRange {
+                triangle: 1,
+                index: 99,
+                vertex_count: 24,
+            })
+        );
+
+        let mut mesh = generate_test_cube(Block::Stone);
+        mesh.vertices[5].position[1] = f32::NAN;
+        assert!(matches!(
+            mesh.validate(),
+            Err(MeshError::NonFinitePosition { vertex: 5, .. })
+        ));
+
+        let mut mesh = generate_test_cube(Block::Stone);
+        mesh.indices.pop();
+        assert_eq!(
+            mesh.validate(),
+            Err(MeshError::IncompleteTriangle { index_count: 35 })
+        );
     }
 }
@@ -1,18 +1,51 @@
 //! World module.
 //!
-//! Contains block definitions, chunk data structures, mesh generation,
-//! and chunk management.
+//! Contains block definitions and their registry, chunk data structures, mesh generation,
+//! block shapes, chunk storage, terrain generation, biomes, structures and chunk
+//! management.
 
+pub mod biome;
 pub mod block;
+pub mod block_registry;
+pub mod block_update;
 pub mod chunk;
 pub mod chunk_manager;
+pub mod fluid;
+pub mod light;
 pub mod mesh;
+pub mod orientation;
 pub mod raycast;
+pub mod shape;
+pub mod storage;
+pub mod structure;
+pub mod terrain;
 pub mod texture_atlas;
+pub mod validate;
 
-pub use block::{Block, BlockId, BlockProperties};
-pub use chunk::{CHUNK_HEIGHT, Chunk, ChunkPos, ChunkSection, SECTION_SIZE};
-pub use chunk_manager::{ChunkManager, ChunkManagerConfig, GeneratedChunk};
-pub use mesh::{ChunkMesh, ChunkVertex, Face, MeshGenerator};
-pub use raycast::{BlockPos, HitFace, RaycastHit, raycast};
-pub use texture_atlas::TextureAtlas;
+pub use biome::Biome;
+pub use block::{Block, BlockId, BlockProperties, ColorOverrides};
+pub use block_registry::{
+    BlockDef, BlockRegistry, FaceSlots, MAX_BLOCKS, RegistryError, TextureLayout, TextureSide,
+};
+pub use block_update::BlockUpdateQueue;
+pub use chunk::{
+    CHUNK_HEIGHT, Chunk, ChunkPos, ChunkSection, Heightmap, MAX_CHUNK_HEIGHT, SECTION_SIZE,
+};
+pub use chunk_manager::{ChunkEvent, ChunkManager, ChunkManagerConfig, GeneratedChunk, MAX_LOD};
+pub use fluid::{FluidConfig, update_water};
+pub use light::{COVERED_SKY_LIGHT, LightMap, MAX_LIGHT};
+pub use mesh::{
+    ChunkMesh, ChunkVertex, Face, GreedyQuad, MeshError, MeshGenerator, MeshLayer, MeshLayers,
+};
+pub use orientation::{Axis, Orientation, Placement};
+pub use raycast::{
+    BlockPos, HitFace, RaycastHit, raycast, raycast_chunk, raycast_shapes, raycast_with_height,
+};
+pub use shape::BlockShape;
+pub use storage::{
+    CHUNK_FORMAT_VERSION, ChunkSaver, ChunkStore, MESH_CACHE_VERSION, StorageError, StoredChunk,
+};
+pub use structure::{OreVein, Structure, StructureBlock, Tree};
+pub use terrain::TerrainGenerator;
+pub use texture_atlas::{ANIMATION_LOOP_SECONDS, DEFAULT_ATLAS_SEED, TextureAtlas};
+pub use validate::WorldIssue;
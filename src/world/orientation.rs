@@ -0,0 +1,162 @@
+//! Block orientation.
+//!
+//! Directional blocks store their orientation in the block metadata nibble.
+//! Metadata 0 is the natural orientation (vertical logs), so generated
+//! terrain needs no metadata.
+
+use super::block::Block;
+use super::raycast::HitFace;
+
+/// How a block chooses its orientation when placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placement {
+    /// Always placed the same way.
+    Fixed,
+    /// Aligned to the axis of the clicked face (logs, pillars).
+    Axis,
+    /// Faces back towards the player, horizontally (stairs, furnaces).
+    Facing,
+}
+
+/// A coordinate axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    /// The vertical axis.
+    Y,
+    /// The east-west axis.
+    X,
+    /// The north-south axis.
+    Z,
+}
+
+impl Axis {
+    /// Returns the axis a face's normal points along.
+    #[must_use]
+    pub const fn of_face(face: HitFace) -> Self {
+        match face {
+            HitFace::Top | HitFace::Bottom => Self::Y,
+            HitFace::East | HitFace::West => Self::X,
+            HitFace::North | HitFace::South => Self::Z,
+        }
+    }
+}
+
+/// A placed block's orientation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    /// No orientation.
+    Fixed,
+    /// Aligned to an axis.
+    Axis(Axis),
+    /// Facing a horizontal direction.
+    Facing(HitFace),
+}
+
+impl Orientation {
+    /// Returns the orientation for placing `block` against `face`, with the
+    /// camera at `yaw` degrees (0 = +X, 90 = +Z).
+    #[must_use]
+    pub fn for_placement(block: Block, face: HitFace, yaw: f32) -> Self {
+        match block.placement() {
+            Placement::Fixed => Self::Fixed,
+            Placement::Axis => Self::Axis(Axis::of_face(face)),
+            Placement::Facing => Self::Facing(Self::facing_towards_camera(yaw)),
+        }
+    }
+
+    /// Returns the horizontal direction pointing back at a camera looking
+    /// along `yaw`.
+    fn facing_towards_camera(yaw: f32) -> HitFace {
+        // Quadrants centered on the axes: 315..45 looks towards +X, etc.
+        match (yaw.rem_euclid(360.0) + 45.0).rem_euclid(360.0) {
+            q if q < 90.0 => HitFace::West,
+            q if q < 180.0 => HitFace::North,
+            q if q < 270.0 => HitFace::East,
+            _ => HitFace::South,
+        }
+    }
+
+    /// Encodes the orientation as a metadata nibble.
+    ///
+    /// Facings are horizontal; vertical faces encode as north.
+    #[must_use]
+    pub const fn to_metadata(self) -> u8 {
+        match self {
+            Self::Fixed | Self::Axis(Axis::Y) => 0,
+            Self::Axis(Axis::X) => 1,
+            Self::Axis(Axis::Z) => 2,
+            Self::Facing(face) => match face {
+                HitFace::North | HitFace::Top | HitFace::Bottom => 0,
+                HitFace::South => 1,
+                HitFace::East => 2,
+                HitFace::West => 3,
+            },
+        }
+    }
+
+    /// Decodes the orientation of `block` from its metadata nibble.
+    #[must_use]
+    pub fn from_metadata(block: Block, metadata: u8) -> Self {
+        match block.placement() {
+            Placement::Fixed => Self::Fixed,
+            Placement::Axis => Self::Axis(match metadata {
+                1 => Axis::X,
+                2 => Axis::Z,
+                _ => Axis::Y,
+            }),
+            Placement::Facing => Self::Facing(match metadata {
+                1 => HitFace::South,
+                2 => HitFace::East,
+                3 => HitFace::West,
+                _ => HitFace::North,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_axis_follows_clicked_face() {
+        let top = Orientation::for_placement(Block::Log, HitFace::Top, 37.0);
+        assert_eq!(top, Orientation::Axis(Axis::Y));
+
+        let east = Orientation::for_placement(Block::Log, HitFace::East, 37.0);
+        assert_eq!(east, Orientation::Axis(Axis::X));
+
+        let south = Orientation::for_placement(Block::Log, HitFace::South, 37.0);
+        assert_eq!(south, Orientation::Axis(Axis::Z));
+    }
+
+    #[test]
+    fn non_directional_blocks_are_fixed() {
+        let stone = Orientation::for_placement(Block::Stone, HitFace::East, 0.0);
+        assert_eq!(stone, Orientation::Fixed);
+        assert_eq!(stone.to_metadata(), 0);
+    }
+
+    #[test]
+    fn facing_points_back_at_camera() {
+        assert_eq!(Orientation::facing_towards_camera(0.0), HitFace::West);
+        assert_eq!(Orientation::facing_towards_camera(90.0), HitFace::North);
+        assert_eq!(Orientation::facing_towards_camera(180.0), HitFace::East);
+        assert_eq!(Orientation::facing_towards_camera(270.0), HitFace::South);
+        assert_eq!(Orientation::facing_towards_camera(-30.0), HitFace::West);
+    }
+
+    #[test]
+    fn metadata_roundtrip() {
+        for axis in [Axis::X, Axis::Y, Axis::Z] {
+            let orientation = Orientation::Axis(axis);
+            let decoded = Orientation::from_metadata(Block::Log, orientation.to_metadata());
+            assert_eq!(decoded, orientation);
+        }
+        // Generated logs carry no metadata and stand upright
+        assert_eq!(
+            Orientation::from_metadata(Block::Log, 0),
+            Orientation::Axis(Axis::Y)
+        );
+    }
+}
@@ -2,6 +2,11 @@
 //!
 //! Uses DDA (Digital Differential Analyzer) algorithm to efficiently
 //! step through the voxel grid and find block intersections.
+//!
+//! [`raycast`] asks a closure whether each block is solid, which works
+//! across chunks; [`raycast_chunk`] reads a single chunk's blocks directly.
+//! [`raycast_shapes`] tests against the boxes of each block's shape, so rays
+//! pass over slabs.
 
 // Allow patterns that are clearer for DDA algorithm
 #![allow(
@@ -10,7 +15,11 @@
     clippy::cast_precision_loss
 )]
 
-use glam::Vec3;
+use glam::{Vec2, Vec3};
+
+use super::chunk::{CHUNK_HEIGHT, Chunk};
+use super::shape::BlockShape;
+use crate::math::Aabb;
 
 /// Result of a ray cast hit.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -23,6 +32,9 @@ pub struct RaycastHit {
     pub distance: f32,
     /// Exact world position of the hit point.
     pub hit_point: Vec3,
+    /// Where on the hit face the ray struck, from 0 to 1 along the face's
+    /// [`uv_axes`](HitFace::uv_axes). For placing decals and particles.
+    pub face_uv: Vec2,
 }
 
 /// A block position in world coordinates.
@@ -97,6 +109,19 @@ pub enum HitFace {
 }
 
 impl HitFace {
+    /// All six faces.
+    pub const ALL: [Self; 6] = [
+        Self::Top,
+        Self::Bottom,
+        Self::North,
+        Self::South,
+        Self::East,
+        Self::West,
+    ];
+
+    /// The four horizontal faces.
+    pub const HORIZONTAL: [Self; 4] = [Self::North, Self::South, Self::East, Self::West];
+
     /// Returns the normal vector for this face.
     #[must_use]
     pub const fn normal(&self) -> [f32; 3] {
@@ -109,6 +134,25 @@ impl HitFace {
             Self::West => [-1.0, 0.0, 0.0],
         }
     }
+
+    /// Returns the normal vector for this face as a [`Vec3`].
+    #[must_use]
+    pub const fn to_vec3(&self) -> Vec3 {
+        let [x, y, z] = self.normal();
+        Vec3::new(x, y, z)
+    }
+
+    /// Returns the world axes (0 = X, 1 = Y, 2 = Z) that U and V run along
+    /// on this face: X and Z on the top and bottom, the horizontal axis and
+    /// Y on the sides.
+    #[must_use]
+    pub const fn uv_axes(&self) -> [usize; 2] {
+        match self {
+            Self::Top | Self::Bottom => [0, 2],
+            Self::North | Self::South => [0, 1],
+            Self::East | Self::West => [2, 1],
+        }
+    }
 }
 
 /// Casts a ray through the voxel world using DDA algorithm.
@@ -121,6 +165,9 @@ impl HitFace {
 ///
 /// # Returns
 /// The first solid block hit, or None if no hit within max_distance.
+///
+/// Only blocks from Y 0 up to [`CHUNK_HEIGHT`] are hit;
+/// [`raycast_with_height`] casts through taller or shorter worlds.
 pub fn raycast<F>(
     origin: Vec3,
     direction: Vec3,
@@ -129,6 +176,152 @@ pub fn raycast<F>(
 ) -> Option<RaycastHit>
 where
     F: Fn(i32, i32, i32) -> bool,
+{
+    raycast_with_height(origin, direction, max_distance, CHUNK_HEIGHT, is_solid)
+}
+
+/// Casts a ray like [`raycast`] through a world `world_height` blocks
+/// tall, so only blocks from Y 0 up to `world_height` are hit.
+pub fn raycast_with_height<F>(
+    origin: Vec3,
+    direction: Vec3,
+    max_distance: f32,
+    world_height: usize,
+    is_solid: F,
+) -> Option<RaycastHit>
+where
+    F: Fn(i32, i32, i32) -> bool,
+{
+    let top = i32::try_from(world_height).unwrap_or(i32::MAX);
+    walk(origin, direction, max_distance, |x, y, z| {
+        Some(y >= 0 && y < top && is_solid(x, y, z))
+    })
+}
+
+/// Casts a ray like [`raycast_with_height`], but against the boxes of each
+/// block's [`BlockShape`], so it passes through the open part of slabs and
+/// stairs.
+///
+/// `shape_at` returns the shape of a block the ray stops at, or `None` for
+/// blocks it passes through.
+pub fn raycast_shapes<F>(
+    origin: Vec3,
+    direction: Vec3,
+    max_distance: f32,
+    world_height: usize,
+    shape_at: F,
+) -> Option<RaycastHit>
+where
+    F: Fn(i32, i32, i32) -> Option<BlockShape>,
+{
+    let top = i32::try_from(world_height).unwrap_or(i32::MAX);
+    let dir = direction.normalize();
+    let mut partial_hit = None;
+    let hit = walk(origin, direction, max_distance, |x, y, z| {
+        let shape = (y >= 0 && y < top).then(|| shape_at(x, y, z)).flatten();
+        match shape {
+            None => Some(false),
+            Some(shape) if shape.is_full_cube() => Some(true),
+            Some(shape) => {
+                let block = BlockPos::new(x, y, z);
+                partial_hit = shape
+                    .world_boxes(block)
+                    .filter_map(|part| enter_box(origin, dir, &part))
+                    .filter(|&(distance, _)| distance < max_distance)
+                    .min_by(|a, b| a.0.total_cmp(&b.0))
+                    .map(|(distance, face)| hit_at(origin, dir, block, distance, face));
+                Some(partial_hit.is_some())
+            }
+        }
+    })?;
+    Some(partial_hit.unwrap_or(hit))
+}
+
+/// Returns how far along `dir` a ray from `origin` enters `aabb`, and the
+/// face it enters through, or `None` if it misses. A ray starting inside
+/// enters at distance 0.
+fn enter_box(origin: Vec3, dir: Vec3, aabb: &Aabb) -> Option<(f32, HitFace)> {
+    let mut near = f32::NEG_INFINITY;
+    let mut far = f32::INFINITY;
+    let mut face = HitFace::Top;
+    for axis in 0..3 {
+        if dir[axis].abs() < 1e-10 {
+            if origin[axis] < aabb.min[axis] || origin[axis] > aabb.max[axis] {
+                return None;
+            }
+            continue;
+        }
+        let to_min = (aabb.min[axis] - origin[axis]) / dir[axis];
+        let to_max = (aabb.max[axis] - origin[axis]) / dir[axis];
+        if to_min.min(to_max) > near {
+            near = to_min.min(to_max);
+            face = match (axis, dir[axis] > 0.0) {
+                (0, true) => HitFace::West,
+                (0, false) => HitFace::East,
+                (1, true) => HitFace::Bottom,
+                (1, false) => HitFace::Top,
+                (_, true) => HitFace::North,
+                (_, false) => HitFace::South,
+            };
+        }
+        far = far.min(to_min.max(to_max));
+    }
+    (near <= far && far >= 0.0).then_some((near.max(0.0), face))
+}
+
+/// Builds the hit on `face` of `block`, `distance` along a ray.
+fn hit_at(origin: Vec3, dir: Vec3, block: BlockPos, distance: f32, face: HitFace) -> RaycastHit {
+    let hit_point = origin + dir * distance;
+    let block_min = Vec3::new(block.x as f32, block.y as f32, block.z as f32);
+    let local = (hit_point - block_min).clamp(Vec3::ZERO, Vec3::ONE);
+    let [u, v] = face.uv_axes();
+    RaycastHit {
+        block_pos: block,
+        face,
+        distance,
+        hit_point,
+        face_uv: Vec2::new(local[u], local[v]),
+    }
+}
+
+/// Casts a ray through a single chunk, like [`raycast`] but reading the
+/// chunk's blocks directly instead of through a closure.
+///
+/// `origin` is in world coordinates and hits are reported in world
+/// coordinates ([`BlockPos::to_local`] gives the chunk-local position). A
+/// ray starting outside the chunk may still enter it; the walk stops as
+/// soon as the ray leaves it.
+pub fn raycast_chunk(
+    chunk: &Chunk,
+    origin: Vec3,
+    direction: Vec3,
+    max_distance: f32,
+) -> Option<RaycastHit> {
+    let (origin_x, origin_z) = chunk.position().block_origin();
+    let mut entered = false;
+    walk(origin, direction, max_distance, |x, y, z| {
+        let local = |c: i32| usize::try_from(c).ok();
+        let block = match (local(x - origin_x), local(y), local(z - origin_z)) {
+            (Some(x), Some(y), Some(z)) => chunk.try_get_block(x, y, z),
+            _ => None,
+        };
+        match block {
+            Some(block) => {
+                entered = true;
+                Some(block.is_solid())
+            }
+            // Outside the chunk: keep going until the ray has passed through
+            None => (!entered).then_some(false),
+        }
+    })
+}
+
+/// Steps a ray through the voxel grid, asking `visit` about each block it
+/// passes through: `Some(true)` for a hit, `Some(false)` to keep going, and
+/// `None` to give up.
+fn walk<F>(origin: Vec3, direction: Vec3, max_distance: f32, mut visit: F) -> Option<RaycastHit>
+where
+    F: FnMut(i32, i32, i32) -> Option<bool>,
 {
     // Handle zero direction components to avoid division by zero
     let dir = direction.normalize();
@@ -183,14 +376,9 @@ where
     // Step through grid
     while distance < max_distance {
         // Check if current block is solid
-        if block_y >= 0 && block_y < 256 && is_solid(block_x, block_y, block_z) {
-            let hit_point = origin + dir * distance;
-            return Some(RaycastHit {
-                block_pos: BlockPos::new(block_x, block_y, block_z),
-                face: last_face,
-                distance,
-                hit_point,
-            });
+        if visit(block_x, block_y, block_z)? {
+            let block = BlockPos::new(block_x, block_y, block_z);
+            return Some(hit_at(origin, dir, block, distance, last_face));
         }
 
         // Move to next block
@@ -241,6 +429,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::world::{Block, ChunkPos};
 
     #[test]
     fn raycast_hits_block_ahead() {
@@ -307,6 +496,86 @@ mod tests {
         assert!(hit.is_none());
     }
 
+    #[test]
+    fn face_uv_locates_the_hit_on_the_face() {
+        let is_solid = |x, y, z| (x, y, z) == (5, 2, -3);
+
+        // Striking the west face a quarter of the way up and across
+        let hit = raycast(Vec3::new(0.0, 2.25, -2.75), Vec3::X, 10.0, is_solid).unwrap();
+        assert_eq!(hit.face, HitFace::West);
+        assert_eq!(hit.face.to_vec3(), Vec3::NEG_X);
+        assert!((hit.face_uv - Vec2::new(0.25, 0.25)).length() < 1e-5);
+
+        // The top face runs along X and Z
+        let hit = raycast(Vec3::new(5.1, 6.0, -2.4), Vec3::NEG_Y, 10.0, is_solid).unwrap();
+        assert_eq!(hit.face, HitFace::Top);
+        assert_eq!(hit.face.to_vec3(), Vec3::Y);
+        assert!((hit.face_uv - Vec2::new(0.1, 0.6)).length() < 1e-5);
+
+        // The south face runs along X and Y
+        let hit = raycast(Vec3::new(5.9, 2.5, 0.0), Vec3::NEG_Z, 10.0, is_solid).unwrap();
+        assert_eq!(hit.face, HitFace::South);
+        assert!((hit.face_uv - Vec2::new(0.9, 0.5)).length() < 1e-5);
+    }
+
+    #[test]
+    fn normals_match_offsets() {
+        let pos = BlockPos::new(0, 10, 0);
+        for face in HitFace::ALL {
+            let next = pos.offset(face);
+            let step = Vec3::new(
+                (next.x - pos.x) as f32,
+                (next.y - pos.y) as f32,
+                (next.z - pos.z) as f32,
+            );
+            assert_eq!(face.to_vec3(), step);
+            // Neither face axis runs along the normal
+            let normal = face.normal();
+            assert!(face.uv_axes().iter().all(|&axis| normal[axis] == 0.0));
+        }
+    }
+
+    #[test]
+    fn raycast_chunk_finds_a_block_at_its_local_position() {
+        // Chunk (-1, 2) spans x -16..0 and z 32..48
+        let mut chunk = Chunk::new(ChunkPos::new(-1, 2));
+        chunk.set_block(3, 70, 9, Block::Stone);
+        chunk.set_block(3, 70, 12, Block::Water); // Not solid
+
+        // From inside the chunk, looking down -Z from beyond the water
+        let origin = Vec3::new(-12.5, 70.5, 32.0 + 14.5);
+        let hit = raycast_chunk(&chunk, origin, Vec3::NEG_Z, 20.0).unwrap();
+        assert_eq!(hit.block_pos, BlockPos::new(-13, 70, 41));
+        assert_eq!(hit.block_pos.to_local(), (3, 70, 9));
+        assert_eq!(hit.face, HitFace::South);
+        assert!((hit.distance - 4.5).abs() < 1e-5);
+
+        // The same as the generic raycast
+        let is_solid = |x, y, z| (x, y, z) == (-13, 70, 41);
+        assert_eq!(raycast(origin, Vec3::NEG_Z, 20.0, is_solid), Some(hit));
+
+        // From outside the chunk, entering through its +X side
+        let hit = raycast_chunk(&chunk, Vec3::new(4.5, 70.5, 41.5), Vec3::NEG_X, 20.0).unwrap();
+        assert_eq!(hit.block_pos.to_local(), (3, 70, 9));
+        assert_eq!(hit.face, HitFace::East);
+    }
+
+    #[test]
+    fn raycast_chunk_stops_when_leaving_the_chunk() {
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.set_block(8, 64, 8, Block::Stone);
+
+        // Passes beside the block and out of the chunk
+        let hit = raycast_chunk(&chunk, Vec3::new(2.5, 65.5, 8.5), Vec3::X, 100.0);
+        assert_eq!(hit, None);
+        // Out of reach
+        let hit = raycast_chunk(&chunk, Vec3::new(2.5, 64.5, 8.5), Vec3::X, 4.0);
+        assert_eq!(hit, None);
+        // Above the world
+        let hit = raycast_chunk(&chunk, Vec3::new(8.5, 300.0, 8.5), Vec3::NEG_Y, 500.0);
+        assert_eq!(hit.map(|hit| hit.block_pos), Some(BlockPos::new(8, 64, 8)));
+    }
+
     #[test]
     fn block_pos_to_chunk() {
         assert_eq!(BlockPos::new(0, 0, 0).to_chunk_pos(), (0, 0));
@@ -335,4 +604,92 @@ mod tests {
         assert_eq!(pos.offset(HitFace::East), BlockPos::new(6, 10, 5));
         assert_eq!(pos.offset(HitFace::West), BlockPos::new(4, 10, 5));
     }
+
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn raycast_vertical_bounds_match_chunk_height() {
+        // Everything is solid, so the ray hits the first in-bounds block
+        let everywhere = |_, _, _| true;
+        let top = CHUNK_HEIGHT as f32;
+
+        let from_above = raycast(
+            Vec3::new(0.5, top + 3.5, 0.5),
+            Vec3::NEG_Y,
+            10.0,
+            everywhere,
+        )
+        .expect("should hit the top layer");
+        assert_eq!(
+            from_above.block_pos.y,
+            i32::try_from(CHUNK_HEIGHT).unwrap() - 1
+        );
+        assert_eq!(from_above.face, HitFace::Top);
+
+        let from_below = raycast(Vec3::new(0.5, -3.5, 0.5), Vec3::Y, 10.0, everywhere)
+            .expect("should hit the bottom layer");
+        assert_eq!(from_below.block_pos.y, 0);
+        assert_eq!(from_below.face, HitFace::Bottom);
+    }
+
+    #[test]
+    fn raycast_with_height_follows_the_world_height() {
+        let everywhere = |_, _, _| true;
+        let from_above = |height| {
+            raycast_with_height(
+                Vec3::new(0.5, 300.5, 0.5),
+                Vec3::NEG_Y,
+                400.0,
+                height,
+                everywhere,
+            )
+            .map(|hit| hit.block_pos.y)
+        };
+        assert_eq!(from_above(512), Some(300));
+        assert_eq!(from_above(64), Some(63));
+    }
+
+    #[test]
+    fn raycast_shapes_hits_the_boxes_of_a_slab() {
+        let slab = |x, y, z| (x, y, z) == (0, 64, 0);
+        let shape_at = |x, y, z| slab(x, y, z).then_some(BlockShape::BottomSlab);
+
+        // From above, the ray falls through the empty top half
+        let hit = raycast_shapes(
+            Vec3::new(0.5, 70.0, 0.5),
+            Vec3::NEG_Y,
+            20.0,
+            CHUNK_HEIGHT,
+            shape_at,
+        )
+        .unwrap();
+        assert_eq!(hit.block_pos, BlockPos::new(0, 64, 0));
+        assert_eq!(hit.face, HitFace::Top);
+        assert!((hit.distance - 5.5).abs() < 1e-5);
+        assert!((hit.hit_point.y - 64.5).abs() < 1e-5);
+
+        // Passing over the slab misses it; through its lower half hits
+        let across = |y| {
+            raycast_shapes(
+                Vec3::new(-3.0, y, 0.5),
+                Vec3::X,
+                10.0,
+                CHUNK_HEIGHT,
+                shape_at,
+            )
+        };
+        assert_eq!(across(64.75), None);
+        let hit = across(64.25).unwrap();
+        assert_eq!(hit.face, HitFace::West);
+        assert!((hit.distance - 3.0).abs() < 1e-5);
+
+        // Full cubes still stop the ray at the block boundary
+        let full = raycast_shapes(
+            Vec3::new(-3.0, 64.75, 0.5),
+            Vec3::X,
+            10.0,
+            CHUNK_HEIGHT,
+            |x, y, z| slab(x, y, z).then_some(BlockShape::FullCube),
+        );
+        assert_eq!(full.map(|hit| hit.block_pos), Some(BlockPos::new(0, 64, 0)));
+    }
 }
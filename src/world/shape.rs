@@ -0,0 +1,140 @@
+//! Block shapes.
+//!
+//! Most blocks fill their whole cell, but slabs and stairs only fill part
+//! of it. A [`BlockShape`] lists the boxes a block is made of, which the
+//! mesher draws and ray casts and collision test against.
+
+use glam::Vec3;
+
+use super::mesh::Face;
+use super::raycast::BlockPos;
+use crate::math::Aabb;
+
+/// The boxes a full cube is made of.
+const FULL_CUBE: [Aabb; 1] = [Aabb::new(Vec3::ZERO, Vec3::ONE)];
+
+/// The boxes a bottom slab is made of.
+const BOTTOM_SLAB: [Aabb; 1] = [Aabb::new(Vec3::ZERO, Vec3::new(1.0, 0.5, 1.0))];
+
+/// The boxes a top slab is made of.
+const TOP_SLAB: [Aabb; 1] = [Aabb::new(Vec3::new(0.0, 0.5, 0.0), Vec3::ONE)];
+
+/// The boxes stairs are made of: a bottom slab, and a step on its north
+/// half.
+const STAIRS: [Aabb; 2] = [
+    Aabb::new(Vec3::ZERO, Vec3::new(1.0, 0.5, 1.0)),
+    Aabb::new(Vec3::new(0.0, 0.5, 0.0), Vec3::new(1.0, 1.0, 0.5)),
+];
+
+/// The shape of a block within its cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlockShape {
+    /// Fills the whole cell.
+    #[default]
+    FullCube,
+    /// Fills the lower half of the cell.
+    BottomSlab,
+    /// Fills the upper half of the cell.
+    TopSlab,
+    /// A bottom slab with a step on top of its north half. No block uses it
+    /// yet, since stairs also need to turn to face the player.
+    Stairs,
+}
+
+impl BlockShape {
+    /// Returns the boxes the shape is made of, in block-local coordinates
+    /// from 0 to 1.
+    #[must_use]
+    pub const fn boxes(self) -> &'static [Aabb] {
+        match self {
+            Self::FullCube => &FULL_CUBE,
+            Self::BottomSlab => &BOTTOM_SLAB,
+            Self::TopSlab => &TOP_SLAB,
+            Self::Stairs => &STAIRS,
+        }
+    }
+
+    /// Returns the boxes of the shape for the block at `pos`, in world
+    /// coordinates.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn world_boxes(self, pos: BlockPos) -> impl Iterator<Item = Aabb> {
+        let min = Vec3::new(pos.x as f32, pos.y as f32, pos.z as f32);
+        self.boxes()
+            .iter()
+            .map(move |local| Aabb::new(min + local.min, min + local.max))
+    }
+
+    /// Returns true if the shape fills its whole cell.
+    #[must_use]
+    pub const fn is_full_cube(self) -> bool {
+        matches!(self, Self::FullCube)
+    }
+
+    /// Returns true if the shape fills the whole `face` side of its cell,
+    /// hiding the face of the block beyond it.
+    #[must_use]
+    pub const fn covers(self, face: Face) -> bool {
+        match self {
+            Self::FullCube => true,
+            Self::BottomSlab => matches!(face, Face::NegY),
+            Self::TopSlab => matches!(face, Face::PosY),
+            Self::Stairs => matches!(face, Face::NegY | Face::NegZ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns true if the shape fills the point `local` of its cell.
+    fn fills(shape: BlockShape, local: Vec3) -> bool {
+        shape
+            .boxes()
+            .iter()
+            .any(|b| local.cmpgt(b.min).all() && local.cmplt(b.max).all())
+    }
+
+    #[test]
+    fn covered_faces_match_the_boxes() {
+        // Points just inside each quarter of a face
+        let samples = |face: Face| {
+            let normal = Vec3::from_array(face.normal());
+            let across = Vec3::ONE - normal.abs();
+            [[0.25, 0.25], [0.25, 0.75], [0.75, 0.25], [0.75, 0.75]].map(|[a, b]| {
+                let mut point = Vec3::splat(0.5) + normal * 0.49;
+                let mut quarters = [a, b].into_iter();
+                for axis in 0..3 {
+                    if across[axis] > 0.0 {
+                        point[axis] = quarters.next().unwrap();
+                    }
+                }
+                point
+            })
+        };
+        let shapes = [
+            BlockShape::FullCube,
+            BlockShape::BottomSlab,
+            BlockShape::TopSlab,
+            BlockShape::Stairs,
+        ];
+        for shape in shapes {
+            for face in Face::ALL {
+                let filled = samples(face).into_iter().all(|p| fills(shape, p));
+                assert_eq!(shape.covers(face), filled, "{shape:?} {face:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn world_boxes_are_offset_to_the_block() {
+        let boxes: Vec<Aabb> = BlockShape::Stairs
+            .world_boxes(BlockPos::new(3, 64, -2))
+            .collect();
+        assert_eq!(boxes.len(), 2);
+        assert_eq!(boxes[0].min, Vec3::new(3.0, 64.0, -2.0));
+        assert_eq!(boxes[0].max, Vec3::new(4.0, 64.5, -1.0));
+        assert_eq!(boxes[1].min, Vec3::new(3.0, 64.5, -2.0));
+        assert_eq!(boxes[1].max, Vec3::new(4.0, 65.0, -1.5));
+    }
+}
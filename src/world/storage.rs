@@ -0,0 +1,970 @@
+//! On-disk chunk storage with an optional mesh cache.
+//!
+//! Each chunk is saved to its own file, `<x>_<z>.chunk`, in the store's
+//! directory. A chunk can be saved together with the meshes generated from
+//! it; the file then also records a hash of the block data and the mesh
+//! format version. On load the cached meshes are only returned while both
+//! still match, so a chunk edited since it was meshed (or a mesher change
+//! that bumps [`MESH_CACHE_VERSION`]) is re-meshed instead.
+//!
+//! # File format
+//!
+//! | Bytes | Contents                                            |
+//! |-------|-----------------------------------------------------|
+//! | 0..4  | Magic, `VFCK`                                       |
+//! | 4..6  | Format version, little endian ([`CHUNK_FORMAT_VERSION`]) |
+//! | 6     | Flags: bit 0 set if the payload is deflate-compressed |
+//! | 7..   | Payload: the bincode-encoded chunk record           |
+//!
+//! Each section stores a palette of the block IDs it uses and one palette
+//! index byte per block; a section of a single block type stores no indices.
+//! Empty sections take a single byte. Version 1 files, which stored raw
+//! block IDs, are migrated on load. Files with any other version are
+//! rejected, so a format change must bump the version (and migrate old
+//! files if they should still load). Compression needs the `compression`
+//! feature, which is on by default.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+
+use parking_lot::{Condvar, Mutex};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::warn;
+
+use super::block::{Block, BlockId};
+use super::chunk::{Chunk, ChunkPos, ChunkSection, MAX_CHUNK_HEIGHT, SECTION_SIZE, SECTION_VOLUME};
+use super::mesh::{ChunkMesh, ChunkVertex, MeshLayers};
+use super::texture_atlas::TextureAtlas;
+
+/// Version of the cached mesh format.
+///
+/// Bump this whenever the mesher or the [`ChunkVertex`] layout changes so
+/// meshes cached by an older build are regenerated.
+pub const MESH_CACHE_VERSION: u32 = 8;
+
+/// Version of the chunk file format.
+pub const CHUNK_FORMAT_VERSION: u16 = 2;
+
+/// Version 1 of the chunk file format, with unpaletted sections.
+const LEGACY_FORMAT_VERSION: u16 = 1;
+
+/// Magic bytes opening every chunk file.
+const MAGIC: [u8; 4] = *b"VFCK";
+
+/// Length of the header before the payload.
+const HEADER_LEN: usize = 7;
+
+/// Header flag: the payload is deflate-compressed.
+const FLAG_COMPRESSED: u8 = 1;
+
+/// Errors from reading or writing chunk files.
+#[derive(Debug, Error)]
+pub enum StorageError {
+    /// The chunk file couldn't be read or written.
+    #[error("chunk file I/O failed: {0}")]
+    Io(#[from] io::Error),
+
+    /// The chunk file couldn't be encoded or decoded.
+    #[error("failed to encode or decode chunk file: {0}")]
+    Encoding(#[from] bincode::Error),
+
+    /// The file doesn't start with a valid chunk file header.
+    #[error("not a chunk file: {0}")]
+    InvalidHeader(&'static str),
+
+    /// The file was written in a format version this build can't read.
+    #[error("chunk format version {found} is not supported (expected {supported})")]
+    UnsupportedVersion {
+        /// The file's version.
+        found: u16,
+        /// The version this build reads and writes.
+        supported: u16,
+    },
+
+    /// The file is compressed but compression support isn't compiled in.
+    #[error("chunk file is compressed but the `compression` feature is disabled")]
+    CompressionDisabled,
+
+    /// Encoded chunk data decoded but is invalid.
+    #[error("chunk data is invalid: {0}")]
+    InvalidData(&'static str),
+
+    /// The chunk file decoded but holds invalid data.
+    #[error("chunk file {path:?} is corrupt: {reason}")]
+    Corrupt {
+        /// The offending file.
+        path: PathBuf,
+        /// What was wrong with it.
+        reason: &'static str,
+    },
+}
+
+/// A chunk file's contents.
+#[derive(Serialize, Deserialize)]
+struct ChunkRecord<S = SectionRecord> {
+    /// Chunk X coordinate.
+    x: i32,
+    /// Chunk Z coordinate.
+    z: i32,
+    /// Sections, bottom to top; `None` for sections never written to.
+    sections: Vec<Option<S>>,
+    /// Meshes generated from this data, if cached.
+    meshes: Option<MeshRecord>,
+}
+
+/// A section's blocks as palette indices, and its metadata.
+#[derive(Serialize, Deserialize)]
+struct SectionRecord {
+    /// The distinct block IDs in the section, in order of first use.
+    palette: Vec<BlockId>,
+    /// Palette index of each block, in storage index order; empty when the
+    /// palette has a single entry.
+    indices: Vec<u8>,
+    /// Metadata nibbles, two per byte.
+    metadata: Vec<u8>,
+}
+
+/// A section's raw block IDs and metadata, as stored by version 1.
+#[derive(Serialize, Deserialize)]
+struct LegacySectionRecord {
+    /// Block IDs.
+    blocks: Vec<BlockId>,
+    /// Metadata nibbles, two per byte.
+    metadata: Vec<u8>,
+}
+
+/// Cached meshes and the block data they were built from.
+#[derive(Serialize, Deserialize)]
+struct MeshRecord {
+    /// [`mesh_version`] when the meshes were cached.
+    version: u32,
+    /// [`content_hash`] of the chunk the meshes were built from.
+    content_hash: u64,
+    /// The opaque layer.
+    opaque: MeshData,
+    /// The transparent layer.
+    transparent: MeshData,
+}
+
+/// A mesh's raw vertex bytes and indices.
+#[derive(Serialize, Deserialize)]
+struct MeshData {
+    /// Vertices as raw [`ChunkVertex`] bytes.
+    vertices: Vec<u8>,
+    /// Triangle indices.
+    indices: Vec<u32>,
+}
+
+impl SectionRecord {
+    /// Captures a section's data.
+    fn encode(section: &ChunkSection) -> Self {
+        let mut palette: Vec<BlockId> = Vec::new();
+        let mut indices: Vec<u8> = section
+            .blocks()
+            .map(|block| {
+                let index = palette.iter().position(|&id| id == block.id());
+                let index = index.unwrap_or_else(|| {
+                    palette.push(block.id());
+                    palette.len() - 1
+                });
+                // The registry holds far fewer than 256 block types
+                u8::try_from(index).expect("palette fits in a byte")
+            })
+            .collect();
+        if palette.len() == 1 {
+            indices.clear();
+        }
+        Self {
+            palette,
+            indices,
+            metadata: section.metadata().to_vec(),
+        }
+    }
+
+    /// Rebuilds the section, or returns None if the data is malformed.
+    fn decode(self) -> Option<ChunkSection> {
+        let palette: Vec<Block> = self
+            .palette
+            .into_iter()
+            .map(Block::from_id)
+            .collect::<Option<_>>()?;
+        let blocks: Vec<Block> = match palette.as_slice() {
+            [block] if self.indices.is_empty() => vec![*block; SECTION_VOLUME],
+            _ => self
+                .indices
+                .iter()
+                .map(|&index| palette.get(usize::from(index)).copied())
+                .collect::<Option<_>>()?,
+        };
+        let blocks: Box<[Block; SECTION_VOLUME]> = blocks.into_boxed_slice().try_into().ok()?;
+        let metadata = self.metadata.into_boxed_slice().try_into().ok()?;
+        Some(ChunkSection::from_raw(&blocks, metadata))
+    }
+}
+
+impl LegacySectionRecord {
+    /// Rebuilds the section, or returns None if the data is malformed.
+    fn decode(self) -> Option<ChunkSection> {
+        let blocks: Vec<Block> = self
+            .blocks
+            .into_iter()
+            .map(Block::from_id)
+            .collect::<Option<_>>()?;
+        let blocks: Box<[Block; SECTION_VOLUME]> = blocks.into_boxed_slice().try_into().ok()?;
+        let metadata = self.metadata.into_boxed_slice().try_into().ok()?;
+        Some(ChunkSection::from_raw(&blocks, metadata))
+    }
+}
+
+impl MeshData {
+    /// Captures a mesh's data.
+    fn encode(mesh: &ChunkMesh) -> Self {
+        Self {
+            vertices: bytemuck::cast_slice(&mesh.vertices).to_vec(),
+            indices: mesh.indices.clone(),
+        }
+    }
+
+    /// Rebuilds the mesh, or returns None if it fails validation.
+    fn decode(self) -> Option<ChunkMesh> {
+        if !self.vertices.len().is_multiple_of(size_of::<ChunkVertex>()) {
+            return None;
+        }
+        let mesh = ChunkMesh {
+            vertices: bytemuck::pod_collect_to_vec(&self.vertices),
+            indices: self.indices,
+        };
+        mesh.validate().is_ok().then_some(mesh)
+    }
+}
+
+impl ChunkRecord {
+    /// Captures a chunk's data along with any cached meshes.
+    fn new(chunk: &Chunk, meshes: Option<MeshRecord>) -> Self {
+        Self {
+            x: chunk.position().x,
+            z: chunk.position().z,
+            sections: (0..chunk.section_count())
+                .map(|y| chunk.get_section(y).map(SectionRecord::encode))
+                .collect(),
+            meshes,
+        }
+    }
+
+    /// Encodes the record as a chunk file, compressing the payload if asked.
+    fn to_bytes(&self, compress: bool) -> Result<Vec<u8>, StorageError> {
+        let payload = bincode::serialize(self)?;
+        let (flags, payload) = if compress {
+            (FLAG_COMPRESSED, deflate(&payload)?)
+        } else {
+            (0, payload)
+        };
+
+        let mut bytes = Vec::with_capacity(HEADER_LEN + payload.len());
+        bytes.extend_from_slice(&MAGIC);
+        bytes.extend_from_slice(&CHUNK_FORMAT_VERSION.to_le_bytes());
+        bytes.push(flags);
+        bytes.extend_from_slice(&payload);
+        Ok(bytes)
+    }
+
+    /// Decodes a chunk file, compressed or not, migrating older versions.
+    fn from_bytes(bytes: &[u8]) -> Result<Self, StorageError> {
+        let (header, payload) = bytes
+            .split_at_checked(HEADER_LEN)
+            .ok_or(StorageError::InvalidHeader("file too short"))?;
+        if header[..4] != MAGIC {
+            return Err(StorageError::InvalidHeader("wrong magic bytes"));
+        }
+        let version = u16::from_le_bytes([header[4], header[5]]);
+        if version != CHUNK_FORMAT_VERSION && version != LEGACY_FORMAT_VERSION {
+            return Err(StorageError::UnsupportedVersion {
+                found: version,
+                supported: CHUNK_FORMAT_VERSION,
+            });
+        }
+
+        let payload = match header[6] {
+            0 => payload.to_vec(),
+            FLAG_COMPRESSED => inflate(payload)?,
+            _ => return Err(StorageError::InvalidHeader("unknown flags")),
+        };
+        if version == CHUNK_FORMAT_VERSION {
+            return Ok(bincode::deserialize(&payload)?);
+        }
+
+        let legacy: ChunkRecord<LegacySectionRecord> = bincode::deserialize(&payload)?;
+        let mut sections = Vec::with_capacity(legacy.sections.len());
+        for section in legacy.sections {
+            let Some(section) = section else {
+                sections.push(None);
+                continue;
+            };
+            let section = section
+                .decode()
+                .ok_or(StorageError::InvalidData("invalid section"))?;
+            sections.push(Some(SectionRecord::encode(&section)));
+        }
+        Ok(Self {
+            x: legacy.x,
+            z: legacy.z,
+            sections,
+            meshes: legacy.meshes,
+        })
+    }
+
+    /// Rebuilds the chunk, or returns why the data is invalid.
+    ///
+    /// The chunk comes back clean, since nothing changed it since it was
+    /// saved.
+    fn into_chunk(self) -> Result<(Chunk, Option<MeshRecord>), &'static str> {
+        let height = self.sections.len() * SECTION_SIZE;
+        if self.sections.is_empty() || height > MAX_CHUNK_HEIGHT {
+            return Err("wrong number of sections");
+        }
+        let mut chunk = Chunk::with_height(ChunkPos::new(self.x, self.z), height);
+        for (section_y, section) in self.sections.into_iter().enumerate() {
+            if let Some(section) = section {
+                let section = section.decode().ok_or("invalid section")?;
+                chunk.set_section(section_y, Some(section));
+            }
+        }
+        chunk.mark_clean();
+        Ok((chunk, self.meshes))
+    }
+}
+
+impl Chunk {
+    /// Encodes the chunk in the chunk file format, uncompressed.
+    ///
+    /// # Panics
+    ///
+    /// Never in practice: encoding only fails for compressed files.
+    #[must_use]
+    pub fn serialize(&self) -> Vec<u8> {
+        ChunkRecord::new(self, None)
+            .to_bytes(false)
+            .expect("uncompressed chunk records always encode")
+    }
+
+    /// Decodes a chunk encoded by [`serialize`](Self::serialize) or read
+    /// from a chunk file. The chunk comes back clean (not dirty).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StorageError`] if the bytes aren't a chunk file of a
+    /// supported version, or hold invalid chunk data.
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, StorageError> {
+        ChunkRecord::from_bytes(bytes)?
+            .into_chunk()
+            .map(|(chunk, _)| chunk)
+            .map_err(StorageError::InvalidData)
+    }
+}
+
+/// Deflate-compresses a payload.
+#[cfg(feature = "compression")]
+fn deflate(payload: &[u8]) -> Result<Vec<u8>, StorageError> {
+    use std::io::Write;
+
+    let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::fast());
+    encoder.write_all(payload)?;
+    Ok(encoder.finish()?)
+}
+
+/// Decompresses a deflate-compressed payload.
+#[cfg(feature = "compression")]
+fn inflate(data: &[u8]) -> Result<Vec<u8>, StorageError> {
+    use std::io::Read;
+
+    let mut payload = Vec::new();
+    flate2::read::DeflateDecoder::new(data).read_to_end(&mut payload)?;
+    Ok(payload)
+}
+
+/// Compression isn't compiled in.
+#[cfg(not(feature = "compression"))]
+const fn deflate(_payload: &[u8]) -> Result<Vec<u8>, StorageError> {
+    Err(StorageError::CompressionDisabled)
+}
+
+/// Compression isn't compiled in.
+#[cfg(not(feature = "compression"))]
+const fn inflate(_data: &[u8]) -> Result<Vec<u8>, StorageError> {
+    Err(StorageError::CompressionDisabled)
+}
+
+/// Version recorded with cached meshes: [`MESH_CACHE_VERSION`] with the
+/// atlas row count in the high bits, since mesh UVs are relative to the atlas
+/// height and move when registering blocks grows it.
+fn mesh_version() -> u32 {
+    MESH_CACHE_VERSION | (TextureAtlas::rows() << 16)
+}
+
+/// Hashes a chunk's blocks and metadata (FNV-1a), to tell whether a cached
+/// mesh was built from the same data.
+fn content_hash(chunk: &Chunk) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+    let mut hash = FNV_OFFSET;
+    let mut write = |bytes: &[u8]| {
+        for &byte in bytes {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    };
+    for section_y in 0..chunk.section_count() {
+        if let Some(section) = chunk.get_section(section_y) {
+            write(&[1]);
+            for block in section.blocks() {
+                write(&block.id().to_le_bytes());
+            }
+            write(section.metadata());
+        } else {
+            write(&[0]);
+        }
+    }
+    hash
+}
+
+/// A chunk read back from disk.
+pub struct StoredChunk {
+    /// The chunk data.
+    pub chunk: Chunk,
+    /// The cached meshes, if present and still valid for the data.
+    pub meshes: Option<MeshLayers>,
+}
+
+/// A directory of saved chunks.
+#[derive(Debug, Clone)]
+pub struct ChunkStore {
+    /// Directory holding the chunk files.
+    dir: PathBuf,
+    /// Whether saved files are compressed.
+    compress: bool,
+}
+
+impl ChunkStore {
+    /// Creates a store backed by `dir`. The directory is created on first save.
+    ///
+    /// Files are compressed when the `compression` feature is enabled.
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            compress: cfg!(feature = "compression"),
+        }
+    }
+
+    /// Sets whether saved files are compressed. Either kind of file loads.
+    #[must_use]
+    pub const fn with_compression(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+
+    /// Returns the directory holding the chunk files.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the file a chunk is saved to.
+    fn chunk_path(&self, pos: ChunkPos) -> PathBuf {
+        self.dir.join(format!("{}_{}.chunk", pos.x, pos.z))
+    }
+
+    /// Returns true if a chunk has been saved at `pos`.
+    #[must_use]
+    pub fn contains(&self, pos: ChunkPos) -> bool {
+        self.chunk_path(pos).is_file()
+    }
+
+    /// Saves a chunk, caching `meshes` alongside it if given.
+    ///
+    /// Without meshes, any mesh already cached for the chunk is kept; it is
+    /// discarded on load if the block data no longer matches.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StorageError`] if the file can't be encoded, compressed
+    /// or written.
+    pub fn save(&self, chunk: &Chunk, meshes: Option<&MeshLayers>) -> Result<(), StorageError> {
+        let path = self.chunk_path(chunk.position());
+        let meshes = meshes
+            .map(|layers| MeshRecord {
+                version: mesh_version(),
+                content_hash: content_hash(chunk),
+                opaque: MeshData::encode(&layers.opaque),
+                transparent: MeshData::encode(&layers.transparent),
+            })
+            // A stale or unreadable file just means there's nothing to keep
+            .or_else(|| {
+                Self::read_record(&path)
+                    .ok()
+                    .flatten()
+                    .and_then(|record| record.meshes)
+            });
+
+        let bytes = ChunkRecord::new(chunk, meshes).to_bytes(self.compress)?;
+
+        // Write then rename, so a crash mid-save never leaves a torn file
+        fs::create_dir_all(&self.dir)?;
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, bytes)?;
+        fs::rename(temp, path)?;
+        Ok(())
+    }
+
+    /// Reads and decodes a chunk file, or returns None if it doesn't exist.
+    fn read_record(path: &Path) -> Result<Option<ChunkRecord>, StorageError> {
+        match fs::read(path) {
+            Ok(bytes) => ChunkRecord::from_bytes(&bytes).map(Some),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Loads the chunk saved at `pos`, or returns None if there isn't one.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StorageError`] if the file can't be read or is corrupt.
+    pub fn load(&self, pos: ChunkPos) -> Result<Option<StoredChunk>, StorageError> {
+        let path = self.chunk_path(pos);
+        let Some(record) = Self::read_record(&path)? else {
+            return Ok(None);
+        };
+        let corrupt = |reason| StorageError::Corrupt {
+            path: path.clone(),
+            reason,
+        };
+
+        if (record.x, record.z) != (pos.x, pos.z) {
+            return Err(corrupt("position doesn't match file name"));
+        }
+        let (chunk, meshes) = record.into_chunk().map_err(corrupt)?;
+
+        let meshes = meshes
+            .filter(|cached| {
+                cached.version == mesh_version() && cached.content_hash == content_hash(&chunk)
+            })
+            .map(|cached| {
+                Ok::<_, StorageError>(MeshLayers {
+                    opaque: cached
+                        .opaque
+                        .decode()
+                        .ok_or_else(|| corrupt("invalid mesh"))?,
+                    transparent: cached
+                        .transparent
+                        .decode()
+                        .ok_or_else(|| corrupt("invalid mesh"))?,
+                })
+            })
+            .transpose()?;
+
+        Ok(Some(StoredChunk { chunk, meshes }))
+    }
+
+    /// Loads the chunk saved at `pos` with its meshes, or returns None if
+    /// there isn't one.
+    ///
+    /// `mesh` is only called when the cached meshes are missing or stale;
+    /// the fresh meshes are then written back to the cache.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StorageError`] if the file can't be read, is corrupt, or
+    /// the refreshed cache can't be written.
+    pub fn load_meshed<F>(
+        &self,
+        pos: ChunkPos,
+        mesh: F,
+    ) -> Result<Option<(Chunk, MeshLayers)>, StorageError>
+    where
+        F: FnOnce(&Chunk) -> MeshLayers,
+    {
+        let Some(stored) = self.load(pos)? else {
+            return Ok(None);
+        };
+        if let Some(meshes) = stored.meshes {
+            return Ok(Some((stored.chunk, meshes)));
+        }
+
+        let meshes = mesh(&stored.chunk);
+        self.save(&stored.chunk, Some(&meshes))?;
+        Ok(Some((stored.chunk, meshes)))
+    }
+}
+
+/// Saves chunks on a background thread, so encoding, compression and disk
+/// writes stay off the caller's thread.
+///
+/// Saves of the same chunk are written in the order they were queued.
+/// While a chunk has a save queued, [`ChunkSaver::is_pending`] returns true;
+/// reload it only after that clears, or the load may see the older file.
+pub struct ChunkSaver {
+    /// Queue of chunks to save; `None` once the saver shuts down.
+    sender: Option<Sender<Chunk>>,
+    /// The save thread, which returns how many chunks it saved.
+    thread: Option<JoinHandle<usize>>,
+    /// Queued save counts and the first failure, shared with the thread.
+    state: Arc<(Mutex<SaverState>, Condvar)>,
+}
+
+/// State shared between a [`ChunkSaver`] and its thread.
+#[derive(Default)]
+struct SaverState {
+    /// Number of queued saves per chunk.
+    pending: HashMap<ChunkPos, usize>,
+    /// The first save that failed since the last flush.
+    error: Option<StorageError>,
+}
+
+impl ChunkSaver {
+    /// Starts a save thread writing to `store`.
+    #[must_use]
+    pub fn new(store: ChunkStore) -> Self {
+        let (sender, receiver) = mpsc::channel::<Chunk>();
+        let state = Arc::new((Mutex::new(SaverState::default()), Condvar::new()));
+
+        let thread_state = Arc::clone(&state);
+        let thread = thread::spawn(move || {
+            let mut saved = 0;
+            for chunk in receiver {
+                let pos = chunk.position();
+                let result = store.save(&chunk, None);
+
+                let (lock, done) = &*thread_state;
+                let mut state = lock.lock();
+                match result {
+                    Ok(()) => saved += 1,
+                    Err(err) => {
+                        warn!("Failed to save chunk {pos:?}: {err}");
+                        state.error.get_or_insert(err);
+                    }
+                }
+                if let Some(count) = state.pending.get_mut(&pos) {
+                    *count -= 1;
+                    if *count == 0 {
+                        state.pending.remove(&pos);
+                    }
+                }
+                drop(state);
+                done.notify_all();
+            }
+            saved
+        });
+
+        Self {
+            sender: Some(sender),
+            thread: Some(thread),
+            state,
+        }
+    }
+
+    /// Queues a chunk to be saved.
+    pub fn save(&self, chunk: Chunk) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        let pos = chunk.position();
+        *self.state.0.lock().pending.entry(pos).or_default() += 1;
+        if sender.send(chunk).is_err() {
+            // The thread is gone (it panicked); don't leave the chunk pending forever
+            self.state.0.lock().pending.remove(&pos);
+            warn!("Chunk save thread stopped; chunk {pos:?} was not saved");
+        }
+    }
+
+    /// Returns true if a save of this chunk is queued or in progress.
+    #[must_use]
+    pub fn is_pending(&self, pos: ChunkPos) -> bool {
+        self.state.0.lock().pending.contains_key(&pos)
+    }
+
+    /// Returns the number of queued saves.
+    #[must_use]
+    pub fn pending_count(&self) -> usize {
+        self.state.0.lock().pending.values().sum()
+    }
+
+    /// Blocks until every queued save has been written.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`StorageError`] since the last flush.
+    pub fn flush(&self) -> Result<(), StorageError> {
+        let (lock, done) = &*self.state;
+        let mut state = lock.lock();
+        while !state.pending.is_empty() {
+            done.wait(&mut state);
+        }
+        state.error.take().map_or(Ok(()), Err)
+    }
+
+    /// Writes every queued save, stops the thread and returns how many
+    /// chunks it saved in total.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`StorageError`] since the last flush.
+    pub fn finish(mut self) -> Result<usize, StorageError> {
+        let saved = self.shut_down();
+        self.state.0.lock().error.take().map_or(Ok(saved), Err)
+    }
+
+    /// Closes the queue and joins the thread once it has drained it.
+    fn shut_down(&mut self) -> usize {
+        drop(self.sender.take());
+        self.thread
+            .take()
+            .map_or(0, |thread| thread.join().unwrap_or(0))
+    }
+}
+
+impl Drop for ChunkSaver {
+    fn drop(&mut self) {
+        self.shut_down();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::chunk::SECTIONS_PER_CHUNK;
+    use crate::world::mesh::MeshGenerator;
+
+    /// Creates an empty store in a fresh temporary directory.
+    fn temp_store(name: &str) -> ChunkStore {
+        let dir = std::env::temp_dir().join(format!("voxel-forge-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        ChunkStore::new(dir)
+    }
+
+    fn mesh(chunk: &Chunk) -> MeshLayers {
+        MeshGenerator::new(chunk.clone()).generate_layers()
+    }
+
+    #[test]
+    fn chunk_data_roundtrips() {
+        let store = temp_store("roundtrip");
+        let mut chunk = Chunk::new(ChunkPos::new(-2, 5));
+        chunk.fill_test_pattern();
+        chunk.set_block(3, 70, 9, Block::Water);
+        chunk.set_metadata(3, 70, 9, 5);
+
+        assert!(store.load(ChunkPos::new(-2, 5)).unwrap().is_none());
+        store.save(&chunk, None).unwrap();
+        assert!(store.contains(ChunkPos::new(-2, 5)));
+
+        let loaded = store.load(ChunkPos::new(-2, 5)).unwrap().unwrap();
+        assert!(loaded.meshes.is_none());
+        assert_eq!(loaded.chunk.get_block(3, 70, 9), Block::Water);
+        assert_eq!(loaded.chunk.get_metadata(3, 70, 9), 5);
+        assert_eq!(content_hash(&loaded.chunk), content_hash(&chunk));
+
+        let _ = fs::remove_dir_all(store.dir());
+    }
+
+    #[test]
+    fn serialized_chunk_roundtrips_clean() {
+        let mut chunk = Chunk::new(ChunkPos::new(7, -3));
+        chunk.fill_test_pattern();
+        chunk.set_metadata(4, 64, 4, 2);
+        assert!(chunk.is_dirty());
+
+        let loaded = Chunk::deserialize(&chunk.serialize()).unwrap();
+        assert_eq!(loaded.position(), chunk.position());
+        assert!(!loaded.is_dirty());
+        for section_y in 0..SECTIONS_PER_CHUNK {
+            let (original, loaded) = (chunk.get_section(section_y), loaded.get_section(section_y));
+            assert_eq!(original.is_some(), loaded.is_some());
+            if let (Some(original), Some(loaded)) = (original, loaded) {
+                assert!(original.blocks().eq(loaded.blocks()));
+                assert_eq!(original.metadata(), loaded.metadata());
+            }
+        }
+
+        assert!(matches!(
+            Chunk::deserialize(b"VFCK"),
+            Err(StorageError::InvalidHeader(_))
+        ));
+    }
+
+    #[test]
+    fn chunks_keep_their_height_through_storage() {
+        for height in [32, 512] {
+            let mut chunk = Chunk::with_height(ChunkPos::new(2, 5), height);
+            chunk.set_block(1, height - 1, 2, Block::Glass);
+
+            let loaded = Chunk::deserialize(&chunk.serialize()).unwrap();
+            assert_eq!(loaded.height(), height);
+            assert_eq!(loaded.get_block(1, height - 1, 2), Block::Glass);
+        }
+    }
+
+    #[test]
+    fn empty_and_uniform_sections_encode_compactly() {
+        // Position, section count and mesh flag around one byte per section
+        let empty = Chunk::new(ChunkPos::new(0, 0)).serialize();
+        assert_eq!(empty.len(), HEADER_LEN + 8 + 8 + SECTIONS_PER_CHUNK + 1);
+
+        // A single-block section stores its palette and metadata, no indices
+        let mut chunk = Chunk::new(ChunkPos::new(0, 0));
+        chunk.set_section(0, Some(ChunkSection::filled(Block::Stone)));
+        let section = SectionRecord::encode(chunk.get_section(0).unwrap());
+        assert_eq!(section.palette, [Block::Stone.id()]);
+        assert!(section.indices.is_empty());
+        assert!(chunk.serialize().len() < empty.len() + SECTION_VOLUME);
+    }
+
+    #[test]
+    fn version_1_files_are_migrated() {
+        let mut chunk = Chunk::new(ChunkPos::new(1, 2));
+        chunk.fill_test_pattern();
+        let legacy = ChunkRecord {
+            x: 1,
+            z: 2,
+            sections: (0..SECTIONS_PER_CHUNK)
+                .map(|y| {
+                    chunk.get_section(y).map(|section| LegacySectionRecord {
+                        blocks: section.blocks().map(Block::id).collect(),
+                        metadata: section.metadata().to_vec(),
+                    })
+                })
+                .collect(),
+            meshes: None,
+        };
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&LEGACY_FORMAT_VERSION.to_le_bytes());
+        bytes.push(0);
+        bytes.extend_from_slice(&bincode::serialize(&legacy).unwrap());
+
+        let loaded = Chunk::deserialize(&bytes).unwrap();
+        assert_eq!(content_hash(&loaded), content_hash(&chunk));
+    }
+
+    #[test]
+    fn unknown_format_version_is_rejected() {
+        let chunk = Chunk::new(ChunkPos::new(0, 0));
+        let mut bytes = ChunkRecord::new(&chunk, None).to_bytes(false).unwrap();
+        bytes[4..6].copy_from_slice(&(CHUNK_FORMAT_VERSION + 1).to_le_bytes());
+
+        assert!(matches!(
+            ChunkRecord::from_bytes(&bytes),
+            Err(StorageError::UnsupportedVersion { found, supported })
+                if found == CHUNK_FORMAT_VERSION + 1 && supported == CHUNK_FORMAT_VERSION
+        ));
+        assert!(matches!(
+            ChunkRecord::from_bytes(b"PNG"),
+            Err(StorageError::InvalidHeader(_))
+        ));
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn compressed_and_plain_files_both_load() {
+        let mut chunk = Chunk::new(ChunkPos::new(4, -4));
+        chunk.fill_test_pattern();
+        let plain = ChunkRecord::new(&chunk, None).to_bytes(false).unwrap();
+        let compressed = ChunkRecord::new(&chunk, None).to_bytes(true).unwrap();
+        assert!(compressed.len() < plain.len() / 4);
+
+        // Through the store: compress, write, read back, decompress
+        let store = temp_store("compressed");
+        store.save(&chunk, None).unwrap();
+        let on_disk = fs::read(store.chunk_path(chunk.position())).unwrap();
+        assert_eq!(on_disk[6], FLAG_COMPRESSED);
+        let loaded = store.load(chunk.position()).unwrap().unwrap().chunk;
+        assert_eq!(content_hash(&loaded), content_hash(&chunk));
+
+        // A store writing plain files still reads the compressed one
+        let plain_store = ChunkStore::new(store.dir()).with_compression(false);
+        let loaded = plain_store.load(chunk.position()).unwrap().unwrap().chunk;
+        assert_eq!(content_hash(&loaded), content_hash(&chunk));
+
+        let _ = fs::remove_dir_all(store.dir());
+    }
+
+    #[cfg(not(feature = "compression"))]
+    #[test]
+    fn compressing_without_the_feature_is_an_error() {
+        let chunk = Chunk::new(ChunkPos::new(0, 0));
+        assert!(matches!(
+            ChunkRecord::new(&chunk, None).to_bytes(true),
+            Err(StorageError::CompressionDisabled)
+        ));
+    }
+
+    #[test]
+    fn saver_writes_queued_chunks_in_background() {
+        let store = temp_store("saver");
+        let saver = ChunkSaver::new(store.clone());
+        let mut chunk = Chunk::new(ChunkPos::new(0, 3));
+        chunk.set_block(1, 1, 1, Block::Stone);
+        saver.save(chunk.clone());
+        chunk.set_block(1, 1, 1, Block::Glass);
+        saver.save(chunk.clone());
+        saver.save(Chunk::new(ChunkPos::new(1, 3)));
+
+        saver.flush().unwrap();
+        assert!(!saver.is_pending(chunk.position()));
+        assert_eq!(saver.pending_count(), 0);
+        // The later save of the same chunk wins
+        let loaded = store.load(chunk.position()).unwrap().unwrap().chunk;
+        assert_eq!(loaded.get_block(1, 1, 1), Block::Glass);
+        assert_eq!(saver.finish().unwrap(), 3);
+
+        let _ = fs::remove_dir_all(store.dir());
+    }
+
+    #[test]
+    fn cached_mesh_skips_regeneration_until_edited() {
+        let store = temp_store("mesh-cache");
+        let pos = ChunkPos::new(1, 1);
+        let mut chunk = Chunk::new(pos);
+        chunk.fill_test_pattern();
+        let layers = mesh(&chunk);
+        store.save(&chunk, Some(&layers)).unwrap();
+
+        // Unchanged data, even re-saved without meshes, uses the cache
+        store.save(&chunk, None).unwrap();
+        let mut calls = 0;
+        let (_, cached) = store
+            .load_meshed(pos, |c| {
+                calls += 1;
+                mesh(c)
+            })
+            .unwrap()
+            .unwrap();
+        assert_eq!(calls, 0);
+        assert_eq!(cached.opaque.vertices.len(), layers.opaque.vertices.len());
+        assert_eq!(cached.opaque.indices, layers.opaque.indices);
+
+        // An edit invalidates the cached mesh
+        chunk.set_block(8, 100, 8, Block::Stone);
+        store.save(&chunk, None).unwrap();
+        let (_, fresh) = store
+            .load_meshed(pos, |c| {
+                calls += 1;
+                mesh(c)
+            })
+            .unwrap()
+            .unwrap();
+        assert_eq!(calls, 1);
+        assert!(fresh.opaque.vertices.len() > layers.opaque.vertices.len());
+
+        // ...and the regenerated mesh is cached for next time
+        assert!(store.load(pos).unwrap().unwrap().meshes.is_some());
+
+        let _ = fs::remove_dir_all(store.dir());
+    }
+}
@@ -0,0 +1,257 @@
+//! Structures stamped into the world, such as trees and ore veins.
+//!
+//! A [`Structure`] lists the blocks it places relative to an origin, so the
+//! same structure can be written into a single chunk during generation or
+//! into a [`ChunkManager`], where it may span chunk borders. Blocks that
+//! land in unloaded chunks are held by the manager and placed when those
+//! chunks load.
+
+use super::block::Block;
+use super::chunk_manager::ChunkManager;
+use super::raycast::BlockPos;
+use super::terrain::hash;
+
+/// One block of a structure, and what it may overwrite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StructureBlock {
+    /// World position of the block.
+    pub pos: BlockPos,
+    /// The block to place.
+    pub block: Block,
+    /// The only block this may replace, or `None` to replace anything.
+    pub replaces: Option<Block>,
+}
+
+impl StructureBlock {
+    /// Creates a block that replaces whatever is there.
+    #[must_use]
+    pub const fn new(pos: BlockPos, block: Block) -> Self {
+        Self {
+            pos,
+            block,
+            replaces: None,
+        }
+    }
+
+    /// Only replaces `existing`, leaving any other block alone.
+    #[must_use]
+    pub const fn replacing(mut self, existing: Block) -> Self {
+        self.replaces = Some(existing);
+        self
+    }
+
+    /// Returns true if this may be placed over `existing`.
+    #[must_use]
+    pub fn can_replace(&self, existing: Block) -> bool {
+        self.replaces.is_none_or(|only| only == existing)
+    }
+}
+
+/// A group of blocks placed together.
+pub trait Structure {
+    /// Returns the blocks of the structure grown from `origin`, in the
+    /// order they're placed. Later blocks see earlier ones when checking
+    /// what they replace.
+    fn blocks(&self, origin: BlockPos) -> Vec<StructureBlock>;
+
+    /// Places the structure into `manager`, returning the number of blocks
+    /// placed now. Blocks in unloaded chunks are placed once those chunks
+    /// load; blocks outside the world are dropped.
+    fn place(&self, manager: &mut ChunkManager, origin: BlockPos) -> usize {
+        self.blocks(origin)
+            .into_iter()
+            .filter(|&block| manager.place_structure_block(block))
+            .count()
+    }
+}
+
+/// A tree: a log trunk topped with a rough sphere of leaves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tree {
+    /// Number of logs in the trunk.
+    pub trunk: i32,
+}
+
+impl Tree {
+    /// Squared radius of the leaf sphere around the top of the trunk.
+    const LEAF_RADIUS_SQUARED: i32 = 6;
+
+    /// Creates a tree with a trunk of `trunk` logs.
+    #[must_use]
+    pub const fn new(trunk: i32) -> Self {
+        Self { trunk }
+    }
+}
+
+impl Structure for Tree {
+    /// The trunk starts at `origin`; leaves only fill air, so they don't
+    /// replace the trunk or the ground.
+    fn blocks(&self, origin: BlockPos) -> Vec<StructureBlock> {
+        let mut blocks: Vec<_> = (0..self.trunk)
+            .map(|dy| {
+                StructureBlock::new(BlockPos::new(origin.x, origin.y + dy, origin.z), Block::Log)
+            })
+            .collect();
+
+        let top = origin.y + self.trunk - 1;
+        for dy in -2..=2 {
+            for dx in -2..=2 {
+                for dz in -2..=2 {
+                    if dx * dx + dy * dy + dz * dz <= Self::LEAF_RADIUS_SQUARED {
+                        let pos = BlockPos::new(origin.x + dx, top + dy, origin.z + dz);
+                        blocks.push(StructureBlock::new(pos, Block::Leaves).replacing(Block::Air));
+                    }
+                }
+            }
+        }
+        blocks
+    }
+}
+
+/// A winding vein of ore through stone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OreVein {
+    /// The ore block.
+    pub ore: Block,
+    /// Number of steps the vein takes from its origin.
+    pub length: u32,
+    /// Seed for the vein's path; the same seed and origin give the same vein.
+    pub seed: u32,
+}
+
+impl OreVein {
+    /// Creates a vein of `ore` taking `length` steps.
+    #[must_use]
+    pub const fn new(ore: Block, length: u32, seed: u32) -> Self {
+        Self { ore, length, seed }
+    }
+}
+
+impl Structure for OreVein {
+    /// Each step moves one block along an axis picked by hashing the seed,
+    /// origin and step. Ore only replaces stone.
+    fn blocks(&self, origin: BlockPos) -> Vec<StructureBlock> {
+        let mut pos = origin;
+        let mut blocks = vec![StructureBlock::new(pos, self.ore).replacing(Block::Stone)];
+        for step in 0..self.length {
+            let roll = hash(self.seed ^ step, origin.x, origin.y, origin.z);
+            let delta = if roll & 1 == 0 { 1 } else { -1 };
+            match (roll >> 1) & 3 {
+                0 => pos.y += delta,
+                1 | 2 => pos.x += delta,
+                _ => pos.z += delta,
+            }
+            blocks.push(StructureBlock::new(pos, self.ore).replacing(Block::Stone));
+        }
+        blocks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::chunk::ChunkSection;
+    use crate::world::{Chunk, ChunkManagerConfig, ChunkPos};
+
+    fn load_stone_chunk(manager: &mut ChunkManager, pos: ChunkPos) {
+        let mut chunk = Chunk::new(pos);
+        chunk.set_section(0, Some(ChunkSection::filled(Block::Stone)));
+        manager.insert_chunk(chunk);
+    }
+
+    #[test]
+    fn tree_at_a_chunk_border_spans_both_chunks() {
+        let mut manager = ChunkManager::new(ChunkManagerConfig::default());
+        for x in [0, 1] {
+            load_stone_chunk(&mut manager, ChunkPos::new(x, 0));
+        }
+        manager.take_dirty_chunks();
+
+        let tree = Tree::new(5);
+        let placed = tree.place(&mut manager, BlockPos::new(15, 16, 8));
+        // Leaves don't replace the top three logs of the trunk
+        assert_eq!(placed, tree.blocks(BlockPos::new(15, 16, 8)).len() - 3);
+
+        for y in 16..21 {
+            assert_eq!(manager.get_block(15, y, 8), Some(Block::Log));
+        }
+        // Leaves on both sides of the border, around the top of the trunk
+        assert_eq!(manager.get_block(13, 20, 8), Some(Block::Leaves));
+        assert_eq!(manager.get_block(17, 20, 8), Some(Block::Leaves));
+        assert_eq!(manager.get_block(16, 22, 8), Some(Block::Leaves));
+        assert_eq!(manager.get_block(15, 21, 8), Some(Block::Leaves));
+
+        let mut dirty = manager.take_dirty_chunks();
+        dirty.sort_by_key(|pos| pos.x);
+        assert_eq!(dirty, vec![ChunkPos::new(0, 0), ChunkPos::new(1, 0)]);
+    }
+
+    #[test]
+    fn blocks_in_unloaded_chunks_wait_for_them_to_load() {
+        let mut manager = ChunkManager::new(ChunkManagerConfig::default());
+        load_stone_chunk(&mut manager, ChunkPos::new(0, 0));
+
+        let tree = Tree::new(4);
+        let origin = BlockPos::new(15, 16, 8);
+        let placed = tree.place(&mut manager, origin);
+        let east = tree
+            .blocks(origin)
+            .iter()
+            .filter(|block| block.pos.x >= 16)
+            .count();
+        assert_eq!(placed, tree.blocks(origin).len() - east - 3);
+        assert_eq!(manager.get_block(17, 19, 8), None);
+
+        load_stone_chunk(&mut manager, ChunkPos::new(1, 0));
+        assert_eq!(manager.get_block(17, 19, 8), Some(Block::Leaves));
+        assert_eq!(manager.get_block(16, 19, 8), Some(Block::Leaves));
+    }
+
+    #[test]
+    fn leaves_only_fill_air() {
+        let mut manager = ChunkManager::new(ChunkManagerConfig::default());
+        load_stone_chunk(&mut manager, ChunkPos::new(0, 0));
+
+        // Planted with its crown in the stone
+        Tree::new(3).place(&mut manager, BlockPos::new(8, 12, 8));
+        assert_eq!(manager.get_block(8, 14, 8), Some(Block::Log));
+        assert_eq!(manager.get_block(10, 14, 8), Some(Block::Stone));
+        assert_eq!(manager.get_block(8, 16, 8), Some(Block::Leaves));
+    }
+
+    #[test]
+    fn ore_veins_are_deterministic_and_only_replace_stone() {
+        let vein = OreVein::new(Block::IronOre, 12, 42);
+        let origin = BlockPos::new(8, 14, 8);
+        assert_eq!(vein.blocks(origin), vein.blocks(origin));
+        assert_ne!(
+            vein.blocks(origin),
+            OreVein::new(Block::IronOre, 12, 43).blocks(origin)
+        );
+
+        let blocks = vein.blocks(origin);
+        assert_eq!(blocks.len(), 13);
+        // Each step moves to an adjacent block
+        for pair in blocks.windows(2) {
+            let (a, b) = (pair[0].pos, pair[1].pos);
+            let steps = a.x.abs_diff(b.x) + a.y.abs_diff(b.y) + a.z.abs_diff(b.z);
+            assert_eq!(steps, 1);
+        }
+
+        let mut manager = ChunkManager::new(ChunkManagerConfig::default());
+        load_stone_chunk(&mut manager, ChunkPos::new(0, 0));
+        manager.set_block(origin.x, origin.y, origin.z, Block::Dirt);
+        vein.place(&mut manager, origin);
+        assert_eq!(
+            manager.get_block(origin.x, origin.y, origin.z),
+            Some(Block::Dirt)
+        );
+        // Everywhere else the vein ran through stone
+        for block in &blocks[1..] {
+            let found = manager.get_block(block.pos.x, block.pos.y, block.pos.z);
+            if block.pos != origin && block.pos.y < 16 && found.is_some() {
+                assert_eq!(found, Some(Block::IronOre));
+            }
+        }
+    }
+}
@@ -0,0 +1,381 @@
+//! Procedural terrain generation.
+//!
+//! Terrain is a function of world coordinates and the seed alone, so
+//! neighboring chunks line up without knowing about each other. Surface
+//! heights come from fractal value noise; stone gets ore veins from 3D
+//! noise, and trees are placed by hashing the column. Biomes come from two
+//! more noise fields, much larger than the hills.
+
+use super::biome::Biome;
+use super::block::Block;
+use super::chunk::{CHUNK_HEIGHT, Chunk, ChunkPos, SECTION_SIZE, sections_for_height};
+use super::raycast::BlockPos;
+use super::structure::{Structure, Tree};
+
+/// Lowest surface height.
+const BASE_HEIGHT: usize = 56;
+
+/// Blocks of variation above [`BASE_HEIGHT`].
+const HEIGHT_RANGE: f32 = 22.0;
+
+/// Horizontal size of the largest hills, in blocks.
+const HEIGHT_SCALE: f32 = 96.0;
+
+/// Noise layers summed for the surface, each half the size of the last.
+const HEIGHT_OCTAVES: u32 = 4;
+
+/// Depth of filler (dirt or sand) below the surface block.
+const DIRT_DEPTH: usize = 4;
+
+/// Size of ore blobs, in blocks.
+const ORE_SCALE: f32 = 3.0;
+
+/// Ores as (block, highest Y, noise threshold), rarest first.
+///
+/// Stone becomes the first ore whose noise is above its threshold and whose
+/// ceiling it is below; higher thresholds make smaller, rarer veins.
+const ORES: [(Block, usize, f32); 4] = [
+    (Block::DiamondOre, 16, 0.86),
+    (Block::GoldOre, 32, 0.84),
+    (Block::IronOre, 64, 0.8),
+    (Block::CoalOre, CHUNK_HEIGHT, 0.78),
+];
+
+/// Horizontal size of biomes, in blocks.
+const BIOME_SCALE: f32 = 256.0;
+
+/// Generates chunk terrain from a seed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerrainGenerator {
+    /// World seed; each seed generates different terrain.
+    seed: u32,
+    /// Height of generated chunks in blocks.
+    height: usize,
+}
+
+impl TerrainGenerator {
+    /// Creates a generator for `seed`, making chunks [`CHUNK_HEIGHT`]
+    /// blocks tall.
+    #[must_use]
+    pub const fn new(seed: u32) -> Self {
+        Self {
+            seed,
+            height: CHUNK_HEIGHT,
+        }
+    }
+
+    /// Makes chunks `height` blocks tall, rounded up to whole sections.
+    /// The surface is capped just below the top of shorter worlds.
+    #[must_use]
+    pub const fn with_height(mut self, height: usize) -> Self {
+        self.height = sections_for_height(height) * SECTION_SIZE;
+        self
+    }
+
+    /// Returns the height of generated chunks in blocks.
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the world seed.
+    #[must_use]
+    pub const fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    /// Returns the Y of the surface block in the column at world `x`, `z`.
+    #[must_use]
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    pub fn height_at(&self, x: i32, z: i32) -> usize {
+        let (x, z) = (x as f32 / HEIGHT_SCALE, z as f32 / HEIGHT_SCALE);
+        let mut sum = 0.0;
+        let mut total = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+        for octave in 0..HEIGHT_OCTAVES {
+            let seed = self.seed.wrapping_add(octave);
+            sum += amplitude * value_noise(seed, [x * frequency, 0.0, z * frequency]);
+            total += amplitude;
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+        BASE_HEIGHT + (sum / total * HEIGHT_RANGE) as usize
+    }
+
+    /// Returns the ore at world `x`, `y`, `z` if the stone there is part of
+    /// a vein.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn ore_at(&self, x: i32, y: usize, z: i32) -> Option<Block> {
+        let point = [x as f32, y as f32, z as f32].map(|v| v / ORE_SCALE);
+        ORES.iter()
+            .enumerate()
+            .find_map(|(i, &(ore, ceiling, threshold))| {
+                // Each ore gets its own noise field
+                let seed = self.seed ^ (0x5EED_0000 + u32::try_from(i).unwrap_or(0));
+                (y < ceiling && value_noise(seed, point) > threshold).then_some(ore)
+            })
+    }
+
+    /// Returns the biome of the column at world `x`, `z`.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn biome_at(&self, x: i32, z: i32) -> Biome {
+        let point = [x as f32 / BIOME_SCALE, 0.0, z as f32 / BIOME_SCALE];
+        let temperature = value_noise(self.seed ^ 0xB10E_0001, point);
+        let humidity = value_noise(self.seed ^ 0xB10E_0002, point);
+        Biome::from_climate(temperature, humidity)
+    }
+
+    /// Generates the chunk at `pos`.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub fn generate(&self, pos: ChunkPos) -> Chunk {
+        let mut chunk = Chunk::with_height(pos, self.height);
+        let (origin_x, origin_z) = pos.block_origin();
+
+        for x in 0..SECTION_SIZE {
+            for z in 0..SECTION_SIZE {
+                let (wx, wz) = (origin_x + x as i32, origin_z + z as i32);
+                let height = self.height_at(wx, wz).min(self.height - 2);
+                let biome = self.biome_at(wx, wz);
+
+                chunk.set_block(x, 0, z, Block::Bedrock);
+
+                for y in 1..height.saturating_sub(DIRT_DEPTH) {
+                    let block = self.ore_at(wx, y, wz).unwrap_or(Block::Stone);
+                    chunk.set_block(x, y, z, block);
+                }
+
+                for y in height.saturating_sub(DIRT_DEPTH)..height {
+                    chunk.set_block(x, y, z, biome.filler_block());
+                }
+
+                chunk.set_block(x, height, z, biome.surface_block());
+            }
+        }
+
+        // Trees keep clear of the edges so their leaves stay in this chunk
+        for x in 2..SECTION_SIZE - 2 {
+            for z in 2..SECTION_SIZE - 2 {
+                let (wx, wz) = (origin_x + x as i32, origin_z + z as i32);
+                let Some(rarity) = self.biome_at(wx, wz).tree_rarity() else {
+                    continue;
+                };
+                let roll = hash(self.seed ^ 0x7EE5, wx, 0, wz);
+                if roll.is_multiple_of(rarity) {
+                    let height = self.height_at(wx, wz).min(self.height - 2);
+                    let tree = Tree::new(4 + (roll / rarity % 3) as i32);
+                    Self::place_structure(
+                        &mut chunk,
+                        &tree,
+                        BlockPos::new(wx, height as i32 + 1, wz),
+                    );
+                }
+            }
+        }
+
+        chunk
+    }
+
+    /// Writes the blocks of `structure` grown from world position `origin`
+    /// into `chunk`. Blocks outside the chunk are skipped.
+    fn place_structure(chunk: &mut Chunk, structure: &impl Structure, origin: BlockPos) {
+        let (origin_x, origin_z) = chunk.position().block_origin();
+        for block in structure.blocks(origin) {
+            let local = |c: i32| usize::try_from(c).ok();
+            let pos = block.pos;
+            let (Some(x), Some(y), Some(z)) = (
+                local(pos.x - origin_x),
+                local(pos.y),
+                local(pos.z - origin_z),
+            ) else {
+                continue;
+            };
+            if chunk
+                .try_get_block(x, y, z)
+                .is_some_and(|existing| block.can_replace(existing))
+            {
+                chunk.set_block(x, y, z, block.block);
+            }
+        }
+    }
+}
+
+/// Hashes a lattice point to a pseudo-random value.
+pub(super) const fn hash(seed: u32, x: i32, y: i32, z: i32) -> u32 {
+    let mut h = seed
+        ^ x.cast_unsigned().wrapping_mul(0x27D4_EB2D)
+        ^ y.cast_unsigned().wrapping_mul(0x1656_67B1)
+        ^ z.cast_unsigned().wrapping_mul(0x9E37_79B9);
+    h = (h ^ (h >> 15)).wrapping_mul(0x85EB_CA6B);
+    h = (h ^ (h >> 13)).wrapping_mul(0xC2B2_AE35);
+    h ^ (h >> 16)
+}
+
+/// Returns smooth noise in `0.0..1.0` at `point`, interpolating random
+/// values on the integer lattice.
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+fn value_noise(seed: u32, point: [f32; 3]) -> f32 {
+    let cell = point.map(|v| v.floor() as i32);
+    // Smoothstep the position within the cell so slopes are continuous
+    let t: [f32; 3] = std::array::from_fn(|i| {
+        let f = point[i] - point[i].floor();
+        f * f * 2.0f32.mul_add(-f, 3.0)
+    });
+    let corner = |dx: i32, dy: i32, dz: i32| {
+        let h = hash(seed, cell[0] + dx, cell[1] + dy, cell[2] + dz);
+        (h >> 8) as f32 / (1 << 24) as f32
+    };
+    let lerp = |a: f32, b: f32, t: f32| (b - a).mul_add(t, a);
+
+    let plane = |dy| {
+        let near = lerp(corner(0, dy, 0), corner(1, dy, 0), t[0]);
+        let far = lerp(corner(0, dy, 1), corner(1, dy, 1), t[0]);
+        lerp(near, far, t[2])
+    };
+    lerp(plane(0), plane(1), t[1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the height of every column's top non-air block.
+    fn surface(chunk: &Chunk) -> Vec<usize> {
+        (0..SECTION_SIZE)
+            .flat_map(|x| (0..SECTION_SIZE).map(move |z| (x, z)))
+            .map(|(x, z)| {
+                (0..CHUNK_HEIGHT)
+                    .rev()
+                    .find(|&y| !chunk.get_block(x, y, z).is_air())
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_generates_identical_chunks() {
+        let pos = ChunkPos::new(3, -2);
+        let a = TerrainGenerator::new(1234).generate(pos);
+        let b = TerrainGenerator::new(1234).generate(pos);
+        for y in 0..CHUNK_HEIGHT / SECTION_SIZE {
+            let (a, b) = (a.get_section(y), b.get_section(y));
+            assert_eq!(a.is_some(), b.is_some());
+            if let (Some(a), Some(b)) = (a, b) {
+                assert!(a.blocks().eq(b.blocks()));
+            }
+        }
+    }
+
+    #[test]
+    fn seed_changes_terrain() {
+        let pos = ChunkPos::new(3, -2);
+        let seven = surface(&TerrainGenerator::new(7).generate(pos));
+        assert_eq!(seven, surface(&TerrainGenerator::new(7).generate(pos)));
+        assert_ne!(seven, surface(&TerrainGenerator::new(0).generate(pos)));
+    }
+
+    #[test]
+    fn heights_vary_and_line_up_across_chunks() {
+        let terrain = TerrainGenerator::new(42);
+        let heights: Vec<usize> = (-64..64).map(|x| terrain.height_at(x, 5)).collect();
+        assert!(heights.iter().max() > heights.iter().min());
+        // Neighboring columns never jump more than a couple of blocks
+        assert!(
+            heights
+                .windows(2)
+                .all(|pair| pair[0].abs_diff(pair[1]) <= 2)
+        );
+
+        // Columns on either side of a chunk border match the height function
+        let left = terrain.generate(ChunkPos::new(-1, 0));
+        let right = terrain.generate(ChunkPos::new(0, 0));
+        for z in 0..SECTION_SIZE {
+            let wz = i32::try_from(z).unwrap();
+            let top = |chunk: &Chunk, x, wx| {
+                let surface = terrain.biome_at(wx, wz).surface_block();
+                (0..CHUNK_HEIGHT)
+                    .rev()
+                    .find(|&y| chunk.get_block(x, y, z) == surface)
+            };
+            assert_eq!(top(&left, 15, -1), Some(terrain.height_at(-1, wz)));
+            assert_eq!(top(&right, 0, 0), Some(terrain.height_at(0, wz)));
+        }
+    }
+
+    #[test]
+    fn biomes_are_stable_for_a_seed_and_vary_across_the_world() {
+        let terrain = TerrainGenerator::new(42);
+        let same_seed = TerrainGenerator::new(42);
+        let mut seen = Vec::new();
+        for x in (-4096..4096).step_by(128) {
+            for z in (-4096..4096).step_by(128) {
+                let biome = terrain.biome_at(x, z);
+                assert_eq!(biome, same_seed.biome_at(x, z));
+                if !seen.contains(&biome) {
+                    seen.push(biome);
+                }
+            }
+        }
+        assert_eq!(seen.len(), Biome::ALL.len());
+
+        // Neighboring columns almost always share a biome
+        let changes = (0..512)
+            .filter(|&x| terrain.biome_at(x, 0) != terrain.biome_at(x + 1, 0))
+            .count();
+        assert!(changes <= 8, "{changes} biome changes");
+    }
+
+    #[test]
+    fn biomes_pick_the_surface_block() {
+        let terrain = TerrainGenerator::new(42);
+        for biome in Biome::ALL {
+            let (x, z) = (-4096..4096)
+                .step_by(64)
+                .flat_map(|x| (-4096..4096).step_by(64).map(move |z| (x, z)))
+                .find(|&(x, z)| terrain.biome_at(x, z) == biome)
+                .unwrap();
+            let pos = ChunkPos::from_block(x, z);
+            let chunk = terrain.generate(pos);
+            let (origin_x, origin_z) = pos.block_origin();
+            let local = |c: i32| usize::try_from(c).unwrap();
+            let (lx, lz) = (local(x - origin_x), local(z - origin_z));
+            let height = terrain.height_at(x, z);
+            assert_eq!(chunk.get_block(lx, height, lz), biome.surface_block());
+            assert_eq!(chunk.get_block(lx, height - 1, lz), biome.filler_block());
+        }
+    }
+
+    #[test]
+    fn ore_veins_replace_stone_below_their_ceiling() {
+        let terrain = TerrainGenerator::new(9);
+        let mut found = Vec::new();
+        for cx in 0..4 {
+            let chunk = terrain.generate(ChunkPos::new(cx, 0));
+            let (origin_x, _) = chunk.position().block_origin();
+            for (x, y, z) in (0..SECTION_SIZE).flat_map(|x| {
+                (0..CHUNK_HEIGHT).flat_map(move |y| (0..SECTION_SIZE).map(move |z| (x, y, z)))
+            }) {
+                let block = chunk.get_block(x, y, z);
+                let Some(&(_, ceiling, _)) = ORES.iter().find(|(ore, ..)| *ore == block) else {
+                    continue;
+                };
+                let (wx, wz) = (
+                    origin_x + i32::try_from(x).unwrap(),
+                    i32::try_from(z).unwrap(),
+                );
+                assert!(y < ceiling, "{} at y {y}", block.name());
+                assert!(y < terrain.height_at(wx, wz) - DIRT_DEPTH);
+                found.push(block);
+            }
+        }
+        assert!(found.contains(&Block::CoalOre));
+        assert!(found.contains(&Block::IronOre));
+    }
+}
@@ -1,6 +1,14 @@
 //! Texture atlas generation and management.
 //!
 //! Generates procedural textures for blocks and combines them into an atlas.
+//!
+//! Animated blocks (water) get a strip of frames side by side in one atlas
+//! row; the block shader steps through them with time, so each frame is
+//! [`FRAME_STRIDE`] further along U than the one before.
+//!
+//! The atlas is always [`ATLAS_COLUMNS`] wide and grows downward to fit the
+//! registered blocks, in a power-of-two number of rows so the tiles stay
+//! aligned for mipmapping.
 
 // Allow some patterns that are intentional for procedural generation
 #![allow(
@@ -15,7 +23,9 @@
     clippy::too_many_lines,           // Procedural texture gen is complex
 )]
 
-use super::block::Block;
+use super::block::{Block, ColorOverrides};
+use super::block_registry::{BlockRegistry, TextureSide};
+use super::mesh::Face;
 
 /// Size of each texture in pixels.
 pub const TEXTURE_SIZE: u32 = 16;
@@ -23,12 +33,38 @@ pub const TEXTURE_SIZE: u32 = 16;
 /// Number of textures per row in the atlas.
 pub const ATLAS_COLUMNS: u32 = 8;
 
-/// Number of texture rows in the atlas.
-pub const ATLAS_ROWS: u32 = 4;
+/// Most texture rows the atlas grows to.
+pub const MAX_ATLAS_ROWS: u32 = 32;
 
-/// Total atlas size in pixels.
+/// Most textures the atlas holds.
+pub const ATLAS_SLOTS: u32 = ATLAS_COLUMNS * MAX_ATLAS_ROWS;
+
+/// Atlas width in pixels.
 pub const ATLAS_SIZE: u32 = TEXTURE_SIZE * ATLAS_COLUMNS;
 
+// The GPU copies whole atlas rows, which must be 256-byte aligned
+const _: () = assert!((ATLAS_SIZE * 4) % wgpu::COPY_BYTES_PER_ROW_ALIGNMENT == 0);
+
+/// Number of mip levels of the atlas, halving down to one pixel per tile.
+pub const MIP_LEVELS: u32 = TEXTURE_SIZE.ilog2() + 1;
+
+/// Most frames an animated texture can have: one atlas row.
+pub const MAX_ANIMATION_FRAMES: u32 = ATLAS_COLUMNS;
+
+/// U distance between consecutive frames of an animated texture.
+pub const FRAME_STRIDE: f32 = 1.0 / ATLAS_COLUMNS as f32;
+
+/// Animation frames shown per second. Must match block.wgsl.
+pub const ANIMATION_FPS: f32 = 8.0;
+
+/// Seconds after which every animation is back at its first frame: 840
+/// frames, which every frame count up to [`MAX_ANIMATION_FRAMES`] divides.
+/// Animation time can wrap here without a visible jump.
+pub const ANIMATION_LOOP_SECONDS: f32 = 840.0 / ANIMATION_FPS;
+
+/// Seed for the default block textures.
+pub const DEFAULT_ATLAS_SEED: u32 = 0;
+
 /// A texture atlas containing all block textures.
 pub struct TextureAtlas {
     /// RGBA pixel data.
@@ -41,17 +77,37 @@ pub struct TextureAtlas {
 
 impl TextureAtlas {
     /// Creates a new texture atlas with procedurally generated block textures.
+    ///
+    /// The `seed` varies the texture noise, so different worlds can look
+    /// slightly different; the same seed always produces the same atlas.
+    /// [`DEFAULT_ATLAS_SEED`] gives the default look.
+    #[must_use]
+    pub fn generate(seed: u32) -> Self {
+        Self::generate_with_colors(seed, &ColorOverrides::new())
+    }
+
+    /// Creates a texture atlas like [`generate`](Self::generate), tinting
+    /// each block with its color from `overrides`.
     #[must_use]
-    pub fn generate() -> Self {
+    pub fn generate_with_colors(seed: u32, overrides: &ColorOverrides) -> Self {
         let width = ATLAS_SIZE;
-        let height = TEXTURE_SIZE * ATLAS_ROWS;
+        let height = TEXTURE_SIZE * Self::rows();
         let mut data = vec![0u8; (width * height * 4) as usize];
 
-        // Generate texture for each block type
-        for block_id in 0..=17u16 {
-            if let Some(block) = Block::from_id(block_id) {
-                let (atlas_x, atlas_y) = Self::block_atlas_position(block);
-                Self::generate_block_texture(&mut data, width, atlas_x, atlas_y, block);
+        // Generate each texture of each block type, frame by frame
+        for &block in Block::all() {
+            let frames = block.def().frames;
+            for (side, slot) in block.def().slots.distinct() {
+                for frame in 0..frames {
+                    Self::generate_block_texture(
+                        &mut data,
+                        width,
+                        Self::slot_position(slot + frame),
+                        (block, side, frame as f32 / frames as f32),
+                        overrides,
+                        seed,
+                    );
+                }
             }
         }
 
@@ -62,35 +118,129 @@ impl TextureAtlas {
         }
     }
 
-    /// Returns the (column, row) position in the atlas for a block type.
+    /// Returns mip levels 1 and up of the atlas, each half the size of the
+    /// one before, so distant blocks don't shimmer.
+    ///
+    /// Each texel is the average of a 2x2 box of the level above. Tiles are
+    /// a power of two in size and aligned to it, so at every level a box
+    /// lies within one tile and colors never bleed between blocks.
+    #[must_use]
+    pub fn mipmaps(&self) -> Vec<Self> {
+        let mut levels: Vec<Self> = Vec::with_capacity(MIP_LEVELS as usize - 1);
+        for _ in 1..MIP_LEVELS {
+            let above = levels.last().unwrap_or(self);
+            let next = above.downsample();
+            levels.push(next);
+        }
+        levels
+    }
+
+    /// Halves the atlas in each direction with a 2x2 box filter.
+    fn downsample(&self) -> Self {
+        let width = (self.width / 2).max(1);
+        let height = (self.height / 2).max(1);
+        let mut data = vec![0u8; (width * height * 4) as usize];
+        for y in 0..height {
+            for x in 0..width {
+                for channel in 0..4 {
+                    let sum: u32 = [(0, 0), (1, 0), (0, 1), (1, 1)]
+                        .iter()
+                        .map(|&(dx, dy)| {
+                            let sx = (x * 2 + dx).min(self.width - 1);
+                            let sy = (y * 2 + dy).min(self.height - 1);
+                            self.data[((sy * self.width + sx) * 4 + channel) as usize] as u32
+                        })
+                        .sum();
+                    // Rounded to the nearest value
+                    data[((y * width + x) * 4 + channel) as usize] = ((sum + 2) / 4) as u8;
+                }
+            }
+        }
+        Self {
+            data,
+            width,
+            height,
+        }
+    }
+
+    /// Returns the number of texture rows in the atlas of the
+    /// [global](BlockRegistry::global) registry's blocks.
+    #[must_use]
+    pub fn rows() -> u32 {
+        Self::rows_for(BlockRegistry::global().slot_count())
+    }
+
+    /// Returns the number of texture rows an atlas of `slots` textures
+    /// needs: the next power of two that fits them all.
+    #[must_use]
+    pub const fn rows_for(slots: u32) -> u32 {
+        slots.div_ceil(ATLAS_COLUMNS).next_power_of_two()
+    }
+
+    /// Returns the (column, row) position in the atlas of an atlas slot.
+    #[must_use]
+    pub const fn slot_position(slot: u32) -> (u32, u32) {
+        (slot % ATLAS_COLUMNS, slot / ATLAS_COLUMNS)
+    }
+
+    /// Returns the (column, row) position in the atlas of a block type's
+    /// side texture.
     #[must_use]
     pub fn block_atlas_position(block: Block) -> (u32, u32) {
-        let id = block.id() as u32;
-        (id % ATLAS_COLUMNS, id / ATLAS_COLUMNS)
+        Self::slot_position(block.def().slots.side)
     }
 
-    /// Returns UV coordinates for a block face.
+    /// Returns UV coordinates of a block's side texture.
     ///
     /// Returns `(u_min, v_min, u_max, v_max)` normalized to `[0, 1]`.
     #[must_use]
     pub fn block_uvs(block: Block) -> (f32, f32, f32, f32) {
-        let (col, row) = Self::block_atlas_position(block);
+        Self::slot_uvs(block.def().slots.side)
+    }
+
+    /// Returns UV coordinates of the texture drawn on one face of a block.
+    ///
+    /// Returns `(u_min, v_min, u_max, v_max)` normalized to `[0, 1]`.
+    #[must_use]
+    pub fn block_face_uvs(block: Block, face: Face) -> (f32, f32, f32, f32) {
+        Self::slot_uvs(block.def().slots.slot(face.texture_side()))
+    }
+
+    /// Returns how far along U to sample an animated texture of `frames`
+    /// frames at `time` seconds, from 0 for the first frame in steps of
+    /// [`FRAME_STRIDE`]. Still textures (`frames <= 1`) never move.
+    ///
+    /// Must match `frame_offset` in block.wgsl.
+    #[must_use]
+    pub fn frame_offset(time: f32, frames: u32) -> f32 {
+        if frames <= 1 {
+            return 0.0;
+        }
+        let frame = (time.max(0.0) * ANIMATION_FPS).floor() as u32 % frames;
+        frame as f32 * FRAME_STRIDE
+    }
+
+    /// Returns UV coordinates of an atlas slot.
+    fn slot_uvs(slot: u32) -> (f32, f32, f32, f32) {
+        let (col, row) = Self::slot_position(slot);
+        let rows = Self::rows();
         let u_min = col as f32 / ATLAS_COLUMNS as f32;
-        let v_min = row as f32 / ATLAS_ROWS as f32;
+        let v_min = row as f32 / rows as f32;
         let u_max = (col + 1) as f32 / ATLAS_COLUMNS as f32;
-        let v_max = (row + 1) as f32 / ATLAS_ROWS as f32;
+        let v_max = (row + 1) as f32 / rows as f32;
         (u_min, v_min, u_max, v_max)
     }
 
-    /// Generates a procedural texture for a block at the given atlas position.
+    /// Generates a procedural texture for one side of a block at the given
+    /// atlas position, `phase` of the way through its animation.
     fn generate_block_texture(
         data: &mut [u8],
         atlas_width: u32,
-        atlas_x: u32,
-        atlas_y: u32,
-        block: Block,
+        (atlas_x, atlas_y): (u32, u32),
+        (block, side, phase): (Block, TextureSide, f32),
+        colors: &ColorOverrides,
+        seed: u32,
     ) {
-        let base_color = block.color();
         let base_x = atlas_x * TEXTURE_SIZE;
         let base_y = atlas_y * TEXTURE_SIZE;
 
@@ -100,7 +250,8 @@ impl TextureAtlas {
                 let py = base_y + local_y;
                 let idx = ((py * atlas_width + px) * 4) as usize;
 
-                let (r, g, b) = Self::generate_pixel(block, local_x, local_y, base_color);
+                let (r, g, b) =
+                    Self::generate_pixel((block, side, phase), local_x, local_y, colors, seed);
 
                 data[idx] = (r * 255.0) as u8;
                 data[idx + 1] = (g * 255.0) as u8;
@@ -111,10 +262,14 @@ impl TextureAtlas {
                     Block::Air => 0,
                     Block::Leaves => {
                         // Cutout transparency - some pixels fully transparent
-                        let noise = Self::hash_noise(local_x, local_y, 5);
+                        let noise = Self::hash_noise(local_x, local_y, 5, seed);
                         if noise > 0.65 { 0 } else { 255 }
                     }
                     Block::Glass => 180, // Semi-transparent
+                    Block::RedStainedGlass
+                    | Block::GreenStainedGlass
+                    | Block::BlueStainedGlass
+                    | Block::YellowStainedGlass => 150, // Tinted, semi-transparent
                     Block::Water => 160, // Semi-transparent
                     _ => 255,            // Fully opaque
                 };
@@ -122,21 +277,31 @@ impl TextureAtlas {
         }
     }
 
-    /// Generates a pixel color for a specific block texture.
-    fn generate_pixel(block: Block, x: u32, y: u32, base: [f32; 3]) -> (f32, f32, f32) {
+    /// Generates a pixel color for one side of a block's texture, `phase`
+    /// (0 to 1) of the way through its animation.
+    ///
+    /// On side faces, pixel row 0 is drawn at the bottom of the block.
+    fn generate_pixel(
+        (block, side, phase): (Block, TextureSide, f32),
+        x: u32,
+        y: u32,
+        colors: &ColorOverrides,
+        seed: u32,
+    ) -> (f32, f32, f32) {
+        let base = colors.color(block);
         match block {
             Block::Air => (0.0, 0.0, 0.0),
 
-            Block::Stone | Block::Cobblestone => {
+            Block::Stone | Block::Cobblestone | Block::StoneSlab => {
                 // Noisy gray stone texture
-                let noise = Self::hash_noise(x, y, 0) * 0.15;
+                let noise = Self::hash_noise(x, y, 0, seed) * 0.15;
                 let v = base[0] + noise - 0.075;
                 (v, v, v)
             }
 
             Block::Dirt => {
                 // Brown with darker spots
-                let noise = Self::hash_noise(x, y, 1) * 0.2;
+                let noise = Self::hash_noise(x, y, 1, seed) * 0.2;
                 (
                     base[0] + noise - 0.1,
                     base[1] + noise - 0.1,
@@ -145,18 +310,29 @@ impl TextureAtlas {
             }
 
             Block::Grass => {
-                // Green top with variation
-                let noise = Self::hash_noise(x, y, 2) * 0.15;
-                (
-                    base[0] + noise * 0.5 - 0.05,
-                    base[1] + noise - 0.075,
-                    base[2] + noise * 0.3 - 0.02,
-                )
+                // Uneven fringe of grass hanging over the top of the sides
+                let fringe = 3 + (Self::hash_noise(x, 0, 9, seed) * 3.0) as u32;
+                let grassy = match side {
+                    TextureSide::Top => true,
+                    TextureSide::Side => y >= TEXTURE_SIZE - fringe,
+                    TextureSide::Bottom => false,
+                };
+                if grassy {
+                    // Green with variation
+                    let noise = Self::hash_noise(x, y, 2, seed) * 0.15;
+                    (
+                        base[0] + noise * 0.5 - 0.05,
+                        base[1] + noise - 0.075,
+                        base[2] + noise * 0.3 - 0.02,
+                    )
+                } else {
+                    Self::generate_pixel((Block::Dirt, side, phase), x, y, colors, seed)
+                }
             }
 
             Block::Sand => {
                 // Sandy tan with slight noise
-                let noise = Self::hash_noise(x, y, 3) * 0.1;
+                let noise = Self::hash_noise(x, y, 3, seed) * 0.1;
                 (
                     base[0] + noise - 0.05,
                     base[1] + noise - 0.05,
@@ -166,11 +342,19 @@ impl TextureAtlas {
 
             Block::Gravel => {
                 // Rough gray with larger noise
-                let noise = Self::hash_noise(x, y, 4) * 0.25;
+                let noise = Self::hash_noise(x, y, 4, seed) * 0.25;
                 let v = base[0] + noise - 0.125;
                 (v, v, v)
             }
 
+            Block::Log if side == TextureSide::Side => {
+                // Bark: dark vertical grooves
+                let groove = Self::hash_noise(x, 0, 10, seed) * 0.15;
+                let noise = Self::hash_noise(x, y, 10, seed) * 0.05;
+                let v = noise - groove;
+                (base[0] + v, base[1] + v * 0.7, base[2] + v * 0.3)
+            }
+
             Block::Log => {
                 // Wood rings pattern
                 let cx = (x as i32 - 8).abs() as f32;
@@ -182,7 +366,7 @@ impl TextureAtlas {
 
             Block::Leaves => {
                 // Leafy pattern with cutout holes
-                let noise = Self::hash_noise(x, y, 5);
+                let noise = Self::hash_noise(x, y, 5, seed);
                 // Return special marker for transparent pixels (will be handled in alpha)
                 let v = noise * 0.2;
                 (base[0] + v * 0.5, base[1] + v, base[2] + v * 0.3)
@@ -194,16 +378,26 @@ impl TextureAtlas {
                 (base[0] + edge, base[1] + edge, base[2])
             }
 
+            Block::RedStainedGlass
+            | Block::GreenStainedGlass
+            | Block::BlueStainedGlass
+            | Block::YellowStainedGlass => {
+                // Solid tint with a lighter frame, like regular glass
+                let edge = (x == 0 || x == 15 || y == 0 || y == 15) as u8 as f32 * 0.15;
+                (base[0] + edge, base[1] + edge, base[2] + edge)
+            }
+
             Block::Water => {
-                // Wavy blue
-                let wave = ((x as f32 * 0.5 + y as f32 * 0.3).sin() * 0.5 + 0.5) * 0.2;
+                // Wavy blue, the waves rolling along over a full animation
+                let shift = phase * std::f32::consts::TAU;
+                let wave = ((x as f32 * 0.5 + y as f32 * 0.3 + shift).sin() * 0.5 + 0.5) * 0.2;
                 (base[0] + wave * 0.3, base[1] + wave * 0.5, base[2] + wave)
             }
 
-            Block::Planks => {
+            Block::Planks | Block::PlanksSlab => {
                 // Wood grain pattern
                 let grain = if y % 4 == 0 { 0.9 } else { 1.0 };
-                let noise = Self::hash_noise(x, y, 6) * 0.1;
+                let noise = Self::hash_noise(x, y, 6, seed) * 0.1;
                 (
                     (base[0] + noise) * grain,
                     (base[1] + noise * 0.7) * grain,
@@ -224,7 +418,7 @@ impl TextureAtlas {
                 if is_mortar {
                     (0.7, 0.7, 0.65) // Mortar color
                 } else {
-                    let noise = Self::hash_noise(x, y, 7) * 0.15;
+                    let noise = Self::hash_noise(x, y, 7, seed) * 0.15;
                     (
                         base[0] + noise,
                         base[1] + noise * 0.5,
@@ -235,7 +429,7 @@ impl TextureAtlas {
 
             Block::CoalOre | Block::IronOre | Block::GoldOre | Block::DiamondOre => {
                 // Stone with ore spots
-                let ore_noise = Self::hash_noise(x, y, block.id() as u32);
+                let ore_noise = Self::hash_noise(x, y, block.id() as u32, seed);
                 if ore_noise > 0.75 {
                     // Ore spot
                     match block {
@@ -243,11 +437,11 @@ impl TextureAtlas {
                         Block::IronOre => (0.7, 0.6, 0.5),
                         Block::GoldOre => (1.0, 0.9, 0.2),
                         Block::DiamondOre => (0.3, 0.9, 1.0),
-                        _ => (base[0], base[1], base[2]),
+                        _ => base.into(),
                     }
                 } else {
                     // Stone background
-                    let noise = Self::hash_noise(x, y, 0) * 0.15;
+                    let noise = Self::hash_noise(x, y, 0, seed) * 0.15;
                     let v = 0.5 + noise - 0.075;
                     (v, v, v)
                 }
@@ -255,21 +449,31 @@ impl TextureAtlas {
 
             Block::Bedrock => {
                 // Very dark with cracks
-                let noise = Self::hash_noise(x, y, 8) * 0.2;
+                let noise = Self::hash_noise(x, y, 8, seed) * 0.2;
                 let v = base[0] + noise - 0.1;
                 (v, v, v)
             }
+
+            _ => {
+                // Registered blocks without a pattern: lightly noised base color
+                let noise = Self::hash_noise(x, y, block.id().into(), seed) * 0.1 - 0.05;
+                (base[0] + noise, base[1] + noise, base[2] + noise)
+            }
         }
     }
 
     /// Simple hash-based noise function.
-    fn hash_noise(x: u32, y: u32, seed: u32) -> f32 {
+    ///
+    /// `channel` separates the noise used for different features; `seed`
+    /// perturbs all of it (seed 0 leaves it unperturbed).
+    fn hash_noise(x: u32, y: u32, channel: u32, seed: u32) -> f32 {
         let n = x
-            .wrapping_mul(374761393)
-            .wrapping_add(y.wrapping_mul(668265263))
-            .wrapping_add(seed.wrapping_mul(1013904223));
+            .wrapping_mul(374_761_393)
+            .wrapping_add(y.wrapping_mul(668_265_263))
+            .wrapping_add(channel.wrapping_mul(1_013_904_223));
+        let n = n ^ seed.wrapping_mul(0x9E37_79B9);
         let n = n ^ (n >> 13);
-        let n = n.wrapping_mul(1274126177);
+        let n = n.wrapping_mul(1_274_126_177);
         let n = n ^ (n >> 16);
         (n & 0xFFFF) as f32 / 65535.0
     }
@@ -278,44 +482,112 @@ impl TextureAtlas {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::world::{BlockDef, BlockProperties};
 
     #[test]
     fn atlas_has_correct_size() {
-        let atlas = TextureAtlas::generate();
+        let atlas = TextureAtlas::generate(DEFAULT_ATLAS_SEED);
         assert_eq!(atlas.width, ATLAS_SIZE);
-        assert_eq!(atlas.height, TEXTURE_SIZE * ATLAS_ROWS);
+        assert_eq!(atlas.height, TEXTURE_SIZE * TextureAtlas::rows());
         assert_eq!(atlas.data.len(), (atlas.width * atlas.height * 4) as usize);
     }
 
     #[test]
-    fn block_uvs_are_normalized() {
-        for id in 0..=17u16 {
-            if let Some(block) = Block::from_id(id) {
-                let (u_min, v_min, u_max, v_max) = TextureAtlas::block_uvs(block);
-                assert!(u_min >= 0.0 && u_min <= 1.0);
-                assert!(v_min >= 0.0 && v_min <= 1.0);
-                assert!(u_max >= 0.0 && u_max <= 1.0);
-                assert!(v_max >= 0.0 && v_max <= 1.0);
-                assert!(u_max > u_min);
-                assert!(v_max > v_min);
+    fn atlas_grows_to_fit_registered_blocks() {
+        assert_eq!(TextureAtlas::rows_for(1), 1);
+        assert_eq!(TextureAtlas::rows_for(ATLAS_COLUMNS), 1);
+        assert_eq!(TextureAtlas::rows_for(ATLAS_COLUMNS + 1), 2);
+        assert_eq!(TextureAtlas::rows_for(ATLAS_COLUMNS * 5), 8);
+        assert_eq!(TextureAtlas::rows_for(ATLAS_SLOTS), MAX_ATLAS_ROWS);
+
+        let mut registry = BlockRegistry::new();
+        while registry.len() < 40 {
+            let name = format!("block_{}", registry.len());
+            let def = BlockDef::new(name, BlockProperties::SOLID, [0.0; 3]);
+            registry.register(def).unwrap();
+        }
+        let rows = TextureAtlas::rows_for(registry.slot_count());
+        assert!(rows.is_power_of_two());
+        assert!(rows * ATLAS_COLUMNS >= registry.slot_count());
+        assert!(rows / 2 * ATLAS_COLUMNS < registry.slot_count());
+
+        let mut seen = std::collections::HashSet::new();
+        for &block in registry.blocks() {
+            let def = registry.def(block);
+            for (_, slot) in def.slots.distinct() {
+                for frame in 0..def.frames {
+                    let (col, row) = TextureAtlas::slot_position(slot + frame);
+                    assert!(col < ATLAS_COLUMNS && row < rows);
+                    assert!(seen.insert((col, row)), "{} shares a tile", def.name);
+                }
             }
         }
     }
 
+    #[test]
+    fn block_uvs_are_normalized() {
+        for &block in Block::all() {
+            let (u_min, v_min, u_max, v_max) = TextureAtlas::block_uvs(block);
+            assert!(u_min >= 0.0 && u_min <= 1.0);
+            assert!(v_min >= 0.0 && v_min <= 1.0);
+            assert!(u_max >= 0.0 && u_max <= 1.0);
+            assert!(v_max >= 0.0 && v_max <= 1.0);
+            assert!(u_max > u_min);
+            assert!(v_max > v_min);
+        }
+    }
+
     #[test]
     fn atlas_positions_are_unique() {
         let mut positions = std::collections::HashSet::new();
-        for id in 0..=17u16 {
-            if let Some(block) = Block::from_id(id) {
-                let pos = TextureAtlas::block_atlas_position(block);
-                assert!(positions.insert(pos), "Duplicate position for block {id}");
-            }
+        for &block in Block::all() {
+            let pos = TextureAtlas::block_atlas_position(block);
+            assert!(
+                positions.insert(pos),
+                "Duplicate position for block {block:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn grass_has_top_side_and_bottom_textures() {
+        let uvs = |face| TextureAtlas::block_face_uvs(Block::Grass, face);
+        assert_ne!(uvs(Face::PosY), uvs(Face::PosX));
+        assert_ne!(uvs(Face::NegY), uvs(Face::PosX));
+        assert_ne!(uvs(Face::PosY), uvs(Face::NegY));
+        for face in [Face::NegX, Face::PosZ, Face::NegZ] {
+            assert_eq!(uvs(face), uvs(Face::PosX));
+        }
+        // Logs share one texture between their ends
+        let log = |face| TextureAtlas::block_face_uvs(Block::Log, face);
+        assert_eq!(log(Face::PosY), log(Face::NegY));
+        assert_ne!(log(Face::PosY), log(Face::PosX));
+        assert_eq!(TextureAtlas::block_uvs(Block::Grass), uvs(Face::PosX));
+    }
+
+    #[test]
+    fn grass_bottom_is_plain_dirt_and_sides_have_a_fringe() {
+        let atlas = TextureAtlas::generate(DEFAULT_ATLAS_SEED);
+        let row = |slot: u32, y: u32| {
+            let (col, row) = TextureAtlas::slot_position(slot);
+            let start =
+                (((row * TEXTURE_SIZE + y) * atlas.width + col * TEXTURE_SIZE) * 4) as usize;
+            atlas.data[start..start + (TEXTURE_SIZE * 4) as usize].to_vec()
+        };
+        let grass = Block::Grass.def().slots;
+        let dirt = Block::Dirt.def().slots.side;
+        for y in 0..TEXTURE_SIZE {
+            assert_eq!(row(grass.bottom, y), row(dirt, y));
         }
+        // Row 0 is the bottom of a side face: dirt there, grass at the top
+        assert_eq!(row(grass.side, 0), row(dirt, 0));
+        let top = TEXTURE_SIZE - 1;
+        assert_eq!(row(grass.side, top), row(grass.top, top));
     }
 
     #[test]
     fn atlas_pixels_are_non_zero_for_solid_blocks() {
-        let atlas = TextureAtlas::generate();
+        let atlas = TextureAtlas::generate(DEFAULT_ATLAS_SEED);
         // Check stone block (id=1) has non-zero pixels
         let (col, row) = TextureAtlas::block_atlas_position(Block::Stone);
         let px = col * TEXTURE_SIZE;
@@ -324,4 +596,130 @@ mod tests {
         // At least one RGB channel should be non-zero
         assert!(atlas.data[idx] > 0 || atlas.data[idx + 1] > 0 || atlas.data[idx + 2] > 0);
     }
+
+    #[test]
+    fn seed_varies_atlas_reproducibly() {
+        let default = TextureAtlas::generate(DEFAULT_ATLAS_SEED);
+        let seeded = TextureAtlas::generate(1234);
+
+        assert_eq!(
+            TextureAtlas::generate(DEFAULT_ATLAS_SEED).data,
+            default.data
+        );
+        assert_eq!(TextureAtlas::generate(1234).data, seeded.data);
+        assert_ne!(seeded.data, default.data);
+        assert_ne!(TextureAtlas::generate(1235).data, seeded.data);
+    }
+
+    #[test]
+    fn color_overrides_tint_only_their_tile() {
+        let default = TextureAtlas::generate(DEFAULT_ATLAS_SEED);
+        let mut overrides = ColorOverrides::new();
+        overrides.set(Block::Stone, [0.0, 0.45, 0.7]);
+        let tinted = TextureAtlas::generate_with_colors(DEFAULT_ATLAS_SEED, &overrides);
+
+        let pixel = |atlas: &TextureAtlas, block: Block| {
+            let (col, row) = TextureAtlas::block_atlas_position(block);
+            let idx = ((row * TEXTURE_SIZE * atlas.width + col * TEXTURE_SIZE) * 4) as usize;
+            atlas.data[idx..idx + 4].to_vec()
+        };
+        assert_ne!(pixel(&tinted, Block::Stone), pixel(&default, Block::Stone));
+        assert_eq!(pixel(&tinted, Block::Dirt), pixel(&default, Block::Dirt));
+    }
+
+    #[test]
+    fn frame_offset_steps_through_the_strip() {
+        let offset = |time| TextureAtlas::frame_offset(time, 4);
+        let frame = 1.0 / ANIMATION_FPS;
+        let same = |a: f32, b: f32| (a - b).abs() < 1e-6;
+
+        assert!(same(offset(0.0), 0.0));
+        assert!(same(offset(frame * 0.5), 0.0));
+        assert!(same(offset(frame * 1.5), FRAME_STRIDE));
+        assert!(same(offset(frame * 3.5), 3.0 * FRAME_STRIDE));
+        // Wraps back to the first frame
+        assert!(same(offset(frame * 4.5), 0.0));
+        assert!(same(
+            offset(ANIMATION_LOOP_SECONDS + frame * 2.5),
+            2.0 * FRAME_STRIDE
+        ));
+
+        // Still textures never move
+        assert!(same(TextureAtlas::frame_offset(12.3, 1), 0.0));
+        assert!(same(TextureAtlas::frame_offset(12.3, 0), 0.0));
+    }
+
+    #[test]
+    fn water_frames_differ() {
+        let atlas = TextureAtlas::generate(DEFAULT_ATLAS_SEED);
+        let frame = |index: u32| {
+            let (col, row) = TextureAtlas::slot_position(Block::Water.def().slots.side + index);
+            (0..TEXTURE_SIZE)
+                .flat_map(|y| {
+                    let start = (((row * TEXTURE_SIZE + y) * atlas.width + col * TEXTURE_SIZE) * 4)
+                        as usize;
+                    atlas.data[start..start + (TEXTURE_SIZE * 4) as usize].to_vec()
+                })
+                .collect::<Vec<u8>>()
+        };
+
+        assert!(Block::Water.def().frames > 1);
+        assert_ne!(frame(0), frame(1));
+        assert!(frame(1).iter().skip(3).step_by(4).all(|&alpha| alpha > 0));
+    }
+
+    #[test]
+    fn mip_levels_keep_solid_tiles_their_color() {
+        // Two solid tiles side by side, which must not bleed into each other
+        let (width, height) = (TEXTURE_SIZE * 2, TEXTURE_SIZE);
+        let mut data = Vec::new();
+        for _ in 0..height {
+            for x in 0..width {
+                let color = if x < TEXTURE_SIZE {
+                    [200, 40, 30, 255]
+                } else {
+                    [20, 60, 220, 255]
+                };
+                data.extend_from_slice(&color);
+            }
+        }
+        let atlas = TextureAtlas {
+            data,
+            width,
+            height,
+        };
+
+        let mipmaps = atlas.mipmaps();
+        assert_eq!(mipmaps.len(), MIP_LEVELS as usize - 1);
+        let level1 = &mipmaps[0];
+        assert_eq!((level1.width, level1.height), (width / 2, height / 2));
+        for (i, pixel) in level1.data.chunks(4).enumerate() {
+            let x = i as u32 % level1.width;
+            let expected = if x < TEXTURE_SIZE / 2 {
+                [200, 40, 30, 255]
+            } else {
+                [20, 60, 220, 255]
+            };
+            assert_eq!(pixel, expected);
+        }
+
+        // The last level is one pixel per tile
+        let last = mipmaps.last().unwrap();
+        assert_eq!((last.width, last.height), (2, 1));
+        assert_eq!(last.data, [200, 40, 30, 255, 20, 60, 220, 255]);
+    }
+
+    #[test]
+    fn mip_levels_average_pixels() {
+        let atlas = TextureAtlas {
+            data: vec![
+                0, 0, 0, 0, 255, 255, 255, 255, 100, 50, 0, 255, 101, 50, 0, 255,
+            ],
+            width: 2,
+            height: 2,
+        };
+        let level1 = atlas.downsample();
+        assert_eq!((level1.width, level1.height), (1, 1));
+        assert_eq!(level1.data, [114, 89, 64, 191]);
+    }
 }
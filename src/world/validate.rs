@@ -0,0 +1,157 @@
+//! Runtime validation of world invariants.
+//!
+//! Catches corruption that would otherwise show up as rendering glitches:
+//! stale cached counts in sections and meshes indexing past their vertices.
+//! Nothing here is run automatically; it backs a debug command.
+
+use thiserror::Error;
+
+use super::chunk::{Chunk, ChunkPos};
+use super::mesh::{ChunkMesh, MeshLayer};
+
+/// A broken invariant found by validation.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum WorldIssue {
+    /// A section's cached solid count disagrees with its blocks.
+    #[error(
+        "chunk {chunk:?} section {section}: solid_count is {stored}, but {actual} blocks are non-air"
+    )]
+    SolidCountMismatch {
+        /// Chunk containing the section.
+        chunk: ChunkPos,
+        /// Section index within the chunk.
+        section: usize,
+        /// The cached count.
+        stored: u32,
+        /// The recounted value.
+        actual: u32,
+    },
+
+    /// A mesh index points past the end of the vertex data.
+    #[error(
+        "chunk {chunk:?} {layer} mesh: index {index} is out of range for {vertex_count} vertices"
+    )]
+    IndexOutOfRange {
+        /// Chunk the mesh belongs to.
+        chunk: ChunkPos,
+        /// Mesh layer.
+        layer: MeshLayer,
+        /// The first offending index.
+        index: u32,
+        /// Number of vertices in the mesh.
+        vertex_count: usize,
+    },
+
+    /// A clean chunk's GPU buffers don't match whether its mesh is empty.
+    #[error(
+        "chunk {chunk:?} {layer} mesh: has_buffers is {has_buffers}, but mesh_empty is {mesh_empty}"
+    )]
+    BufferMismatch {
+        /// The chunk.
+        chunk: ChunkPos,
+        /// Mesh layer.
+        layer: MeshLayer,
+        /// Whether GPU buffers exist for the layer.
+        has_buffers: bool,
+        /// Whether the freshly built mesh is empty.
+        mesh_empty: bool,
+    },
+
+    /// GPU buffers exist for a chunk that isn't loaded.
+    #[error("chunk {chunk:?} has GPU buffers but is not loaded")]
+    StaleBuffers {
+        /// The chunk.
+        chunk: ChunkPos,
+    },
+}
+
+/// Checks that every section's cached solid count matches its blocks.
+#[must_use]
+pub fn validate_chunk(chunk: &Chunk) -> Vec<WorldIssue> {
+    (0..chunk.section_count())
+        .filter_map(|section_y| {
+            let section = chunk.get_section(section_y)?;
+            let (stored, actual) = (section.solid_count(), section.count_solid());
+            (stored != actual).then_some(WorldIssue::SolidCountMismatch {
+                chunk: chunk.position(),
+                section: section_y,
+                stored,
+                actual,
+            })
+        })
+        .collect()
+}
+
+/// Checks that every index in `mesh` refers to an existing vertex.
+///
+/// Reports at most one issue per mesh, for the first bad index.
+#[must_use]
+pub fn validate_mesh(chunk: ChunkPos, layer: MeshLayer, mesh: &ChunkMesh) -> Option<WorldIssue> {
+    let vertex_count = mesh.vertices.len();
+    mesh.indices
+        .iter()
+        .find(|&&index| usize::try_from(index).map_or(true, |i| i >= vertex_count))
+        .map(|&index| WorldIssue::IndexOutOfRange {
+            chunk,
+            layer,
+            index,
+            vertex_count,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::block::Block;
+    use crate::world::mesh::MeshGenerator;
+
+    fn chunk_with_terrain() -> Chunk {
+        let mut chunk = Chunk::new(ChunkPos::new(2, -1));
+        chunk.fill_test_pattern();
+        chunk
+    }
+
+    #[test]
+    fn healthy_chunk_has_no_issues() {
+        let chunk = chunk_with_terrain();
+        assert!(validate_chunk(&chunk).is_empty());
+
+        let layers = MeshGenerator::new(chunk.clone()).generate_layers();
+        for layer in MeshLayer::ALL {
+            assert!(validate_mesh(chunk.position(), layer, layers.layer(layer)).is_none());
+        }
+    }
+
+    #[test]
+    fn corrupted_solid_count_is_detected() {
+        let mut chunk = chunk_with_terrain();
+        chunk.set_block(0, 70, 0, Block::Stone);
+        let section = chunk.get_section_mut(4).unwrap();
+        let actual = section.solid_count();
+        section.corrupt_solid_count(actual + 3);
+
+        assert_eq!(
+            validate_chunk(&chunk),
+            vec![WorldIssue::SolidCountMismatch {
+                chunk: chunk.position(),
+                section: 4,
+                stored: actual + 3,
+                actual,
+            }]
+        );
+    }
+
+    #[test]
+    fn out_of_range_index_is_detected() {
+        let chunk = chunk_with_terrain();
+        let mut mesh = MeshGenerator::new(chunk.clone()).generate();
+        let bad = u32::try_from(mesh.vertices.len()).unwrap();
+        mesh.indices.push(bad);
+
+        let issue = validate_mesh(chunk.position(), MeshLayer::Opaque, &mesh);
+        assert!(matches!(
+            issue,
+            Some(WorldIssue::IndexOutOfRange { index, .. }) if index == bad
+        ));
+    }
+}